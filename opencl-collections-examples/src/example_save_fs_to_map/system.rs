@@ -15,7 +15,7 @@ pub type ArcOpenclBlock = Arc<System>;
 
 /// big files - gpu 8gb
 pub fn create_system_8gb<T: ClTypeTrait>(total_maps: usize) -> (ArcOpenclBlock, MapSrc<T>) {
-    let mut map_src: MapSrc<T> = MapSrc::new(total_maps);
+    let mut map_src: MapSrc<T> = MapSrc::new(total_maps).unwrap();
 
     map_src.add(KB * 512, 512);
     map_src.add(MB, 16);
@@ -51,7 +51,7 @@ pub fn create_system_8gb<T: ClTypeTrait>(total_maps: usize) -> (ArcOpenclBlock,
 
 // small files - gpu 4gb
 pub fn create_system_4gb<T: ClTypeTrait>(total_maps: usize) -> (ArcOpenclBlock, MapSrc<T>) {
-    let mut map_src: MapSrc<T> = MapSrc::new(total_maps);
+    let mut map_src: MapSrc<T> = MapSrc::new(total_maps).unwrap();
 
     let memory_blocks = 2;
 
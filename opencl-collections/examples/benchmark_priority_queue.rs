@@ -0,0 +1,77 @@
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::System;
+use opencl_collections::config::DEFAULT_DEVICE_INDEX;
+use opencl_collections::queue::config::{PriorityQueueType, QueueSrc};
+use opencl_collections::queue::handle::priority::PriorityQueueHandle;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+const QUEUE_CAPACITY: usize = 1024;
+const BATCH_SIZE: usize = 64;
+
+fn get_input() -> (Vec<cl_int>, Vec<cl_int>) {
+    let values: Vec<cl_int> = (0..QUEUE_CAPACITY as cl_int).collect();
+    let priorities = values.iter().map(|x| (x * 7919) % 1021).collect();
+    (values, priorities)
+}
+
+fn run(pq: &PriorityQueueHandle<&System>) -> (Duration, Duration) {
+    let (values, priorities) = get_input();
+
+    pq.reset().unwrap();
+
+    let now = Instant::now();
+    for i in (0..QUEUE_CAPACITY).step_by(BATCH_SIZE) {
+        let range = i..(i + BATCH_SIZE);
+        pq.enqueue(&values[range.clone()], &priorities[range])
+            .unwrap();
+    }
+    let enqueue_time = now.elapsed();
+
+    let mut binary_heap: BinaryHeap<cl_int> = priorities.iter().copied().collect();
+
+    let now = Instant::now();
+    let mut output = Vec::with_capacity(QUEUE_CAPACITY / BATCH_SIZE);
+    for _ in (0..QUEUE_CAPACITY).step_by(BATCH_SIZE) {
+        output.push(pq.dequeue(BATCH_SIZE).unwrap());
+    }
+    let dequeue_time = now.elapsed();
+
+    // same priorities as the host binary heap, the order inside a batch is not
+    // defined for the ordered queue (the items are dequeued in parallel)
+    for batch in output {
+        let mut batch_priorities: Vec<cl_int> =
+            batch.iter().map(|&x| priorities[x as usize]).collect();
+        batch_priorities.sort_by_key(|&x| Reverse(x));
+
+        let expected: Vec<cl_int> = (0..batch.len())
+            .map(|_| binary_heap.pop().unwrap())
+            .collect();
+        assert_eq!(batch_priorities, expected);
+    }
+
+    (enqueue_time, dequeue_time)
+}
+
+fn main() {
+    let mut queue_src = QueueSrc::new();
+    queue_src.add_pq_with_type(QUEUE_CAPACITY, PriorityQueueType::Ordered);
+    queue_src.add_pq_with_type(QUEUE_CAPACITY, PriorityQueueType::Heap);
+
+    let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+    for id in 0..2 {
+        let config = queue_src.get_pq_config_by_id(id).unwrap();
+        let pq = PriorityQueueHandle::new(config, &system);
+
+        let (enqueue_time, dequeue_time) = run(&pq);
+
+        println!(
+            "{:?} priority queue ({QUEUE_CAPACITY} items, batches of {BATCH_SIZE})",
+            config.priority_queue_type
+        );
+        println!("    enqueue {enqueue_time:?}");
+        println!("    dequeue {dequeue_time:?}");
+    }
+}
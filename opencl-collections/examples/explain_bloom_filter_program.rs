@@ -0,0 +1,29 @@
+use opencl::wrapper::system::{OpenclCommonOperation, System};
+use opencl_collections::bloom_filter::config::BloomFilterSrc;
+use opencl_collections::config::DEFAULT_DEVICE_INDEX;
+use std::time::{Duration, Instant};
+use std::{fs, thread};
+
+const SECOND_SLEEP: u64 = 5;
+
+fn main() {
+    let mut bloom_filter_src = BloomFilterSrc::new();
+    bloom_filter_src.add(8192, 4, 1).unwrap();
+    bloom_filter_src.add(65536, 6, 8).unwrap();
+    bloom_filter_src.get_bitset_src_mut().add(1024);
+
+    println!("{:#?}", bloom_filter_src);
+
+    let program_source = bloom_filter_src.build();
+    // println!("{program_source}");
+    fs::write("./tmp/bloom_filter_src.cl", &program_source).unwrap();
+
+    println!("start compile cl");
+    let now = Instant::now();
+    let system = System::new(DEFAULT_DEVICE_INDEX, &program_source).unwrap();
+    println!("system {}", system.get_id());
+    system.initialize_memory().unwrap();
+    println!("{} seg compile cl", now.elapsed().as_secs());
+
+    thread::sleep(Duration::from_secs(SECOND_SLEEP));
+}
@@ -0,0 +1,50 @@
+use opencl::wrapper::system::{OpenclCommonOperation, System};
+use opencl_collections::cache::config::CacheSrc;
+use opencl_collections::collections::config::CollectionsSrc;
+use opencl_collections::config::DEFAULT_DEVICE_INDEX;
+use opencl_collections::map::config::MapSrc;
+use opencl_collections::queue::config::QueueSrc;
+use opencl_collections::stack::config::StackSrc;
+use std::time::{Duration, Instant};
+use std::{fs, thread};
+
+const SECOND_SLEEP: u64 = 5;
+
+fn main() {
+    let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+    map_src.add(256, 32);
+    map_src.add(512, 32);
+
+    let mut cache_src = CacheSrc::new();
+    cache_src.add_mini_lru(32);
+    cache_src.add_lru(256, 256, 32);
+
+    let mut queue_src = QueueSrc::new();
+    queue_src.add_lq(32);
+    queue_src.add_pq(32);
+
+    let mut stack_src = StackSrc::new();
+    stack_src.add(256);
+
+    let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+    collections_src.add_map("map", &map_src).unwrap();
+    collections_src.add_cache("cache", &cache_src).unwrap();
+    collections_src.add_queue("jobs", &queue_src).unwrap();
+    collections_src.add_stack("workers", &stack_src).unwrap();
+
+    println!("{:#?}", collections_src.get_namespaces());
+    println!("{:#?}", collections_src.summary());
+
+    let program_source = collections_src.build().unwrap();
+    // println!("{program_source}");
+    fs::write("./tmp/collections_src.cl", &program_source).unwrap();
+
+    println!("start compile cl");
+    let now = Instant::now();
+    let system = System::new(DEFAULT_DEVICE_INDEX, &program_source).unwrap();
+    println!("system {}", system.get_id());
+    system.initialize_memory().unwrap();
+    println!("{} seg compile cl", now.elapsed().as_secs());
+
+    thread::sleep(Duration::from_secs(SECOND_SLEEP));
+}
@@ -0,0 +1,62 @@
+use opencl::wrapper::system::{OpenclCommonOperation, System};
+use opencl_collections::collections::config::CollectionsSrc;
+use opencl_collections::config::DEFAULT_DEVICE_INDEX;
+use opencl_collections::queue::config::QueueSrc;
+use opencl_collections::set::config::SetSrc;
+use opencl_collections::stack::config::StackSrc;
+use std::time::{Duration, Instant};
+use std::{fs, thread};
+
+const SECOND_SLEEP: u64 = 5;
+
+// the jobs of the stack are moved to the queue, only once each
+const USER_SRC: &str = r#"
+    kernel void move_jobs(
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        int job = -1;
+        int result = stack_pop__0(&job);
+
+        if (result >= 0 && set_insert__0(job) >= 0) {
+            result = lq_enqueue__0(job);
+        }
+
+        output[i] = result;
+    }
+    "#;
+
+fn main() {
+    let mut stack_src = StackSrc::new();
+    stack_src.add(256);
+
+    let mut queue_src = QueueSrc::new();
+    queue_src.add_lq(256);
+
+    let mut set_src = SetSrc::new();
+    set_src.add_hash_set(256);
+
+    let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+    collections_src.add_stack("jobs", &stack_src).unwrap();
+    collections_src.add_queue("pending", &queue_src).unwrap();
+    collections_src.add_set("seen", &set_src).unwrap();
+
+    collections_src.enable_device_api();
+    collections_src.add_user_src(USER_SRC);
+
+    println!("{:#?}", collections_src.summary());
+
+    let program_source = collections_src.build().unwrap();
+    // println!("{program_source}");
+    fs::write("./tmp/device_api_src.cl", &program_source).unwrap();
+
+    println!("start compile cl");
+    let now = Instant::now();
+    let system = System::new(DEFAULT_DEVICE_INDEX, &program_source).unwrap();
+    println!("system {}", system.get_id());
+    system.initialize_memory().unwrap();
+    println!("{} seg compile cl", now.elapsed().as_secs());
+
+    thread::sleep(Duration::from_secs(SECOND_SLEEP));
+}
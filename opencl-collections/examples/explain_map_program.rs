@@ -8,7 +8,7 @@ use std::{fs, thread};
 const SECOND_SLEEP: u64 = 5;
 
 fn main() {
-    let mut map_src: MapSrc<i32> = MapSrc::new(1);
+    let mut map_src: MapSrc<i32> = MapSrc::new(1).unwrap();
     map_src.add(256, 256);
     map_src.add(512, 256);
     // 50mb -> 200mb (ClType::I32)
@@ -11,6 +11,7 @@ fn main() {
     let mut stack_src = StackSrc::new();
     stack_src.add(256);
     stack_src.add(512);
+    stack_src.add_value_stack(16, 256);
 
     println!("{:#?}", stack_src);
 
@@ -0,0 +1,126 @@
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_BITSET_ID};
+
+pub const BITSET_WORD_BITS: usize = 32;
+
+pub fn get_bitset_words(bits: usize) -> usize {
+    bits.div_ceil(BITSET_WORD_BITS)
+}
+
+/// the bit i is stored in the word i / 32, mask 1 << (i % 32)
+///
+/// ```c
+/// __global uint bitset__BITSET_ID[BITSET_WORDS];
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitsetConfig {
+    pub id: usize,
+    pub bits: usize,
+    pub words: usize,
+}
+
+impl BitsetConfig {
+    pub fn new(id: usize, bits: usize) -> Self {
+        Self {
+            id,
+            bits,
+            words: get_bitset_words(bits),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BitsetSrc {
+    blocks: Vec<BitsetConfig>,
+}
+
+impl BitsetSrc {
+    pub fn new() -> Self {
+        Self { blocks: Vec::new() }
+    }
+
+    pub fn get_configs(&self) -> &Vec<BitsetConfig> {
+        &self.blocks
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn add(&mut self, bits: usize) {
+        let id = self.blocks.len();
+
+        self.blocks.push(BitsetConfig::new(id, bits));
+    }
+
+    pub fn add_many(&mut self, bits: usize, quantity: usize) {
+        for _ in 0..quantity {
+            self.add(bits);
+        }
+    }
+
+    pub fn get_config_by_id(&self, id: usize) -> OpenClResult<&BitsetConfig> {
+        match self.blocks.iter().find(|x| x.id == id) {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BITSET_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
+}
+
+impl Default for BitsetSrc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset_src_add() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(8);
+        bitset_src.add_many(64, 2);
+
+        assert_eq!(bitset_src.len(), 3);
+        let ids: Vec<usize> = bitset_src.get_configs().iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_bitset_src_get_config_by_id() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(8);
+        bitset_src.add(100);
+
+        assert_eq!(
+            bitset_src.get_config_by_id(1),
+            Ok(&BitsetConfig {
+                id: 1,
+                bits: 100,
+                words: 4,
+            })
+        );
+
+        assert_eq!(
+            bitset_src.get_config_by_id(5),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BITSET_ID
+            ))
+        );
+    }
+
+    #[test]
+    fn test_get_bitset_words() {
+        assert_eq!(get_bitset_words(1), 1);
+        assert_eq!(get_bitset_words(32), 1);
+        assert_eq!(get_bitset_words(33), 2);
+        assert_eq!(get_bitset_words(1024), 32);
+    }
+}
@@ -0,0 +1,417 @@
+use crate::bitset::config::{BitsetConfig, BITSET_WORD_BITS};
+use crate::bitset::kernel::name::{
+    get_bitset_kernel_name, BITSET_DEBUG, BITSET_RESET, CLEAR_ON_BITSET, GET_BITSET_POPCOUNT,
+    SET_ON_BITSET, TEST_ON_BITSET, WRITE_TO_BITSET,
+};
+use crate::config::DEBUG_MODE;
+use crate::error::OpenClResult;
+use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+use opencl::wrapper::system::OpenclCommonOperation;
+
+#[derive(Debug, PartialEq)]
+pub struct BitsetSnapshot {
+    pub bits: usize,
+    pub words: Vec<cl_uint>,
+}
+
+impl BitsetSnapshot {
+    pub fn new(bits: usize, words: Vec<cl_uint>) -> Self {
+        Self { bits, words }
+    }
+
+    pub fn create_empty(config: &BitsetConfig) -> Self {
+        Self::new(config.bits, vec![0; config.words])
+    }
+
+    pub fn test(&self, bit: usize) -> bool {
+        if bit >= self.bits {
+            return false;
+        }
+
+        let mask = 1 << (bit % BITSET_WORD_BITS);
+        self.words[bit / BITSET_WORD_BITS] & mask != 0
+    }
+
+    pub fn popcount(&self) -> usize {
+        self.words.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    /// index of the bits that are set
+    pub fn get_set_bits(&self) -> Vec<usize> {
+        (0..self.bits).filter(|&bit| self.test(bit)).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct BitsetHandle<T: OpenclCommonOperation> {
+    config: BitsetConfig,
+    system: T,
+}
+
+impl<T: OpenclCommonOperation> BitsetHandle<T> {
+    pub fn new(config: &BitsetConfig, system: T) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub(crate) fn get_system(&self) -> &T {
+        &self.system
+    }
+
+    pub fn debug(&self) -> OpenClResult<BitsetSnapshot> {
+        let global_work_size = self.config.words;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_bitset_kernel_name(BITSET_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_uint> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        Ok(BitsetSnapshot::new(self.config.bits, output))
+    }
+
+    pub fn print(&self) -> OpenClResult<BitsetSnapshot> {
+        let sn = self.debug()?;
+        println!("{sn:?}");
+        Ok(sn)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.words;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_bitset_kernel_name(BITSET_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_words(&self) -> OpenClResult<Vec<cl_uint>> {
+        Ok(self.debug()?.words)
+    }
+
+    /// replaces all the words of the bitset, the missing words are 0
+    pub fn write_words(&self, words: &[cl_uint]) -> OpenClResult<()> {
+        let global_work_size = self.config.words;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let mut input = words.to_vec();
+        input.resize(global_work_size, 0);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(&input)?;
+
+        let kernel_name = get_bitset_kernel_name(WRITE_TO_BITSET, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn popcount(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_bitset_kernel_name(GET_BITSET_POPCOUNT, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    fn enqueue_bits_kernel(
+        &self,
+        kernel_name: &str,
+        input: &[cl_uint],
+    ) -> OpenClResult<Vec<cl_int>> {
+        if input.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = input.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let mut kernel = self.system.create_kernel(kernel_name)?;
+
+        let bitset_id = self.get_id() as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&bitset_id)?;
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("{kernel_name} output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// previous value of each bit (0 or 1), -1 if the bit is out of range
+    pub fn set(&self, input: &[cl_uint]) -> OpenClResult<Vec<cl_int>> {
+        self.enqueue_bits_kernel(SET_ON_BITSET, input)
+    }
+
+    /// previous value of each bit (0 or 1), -1 if the bit is out of range
+    pub fn clear(&self, input: &[cl_uint]) -> OpenClResult<Vec<cl_int>> {
+        self.enqueue_bits_kernel(CLEAR_ON_BITSET, input)
+    }
+
+    /// value of each bit (0 or 1), -1 if the bit is out of range
+    pub fn test(&self, input: &[cl_uint]) -> OpenClResult<Vec<cl_int>> {
+        self.enqueue_bits_kernel(TEST_ON_BITSET, input)
+    }
+}
+
+#[cfg(test)]
+mod tests_bitset_snapshot {
+    use super::*;
+
+    #[test]
+    fn test_and_popcount() {
+        let snapshot = BitsetSnapshot::new(40, vec![0b1001, 0b1000_0001]);
+
+        assert!(snapshot.test(0));
+        assert!(!snapshot.test(1));
+        assert!(snapshot.test(3));
+        assert!(snapshot.test(32));
+        assert!(snapshot.test(39));
+        assert!(!snapshot.test(40));
+        assert_eq!(snapshot.popcount(), 4);
+        assert_eq!(snapshot.get_set_bits(), vec![0, 3, 32, 39]);
+
+        let snapshot = BitsetSnapshot::create_empty(&BitsetConfig::new(0, 40));
+        assert_eq!(snapshot.words, vec![0, 0]);
+        assert_eq!(snapshot.popcount(), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests_bitset_debug {
+    use super::*;
+    use crate::bitset::config::BitsetSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn bitset_is_empty() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(100);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let config = bitset_src.get_config_by_id(0).unwrap();
+        let bitset = BitsetHandle::new(config, system);
+
+        let snapshot = bitset.print().unwrap();
+
+        assert_eq!(snapshot, BitsetSnapshot::create_empty(config));
+        assert_eq!(bitset.popcount().unwrap(), 0);
+    }
+
+    #[test]
+    fn bitset_reset() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(100);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let config = bitset_src.get_config_by_id(0).unwrap();
+        let bitset = BitsetHandle::new(config, system);
+
+        bitset.set(&[1, 2, 99]).unwrap();
+        assert_eq!(bitset.popcount().unwrap(), 3);
+
+        bitset.reset().unwrap();
+
+        assert_eq!(
+            bitset.print().unwrap(),
+            BitsetSnapshot::create_empty(config)
+        );
+    }
+
+    #[test]
+    fn bitset_write_words() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(100);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let config = bitset_src.get_config_by_id(0).unwrap();
+        let bitset = BitsetHandle::new(config, system);
+
+        bitset.write_words(&[0b101, cl_uint::MAX]).unwrap();
+
+        assert_eq!(bitset.get_words().unwrap(), vec![0b101, cl_uint::MAX, 0, 0]);
+        assert_eq!(bitset.popcount().unwrap(), 34);
+    }
+}
+
+#[cfg(test)]
+mod tests_bitset_set_clear_test {
+    use super::*;
+    use crate::bitset::config::BitsetSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn set_and_test() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(100);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let config = bitset_src.get_config_by_id(0).unwrap();
+        let bitset = BitsetHandle::new(config, system);
+
+        let result = bitset.set(&[0, 31, 32, 99, 100]).unwrap();
+        assert_eq!(result, vec![0, 0, 0, 0, -1]);
+
+        // already set
+        let result = bitset.set(&[31]).unwrap();
+        assert_eq!(result, vec![1]);
+
+        let result = bitset.test(&[0, 1, 31, 32, 99, 100]).unwrap();
+        assert_eq!(result, vec![1, 0, 1, 1, 1, -1]);
+
+        let snapshot = bitset.print().unwrap();
+        assert_eq!(snapshot.get_set_bits(), vec![0, 31, 32, 99]);
+        assert_eq!(bitset.popcount().unwrap(), 4);
+    }
+
+    #[test]
+    fn set_the_same_word_concurrently() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(64);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let config = bitset_src.get_config_by_id(0).unwrap();
+        let bitset = BitsetHandle::new(config, system);
+
+        let input: Vec<cl_uint> = (0..64).collect();
+        let result = bitset.set(&input).unwrap();
+        assert_eq!(result, vec![0; 64]);
+
+        assert_eq!(
+            bitset.get_words().unwrap(),
+            vec![cl_uint::MAX, cl_uint::MAX]
+        );
+    }
+
+    #[test]
+    fn clear() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(100);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let config = bitset_src.get_config_by_id(0).unwrap();
+        let bitset = BitsetHandle::new(config, system);
+
+        bitset.set(&[3, 4, 5]).unwrap();
+
+        let result = bitset.clear(&[4, 6, 200]).unwrap();
+        assert_eq!(result, vec![1, 0, -1]);
+
+        let result = bitset.test(&[3, 4, 5]).unwrap();
+        assert_eq!(result, vec![1, 0, 1]);
+        assert_eq!(bitset.popcount().unwrap(), 2);
+    }
+
+    #[test]
+    fn multiple_bitsets() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(32);
+        bitset_src.add(64);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bitset_src.build()).unwrap();
+
+        let bitset_0 = BitsetHandle::new(bitset_src.get_config_by_id(0).unwrap(), &system);
+        let bitset_1 = BitsetHandle::new(bitset_src.get_config_by_id(1).unwrap(), &system);
+
+        bitset_0.set(&[1]).unwrap();
+        bitset_1.set(&[2, 40]).unwrap();
+
+        assert_eq!(bitset_0.get_words().unwrap(), vec![0b10]);
+        assert_eq!(bitset_1.get_words().unwrap(), vec![0b100, 1 << 8]);
+
+        let result = bitset_0.test(&[40]).unwrap();
+        assert_eq!(result, vec![-1]);
+    }
+}
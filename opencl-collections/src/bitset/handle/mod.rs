@@ -0,0 +1 @@
+pub mod bitset_v1;
@@ -0,0 +1,261 @@
+// the words are uint, set and clear use atomic_or and atomic_and so that work items
+// can change bits of the same word concurrently
+//
+// set, clear and test return the previous value of the bit (0 or 1),
+// -1 if the bit is out of range
+
+use crate::bitset::config::BitsetSrc;
+use crate::bitset::kernel::common_replace;
+
+const GLOBALS: &str = r#"
+    __global uint bitset__BITSET_ID[BITSET_WORDS];
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int bitset_set__BITSET_ID(uint bit) {
+        if (bit >= BITSET_BITS) {
+            return -1;
+        }
+
+        uint mask = 1u << (bit % 32);
+        uint old = atomic_or(&bitset__BITSET_ID[bit / 32], mask);
+
+        return (old & mask) != 0;
+    }
+
+    int bitset_clear__BITSET_ID(uint bit) {
+        if (bit >= BITSET_BITS) {
+            return -1;
+        }
+
+        uint mask = 1u << (bit % 32);
+        uint old = atomic_and(&bitset__BITSET_ID[bit / 32], ~mask);
+
+        return (old & mask) != 0;
+    }
+
+    int bitset_test__BITSET_ID(uint bit) {
+        if (bit >= BITSET_BITS) {
+            return -1;
+        }
+
+        uint mask = 1u << (bit % 32);
+
+        return (bitset__BITSET_ID[bit / 32] & mask) != 0;
+    }
+
+    int bitset_popcount__BITSET_ID() {
+        int count = 0;
+
+        for (int i = 0; i < BITSET_WORDS; i++) {
+            count += popcount(bitset__BITSET_ID[i]);
+        }
+
+        return count;
+    }
+    "#;
+
+// the functions are called with the bitset id, similar to the deque functions
+const ID_FUNCTIONS: &str = r#"
+    int bitset_set(uint bitset_id, uint bit) {
+        switch (bitset_id) {
+            SET_BODY_CASE
+        }
+
+        return -1;
+    }
+
+    int bitset_clear(uint bitset_id, uint bit) {
+        switch (bitset_id) {
+            CLEAR_BODY_CASE
+        }
+
+        return -1;
+    }
+
+    int bitset_test(uint bitset_id, uint bit) {
+        switch (bitset_id) {
+            TEST_BODY_CASE
+        }
+
+        return -1;
+    }
+    "#;
+
+const SET_BODY_CASE: &str = r#"
+            case BITSET_ID:
+                return bitset_set__BITSET_ID(bit);
+    "#;
+
+const CLEAR_BODY_CASE: &str = r#"
+            case BITSET_ID:
+                return bitset_clear__BITSET_ID(bit);
+    "#;
+
+const TEST_BODY_CASE: &str = r#"
+            case BITSET_ID:
+                return bitset_test__BITSET_ID(bit);
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void bitset_reset__BITSET_ID() {
+        int i = get_global_id(0);
+        bitset__BITSET_ID[i] = 0;
+    }
+
+    kernel void bitset_debug__BITSET_ID(
+        global uint* words_output
+        ) {
+        int i = get_global_id(0);
+        words_output[i] = bitset__BITSET_ID[i];
+    }
+
+    kernel void write_to_bitset__BITSET_ID(
+        global uint* words_input
+        ) {
+        int i = get_global_id(0);
+        bitset__BITSET_ID[i] = words_input[i];
+    }
+
+    kernel void get_bitset_popcount__BITSET_ID(
+        global int* output
+        ) {
+        output[0] = bitset_popcount__BITSET_ID();
+    }
+    "#;
+
+const BASIC_KERNELS: &str = r#"
+    kernel void set_on_bitset(
+        const uint bitset_id,
+        global uint* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = bitset_set(bitset_id, input[i]);
+    }
+
+    kernel void clear_on_bitset(
+        const uint bitset_id,
+        global uint* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = bitset_clear(bitset_id, input[i]);
+    }
+
+    kernel void test_on_bitset(
+        const uint bitset_id,
+        global uint* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = bitset_test(bitset_id, input[i]);
+    }
+    "#;
+
+impl BitsetSrc {
+    pub(crate) fn generate_bitset_program_source_v1(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+        let mut base_functions = String::new();
+
+        let mut fn_set_cases = String::new();
+        let mut fn_clear_cases = String::new();
+        let mut fn_test_cases = String::new();
+
+        let mut base_kernels = String::new();
+
+        for config in self.get_configs() {
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config);
+            base_functions.push_str(&template);
+
+            let template = common_replace(SET_BODY_CASE, config);
+            fn_set_cases.push_str(&template);
+
+            let template = common_replace(CLEAR_BODY_CASE, config);
+            fn_clear_cases.push_str(&template);
+
+            let template = common_replace(TEST_BODY_CASE, config);
+            fn_test_cases.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config);
+            base_kernels.push_str(&template);
+        }
+
+        let id_functions = ID_FUNCTIONS
+            .replace("SET_BODY_CASE", &fn_set_cases)
+            .replace("CLEAR_BODY_CASE", &fn_clear_cases)
+            .replace("TEST_BODY_CASE", &fn_test_cases);
+
+        format!(
+            "
+    /// *** BITSET V1 SRC *** ///
+
+    /// constants
+    // ...
+
+    /// globals
+    {globals}
+
+    /// kernels
+    {base_functions}
+
+    {id_functions}
+
+    {base_kernels}
+
+    {BASIC_KERNELS}
+
+    /// *** BITSET V1 SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(64);
+
+        let program_source = bitset_src.generate_bitset_program_source_v1();
+        println!("{program_source}");
+        assert!(program_source.contains("__global uint bitset__0[2];"));
+        assert!(program_source.contains("if (bit >= 64) {"));
+        assert!(program_source.contains("kernel void test_on_bitset("));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(8);
+        bitset_src.add(100);
+        bitset_src.add(1024);
+
+        let program_source = bitset_src.generate_bitset_program_source_v1();
+        println!("{program_source}");
+        assert!(program_source.contains("__global uint bitset__1[4];"));
+        assert!(program_source.contains("return bitset_clear__2(bit);"));
+        assert_eq!(
+            program_source.matches("kernel void set_on_bitset(").count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let bitset_src = BitsetSrc::new();
+
+        let program_source = bitset_src.generate_bitset_program_source_v1();
+        assert!(program_source.is_empty());
+    }
+}
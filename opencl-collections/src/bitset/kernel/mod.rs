@@ -0,0 +1,39 @@
+use crate::bitset::config::{BitsetConfig, BitsetSrc};
+
+pub mod bitset_v1;
+pub mod name;
+
+fn common_replace(src: &str, config: &BitsetConfig) -> String {
+    src.replace("BITSET_WORDS", &config.words.to_string())
+        .replace("BITSET_BITS", &config.bits.to_string())
+        .replace("BITSET_ID", &config.id.to_string())
+}
+
+impl BitsetSrc {
+    pub fn build(&self) -> String {
+        self.generate_bitset_program_source_v1()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut bitset_src = BitsetSrc::new();
+        bitset_src.add(64);
+
+        let program_source = bitset_src.build();
+        println!("{program_source}");
+        assert!(!program_source.is_empty());
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let bitset_src = BitsetSrc::new();
+
+        let program_source = bitset_src.build();
+        assert!(program_source.is_empty());
+    }
+}
@@ -0,0 +1,13 @@
+pub fn get_bitset_kernel_name(kernel_name: &str, id: usize) -> String {
+    kernel_name.replace("BITSET_ID", &id.to_string())
+}
+
+// bitset v1
+pub const BITSET_DEBUG: &str = "bitset_debug__BITSET_ID";
+pub const BITSET_RESET: &str = "bitset_reset__BITSET_ID";
+pub const WRITE_TO_BITSET: &str = "write_to_bitset__BITSET_ID";
+pub const GET_BITSET_POPCOUNT: &str = "get_bitset_popcount__BITSET_ID";
+
+pub const SET_ON_BITSET: &str = "set_on_bitset";
+pub const CLEAR_ON_BITSET: &str = "clear_on_bitset";
+pub const TEST_ON_BITSET: &str = "test_on_bitset";
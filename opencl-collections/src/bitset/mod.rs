@@ -0,0 +1,8 @@
+//! # Opencl Bitset
+//!
+//! ...
+//!
+
+pub mod config;
+pub mod handle;
+pub mod kernel;
@@ -0,0 +1,211 @@
+use crate::bitset::config::{BitsetConfig, BitsetSrc};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_BLOOM_FILTER_CONFIG,
+    CL_COLLECTION_INVALID_BLOOM_FILTER_ID,
+};
+
+/// every key is key_len items (padded with -1), the bits of the key are
+/// (h1 + i * h2) % bits for i in [0, hashes)
+///
+/// ```c
+/// // bitset (BLOOM_FILTER_BITSET_ID)
+/// __global uint bitset__BITSET_ID[BITSET_WORDS];
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilterConfig {
+    pub id: usize,
+    pub bits: usize,
+    pub hashes: usize,
+    pub key_len: usize,
+    /// id of the bitset in the BitsetSrc of the BloomFilterSrc
+    pub bitset_id: usize,
+}
+
+impl BloomFilterConfig {
+    pub fn new(
+        id: usize,
+        bits: usize,
+        hashes: usize,
+        key_len: usize,
+        bitset_id: usize,
+    ) -> OpenClResult<Self> {
+        // the bits of a key are % bits, and a filter without hashes contains every key
+        if bits == 0 || hashes == 0 || key_len == 0 {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BLOOM_FILTER_CONFIG,
+            ));
+        }
+
+        Ok(Self {
+            id,
+            bits,
+            hashes,
+            key_len,
+            bitset_id,
+        })
+    }
+
+    pub fn get_bitset_config(&self) -> BitsetConfig {
+        BitsetConfig::new(self.bitset_id, self.bits)
+    }
+
+    /// the filters produce the same words for the same keys
+    pub fn is_compatible(&self, other: &BloomFilterConfig) -> bool {
+        self.bits == other.bits && self.hashes == other.hashes && self.key_len == other.key_len
+    }
+}
+
+/// the bitsets of the bloom filters are added to the BitsetSrc of the BloomFilterSrc,
+/// other bitsets can be added to the same program with get_bitset_src_mut
+#[derive(Debug, Clone)]
+pub struct BloomFilterSrc {
+    blocks: Vec<BloomFilterConfig>,
+    bitset: BitsetSrc,
+}
+
+impl BloomFilterSrc {
+    pub fn new() -> Self {
+        Self {
+            blocks: Vec::new(),
+            bitset: BitsetSrc::new(),
+        }
+    }
+
+    pub fn get_configs(&self) -> &Vec<BloomFilterConfig> {
+        &self.blocks
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn get_bitset_src(&self) -> &BitsetSrc {
+        &self.bitset
+    }
+
+    pub fn get_bitset_src_mut(&mut self) -> &mut BitsetSrc {
+        &mut self.bitset
+    }
+
+    pub fn add(&mut self, bits: usize, hashes: usize, key_len: usize) -> OpenClResult<()> {
+        let id = self.blocks.len();
+        let bitset_id = self.bitset.len();
+
+        let config = BloomFilterConfig::new(id, bits, hashes, key_len, bitset_id)?;
+
+        self.bitset.add(bits);
+        self.blocks.push(config);
+
+        Ok(())
+    }
+
+    pub fn add_many(
+        &mut self,
+        bits: usize,
+        hashes: usize,
+        key_len: usize,
+        quantity: usize,
+    ) -> OpenClResult<()> {
+        for _ in 0..quantity {
+            self.add(bits, hashes, key_len)?;
+        }
+        Ok(())
+    }
+
+    pub fn get_config_by_id(&self, id: usize) -> OpenClResult<&BloomFilterConfig> {
+        match self.blocks.iter().find(|x| x.id == id) {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BLOOM_FILTER_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
+}
+
+impl Default for BloomFilterSrc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter_src_add() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(1024, 4, 1).unwrap();
+        bloom_filter_src.add_many(256, 2, 8, 2).unwrap();
+
+        assert_eq!(bloom_filter_src.len(), 3);
+        assert_eq!(bloom_filter_src.get_bitset_src().len(), 3);
+
+        let ids: Vec<usize> = bloom_filter_src
+            .get_configs()
+            .iter()
+            .map(|x| x.id)
+            .collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_bloom_filter_src_with_other_bitsets() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.get_bitset_src_mut().add(64);
+        bloom_filter_src.add(1024, 4, 1).unwrap();
+
+        let config = bloom_filter_src.get_config_by_id(0).unwrap();
+        assert_eq!(config, &BloomFilterConfig::new(0, 1024, 4, 1, 1).unwrap());
+        assert_eq!(config.get_bitset_config(), BitsetConfig::new(1, 1024));
+        assert_eq!(
+            bloom_filter_src.get_bitset_src().get_config_by_id(1),
+            Ok(&BitsetConfig::new(1, 1024))
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter_src_get_config_by_id() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(1024, 4, 1).unwrap();
+
+        assert_eq!(
+            bloom_filter_src.get_config_by_id(5),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BLOOM_FILTER_ID
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let error = || OpenclError::OpenclCollection(CL_COLLECTION_INVALID_BLOOM_FILTER_CONFIG);
+
+        assert_eq!(BloomFilterConfig::new(0, 0, 4, 1, 0), Err(error()));
+        assert_eq!(BloomFilterConfig::new(0, 1024, 0, 1, 0), Err(error()));
+        assert_eq!(BloomFilterConfig::new(0, 1024, 4, 0, 0), Err(error()));
+
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        assert_eq!(bloom_filter_src.add(0, 4, 1), Err(error()));
+        assert_eq!(bloom_filter_src.add(1024, 0, 1), Err(error()));
+        assert_eq!(bloom_filter_src.add_many(1024, 4, 0, 2), Err(error()));
+
+        // nothing is added
+        assert!(bloom_filter_src.is_empty());
+        assert!(bloom_filter_src.get_bitset_src().is_empty());
+    }
+
+    #[test]
+    fn test_is_compatible() {
+        let config = BloomFilterConfig::new(0, 1024, 4, 2, 0).unwrap();
+
+        assert!(config.is_compatible(&BloomFilterConfig::new(3, 1024, 4, 2, 5).unwrap()));
+        assert!(!config.is_compatible(&BloomFilterConfig::new(0, 1024, 3, 2, 0).unwrap()));
+        assert!(!config.is_compatible(&BloomFilterConfig::new(0, 512, 4, 2, 0).unwrap()));
+        assert!(!config.is_compatible(&BloomFilterConfig::new(0, 1024, 4, 1, 0).unwrap()));
+    }
+}
@@ -0,0 +1,289 @@
+use crate::bitset::handle::bitset_v1::{BitsetHandle, BitsetSnapshot};
+use crate::bloom_filter::config::BloomFilterConfig;
+use crate::bloom_filter::host::HostBloomFilter;
+use crate::bloom_filter::kernel::name::{
+    get_bloom_filter_kernel_name, ADD_TO_BLOOM_FILTER, MIGHT_CONTAIN_ON_BLOOM_FILTER,
+};
+use crate::config::DEBUG_MODE;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+use opencl::wrapper::system::OpenclCommonOperation;
+
+#[derive(Debug)]
+pub struct BloomFilterHandle<T: OpenclCommonOperation> {
+    config: BloomFilterConfig,
+    bitset: BitsetHandle<T>,
+}
+
+impl<T: OpenclCommonOperation> BloomFilterHandle<T> {
+    pub fn new(config: &BloomFilterConfig, system: T) -> Self {
+        Self {
+            config: config.clone(),
+            bitset: BitsetHandle::new(&config.get_bitset_config(), system),
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn get_bitset(&self) -> &BitsetHandle<T> {
+        &self.bitset
+    }
+
+    pub fn debug(&self) -> OpenClResult<BitsetSnapshot> {
+        self.bitset.debug()
+    }
+
+    pub fn print(&self) -> OpenClResult<BitsetSnapshot> {
+        self.bitset.print()
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        self.bitset.reset()
+    }
+
+    /// bits set in the filter
+    pub fn popcount(&self) -> OpenClResult<usize> {
+        self.bitset.popcount()
+    }
+
+    pub fn get_words(&self) -> OpenClResult<Vec<cl_uint>> {
+        self.bitset.get_words()
+    }
+
+    pub fn write_words(&self, words: &[cl_uint]) -> OpenClResult<()> {
+        self.bitset.write_words(words)
+    }
+
+    /// filter built on the device, to check keys on the host
+    pub fn to_host(&self) -> OpenClResult<HostBloomFilter> {
+        let words = self.get_words()?;
+        Ok(HostBloomFilter::from_words(&self.config, &words))
+    }
+
+    /// replaces the filter with a filter built on the host
+    pub fn write_host(&self, bloom_filter: &HostBloomFilter) -> OpenClResult<()> {
+        if !self.config.is_compatible(bloom_filter.get_config()) {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH,
+            ));
+        }
+
+        self.write_words(bloom_filter.get_words())
+    }
+
+    fn enqueue_keys_kernel(
+        &self,
+        kernel_name: &str,
+        keys: &[Vec<cl_int>],
+    ) -> OpenClResult<Vec<cl_int>> {
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let system = self.bitset.get_system();
+
+        let global_work_size = keys.len();
+        let local_work_size = system.first_device_check_local_work_size(global_work_size);
+
+        let mut input: Vec<cl_int> = Vec::with_capacity(global_work_size * self.config.key_len);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            input.append(&mut k);
+        }
+
+        let input_buf = system.blocking_prepare_input_buffer(&input)?;
+        let output_buf = system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_bloom_filter_kernel_name(kernel_name, self.get_id());
+        let mut kernel = system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output = system.blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("{kernel_name} output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// 1 if at least one bit of the key was not set, 0 otherwise
+    /// (the keys repeated in the same call can all return 1)
+    pub fn add(&self, keys: &[Vec<cl_int>]) -> OpenClResult<Vec<cl_int>> {
+        self.enqueue_keys_kernel(ADD_TO_BLOOM_FILTER, keys)
+    }
+
+    /// false if the key was never added, true if it might have been added
+    pub fn might_contain(&self, keys: &[Vec<cl_int>]) -> OpenClResult<Vec<bool>> {
+        let output = self.enqueue_keys_kernel(MIGHT_CONTAIN_ON_BLOOM_FILTER, keys)?;
+        Ok(output.into_iter().map(|x| x == 1).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests_bloom_filter_add {
+    use super::*;
+    use crate::bloom_filter::config::BloomFilterSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn bloom_filter_is_empty() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(1024, 4, 1).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bloom_filter_src.build()).unwrap();
+
+        let config = bloom_filter_src.get_config_by_id(0).unwrap();
+        let bloom_filter = BloomFilterHandle::new(config, system);
+        bloom_filter.reset().unwrap();
+
+        assert_eq!(bloom_filter.popcount().unwrap(), 0);
+        assert_eq!(
+            bloom_filter.might_contain(&[vec![1], vec![2]]).unwrap(),
+            vec![false, false]
+        );
+    }
+
+    #[test]
+    fn add_and_might_contain() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(1024, 4, 2).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bloom_filter_src.build()).unwrap();
+
+        let config = bloom_filter_src.get_config_by_id(0).unwrap();
+        let bloom_filter = BloomFilterHandle::new(config, system);
+        bloom_filter.reset().unwrap();
+
+        let keys: Vec<Vec<cl_int>> = (0..128).map(|x| vec![x, x * 2]).collect();
+
+        bloom_filter.add(&keys).unwrap();
+
+        // no false negatives
+        let result = bloom_filter.might_contain(&keys).unwrap();
+        assert!(result.into_iter().all(|x| x));
+
+        let result = bloom_filter.add(&keys).unwrap();
+        assert_eq!(result, vec![0; 128]);
+
+        bloom_filter.reset().unwrap();
+        assert_eq!(bloom_filter.popcount().unwrap(), 0);
+    }
+
+    #[test]
+    fn multiple_bloom_filters() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.get_bitset_src_mut().add(64);
+        bloom_filter_src.add(512, 3, 1).unwrap();
+        bloom_filter_src.add(512, 3, 1).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bloom_filter_src.build()).unwrap();
+
+        let bloom_filter_0 =
+            BloomFilterHandle::new(bloom_filter_src.get_config_by_id(0).unwrap(), &system);
+        let bloom_filter_1 =
+            BloomFilterHandle::new(bloom_filter_src.get_config_by_id(1).unwrap(), &system);
+        bloom_filter_0.reset().unwrap();
+        bloom_filter_1.reset().unwrap();
+
+        bloom_filter_0.add(&[vec![10]]).unwrap();
+
+        assert_eq!(
+            bloom_filter_0.might_contain(&[vec![10]]).unwrap(),
+            vec![true]
+        );
+        assert_eq!(
+            bloom_filter_1.might_contain(&[vec![10]]).unwrap(),
+            vec![false]
+        );
+        assert_eq!(bloom_filter_1.popcount().unwrap(), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests_bloom_filter_host {
+    use super::*;
+    use crate::bloom_filter::config::BloomFilterSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    fn create_bloom_filter(
+        bits: usize,
+        hashes: usize,
+        key_len: usize,
+    ) -> BloomFilterHandle<System> {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(bits, hashes, key_len).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &bloom_filter_src.build()).unwrap();
+
+        let config = bloom_filter_src.get_config_by_id(0).unwrap();
+        let bloom_filter = BloomFilterHandle::new(config, system);
+        bloom_filter.reset().unwrap();
+        bloom_filter
+    }
+
+    #[test]
+    fn device_words_are_equal_to_host_words() {
+        let bloom_filter = create_bloom_filter(1000, 5, 3);
+
+        let keys: Vec<Vec<cl_int>> = (0..256).map(|x| vec![x, -x, x * 7]).collect();
+        bloom_filter.add(&keys).unwrap();
+
+        let mut host_bloom_filter = HostBloomFilter::new(&bloom_filter.config);
+        host_bloom_filter.add(&keys);
+
+        assert_eq!(
+            &bloom_filter.get_words().unwrap(),
+            host_bloom_filter.get_words()
+        );
+        assert_eq!(bloom_filter.to_host().unwrap(), host_bloom_filter);
+    }
+
+    #[test]
+    fn host_filter_checked_on_the_device() {
+        let bloom_filter = create_bloom_filter(1024, 4, 1);
+
+        let mut host_bloom_filter = HostBloomFilter::new(&bloom_filter.config);
+        host_bloom_filter.add(&[vec![1], vec![2], vec![3]]);
+
+        bloom_filter.write_host(&host_bloom_filter).unwrap();
+
+        let keys: Vec<Vec<cl_int>> = (0..100).map(|x| vec![x]).collect();
+        assert_eq!(
+            bloom_filter.might_contain(&keys).unwrap(),
+            host_bloom_filter.might_contain(&keys)
+        );
+    }
+
+    #[test]
+    fn write_host_with_other_config() {
+        let bloom_filter = create_bloom_filter(1024, 4, 1);
+
+        let host_bloom_filter =
+            HostBloomFilter::new(&BloomFilterConfig::new(0, 1024, 3, 1, 0).unwrap());
+
+        assert_eq!(
+            bloom_filter.write_host(&host_bloom_filter),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH
+            ))
+        );
+    }
+}
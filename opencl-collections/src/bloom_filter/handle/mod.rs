@@ -0,0 +1 @@
+pub mod bloom_filter_v1;
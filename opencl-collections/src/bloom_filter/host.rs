@@ -0,0 +1,248 @@
+//! host equivalent of the bloom filter kernels, the words are bit-identical to those
+//! of the device (BloomFilterHandle::get_words and BloomFilterHandle::write_host)
+
+use crate::bitset::config::{get_bitset_words, BITSET_WORD_BITS};
+use crate::bloom_filter::config::BloomFilterConfig;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+
+pub const BLOOM_FILTER_FNV_OFFSET_BASIS: u32 = 2166136261;
+pub const BLOOM_FILTER_FNV_PRIME: u32 = 16777619;
+
+/// fnv-1a over the items of the key (bloom_filter_hash_1)
+pub fn bloom_filter_hash_1(key: &[cl_int]) -> u32 {
+    key.iter().fold(BLOOM_FILTER_FNV_OFFSET_BASIS, |h, &k| {
+        (h ^ k as u32).wrapping_mul(BLOOM_FILTER_FNV_PRIME)
+    })
+}
+
+/// murmur3 finalizer of h1, always odd (bloom_filter_hash_2)
+pub fn bloom_filter_hash_2(h1: u32) -> u32 {
+    let mut h = h1;
+
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^= h >> 16;
+
+    h | 1
+}
+
+/// bits of the key, the key is padded (or truncated) to key_len
+pub fn get_bloom_filter_bits(config: &BloomFilterConfig, key: &[cl_int]) -> Vec<usize> {
+    let key = ensure_vec_size(key, config.key_len);
+
+    let h1 = bloom_filter_hash_1(&key);
+    let h2 = bloom_filter_hash_2(h1);
+
+    (0..config.hashes as u32)
+        .map(|i| (h1.wrapping_add(i.wrapping_mul(h2)) % config.bits as u32) as usize)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostBloomFilter {
+    config: BloomFilterConfig,
+    words: Vec<cl_uint>,
+}
+
+impl HostBloomFilter {
+    pub fn new(config: &BloomFilterConfig) -> Self {
+        Self {
+            config: config.clone(),
+            words: vec![0; get_bitset_words(config.bits)],
+        }
+    }
+
+    /// the missing words are 0
+    pub fn from_words(config: &BloomFilterConfig, words: &[cl_uint]) -> Self {
+        let mut bloom_filter = Self::new(config);
+
+        for (word, &w) in bloom_filter.words.iter_mut().zip(words) {
+            *word = w;
+        }
+
+        bloom_filter
+    }
+
+    pub fn get_config(&self) -> &BloomFilterConfig {
+        &self.config
+    }
+
+    pub fn get_words(&self) -> &Vec<cl_uint> {
+        &self.words
+    }
+
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|x| *x = 0);
+    }
+
+    pub fn popcount(&self) -> usize {
+        self.words.iter().map(|x| x.count_ones() as usize).sum()
+    }
+
+    fn set_bit(&mut self, bit: usize) -> bool {
+        let mask = 1 << (bit % BITSET_WORD_BITS);
+        let word = &mut self.words[bit / BITSET_WORD_BITS];
+
+        let previous = *word & mask != 0;
+        *word |= mask;
+
+        previous
+    }
+
+    fn test_bit(&self, bit: usize) -> bool {
+        let mask = 1 << (bit % BITSET_WORD_BITS);
+        self.words[bit / BITSET_WORD_BITS] & mask != 0
+    }
+
+    /// 1 if at least one bit of the key was not set, 0 otherwise
+    pub fn add(&mut self, keys: &[Vec<cl_int>]) -> Vec<cl_int> {
+        keys.iter()
+            .map(|key| {
+                let bits = get_bloom_filter_bits(&self.config, key);
+
+                let mut added = 0;
+
+                for bit in bits {
+                    if !self.set_bit(bit) {
+                        added = 1;
+                    }
+                }
+
+                added
+            })
+            .collect()
+    }
+
+    pub fn might_contain(&self, keys: &[Vec<cl_int>]) -> Vec<bool> {
+        keys.iter()
+            .map(|key| {
+                get_bloom_filter_bits(&self.config, key)
+                    .into_iter()
+                    .all(|bit| self.test_bit(bit))
+            })
+            .collect()
+    }
+
+    /// union of the keys of both filters
+    pub fn merge(&mut self, other: &HostBloomFilter) -> OpenClResult<()> {
+        if !self.config.is_compatible(&other.config) {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH,
+            ));
+        }
+
+        for (word, w) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= w;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash() {
+        // fnv-1a of an empty key is the offset basis
+        assert_eq!(bloom_filter_hash_1(&[]), BLOOM_FILTER_FNV_OFFSET_BASIS);
+        assert_eq!(
+            bloom_filter_hash_1(&[1]),
+            (BLOOM_FILTER_FNV_OFFSET_BASIS ^ 1).wrapping_mul(BLOOM_FILTER_FNV_PRIME)
+        );
+        assert_ne!(bloom_filter_hash_1(&[1, 2]), bloom_filter_hash_1(&[2, 1]));
+
+        for h in [0, 1, 2, 100, u32::MAX] {
+            assert_eq!(bloom_filter_hash_2(h) % 2, 1);
+        }
+    }
+
+    #[test]
+    fn test_get_bits() {
+        let config = BloomFilterConfig::new(0, 100, 4, 2, 0).unwrap();
+
+        let bits = get_bloom_filter_bits(&config, &[7]);
+        assert_eq!(bits.len(), 4);
+        assert!(bits.iter().all(|&x| x < 100));
+
+        // the key is padded with -1
+        assert_eq!(bits, get_bloom_filter_bits(&config, &[7, -1]));
+        assert_eq!(bits, get_bloom_filter_bits(&config, &[7, -1, 5]));
+        assert_ne!(bits, get_bloom_filter_bits(&config, &[7, 0]));
+    }
+
+    #[test]
+    fn test_add_and_might_contain() {
+        let config = BloomFilterConfig::new(0, 1024, 4, 1, 0).unwrap();
+        let mut bloom_filter = HostBloomFilter::new(&config);
+
+        let keys: Vec<Vec<cl_int>> = (0..64).map(|x| vec![x]).collect();
+
+        let result = bloom_filter.add(&keys);
+        assert!(result.iter().all(|&x| x == 0 || x == 1));
+
+        // no false negatives
+        assert!(bloom_filter.might_contain(&keys).into_iter().all(|x| x));
+
+        let result = bloom_filter.add(&keys);
+        assert_eq!(result, vec![0; 64]);
+
+        assert!(bloom_filter.popcount() > 0);
+        assert!(bloom_filter.popcount() <= 64 * 4);
+
+        let others: Vec<Vec<cl_int>> = (1000..2000).map(|x| vec![x]).collect();
+        let false_positives = bloom_filter
+            .might_contain(&others)
+            .into_iter()
+            .filter(|&x| x)
+            .count();
+        assert!(false_positives < 50);
+
+        bloom_filter.clear();
+        assert_eq!(bloom_filter.popcount(), 0);
+        assert!(!bloom_filter.might_contain(&keys).into_iter().any(|x| x));
+    }
+
+    #[test]
+    fn test_from_words() {
+        let config = BloomFilterConfig::new(0, 100, 3, 1, 0).unwrap();
+        let mut bloom_filter = HostBloomFilter::new(&config);
+        bloom_filter.add(&[vec![1], vec![2], vec![3]]);
+
+        let copy = HostBloomFilter::from_words(&config, bloom_filter.get_words());
+        assert_eq!(copy, bloom_filter);
+
+        let copy = HostBloomFilter::from_words(&config, &[1]);
+        assert_eq!(copy.get_words(), &vec![1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let config = BloomFilterConfig::new(0, 256, 3, 1, 0).unwrap();
+
+        let mut bloom_filter_a = HostBloomFilter::new(&config);
+        bloom_filter_a.add(&[vec![1], vec![2]]);
+
+        let mut bloom_filter_b = HostBloomFilter::new(&config);
+        bloom_filter_b.add(&[vec![3]]);
+
+        bloom_filter_a.merge(&bloom_filter_b).unwrap();
+        assert_eq!(
+            bloom_filter_a.might_contain(&[vec![1], vec![2], vec![3]]),
+            vec![true, true, true]
+        );
+
+        let other = HostBloomFilter::new(&BloomFilterConfig::new(0, 256, 4, 1, 0).unwrap());
+        assert_eq!(
+            bloom_filter_a.merge(&other),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH
+            ))
+        );
+    }
+}
@@ -0,0 +1,169 @@
+// the bits of the key are (h1 + i * h2) % BLOOM_FILTER_BITS (double hashing),
+// h1 is fnv-1a over the items of the key and h2 the murmur3 finalizer of h1,
+// the uint arithmetic is the same as the wrapping arithmetic of the host (host.rs)
+
+use crate::bloom_filter::config::BloomFilterSrc;
+use crate::bloom_filter::kernel::common_replace;
+
+const HASH_FUNCTIONS: &str = r#"
+    uint bloom_filter_hash_1(int* key, int key_len) {
+        uint h = 2166136261u;
+
+        for (int j = 0; j < key_len; j++) {
+            h ^= (uint) key[j];
+            h *= 16777619u;
+        }
+
+        return h;
+    }
+
+    uint bloom_filter_hash_2(uint h1) {
+        uint h = h1;
+
+        h ^= h >> 16;
+        h *= 0x85ebca6bu;
+        h ^= h >> 13;
+        h *= 0xc2b2ae35u;
+        h ^= h >> 16;
+
+        return h | 1u;
+    }
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int bloom_filter_add__BLOOM_FILTER_ID(int* key) {
+        uint h1 = bloom_filter_hash_1(key, BLOOM_FILTER_KEY_LEN);
+        uint h2 = bloom_filter_hash_2(h1);
+
+        int added = 0;
+
+        for (uint i = 0; i < BLOOM_FILTER_HASHES; i++) {
+            uint bit = (h1 + i * h2) % BLOOM_FILTER_BITS;
+
+            if (bitset_set__BLOOM_FILTER_BITSET_ID(bit) == 0) {
+                added = 1;
+            }
+        }
+
+        return added;
+    }
+
+    int bloom_filter_might_contain__BLOOM_FILTER_ID(int* key) {
+        uint h1 = bloom_filter_hash_1(key, BLOOM_FILTER_KEY_LEN);
+        uint h2 = bloom_filter_hash_2(h1);
+
+        for (uint i = 0; i < BLOOM_FILTER_HASHES; i++) {
+            uint bit = (h1 + i * h2) % BLOOM_FILTER_BITS;
+
+            if (bitset_test__BLOOM_FILTER_BITSET_ID(bit) != 1) {
+                return 0;
+            }
+        }
+
+        return 1;
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void add_to_bloom_filter__BLOOM_FILTER_ID(
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = bloom_filter_add__BLOOM_FILTER_ID(&input[i * BLOOM_FILTER_KEY_LEN]);
+    }
+
+    kernel void might_contain_on_bloom_filter__BLOOM_FILTER_ID(
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = bloom_filter_might_contain__BLOOM_FILTER_ID(&input[i * BLOOM_FILTER_KEY_LEN]);
+    }
+    "#;
+
+impl BloomFilterSrc {
+    pub(crate) fn generate_bloom_filter_program_source_v1(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut base_functions = String::new();
+        let mut base_kernels = String::new();
+
+        for config in self.get_configs() {
+            let template = common_replace(BASE_FUNCTIONS, config);
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config);
+            base_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// *** BLOOM FILTER V1 SRC *** ///
+
+    /// constants
+    // ...
+
+    /// globals
+    // bitset
+
+    /// kernels
+    {HASH_FUNCTIONS}
+
+    {base_functions}
+
+    {base_kernels}
+
+    /// *** BLOOM FILTER V1 SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(1000, 4, 2).unwrap();
+
+        let program_source = bloom_filter_src.generate_bloom_filter_program_source_v1();
+        println!("{program_source}");
+        assert!(program_source.contains("uint bit = (h1 + i * h2) % 1000;"));
+        assert!(program_source.contains("for (uint i = 0; i < 4; i++) {"));
+        assert!(program_source.contains("if (bitset_set__0(bit) == 0) {"));
+        assert!(program_source.contains("output[i] = bloom_filter_add__0(&input[i * 2]);"));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.get_bitset_src_mut().add(64);
+        bloom_filter_src.add(1000, 4, 2).unwrap();
+        bloom_filter_src.add(256, 2, 1).unwrap();
+
+        let program_source = bloom_filter_src.generate_bloom_filter_program_source_v1();
+        println!("{program_source}");
+        assert!(program_source.contains("if (bitset_test__2(bit) != 1) {"));
+        assert!(program_source.contains("kernel void might_contain_on_bloom_filter__1("));
+        assert_eq!(
+            program_source
+                .matches("uint bloom_filter_hash_1(int* key, int key_len) {")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.get_bitset_src_mut().add(64);
+
+        let program_source = bloom_filter_src.generate_bloom_filter_program_source_v1();
+        assert!(program_source.is_empty());
+    }
+}
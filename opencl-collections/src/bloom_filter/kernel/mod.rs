@@ -0,0 +1,47 @@
+use crate::bloom_filter::config::{BloomFilterConfig, BloomFilterSrc};
+
+pub mod bloom_filter_v1;
+pub mod name;
+
+fn common_replace(src: &str, config: &BloomFilterConfig) -> String {
+    // BLOOM_FILTER_BITSET_ID before BLOOM_FILTER_BITS
+    src.replace("BLOOM_FILTER_BITSET_ID", &config.bitset_id.to_string())
+        .replace("BLOOM_FILTER_BITS", &config.bits.to_string())
+        .replace("BLOOM_FILTER_HASHES", &config.hashes.to_string())
+        .replace("BLOOM_FILTER_KEY_LEN", &config.key_len.to_string())
+        .replace("BLOOM_FILTER_ID", &config.id.to_string())
+}
+
+impl BloomFilterSrc {
+    /// the source of the bitsets is included
+    pub fn build(&self) -> String {
+        let bitset_src = self.get_bitset_src().build();
+        let bloom_filter_src = self.generate_bloom_filter_program_source_v1();
+
+        format!("{bitset_src}{bloom_filter_src}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut bloom_filter_src = BloomFilterSrc::new();
+        bloom_filter_src.add(1024, 4, 1).unwrap();
+
+        let program_source = bloom_filter_src.build();
+        println!("{program_source}");
+        assert!(program_source.contains("__global uint bitset__0[32];"));
+        assert!(program_source.contains("int bloom_filter_add__0(int* key) {"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let bloom_filter_src = BloomFilterSrc::new();
+
+        let program_source = bloom_filter_src.build();
+        assert!(program_source.is_empty());
+    }
+}
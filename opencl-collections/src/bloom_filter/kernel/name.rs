@@ -0,0 +1,7 @@
+pub fn get_bloom_filter_kernel_name(kernel_name: &str, id: usize) -> String {
+    kernel_name.replace("BLOOM_FILTER_ID", &id.to_string())
+}
+
+// bloom filter v1
+pub const ADD_TO_BLOOM_FILTER: &str = "add_to_bloom_filter__BLOOM_FILTER_ID";
+pub const MIGHT_CONTAIN_ON_BLOOM_FILTER: &str = "might_contain_on_bloom_filter__BLOOM_FILTER_ID";
@@ -0,0 +1,10 @@
+//! # Opencl Bloom Filter
+//!
+//! the bits of each bloom filter are stored in a bitset, the host filter (host.rs)
+//! uses the same hash functions so that the words are bit-identical
+//!
+
+pub mod config;
+pub mod handle;
+pub mod host;
+pub mod kernel;
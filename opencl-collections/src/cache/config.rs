@@ -1,12 +1,29 @@
 use crate::error::{
-    OpenClResult, OpenclError, CL_COLLECTION_INVALID_LRU_ID, CL_COLLECTION_INVALID_MINI_LRU_ID,
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_LFU_ID, CL_COLLECTION_INVALID_LRU_ID,
+    CL_COLLECTION_INVALID_MINI_LRU_ID, CL_COLLECTION_INVALID_TTL_CACHE_ID,
+    CL_COLLECTION_INVALID_TWO_Q_ID,
 };
+use opencl::opencl_sys::bindings::cl_long;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CacheType {
     MiniLRU,
     LRU,
-    // TTL
+    TTL,
+    LFU,
+    TwoQ,
+}
+
+/// lru cache counters (hits, misses, inserts, evictions)
+pub const LRU_STATS_LEN: usize = 4;
+
+/// the frequencies of the lfu cache are halved every (CACHE_CAPACITY * LFU_AGING_FACTOR) accesses
+pub const LFU_AGING_FACTOR: usize = 4;
+
+/// sizes of the 2q queues (kin, kout), A1in (fifo) and A1out (ghost keys),
+/// the rest of the capacity is used by Am (lru)
+pub fn get_two_q_sizes(capacity: usize) -> (usize, usize) {
+    ((capacity / 4).max(1), (capacity / 2).max(1))
 }
 
 /// ...
@@ -34,6 +51,38 @@ pub enum CacheType {
 ///
 /// // array set ...
 /// ```
+///
+/// ttl cache
+/// ```c
+/// __global int ttl_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
+/// __global int ttl_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+/// __global long ttl_expiry__CACHE_ID[CACHE_CAPACITY];
+/// __global int ttl_entries__CACHE_ID[CACHE_CAPACITY];
+/// ```
+///
+/// lfu cache
+/// ```c
+/// __global int lfu_tick__CACHE_ID = 0;
+/// __global int lfu_accesses__CACHE_ID = 0;
+///
+/// __global int lfu_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
+/// __global int lfu_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+/// __global int lfu_frequencies__CACHE_ID[CACHE_CAPACITY];
+/// __global int lfu_last_used__CACHE_ID[CACHE_CAPACITY];
+/// ```
+///
+/// 2q cache
+/// ```c
+/// __global int two_q_tick__CACHE_ID = 0;
+///
+/// __global int two_q_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
+/// __global int two_q_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+/// __global int two_q_queues__CACHE_ID[CACHE_CAPACITY];
+/// __global int two_q_priorities__CACHE_ID[CACHE_CAPACITY];
+///
+/// __global int two_q_ghost_keys__CACHE_ID[TWO_Q_KOUT][KEY_LEN];
+/// __global int two_q_ghost_priorities__CACHE_ID[TWO_Q_KOUT];
+/// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct CacheConfig {
     pub id: usize,
@@ -41,6 +90,8 @@ pub struct CacheConfig {
     pub value_len: usize,
     pub capacity: usize,
     pub cache_type: CacheType,
+    /// ttl used when the entries are written without ttl (only CacheType::TTL)
+    pub default_ttl: cl_long,
 }
 
 impl CacheConfig {
@@ -57,6 +108,20 @@ impl CacheConfig {
             value_len,
             capacity,
             cache_type,
+            default_ttl: 0,
+        }
+    }
+
+    pub fn with_ttl(
+        id: usize,
+        key_len: usize,
+        value_len: usize,
+        capacity: usize,
+        default_ttl: cl_long,
+    ) -> Self {
+        Self {
+            default_ttl,
+            ..Self::new(id, key_len, value_len, capacity, CacheType::TTL)
         }
     }
 }
@@ -71,6 +136,14 @@ impl CacheSrc {
         Self { blocks: Vec::new() }
     }
 
+    pub fn get_configs(&self) -> &Vec<CacheConfig> {
+        &self.blocks
+    }
+
+    pub(crate) fn get_configs_mut(&mut self) -> &mut Vec<CacheConfig> {
+        &mut self.blocks
+    }
+
     pub fn get_configs_by_type(&self, cache_type: CacheType) -> Vec<&CacheConfig> {
         self.blocks
             .iter()
@@ -86,6 +159,13 @@ impl CacheSrc {
         self.blocks.is_empty()
     }
 
+    fn get_next_id(&self, cache_type: CacheType) -> usize {
+        self.blocks
+            .iter()
+            .filter(|x| x.cache_type == cache_type)
+            .count()
+    }
+
     pub fn add(
         &mut self,
         key_len: usize,
@@ -93,18 +173,25 @@ impl CacheSrc {
         capacity: usize,
         cache_type: CacheType,
     ) {
-        // get_cache_id
-        let id = self
-            .blocks
-            .iter()
-            .filter(|x| x.cache_type == cache_type)
-            .count();
+        let id = self.get_next_id(cache_type);
 
         self.blocks.push(CacheConfig::new(
             id, key_len, value_len, capacity, cache_type,
         ));
     }
 
+    /// Add a copy of the config (of another src) with a new id, the new id is returned
+    pub(crate) fn add_config(&mut self, config: &CacheConfig) -> usize {
+        let id = self.get_next_id(config.cache_type);
+
+        self.blocks.push(CacheConfig {
+            id,
+            ..config.clone()
+        });
+
+        id
+    }
+
     pub fn add_mini_lru(&mut self, capacity: usize) {
         self.add(1, 1, capacity, CacheType::MiniLRU)
     }
@@ -113,6 +200,32 @@ impl CacheSrc {
         self.add(key_len, value_len, capacity, CacheType::LRU)
     }
 
+    pub fn add_lfu(&mut self, key_len: usize, value_len: usize, capacity: usize) {
+        self.add(key_len, value_len, capacity, CacheType::LFU)
+    }
+
+    pub fn add_two_q(&mut self, key_len: usize, value_len: usize, capacity: usize) {
+        self.add(key_len, value_len, capacity, CacheType::TwoQ)
+    }
+
+    pub fn add_ttl(
+        &mut self,
+        key_len: usize,
+        value_len: usize,
+        capacity: usize,
+        default_ttl: cl_long,
+    ) {
+        let id = self.get_next_id(CacheType::TTL);
+
+        self.blocks.push(CacheConfig::with_ttl(
+            id,
+            key_len,
+            value_len,
+            capacity,
+            default_ttl,
+        ));
+    }
+
     pub fn get_config_by_id(&self, id: usize, cache_type: CacheType) -> Option<&CacheConfig> {
         self.blocks
             .iter()
@@ -134,6 +247,31 @@ impl CacheSrc {
             Some(c) => Ok(c),
         }
     }
+
+    pub fn get_ttl_by_id(&self, id: usize) -> OpenClResult<&CacheConfig> {
+        match self.get_config_by_id(id, CacheType::TTL) {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TTL_CACHE_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
+
+    pub fn get_lfu_by_id(&self, id: usize) -> OpenClResult<&CacheConfig> {
+        match self.get_config_by_id(id, CacheType::LFU) {
+            None => Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_LFU_ID)),
+            Some(c) => Ok(c),
+        }
+    }
+
+    pub fn get_two_q_by_id(&self, id: usize) -> OpenClResult<&CacheConfig> {
+        match self.get_config_by_id(id, CacheType::TwoQ) {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TWO_Q_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
 }
 
 impl Default for CacheSrc {
@@ -191,4 +329,62 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_cache_src_add_ttl() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 16, 32);
+        cache_src.add_ttl(16, 32, 64, 1000);
+        cache_src.add_ttl(8, 8, 8, 50);
+
+        assert_eq!(
+            cache_src.get_ttl_by_id(1),
+            Ok(&CacheConfig::with_ttl(1, 8, 8, 8, 50))
+        );
+        assert_eq!(cache_src.get_ttl_by_id(0).unwrap().default_ttl, 1000);
+        assert_eq!(cache_src.get_lru_by_id(0).unwrap().default_ttl, 0);
+
+        assert_eq!(
+            cache_src.get_ttl_by_id(2),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TTL_CACHE_ID
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cache_src_add_lfu_and_two_q() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 16, 32);
+        cache_src.add_lfu(16, 32, 64);
+        cache_src.add_two_q(8, 8, 16);
+        cache_src.add_lfu(8, 8, 8);
+
+        assert_eq!(
+            cache_src.get_lfu_by_id(1),
+            Ok(&CacheConfig::new(1, 8, 8, 8, CacheType::LFU))
+        );
+        assert_eq!(
+            cache_src.get_two_q_by_id(0),
+            Ok(&CacheConfig::new(0, 8, 8, 16, CacheType::TwoQ))
+        );
+
+        assert_eq!(
+            cache_src.get_two_q_by_id(1),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TWO_Q_ID
+            ))
+        );
+        assert_eq!(
+            cache_src.get_lfu_by_id(2),
+            Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_LFU_ID))
+        );
+    }
+
+    #[test]
+    fn test_get_two_q_sizes() {
+        assert_eq!(get_two_q_sizes(1), (1, 1));
+        assert_eq!(get_two_q_sizes(8), (2, 4));
+        assert_eq!(get_two_q_sizes(256), (64, 128));
+    }
 }
@@ -0,0 +1,529 @@
+use crate::cache::config::CacheConfig;
+use crate::cache::handle::lru::{CacheKeys, CacheValues};
+use crate::cache::handle::CacheIndices;
+use crate::cache::kernel::name::{
+    get_cache_kernel_name, LFU_CACHE_DEBUG, LFU_CACHE_GET, LFU_CACHE_PUT, LFU_CACHE_RESET,
+};
+use crate::config::{ClTypeDefault, DEBUG_MODE};
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_LFU_INPUT_LEN};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::OpenclCommonOperation;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LFUCacheSnapshot {
+    pub tick: cl_int,
+    pub accesses: cl_int,
+    pub keys: Vec<Vec<cl_int>>,
+    pub values: Vec<Vec<cl_int>>,
+    pub frequencies: Vec<cl_int>,
+    pub last_used: Vec<cl_int>,
+}
+
+impl LFUCacheSnapshot {
+    pub fn new(
+        tick: cl_int,
+        accesses: cl_int,
+        keys: Vec<Vec<cl_int>>,
+        values: Vec<Vec<cl_int>>,
+        frequencies: Vec<cl_int>,
+        last_used: Vec<cl_int>,
+    ) -> Self {
+        Self {
+            tick,
+            accesses,
+            keys,
+            values,
+            frequencies,
+            last_used,
+        }
+    }
+
+    pub fn create_empty(key_len: usize, value_len: usize, capacity: usize) -> Self {
+        Self::new(
+            0,
+            0,
+            vec![vec![i32::cl_default(); key_len]; capacity],
+            vec![vec![i32::cl_default(); value_len]; capacity],
+            vec![0; capacity],
+            vec![0; capacity],
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.frequencies.iter().filter(|&&x| x > 0).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn has_entry(&self, key: &Vec<cl_int>, value: &Vec<cl_int>) -> bool {
+        match self
+            .keys
+            .iter()
+            .enumerate()
+            .position(|(i, x)| self.frequencies[i] > 0 && x == key)
+        {
+            None => false,
+            Some(index) => &self.values[index] == value,
+        }
+    }
+
+    /// keys sorted by frequency (higher first), the most recently used first on ties
+    pub fn get_sorted_keys(&self) -> Vec<KeyFrequency> {
+        let mut keys: Vec<(KeyFrequency, cl_int)> = self
+            .keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.frequencies[*i] > 0)
+            .map(|(i, x)| {
+                (
+                    KeyFrequency {
+                        frequency: self.frequencies[i],
+                        key: x.clone(),
+                    },
+                    self.last_used[i],
+                )
+            })
+            .collect();
+
+        keys.sort_by(|(a, a_last_used), (b, b_last_used)| {
+            b.frequency
+                .cmp(&a.frequency)
+                .then(b_last_used.cmp(a_last_used))
+        });
+
+        keys.into_iter().map(|(x, _)| x).collect()
+    }
+
+    // debug
+    pub fn print_all_entries(&self) {
+        for (i, key) in self.keys.iter().enumerate() {
+            println!("i: {} - key:       {:?}", i, key);
+            println!("     - value:     {:?}", self.values[i]);
+            println!("     - frequency: {:?}", self.frequencies[i]);
+            println!("     - last_used: {:?}", self.last_used[i]);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyFrequency {
+    pub frequency: cl_int,
+    pub key: Vec<cl_int>,
+}
+
+/// Least frequently used, the frequencies are halved periodically (aging) so that
+/// entries that were popular in the past can be evicted.
+/// The inputs of a call are processed in order, see `LFUCacheModel` for the host equivalent
+#[derive(Debug)]
+pub struct LFUCacheHandle<T: OpenclCommonOperation> {
+    config: CacheConfig,
+    system: Arc<T>,
+}
+
+impl<T: OpenclCommonOperation> LFUCacheHandle<T> {
+    pub fn new(config: &CacheConfig, system: Arc<T>) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn debug(&self) -> OpenClResult<LFUCacheSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+        let values_output_capacity = self.config.value_len * global_work_size;
+        let meta_output_capacity = 2;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+
+        let frequencies_output_buf = self.system.create_output_buffer(global_work_size)?;
+        let last_used_output_buf = self.system.create_output_buffer(global_work_size)?;
+        let meta_output_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_cache_kernel_name(LFU_CACHE_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&frequencies_output_buf.get_cl_mem())?;
+            kernel.set_arg(&last_used_output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let frequencies_output = self.system.blocking_enqueue_read_buffer(
+            global_work_size,
+            &frequencies_output_buf,
+            &[],
+        )?;
+
+        let last_used_output = self.system.blocking_enqueue_read_buffer(
+            global_work_size,
+            &last_used_output_buf,
+            &[],
+        )?;
+
+        let meta_output = self.system.blocking_enqueue_read_buffer(
+            meta_output_capacity,
+            &meta_output_buf,
+            &[],
+        )?;
+
+        let keys: Vec<Vec<_>> = keys_output
+            .chunks(self.config.key_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        let values: Vec<Vec<_>> = values_output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok(LFUCacheSnapshot::new(
+            meta_output[0],
+            meta_output[1],
+            keys,
+            values,
+            frequencies_output,
+            last_used_output,
+        ))
+    }
+
+    pub fn print(&self) -> OpenClResult<LFUCacheSnapshot> {
+        let cs = self.debug()?;
+        println!(
+            "
+LFUCacheSnapshot (
+   tick:        {},
+   accesses:    {},
+   frequencies: {:?}
+   last_used:   {:?}
+)
+        ",
+            cs.tick, cs.accesses, cs.frequencies, cs.last_used
+        );
+
+        cs.print_all_entries();
+
+        Ok(cs)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_cache_kernel_name(LFU_CACHE_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        };
+
+        Ok(())
+    }
+
+    pub fn initialize(&self) -> OpenClResult<()> {
+        self.reset()
+    }
+
+    pub fn put(&self, keys: &CacheKeys, values: &CacheValues) -> OpenClResult<CacheIndices> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LFU_INPUT_LEN,
+            ));
+        }
+
+        let total = keys.len();
+
+        let keys_input_capacity = self.config.key_len * total;
+        let values_input_capacity = self.config.value_len * total;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let mut values_input: Vec<_> = Vec::with_capacity(values_input_capacity);
+
+        for b in values {
+            let mut v = ensure_vec_size(b, self.config.value_len);
+            values_input.append(&mut v);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_input_buf = self.system.blocking_prepare_input_buffer(&values_input)?;
+
+        let indices_output_buf = self.system.create_output_buffer(total)?;
+
+        let total_input = total as cl_int;
+
+        let kernel_name = get_cache_kernel_name(LFU_CACHE_PUT, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&total_input)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let indices_output =
+            self.system
+                .blocking_enqueue_read_buffer(total, &indices_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("indices_output {indices_output:?}");
+        }
+
+        Ok(indices_output)
+    }
+
+    pub fn get(&self, keys: &CacheKeys) -> OpenClResult<(CacheValues, CacheIndices)> {
+        let total = keys.len();
+
+        let keys_input_capacity = self.config.key_len * total;
+        let values_output_capacity = self.config.value_len * total;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+
+        let indices_output_buf = self.system.create_output_buffer(total)?;
+
+        let total_input = total as cl_int;
+
+        let kernel_name = get_cache_kernel_name(LFU_CACHE_GET, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&total_input)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let indices_output =
+            self.system
+                .blocking_enqueue_read_buffer(total, &indices_output_buf, &[])?;
+
+        let values: Vec<Vec<_>> = values_output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok((values, indices_output))
+    }
+
+    /// keys sorted by frequency (higher first)
+    pub fn keys(&self) -> OpenClResult<Vec<KeyFrequency>> {
+        let cs = self.debug()?;
+        Ok(cs.get_sorted_keys())
+    }
+}
+
+#[cfg(test)]
+mod tests_lfu_cache_reset {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(16, 32, 8);
+        cache_src.add_lfu(64, 64, 16);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lfu_by_id(0).unwrap();
+        let cache = LFUCacheHandle::new(config, system);
+
+        let r = cache.reset();
+        assert!(r.is_ok());
+
+        assert_eq!(
+            cache.print().unwrap(),
+            LFUCacheSnapshot::create_empty(16, 32, 8)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_lfu_cache_put {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::cache::model::LFUCacheModel;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let cache_capacity = 32;
+        let cache_key_len = 64;
+        let cache_value_len = 64;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lfu_by_id(0).unwrap();
+        let cache = LFUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+
+        let indices = cache.put(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        let expected: Vec<cl_int> = (0..cache_capacity as cl_int).collect();
+        assert_eq!(indices, expected);
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs.len(), cache_capacity);
+        assert_eq!(cs.frequencies, vec![1; cache_capacity]);
+
+        let mut model = LFUCacheModel::new(config);
+        model.put(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        assert_eq!(&cs, model.snapshot());
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(16, 16, 8);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lfu_by_id(0).unwrap();
+        let cache = LFUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1; 16]; 4];
+        let values = vec![vec![1; 16]; 3];
+
+        assert_eq!(
+            cache.put(&keys, &values),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LFU_INPUT_LEN
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_lfu_cache_model {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::cache::model::LFUCacheModel;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::skewed_keys;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn skewed_workload() {
+        let cache_capacity = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(8, 8, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lfu_by_id(0).unwrap();
+        let cache = LFUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let mut model = LFUCacheModel::new(config);
+
+        for seed in 0..8 {
+            let keys: CacheKeys = skewed_keys(64, 64, seed)
+                .into_iter()
+                .map(|x| vec![x; 8])
+                .collect();
+
+            if seed % 2 == 0 {
+                let values: CacheValues = keys.iter().map(|x| vec![x[0] + 100; 8]).collect();
+
+                let indices = cache.put(&keys, &values).unwrap();
+                assert_eq!(indices, model.put(&keys, &values).unwrap());
+            } else {
+                let result = cache.get(&keys).unwrap();
+                assert_eq!(result, model.get(&keys));
+            }
+
+            assert_eq!(&cache.debug().unwrap(), model.snapshot());
+        }
+
+        let keys: Vec<_> = cache.keys().unwrap();
+        assert_eq!(keys, model.snapshot().get_sorted_keys());
+    }
+}
@@ -1,15 +1,20 @@
-use crate::cache::config::CacheConfig;
-use crate::cache::handle::{CacheIndices, LruSummary};
+use crate::cache::config::{CacheConfig, LRU_STATS_LEN};
+use crate::cache::handle::{CacheIndices, LruSummary, PriorityPredicate};
 use crate::cache::kernel::name::{
     get_cache_kernel_name, LRU_CACHE_ARRAY_SET_RESET, LRU_CACHE_DEBUG, LRU_CACHE_DEBUG_SORT,
-    LRU_CACHE_GET_KEYS, LRU_CACHE_GET_SORTED_KEYS, LRU_CACHE_PUT, LRU_CACHE_RESET, LRU_CACHE_SORT,
-    READ_ON_LRU_CACHE, WRITE_IN_LRU_CACHE,
+    LRU_CACHE_GET_KEYS, LRU_CACHE_GET_SORTED_KEYS, LRU_CACHE_GET_STATS, LRU_CACHE_PUT,
+    LRU_CACHE_REMOVE, LRU_CACHE_REMOVE_WHERE, LRU_CACHE_RESET, LRU_CACHE_RESET_STATS,
+    LRU_CACHE_SORT, READ_ON_LRU_CACHE, WRITE_IN_LRU_CACHE,
 };
 use crate::config::{ClTypeDefault, DEBUG_MODE};
-use crate::error::OpenClResult;
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_LRU_INPUT_LEN,
+    CL_COLLECTION_LRU_INPUT_TOO_LONG,
+};
 use crate::set::handle::array_set_v2::ArraySetSnapshot;
-use crate::utils::ensure_vec_size;
+use crate::utils::{ensure_vec_size, limit_vec_size};
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+use opencl::wrapper::memory::Buffer;
 use opencl::wrapper::system::OpenclCommonOperation;
 use std::sync::Arc;
 
@@ -21,6 +26,8 @@ pub struct LRUCacheSnapshot {
     pub values: Vec<Vec<cl_int>>,
     pub priorities: Vec<cl_int>,
     pub array_set: ArraySetSnapshot,
+    pub key_sizes: Vec<cl_int>,
+    pub value_sizes: Vec<cl_int>,
 }
 
 impl LRUCacheSnapshot {
@@ -32,6 +39,8 @@ impl LRUCacheSnapshot {
         priorities: Vec<cl_int>,
         array_set: ArraySetSnapshot,
     ) -> Self {
+        let key_sizes = vec![0; priorities.len()];
+        let value_sizes = vec![0; priorities.len()];
         Self {
             last_priority,
             top,
@@ -39,6 +48,8 @@ impl LRUCacheSnapshot {
             values,
             priorities,
             array_set,
+            key_sizes,
+            value_sizes,
         }
     }
 
@@ -53,6 +64,16 @@ impl LRUCacheSnapshot {
         )
     }
 
+    /// key without padding
+    pub fn get_key(&self, index: usize) -> Vec<cl_int> {
+        limit_vec_size(&self.keys[index], self.key_sizes[index] as usize).to_vec()
+    }
+
+    /// value without padding
+    pub fn get_value(&self, index: usize) -> Vec<cl_int> {
+        limit_vec_size(&self.values[index], self.value_sizes[index] as usize).to_vec()
+    }
+
     pub fn get_lower_priority_keys(&mut self, take: usize) -> Vec<Vec<cl_int>> {
         let mut keys: Vec<Vec<cl_int>> = vec![];
 
@@ -156,6 +177,65 @@ pub struct SortEntry {
 pub type CacheKeys = Vec<Vec<cl_int>>;
 pub type CacheValues = Vec<Vec<cl_int>>;
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LRUCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub inserts: usize,
+    pub evictions: usize,
+}
+
+impl LRUCacheStats {
+    pub fn new(hits: usize, misses: usize, inserts: usize, evictions: usize) -> Self {
+        Self {
+            hits,
+            misses,
+            inserts,
+            evictions,
+        }
+    }
+
+    pub fn from_output(output: &[cl_int]) -> Self {
+        Self::new(
+            output[0] as usize,
+            output[1] as usize,
+            output[2] as usize,
+            output[3] as usize,
+        )
+    }
+
+    pub fn lookups(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups() == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / self.lookups() as f64
+    }
+}
+
+/// entry removed from the cache to make room for the input at `input_index`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvictedEntry {
+    pub input_index: usize,
+    pub key: Vec<cl_int>,
+    pub value: Vec<cl_int>,
+}
+
+pub type CacheEvictions = Vec<EvictedEntry>;
+
+/// real lengths of the inputs, longer inputs than the cache key_len / value_len are rejected
+fn get_input_sizes(buf: &[Vec<cl_int>], max_len: usize) -> OpenClResult<Vec<cl_int>> {
+    if buf.iter().any(|x| x.len() > max_len) {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_LRU_INPUT_TOO_LONG,
+        ));
+    }
+    Ok(buf.iter().map(|x| x.len() as cl_int).collect())
+}
+
 impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
     pub fn new(config: &CacheConfig, system: Arc<T>) -> Self {
         Self {
@@ -168,6 +248,10 @@ impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
         self.config.id
     }
 
+    pub fn get_config(&self) -> &CacheConfig {
+        &self.config
+    }
+
     pub fn debug(&self) -> OpenClResult<LRUCacheSnapshot> {
         let global_work_size = self.config.capacity;
         let local_work_size = self
@@ -181,6 +265,7 @@ impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
         let meta_output_capacity = 2;
 
         let set_items_output_capacity = global_work_size * 2;
+        let sizes_output_capacity = global_work_size * 2;
 
         let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
         let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
@@ -193,6 +278,7 @@ impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
         let set_items_output_buf = self
             .system
             .create_output_buffer(set_items_output_capacity)?;
+        let sizes_output_buf = self.system.create_output_buffer(sizes_output_capacity)?;
 
         let kernel_name = get_cache_kernel_name(LRU_CACHE_DEBUG, self.get_id());
         let mut kernel = self.system.create_kernel(&kernel_name)?;
@@ -203,6 +289,7 @@ impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
             kernel.set_arg(&priorities_output_buf.get_cl_mem())?;
             kernel.set_arg(&meta_output_buf.get_cl_mem())?;
             kernel.set_arg(&set_items_output_buf.get_cl_mem())?;
+            kernel.set_arg(&sizes_output_buf.get_cl_mem())?;
 
             kernel.enqueue_nd_range_kernel_dim_1(
                 self.system.get_host_command_queue(),
@@ -242,6 +329,12 @@ impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
             &[],
         )?;
 
+        let sizes_output = self.system.blocking_enqueue_read_buffer(
+            sizes_output_capacity,
+            &sizes_output_buf,
+            &[],
+        )?;
+
         let keys: Vec<Vec<_>> = keys_output
             .chunks(self.config.key_len)
             .map(|x| x.to_vec())
@@ -264,6 +357,8 @@ impl<T: OpenclCommonOperation> LRUCacheHandle<T> {
             values,
             priorities: priorities_output,
             array_set,
+            key_sizes: sizes_output[0..self.config.capacity].to_vec(),
+            value_sizes: sizes_output[self.config.capacity..].to_vec(),
         })
     }
 
@@ -322,10 +417,33 @@ LRUCacheSnapshot (
     }
 
     pub fn insert(&self, keys: &CacheKeys, values: &CacheValues) -> OpenClResult<Vec<cl_int>> {
+        let (indices, _) = self.insert_entries(keys, values, false)?;
+        Ok(indices)
+    }
+
+    pub fn insert_with_evictions(
+        &self,
+        keys: &CacheKeys,
+        values: &CacheValues,
+    ) -> OpenClResult<(CacheIndices, CacheEvictions)> {
+        self.insert_entries(keys, values, true)
+    }
+
+    fn insert_entries(
+        &self,
+        keys: &CacheKeys,
+        values: &CacheValues,
+        log_evictions: bool,
+    ) -> OpenClResult<(CacheIndices, CacheEvictions)> {
         if keys.len() != values.len() {
-            panic!("error handle keys & values len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LRU_INPUT_LEN,
+            ));
         }
 
+        let key_sizes_input = get_input_sizes(keys, self.config.key_len)?;
+        let value_sizes_input = get_input_sizes(values, self.config.value_len)?;
+
         let global_work_size = keys.len();
         let local_work_size = self
             .system
@@ -353,10 +471,21 @@ LRUCacheSnapshot (
         let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
         let values_input_buf = self.system.blocking_prepare_input_buffer(&values_input)?;
 
+        let key_sizes_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&key_sizes_input)?;
+        let value_sizes_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&value_sizes_input)?;
+
         let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
 
+        let (evicted_keys_output_buf, evicted_values_output_buf, evicted_sizes_output_buf) =
+            self.create_eviction_buffers(global_work_size, log_evictions)?;
+
         let key_len = self.config.key_len as cl_uint;
         let buf_len = self.config.value_len as cl_uint;
+        let log = log_evictions as cl_int;
 
         let kernel_name = get_cache_kernel_name(WRITE_IN_LRU_CACHE, self.get_id());
         let mut kernel = self.system.create_kernel(&kernel_name)?;
@@ -364,9 +493,15 @@ LRUCacheSnapshot (
         unsafe {
             kernel.set_arg(&key_len)?;
             kernel.set_arg(&buf_len)?;
+            kernel.set_arg(&log)?;
             kernel.set_arg(&keys_input_buf.get_cl_mem())?;
             kernel.set_arg(&values_input_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_input_buf.get_cl_mem())?;
+            kernel.set_arg(&value_sizes_input_buf.get_cl_mem())?;
             kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+            kernel.set_arg(&evicted_keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&evicted_values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&evicted_sizes_output_buf.get_cl_mem())?;
 
             kernel.enqueue_nd_range_kernel_dim_1(
                 self.system.get_host_command_queue(),
@@ -386,7 +521,129 @@ LRUCacheSnapshot (
             println!("indices_output {indices_output:?}");
         }
 
-        Ok(indices_output)
+        let evictions = self.read_evictions(
+            global_work_size,
+            log_evictions,
+            &evicted_keys_output_buf,
+            &evicted_values_output_buf,
+            &evicted_sizes_output_buf,
+        )?;
+
+        Ok((indices_output, evictions))
+    }
+
+    fn create_eviction_buffers(
+        &self,
+        total: usize,
+        log_evictions: bool,
+    ) -> OpenClResult<(Buffer<cl_int>, Buffer<cl_int>, Buffer<cl_int>)> {
+        // the kernels skip the log, a placeholder is enough
+        let total = if log_evictions { total } else { 1 };
+
+        let keys_buf = self
+            .system
+            .create_output_buffer(self.config.key_len * total)?;
+        let values_buf = self
+            .system
+            .create_output_buffer(self.config.value_len * total)?;
+        let sizes_buf = self.system.create_output_buffer(total * 2)?;
+
+        Ok((keys_buf, values_buf, sizes_buf))
+    }
+
+    fn read_evictions(
+        &self,
+        total: usize,
+        log_evictions: bool,
+        keys_buf: &Buffer<cl_int>,
+        values_buf: &Buffer<cl_int>,
+        sizes_buf: &Buffer<cl_int>,
+    ) -> OpenClResult<CacheEvictions> {
+        if !log_evictions {
+            return Ok(vec![]);
+        }
+
+        let keys_output =
+            self.system
+                .blocking_enqueue_read_buffer(self.config.key_len * total, keys_buf, &[])?;
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            self.config.value_len * total,
+            values_buf,
+            &[],
+        )?;
+
+        let sizes_output = self
+            .system
+            .blocking_enqueue_read_buffer(total * 2, sizes_buf, &[])?;
+
+        let evictions: CacheEvictions = keys_output
+            .chunks(self.config.key_len)
+            .zip(values_output.chunks(self.config.value_len))
+            .enumerate()
+            .filter(|(_, (key, _))| key.iter().any(|&x| x != i32::cl_default()))
+            .map(|(input_index, (key, value))| EvictedEntry {
+                input_index,
+                key: limit_vec_size(key, sizes_output[input_index * 2] as usize).to_vec(),
+                value: limit_vec_size(value, sizes_output[input_index * 2 + 1] as usize).to_vec(),
+            })
+            .collect();
+
+        if DEBUG_MODE {
+            println!("evictions {evictions:?}");
+        }
+
+        Ok(evictions)
+    }
+
+    pub fn stats(&self) -> OpenClResult<LRUCacheStats> {
+        let global_work_size = LRU_STATS_LEN;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let stats_output_buf = self.system.create_output_buffer(LRU_STATS_LEN)?;
+
+        let kernel_name = get_cache_kernel_name(LRU_CACHE_GET_STATS, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&stats_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let stats_output =
+            self.system
+                .blocking_enqueue_read_buffer(LRU_STATS_LEN, &stats_output_buf, &[])?;
+
+        Ok(LRUCacheStats::from_output(&stats_output))
+    }
+
+    pub fn reset_stats(&self) -> OpenClResult<()> {
+        let global_work_size = LRU_STATS_LEN;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_cache_kernel_name(LRU_CACHE_RESET_STATS, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
     }
 
     pub fn reset_array_set(&self) -> OpenClResult<()> {
@@ -421,14 +678,41 @@ LRUCacheSnapshot (
         values: &CacheValues,
         priorities: &[cl_int],
     ) -> OpenClResult<()> {
+        self.put_entries(keys, values, priorities, false)?;
+        Ok(())
+    }
+
+    pub fn put_with_evictions(
+        &self,
+        keys: &CacheKeys,
+        values: &CacheValues,
+        priorities: &[cl_int],
+    ) -> OpenClResult<CacheEvictions> {
+        self.put_entries(keys, values, priorities, true)
+    }
+
+    fn put_entries(
+        &self,
+        keys: &CacheKeys,
+        values: &CacheValues,
+        priorities: &[cl_int],
+        log_evictions: bool,
+    ) -> OpenClResult<CacheEvictions> {
         if keys.len() != values.len() {
-            panic!("error handle keys & values len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LRU_INPUT_LEN,
+            ));
         }
 
         if keys.len() != priorities.len() {
-            panic!("error handle keys & priorities len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LRU_INPUT_LEN,
+            ));
         }
 
+        let key_sizes_input = get_input_sizes(keys, self.config.key_len)?;
+        let value_sizes_input = get_input_sizes(values, self.config.value_len)?;
+
         let global_work_size = keys.len();
         let local_work_size = self
             .system
@@ -456,13 +740,31 @@ LRUCacheSnapshot (
 
         let priorities_input_buf = self.system.blocking_prepare_input_buffer(priorities)?;
 
+        let key_sizes_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&key_sizes_input)?;
+        let value_sizes_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&value_sizes_input)?;
+
+        let (evicted_keys_output_buf, evicted_values_output_buf, evicted_sizes_output_buf) =
+            self.create_eviction_buffers(global_work_size, log_evictions)?;
+
+        let log = log_evictions as cl_int;
+
         let kernel_name = get_cache_kernel_name(LRU_CACHE_PUT, self.get_id());
         let mut kernel = self.system.create_kernel(&kernel_name)?;
 
         unsafe {
+            kernel.set_arg(&log)?;
             kernel.set_arg(&keys_input_buf.get_cl_mem())?;
             kernel.set_arg(&values_input_buf.get_cl_mem())?;
             kernel.set_arg(&priorities_input_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_input_buf.get_cl_mem())?;
+            kernel.set_arg(&value_sizes_input_buf.get_cl_mem())?;
+            kernel.set_arg(&evicted_keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&evicted_values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&evicted_sizes_output_buf.get_cl_mem())?;
 
             kernel.enqueue_nd_range_kernel_dim_1(
                 self.system.get_host_command_queue(),
@@ -472,10 +774,19 @@ LRUCacheSnapshot (
             )?;
         }
 
-        Ok(())
+        self.read_evictions(
+            global_work_size,
+            log_evictions,
+            &evicted_keys_output_buf,
+            &evicted_values_output_buf,
+            &evicted_sizes_output_buf,
+        )
     }
 
+    /// the values are returned without padding, empty if the key is not in the cache
     pub fn get(&self, keys: &CacheKeys) -> OpenClResult<(CacheValues, CacheIndices)> {
+        let key_sizes_input = get_input_sizes(keys, self.config.key_len)?;
+
         let global_work_size = keys.len();
         let local_work_size = self
             .system
@@ -494,9 +805,13 @@ LRUCacheSnapshot (
         }
 
         let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let key_sizes_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&key_sizes_input)?;
         let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
 
         let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
+        let value_sizes_output_buf = self.system.create_output_buffer(global_work_size)?;
 
         let key_len = self.config.key_len as cl_uint;
         let buf_len = self.config.value_len as cl_uint;
@@ -508,8 +823,10 @@ LRUCacheSnapshot (
             kernel.set_arg(&key_len)?;
             kernel.set_arg(&buf_len)?;
             kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_input_buf.get_cl_mem())?;
             kernel.set_arg(&values_output_buf.get_cl_mem())?;
             kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+            kernel.set_arg(&value_sizes_output_buf.get_cl_mem())?;
 
             kernel.enqueue_nd_range_kernel_dim_1(
                 self.system.get_host_command_queue(),
@@ -531,9 +848,16 @@ LRUCacheSnapshot (
             &[],
         )?;
 
+        let value_sizes_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            global_work_size,
+            &value_sizes_output_buf,
+            &[],
+        )?;
+
         let values: Vec<Vec<_>> = values_output
             .chunks(self.config.value_len)
-            .map(|x| x.to_vec())
+            .enumerate()
+            .map(|(i, x)| limit_vec_size(x, value_sizes_output[i] as usize).to_vec())
             .collect();
 
         if DEBUG_MODE {
@@ -546,6 +870,129 @@ LRUCacheSnapshot (
         Ok((values, indices_output))
     }
 
+    /// removed entry index per key, -1 if the key is not in the cache
+    pub fn remove(&self, keys: &CacheKeys) -> OpenClResult<CacheIndices> {
+        let key_sizes_input = get_input_sizes(keys, self.config.key_len)?;
+
+        let global_work_size = keys.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_input_capacity = self.config.key_len * global_work_size;
+        let indices_output_capacity = global_work_size;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let key_sizes_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&key_sizes_input)?;
+        let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
+
+        let kernel_name = get_cache_kernel_name(LRU_CACHE_REMOVE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices_output = self.system.blocking_enqueue_read_buffer(
+            indices_output_capacity,
+            &indices_output_buf,
+            &[],
+        )?;
+
+        if DEBUG_MODE {
+            println!("indices_output {indices_output:?}");
+        }
+
+        Ok(indices_output)
+    }
+
+    /// removes the entries whose priority matches the predicate
+    pub fn remove_where(&self, predicate: PriorityPredicate) -> OpenClResult<Vec<KeyPriority>> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+        let priorities_output_capacity = global_work_size;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let priorities_output_buf = self
+            .system
+            .create_output_buffer(priorities_output_capacity)?;
+        let key_sizes_output_buf = self
+            .system
+            .create_output_buffer(priorities_output_capacity)?;
+
+        let (predicate, value) = predicate.to_kernel_args();
+
+        let kernel_name = get_cache_kernel_name(LRU_CACHE_REMOVE_WHERE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&predicate)?;
+            kernel.set_arg(&value)?;
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&priorities_output_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let priorities_output = self.system.blocking_enqueue_read_buffer(
+            priorities_output_capacity,
+            &priorities_output_buf,
+            &[],
+        )?;
+
+        let key_sizes_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            priorities_output_capacity,
+            &key_sizes_output_buf,
+            &[],
+        )?;
+
+        let keys: Vec<KeyPriority> = keys_output
+            .chunks(self.config.key_len)
+            .enumerate()
+            .filter(|(i, _)| priorities_output[*i] > 0)
+            .map(|(i, x)| KeyPriority {
+                key: limit_vec_size(x, key_sizes_output[i] as usize).to_vec(),
+                priority: priorities_output[i],
+            })
+            .collect();
+
+        Ok(keys)
+    }
+
     pub fn keys(&self) -> OpenClResult<Vec<KeyPriority>> {
         let global_work_size = self.config.capacity;
         let local_work_size = self
@@ -559,6 +1006,9 @@ LRUCacheSnapshot (
         let priorities_output_buf = self
             .system
             .create_output_buffer(priorities_output_capacity)?;
+        let key_sizes_output_buf = self
+            .system
+            .create_output_buffer(priorities_output_capacity)?;
 
         let kernel_name = get_cache_kernel_name(LRU_CACHE_GET_KEYS, self.get_id());
         let mut kernel = self.system.create_kernel(&kernel_name)?;
@@ -566,6 +1016,7 @@ LRUCacheSnapshot (
         unsafe {
             kernel.set_arg(&keys_output_buf.get_cl_mem())?;
             kernel.set_arg(&priorities_output_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_output_buf.get_cl_mem())?;
 
             kernel.enqueue_nd_range_kernel_dim_1(
                 self.system.get_host_command_queue(),
@@ -587,6 +1038,12 @@ LRUCacheSnapshot (
             &[],
         )?;
 
+        let key_sizes_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            priorities_output_capacity,
+            &key_sizes_output_buf,
+            &[],
+        )?;
+
         let keys: Vec<KeyPriority> = keys_output
             .chunks(self.config.key_len)
             .enumerate()
@@ -598,7 +1055,7 @@ LRUCacheSnapshot (
                 }
 
                 Some(KeyPriority {
-                    key: x.to_vec(),
+                    key: limit_vec_size(x, key_sizes_output[i] as usize).to_vec(),
                     priority: priorities_output[i],
                 })
             })
@@ -635,6 +1092,9 @@ LRUCacheSnapshot (
         let priorities_output_buf = self
             .system
             .create_output_buffer(priorities_output_capacity)?;
+        let key_sizes_output_buf = self
+            .system
+            .create_output_buffer(priorities_output_capacity)?;
 
         let enqueue_kernel_output_buf = self
             .system
@@ -657,6 +1117,7 @@ LRUCacheSnapshot (
             kernel.set_arg(&capacity_device_local_work_size)?;
             kernel.set_arg(&keys_output_buf.get_cl_mem())?;
             kernel.set_arg(&priorities_output_buf.get_cl_mem())?;
+            kernel.set_arg(&key_sizes_output_buf.get_cl_mem())?;
             kernel.set_arg(&enqueue_kernel_output_buf.get_cl_mem())?;
 
             kernel.enqueue_nd_range_kernel_dim_1(
@@ -679,6 +1140,12 @@ LRUCacheSnapshot (
             &[],
         )?;
 
+        let key_sizes_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            priorities_output_capacity,
+            &key_sizes_output_buf,
+            &[],
+        )?;
+
         let keys: Vec<KeyPriority> = keys_output
             .chunks(self.config.key_len)
             .enumerate()
@@ -690,7 +1157,7 @@ LRUCacheSnapshot (
                 }
 
                 Some(KeyPriority {
-                    key: x.to_vec(),
+                    key: limit_vec_size(x, key_sizes_output[i] as usize).to_vec(),
                     priority: priorities_output[i],
                 })
             })
@@ -1037,6 +1504,29 @@ mod tests_lru_cache_insert {
         assert_eq!(cs.priorities, expected_priorities);
     }
 
+    #[test]
+    fn invalid_input_len() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 16, 8);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1; 16]; 4];
+        let values = vec![vec![1; 16]; 3];
+
+        let result = cache.insert(&keys, &values);
+        assert_eq!(
+            result,
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LRU_INPUT_LEN
+            ))
+        );
+    }
+
     #[test]
     fn cache_is_empty_2() {
         let cache_capacity = 512;
@@ -1368,10 +1858,7 @@ mod tests_lru_cache_get {
 
         let (values, indices) = cache.get(&test_matrix.keys).unwrap();
 
-        assert_eq!(
-            values,
-            vec![vec![i32::cl_default(); cache_value_len]; cache_capacity]
-        );
+        assert_eq!(values, vec![Vec::<cl_int>::new(); cache_capacity]);
         assert_eq!(indices, vec![-1; cache_capacity]);
 
         let cs = cache.print().unwrap();
@@ -1491,7 +1978,7 @@ mod tests_lru_cache_get {
 
         for (i, value) in test_matrix.values.into_iter().enumerate() {
             if i >= (cache_capacity / 2) {
-                assert_eq!(values[i], vec![i32::cl_default(); cache_value_len]);
+                assert!(values[i].is_empty());
                 continue;
             }
 
@@ -2058,6 +2545,488 @@ mod tests_lru_cache_sort {
 }
 
 // TODO explain tests
+#[cfg(test)]
+mod tests_lru_cache_stats_struct {
+    use super::*;
+
+    #[test]
+    fn hit_rate() {
+        assert_eq!(LRUCacheStats::default().hit_rate(), 0.0);
+
+        let stats = LRUCacheStats::from_output(&[3, 1, 8, 2]);
+        assert_eq!(stats, LRUCacheStats::new(3, 1, 8, 2));
+        assert_eq!(stats.lookups(), 4);
+        assert_eq!(stats.hit_rate(), 0.75);
+    }
+}
+
+#[cfg(test)]
+mod tests_lru_cache_stats {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 16, 32);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        assert_eq!(cache.stats().unwrap(), LRUCacheStats::default());
+    }
+
+    #[test]
+    fn hits_misses_inserts_and_evictions() {
+        let cache_capacity = 32;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+        cache.add(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        // evicts 4 entries
+        let test_matrix_2 = TestMatrix::new(4, cache_key_len, cache_value_len, 100, 100);
+        cache
+            .add(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+
+        let (_, indices) = cache.get(&test_matrix_2.keys).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let (_, indices) = cache.get(&vec![vec![-5; cache_key_len]; 2]).unwrap();
+        assert_eq!(indices, vec![-1, -1]);
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats, LRUCacheStats::new(4, 2, cache_capacity + 4, 4));
+
+        cache.reset_stats().unwrap();
+        assert_eq!(cache.stats().unwrap(), LRUCacheStats::default());
+
+        // the entries are kept
+        let (_, indices) = cache.get(&test_matrix_2.keys).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(cache.stats().unwrap(), LRUCacheStats::new(4, 0, 0, 0));
+    }
+
+    #[test]
+    fn insert_after_remove_is_not_an_eviction() {
+        let cache_capacity = 32;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+        cache.add(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        cache.remove(&test_matrix.keys[0..2].to_vec()).unwrap();
+
+        // the slots freed by remove are reused
+        let test_matrix_2 = TestMatrix::new(2, cache_key_len, cache_value_len, 100, 100);
+        let (indices, evictions) = cache
+            .insert_with_evictions(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+        cache.reset_array_set().unwrap();
+
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert!(evictions.is_empty());
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.evictions, 0);
+        assert_eq!(stats.inserts, cache_capacity + 2);
+
+        let (_, indices) = cache.get(&test_matrix.keys[2..].to_vec()).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+    }
+}
+
+#[cfg(test)]
+mod tests_lru_cache_evictions {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_not_full() {
+        let cache_capacity = 32;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+
+        let (indices, evictions) = cache
+            .insert_with_evictions(&test_matrix.keys, &test_matrix.values)
+            .unwrap();
+        cache.reset_array_set().unwrap();
+
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert!(evictions.is_empty());
+    }
+
+    #[test]
+    fn cache_is_full() {
+        let cache_capacity = 32;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+        cache.add(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        let mut cs = cache.print().unwrap();
+        let mut expected_keys = cs.get_lower_priority_keys(4);
+        expected_keys.sort();
+
+        let test_matrix_2 = TestMatrix::new(4, cache_key_len, cache_value_len, 100, 100);
+
+        let (indices, evictions) = cache
+            .insert_with_evictions(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+        cache.reset_array_set().unwrap();
+
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(evictions.len(), 4);
+
+        let mut evicted_keys: Vec<_> = evictions.iter().map(|x| x.key.clone()).collect();
+        evicted_keys.sort();
+        assert_eq!(evicted_keys, expected_keys);
+
+        for evicted in evictions {
+            let i = test_matrix
+                .keys
+                .iter()
+                .position(|x| x == &evicted.key)
+                .unwrap();
+            assert_eq!(evicted.value, test_matrix.values[i]);
+            assert!(evicted.input_index < 4);
+        }
+    }
+
+    #[test]
+    fn put() {
+        let cache_capacity = 8;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+        let priorities: Vec<cl_int> = (1..=cache_capacity as cl_int).collect();
+
+        let evictions = cache
+            .put_with_evictions(&test_matrix.keys, &test_matrix.values, &priorities)
+            .unwrap();
+        assert!(evictions.is_empty());
+
+        // slot 0 keeps its key, the others are replaced
+        let mut test_matrix_2 =
+            TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 100, 100);
+        test_matrix_2.keys[0] = test_matrix.keys[0].clone();
+
+        let evictions = cache
+            .put_with_evictions(&test_matrix_2.keys, &test_matrix_2.values, &priorities)
+            .unwrap();
+
+        assert_eq!(evictions.len(), cache_capacity - 1);
+
+        for evicted in evictions {
+            assert_eq!(evicted.key, test_matrix.keys[evicted.input_index]);
+            assert_eq!(evicted.value, test_matrix.values[evicted.input_index]);
+        }
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.inserts, cache_capacity * 2);
+        assert_eq!(stats.evictions, cache_capacity - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests_lru_cache_remove {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 16, 32);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let indices = cache.remove(&vec![vec![1; 16], vec![-1; 16]]).unwrap();
+        assert_eq!(indices, vec![-1, -1]);
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs, LRUCacheSnapshot::create_empty(16, 16, 32));
+    }
+
+    #[test]
+    fn remove_keys() {
+        let cache_capacity = 32;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+        let inserted = cache.add(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        let mut keys = test_matrix.keys[0..4].to_vec();
+        keys.push(vec![-5; cache_key_len]);
+
+        let indices = cache.remove(&keys).unwrap();
+        assert_eq!(indices[0..4], inserted[0..4]);
+        assert_eq!(indices[4], -1);
+
+        let (_, indices) = cache.get(&test_matrix.keys).unwrap();
+        assert_eq!(indices[0..4], vec![-1; 4]);
+        assert!(indices[4..].iter().all(|&x| x >= 0));
+
+        assert_eq!(cache.keys().unwrap().len(), cache_capacity - 4);
+
+        // the free slots are used first
+        let test_matrix_2 = TestMatrix::new(4, cache_key_len, cache_value_len, 100, 100);
+        let indices = cache
+            .add(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+
+        let mut indices_sorted = indices.clone();
+        indices_sorted.sort();
+        let mut expected = inserted[0..4].to_vec();
+        expected.sort();
+        assert_eq!(indices_sorted, expected);
+
+        let (_, indices) = cache.get(&test_matrix.keys[4..].to_vec()).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+    }
+
+    #[test]
+    fn remove_where() {
+        let cache_capacity = 8;
+        let cache_key_len = 16;
+        let cache_value_len = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+        let priorities: Vec<cl_int> = (1..=cache_capacity as cl_int).collect();
+
+        cache
+            .put(&test_matrix.keys, &test_matrix.values, &priorities)
+            .unwrap();
+
+        let removed = cache.remove_where(PriorityPredicate::LessThan(4)).unwrap();
+
+        let removed_priorities: Vec<_> = removed.iter().map(|x| x.priority).collect();
+        assert_eq!(removed_priorities, vec![1, 2, 3]);
+
+        for (i, x) in removed.iter().enumerate() {
+            assert_eq!(x.key, test_matrix.keys[i]);
+        }
+
+        let removed = cache
+            .remove_where(PriorityPredicate::GreaterThan(6))
+            .unwrap();
+        assert_eq!(removed.len(), 2);
+
+        let keys = cache.sorted_keys().unwrap();
+        let keys_priorities: Vec<_> = keys.iter().map(|x| x.priority).collect();
+        assert_eq!(keys_priorities, vec![6, 5, 4]);
+
+        let removed = cache
+            .remove_where(PriorityPredicate::GreaterThan(100))
+            .unwrap();
+        assert!(removed.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests_lru_cache_value_sizes {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn values_keep_their_length() {
+        let cache_capacity = 16;
+        let cache_key_len = 16;
+        let cache_value_len = 32;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(cache_key_len, cache_value_len, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys: CacheKeys = (1..=8).map(|i| vec![i; i as usize]).collect();
+        let values: CacheValues = (1..=8)
+            .map(|i| {
+                let mut value = vec![i * 10; i as usize * 4];
+                // trailing zeros are part of the value
+                value.push(0);
+                value
+            })
+            .collect();
+
+        let indices = cache.insert(&keys, &values).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let (output, indices) = cache.get(&keys).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(output, values);
+
+        let mut cache_keys: CacheKeys = cache.keys().unwrap().into_iter().map(|x| x.key).collect();
+        cache_keys.sort();
+        assert_eq!(cache_keys, keys);
+
+        let cs = cache.debug().unwrap();
+        for (i, &index) in indices.iter().enumerate() {
+            assert_eq!(cs.get_key(index as usize), keys[i]);
+            assert_eq!(cs.get_value(index as usize), values[i]);
+        }
+    }
+
+    #[test]
+    fn keys_with_trailing_zeros_are_different_keys() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(8, 8, 16);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1], vec![1, 0]];
+        let values = vec![vec![10], vec![20, 0]];
+
+        let indices = cache.insert(&keys, &values).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_ne!(indices[0], indices[1]);
+
+        let (output, _) = cache.get(&keys).unwrap();
+        assert_eq!(output, values);
+
+        // updates [1, 0] only
+        cache
+            .insert(&vec![vec![1, 0]], &vec![vec![30, 0, 0]])
+            .unwrap();
+
+        let (output, _) = cache.get(&keys).unwrap();
+        assert_eq!(output, vec![vec![10], vec![30, 0, 0]]);
+
+        let indices = cache.remove(&vec![vec![1, 0, 0]]).unwrap();
+        assert_eq!(indices, vec![-1]);
+
+        let indices = cache.remove(&vec![vec![1, 0]]).unwrap();
+        assert!(indices[0] >= 0);
+    }
+
+    #[test]
+    fn over_long_inputs_are_rejected() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(8, 8, 16);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let error = || OpenclError::OpenclCollection(CL_COLLECTION_LRU_INPUT_TOO_LONG);
+
+        // key longer than key_len
+        let keys = vec![vec![1; 9]];
+        let values = vec![vec![2; 8]];
+        assert_eq!(cache.insert(&keys, &values), Err(error()));
+        assert_eq!(cache.put(&keys, &values, &[1]), Err(error()));
+        assert_eq!(cache.get(&keys), Err(error()));
+        assert_eq!(cache.remove(&keys), Err(error()));
+
+        // value longer than value_len
+        let keys = vec![vec![1; 8]];
+        let values = vec![vec![2; 9]];
+        assert_eq!(cache.insert(&keys, &values), Err(error()));
+        assert_eq!(cache.put(&keys, &values, &[1]), Err(error()));
+
+        let (_, indices) = cache.get(&keys).unwrap();
+        assert_eq!(indices, vec![-1]);
+    }
+}
+
 #[cfg(test)]
 mod tests_lru_cache_examples {
     use super::*;
@@ -1,12 +1,13 @@
 use crate::cache::config::CacheConfig;
-use crate::cache::handle::{CacheIndices, LruSummary};
+use crate::cache::handle::{CacheIndices, LruSummary, PriorityPredicate};
 use crate::cache::kernel::name::{
     get_cache_kernel_name, MINI_LRU_CACHE_ARRAY_SET_RESET, MINI_LRU_CACHE_DEBUG,
     MINI_LRU_CACHE_GET_KEYS, MINI_LRU_CACHE_GET_SORTED_KEYS, MINI_LRU_CACHE_PUT,
-    MINI_LRU_CACHE_RESET, MINI_LRU_CACHE_SORT, READ_ON_MINI_LRU_CACHE, WRITE_IN_MINI_LRU_CACHE,
+    MINI_LRU_CACHE_REMOVE, MINI_LRU_CACHE_REMOVE_WHERE, MINI_LRU_CACHE_RESET, MINI_LRU_CACHE_SORT,
+    READ_ON_MINI_LRU_CACHE, WRITE_IN_MINI_LRU_CACHE,
 };
 use crate::config::{ClTypeDefault, DEBUG_MODE};
-use crate::error::OpenClResult;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_MINI_LRU_INPUT_LEN};
 use crate::set::handle::array_set_v2::ArraySetSnapshot;
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
 use opencl::wrapper::system::OpenclCommonOperation;
@@ -284,7 +285,9 @@ MiniLRUCacheSnapshot (
 
     pub fn insert(&self, keys: &[cl_int], values: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
         if keys.len() != values.len() {
-            panic!("error handle keys & values len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MINI_LRU_INPUT_LEN,
+            ));
         }
 
         let global_work_size = keys.len();
@@ -362,11 +365,15 @@ MiniLRUCacheSnapshot (
         priorities: &[cl_int],
     ) -> OpenClResult<()> {
         if keys.len() != values.len() {
-            panic!("error handle keys & values len");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MINI_LRU_INPUT_LEN,
+            ));
         }
 
         if keys.len() != priorities.len() {
-            panic!("error handle keys & priorities len");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MINI_LRU_INPUT_LEN,
+            ));
         }
 
         let global_work_size = keys.len();
@@ -447,6 +454,98 @@ MiniLRUCacheSnapshot (
         Ok((values_output, indices_output))
     }
 
+    /// removed entry index per key, -1 if the key is not in the cache
+    pub fn remove(&self, keys: &[cl_int]) -> OpenClResult<CacheIndices> {
+        let global_work_size = keys.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let indices_output_capacity = global_work_size;
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(keys)?;
+        let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
+
+        let kernel_name = get_cache_kernel_name(MINI_LRU_CACHE_REMOVE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices_output = self.system.blocking_enqueue_read_buffer(
+            indices_output_capacity,
+            &indices_output_buf,
+            &[],
+        )?;
+
+        if DEBUG_MODE {
+            println!("indices_output {indices_output:?}");
+        }
+
+        Ok(indices_output)
+    }
+
+    /// removes the entries whose priority matches the predicate
+    pub fn remove_where(&self, predicate: PriorityPredicate) -> OpenClResult<Vec<KeyPriority>> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_buf = self.system.create_output_buffer(global_work_size)?;
+        let priorities_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let (predicate, value) = predicate.to_kernel_args();
+
+        let kernel_name = get_cache_kernel_name(MINI_LRU_CACHE_REMOVE_WHERE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&predicate)?;
+            kernel.set_arg(&value)?;
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&priorities_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &keys_output_buf, &[])?;
+
+        let priorities_output = self.system.blocking_enqueue_read_buffer(
+            global_work_size,
+            &priorities_output_buf,
+            &[],
+        )?;
+
+        let keys: Vec<KeyPriority> = keys_output
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| priorities_output[*i] > 0)
+            .map(|(i, x)| KeyPriority {
+                key: x,
+                priority: priorities_output[i],
+            })
+            .collect();
+
+        Ok(keys)
+    }
+
     pub fn keys(&self) -> OpenClResult<Vec<KeyPriority>> {
         let global_work_size = self.config.capacity;
         let local_work_size = self
@@ -1680,6 +1779,101 @@ mod tests_mini_lru_cache_sort {
 }
 
 // TODO explain tests
+#[cfg(test)]
+mod tests_mini_lru_cache_remove {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let cache_capacity = 32;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_mini_lru_by_id(0).unwrap();
+        let cache = MiniLRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let indices = cache.remove(&[1, -1]).unwrap();
+        assert_eq!(indices, vec![-1, -1]);
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs, MiniLRUCacheSnapshot::create_empty(cache_capacity));
+    }
+
+    #[test]
+    fn remove_keys() {
+        let cache_capacity = 32;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_mini_lru_by_id(0).unwrap();
+        let cache = MiniLRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let input_keys: Vec<cl_int> = (0..cache_capacity as cl_int).collect();
+        let input_values: Vec<cl_int> = input_keys.iter().map(|&x| x + 10).collect();
+
+        let inserted = cache.add(&input_keys, &input_values).unwrap();
+
+        let indices = cache.remove(&[0, 1, 2, 100]).unwrap();
+        assert_eq!(indices[0..3], inserted[0..3]);
+        assert_eq!(indices[3], -1);
+
+        let (_, indices) = cache.get(&input_keys).unwrap();
+        assert_eq!(indices[0..3], vec![-1; 3]);
+        assert!(indices[3..].iter().all(|&x| x >= 0));
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs.summary(), LruSummary::with(cache_capacity - 3));
+    }
+
+    #[test]
+    fn remove_where() {
+        let cache_capacity = 8;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_mini_lru_by_id(0).unwrap();
+        let cache = MiniLRUCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let input_keys: Vec<cl_int> = (0..cache_capacity as cl_int).collect();
+        let input_values: Vec<cl_int> = input_keys.iter().map(|&x| x + 10).collect();
+        let input_priorities: Vec<cl_int> = input_keys.iter().map(|&x| x + 1).collect();
+
+        cache
+            .put(&input_keys, &input_values, &input_priorities)
+            .unwrap();
+
+        let removed = cache.remove_where(PriorityPredicate::LessThan(3)).unwrap();
+
+        let removed_keys: Vec<_> = removed.iter().map(|x| x.key).collect();
+        assert_eq!(removed_keys, vec![0, 1]);
+
+        let removed = cache
+            .remove_where(PriorityPredicate::GreaterThan(7))
+            .unwrap();
+
+        let removed_keys: Vec<_> = removed.iter().map(|x| x.key).collect();
+        assert_eq!(removed_keys, vec![7]);
+
+        let keys: Vec<_> = cache.keys().unwrap().iter().map(|x| x.key).collect();
+        assert_eq!(keys, vec![2, 3, 4, 5, 6]);
+    }
+}
+
 #[cfg(test)]
 mod tests_mini_lru_cache_examples {
     use super::*;
@@ -1,7 +1,10 @@
 use opencl::opencl_sys::bindings::cl_int;
 
+pub mod lfu;
 pub mod lru;
 pub mod mini_lru;
+pub mod ttl;
+pub mod two_q;
 
 #[derive(Debug, PartialEq)]
 pub struct LruSummary {
@@ -28,6 +31,34 @@ impl LruSummary {
     }
 }
 
+/// entries selected by remove_where
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriorityPredicate {
+    LessThan(cl_int),
+    GreaterThan(cl_int),
+}
+
+impl PriorityPredicate {
+    /// (predicate, value) kernel args
+    pub fn to_kernel_args(&self) -> (cl_int, cl_int) {
+        match self {
+            PriorityPredicate::LessThan(value) => (0, *value),
+            PriorityPredicate::GreaterThan(value) => (1, *value),
+        }
+    }
+}
+
 pub type CacheIndices = Vec<cl_int>;
 
 pub type CachePriorities = Vec<cl_int>;
+
+#[cfg(test)]
+mod tests_priority_predicate {
+    use super::*;
+
+    #[test]
+    fn to_kernel_args() {
+        assert_eq!(PriorityPredicate::LessThan(4).to_kernel_args(), (0, 4));
+        assert_eq!(PriorityPredicate::GreaterThan(-1).to_kernel_args(), (1, -1));
+    }
+}
@@ -0,0 +1,766 @@
+use crate::cache::config::CacheConfig;
+use crate::cache::handle::lru::{CacheKeys, CacheValues};
+use crate::cache::handle::CacheIndices;
+use crate::cache::kernel::name::{
+    get_cache_kernel_name, TTL_CACHE_DEBUG, TTL_CACHE_GET, TTL_CACHE_GET_KEYS, TTL_CACHE_PURGE,
+    TTL_CACHE_PUT, TTL_CACHE_RESET,
+};
+use crate::config::{ClTypeDefault, DEBUG_MODE};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_CACHE_TTL,
+    CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN,
+};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::{cl_int, cl_long};
+use opencl::wrapper::system::OpenclCommonOperation;
+use std::sync::Arc;
+
+pub const TTL_ENTRY_EMPTY: cl_int = 0;
+pub const TTL_ENTRY_ASSIGNED: cl_int = 1;
+
+/// ttl of each entry, the default ttl of the cache is used when ttl is None
+pub fn get_ttl_input(
+    len: usize,
+    ttl: Option<&[cl_long]>,
+    default_ttl: cl_long,
+    now: cl_long,
+) -> OpenClResult<Vec<cl_long>> {
+    let ttl_input = match ttl {
+        None => vec![default_ttl; len],
+        Some(v) => {
+            if v.len() != len {
+                return Err(OpenclError::OpenclCollection(
+                    CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN,
+                ));
+            }
+            v.to_vec()
+        }
+    };
+
+    if now < 0 {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_CACHE_TTL,
+        ));
+    }
+
+    for &x in &ttl_input {
+        if x <= 0 || now.checked_add(x).is_none() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CACHE_TTL,
+            ));
+        }
+    }
+
+    Ok(ttl_input)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TTLCacheSnapshot {
+    pub keys: Vec<Vec<cl_int>>,
+    pub values: Vec<Vec<cl_int>>,
+    pub expiry: Vec<cl_long>,
+    pub entries: Vec<cl_int>,
+}
+
+impl TTLCacheSnapshot {
+    pub fn new(
+        keys: Vec<Vec<cl_int>>,
+        values: Vec<Vec<cl_int>>,
+        expiry: Vec<cl_long>,
+        entries: Vec<cl_int>,
+    ) -> Self {
+        Self {
+            keys,
+            values,
+            expiry,
+            entries,
+        }
+    }
+
+    pub fn create_empty(key_len: usize, value_len: usize, capacity: usize) -> Self {
+        Self::new(
+            vec![vec![i32::cl_default(); key_len]; capacity],
+            vec![vec![i32::cl_default(); value_len]; capacity],
+            vec![0; capacity],
+            vec![TTL_ENTRY_EMPTY; capacity],
+        )
+    }
+
+    /// entries that are assigned and not expired
+    pub fn len(&self, now: cl_long) -> usize {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(i, &x)| x == TTL_ENTRY_ASSIGNED && self.expiry[*i] > now)
+            .count()
+    }
+
+    pub fn is_empty(&self, now: cl_long) -> bool {
+        self.len(now) == 0
+    }
+
+    pub fn has_entry(&self, key: &Vec<cl_int>, value: &Vec<cl_int>) -> bool {
+        match self.keys.iter().position(|x| x == key) {
+            None => false,
+            Some(index) => &self.values[index] == value,
+        }
+    }
+
+    // debug
+    pub fn print_all_entries(&self) {
+        for (i, key) in self.keys.iter().enumerate() {
+            println!("i: {} - key:     {:?}", i, key);
+            println!("     - value:   {:?}", self.values[i]);
+            println!("     - expiry:  {:?}", self.expiry[i]);
+            println!("     - entry:   {:?}", self.entries[i]);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct KeyExpiry {
+    pub expiry: cl_long,
+    pub key: Vec<cl_int>,
+}
+
+#[derive(Debug)]
+pub struct TTLCacheHandle<T: OpenclCommonOperation> {
+    config: CacheConfig,
+    system: Arc<T>,
+}
+
+impl<T: OpenclCommonOperation> TTLCacheHandle<T> {
+    pub fn new(config: &CacheConfig, system: Arc<T>) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn get_default_ttl(&self) -> cl_long {
+        self.config.default_ttl
+    }
+
+    pub fn debug(&self) -> OpenClResult<TTLCacheSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+        let values_output_capacity = self.config.value_len * global_work_size;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+
+        let expiry_output_buf = self.system.create_output_buffer(global_work_size)?;
+        let entries_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_cache_kernel_name(TTL_CACHE_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&expiry_output_buf.get_cl_mem())?;
+            kernel.set_arg(&entries_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let expiry_output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &expiry_output_buf, &[])?;
+
+        let entries_output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &entries_output_buf, &[])?;
+
+        let keys: Vec<Vec<_>> = keys_output
+            .chunks(self.config.key_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        let values: Vec<Vec<_>> = values_output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok(TTLCacheSnapshot::new(
+            keys,
+            values,
+            expiry_output,
+            entries_output,
+        ))
+    }
+
+    pub fn print(&self) -> OpenClResult<TTLCacheSnapshot> {
+        let cs = self.debug()?;
+        println!(
+            "
+TTLCacheSnapshot (
+   expiry:  {:?}
+   entries: {:?}
+)
+        ",
+            cs.expiry, cs.entries
+        );
+
+        cs.print_all_entries();
+
+        Ok(cs)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_cache_kernel_name(TTL_CACHE_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        };
+
+        Ok(())
+    }
+
+    pub fn initialize(&self) -> OpenClResult<()> {
+        self.reset()
+    }
+
+    /// When the cache is full, expired entries are replaced first, then the entries closest to expire.
+    /// The time (now) is supplied by the host, the cache does not read any clock
+    pub fn put(
+        &self,
+        keys: &CacheKeys,
+        values: &CacheValues,
+        ttl: Option<&[cl_long]>,
+        now: cl_long,
+    ) -> OpenClResult<CacheIndices> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN,
+            ));
+        }
+
+        let ttl_input = get_ttl_input(keys.len(), ttl, self.config.default_ttl, now)?;
+
+        let global_work_size = keys.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_input_capacity = self.config.key_len * global_work_size;
+        let values_input_capacity = self.config.value_len * global_work_size;
+
+        let indices_output_capacity = global_work_size;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let mut values_input: Vec<_> = Vec::with_capacity(values_input_capacity);
+
+        for b in values {
+            let mut v = ensure_vec_size(b, self.config.value_len);
+            values_input.append(&mut v);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_input_buf = self.system.blocking_prepare_input_buffer(&values_input)?;
+        let ttl_input_buf = self.system.blocking_prepare_input_buffer(&ttl_input)?;
+
+        let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
+
+        let kernel_name = get_cache_kernel_name(TTL_CACHE_PUT, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&now)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_input_buf.get_cl_mem())?;
+            kernel.set_arg(&ttl_input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices_output = self.system.blocking_enqueue_read_buffer(
+            indices_output_capacity,
+            &indices_output_buf,
+            &[],
+        )?;
+
+        if DEBUG_MODE {
+            println!("indices_output {indices_output:?}");
+        }
+
+        Ok(indices_output)
+    }
+
+    /// expired entries are returned as missing (index -1), the expiry is not refreshed
+    pub fn get(&self, keys: &CacheKeys, now: cl_long) -> OpenClResult<(CacheValues, CacheIndices)> {
+        if now < 0 {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CACHE_TTL,
+            ));
+        }
+
+        let global_work_size = keys.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_input_capacity = self.config.key_len * global_work_size;
+        let values_output_capacity = self.config.value_len * global_work_size;
+
+        let indices_output_capacity = global_work_size;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+
+        let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
+
+        let kernel_name = get_cache_kernel_name(TTL_CACHE_GET, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&now)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let indices_output = self.system.blocking_enqueue_read_buffer(
+            indices_output_capacity,
+            &indices_output_buf,
+            &[],
+        )?;
+
+        let values: Vec<Vec<_>> = values_output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok((values, indices_output))
+    }
+
+    /// remove the expired entries, returns the indices of the removed entries
+    pub fn purge(&self, now: cl_long) -> OpenClResult<Vec<usize>> {
+        if now < 0 {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CACHE_TTL,
+            ));
+        }
+
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let indices_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_cache_kernel_name(TTL_CACHE_PURGE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&now)?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &indices_output_buf, &[])?;
+
+        Ok(indices_output
+            .into_iter()
+            .filter(|&x| x >= 0)
+            .map(|x| x as usize)
+            .collect())
+    }
+
+    /// keys that are not expired
+    pub fn keys(&self, now: cl_long) -> OpenClResult<Vec<KeyExpiry>> {
+        if now < 0 {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CACHE_TTL,
+            ));
+        }
+
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let expiry_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_cache_kernel_name(TTL_CACHE_GET_KEYS, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&now)?;
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&expiry_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let expiry_output: Vec<cl_long> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &expiry_output_buf, &[])?;
+
+        let keys: Vec<KeyExpiry> = keys_output
+            .chunks(self.config.key_len)
+            .enumerate()
+            .filter_map(|(i, x)| -> Option<KeyExpiry> {
+                let expiry = expiry_output[i];
+
+                if expiry == 0 {
+                    return None;
+                }
+
+                Some(KeyExpiry {
+                    key: x.to_vec(),
+                    expiry,
+                })
+            })
+            .collect();
+
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests_ttl_cache_input {
+    use super::*;
+
+    #[test]
+    fn default_ttl() {
+        let ttl = get_ttl_input(3, None, 100, 10).unwrap();
+        assert_eq!(ttl, vec![100; 3]);
+    }
+
+    #[test]
+    fn ttl_per_entry() {
+        let ttl = get_ttl_input(3, Some(&[1, 2, 3]), 100, 10).unwrap();
+        assert_eq!(ttl, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        assert_eq!(
+            get_ttl_input(3, Some(&[1, 2]), 100, 10),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_ttl() {
+        let error = Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_CACHE_TTL,
+        ));
+
+        assert_eq!(get_ttl_input(2, None, 0, 10), error);
+        assert_eq!(get_ttl_input(2, Some(&[1, -1]), 100, 10), error);
+        assert_eq!(get_ttl_input(2, None, 100, -1), error);
+        assert_eq!(get_ttl_input(2, None, cl_long::MAX, 10), error);
+    }
+}
+
+#[cfg(test)]
+mod tests_ttl_cache_reset {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(16, 32, 8, 100);
+        cache_src.add_ttl(64, 64, 16, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+
+        let r = cache.reset();
+        assert!(r.is_ok());
+
+        assert_eq!(
+            cache.print().unwrap(),
+            TTLCacheSnapshot::create_empty(16, 32, 8)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_ttl_cache_put {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let cache_capacity = 32;
+        let cache_key_len = 64;
+        let cache_value_len = 64;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(cache_key_len, cache_value_len, cache_capacity, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(cache_capacity, cache_key_len, cache_value_len, 1, 10);
+
+        let indices = cache
+            .put(&test_matrix.keys, &test_matrix.values, None, 10)
+            .unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs.len(10), cache_capacity);
+        assert_eq!(cs.expiry, vec![110; cache_capacity]);
+
+        for (i, key) in test_matrix.keys.iter().enumerate() {
+            assert!(cs.has_entry(
+                &ensure_vec_size(key, cache_key_len),
+                &ensure_vec_size(&test_matrix.values[i], cache_value_len)
+            ));
+        }
+    }
+
+    #[test]
+    fn update_existing_key() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(16, 16, 8, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1; 16]];
+
+        let i1 = cache.put(&keys, &vec![vec![2; 16]], None, 0).unwrap();
+        let i2 = cache
+            .put(&keys, &vec![vec![3; 16]], Some(&[50]), 20)
+            .unwrap();
+        assert_eq!(i1, i2);
+
+        let (values, indices) = cache.get(&keys, 30).unwrap();
+        assert_eq!(values, vec![vec![3; 16]]);
+        assert_eq!(indices, i1);
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs.len(30), 1);
+        assert_eq!(cs.expiry[i1[0] as usize], 70);
+    }
+
+    #[test]
+    fn cache_is_full_replace_closest_to_expire() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(16, 16, 4, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys: Vec<Vec<cl_int>> = (1..=4).map(|x| vec![x; 16]).collect();
+        let ttl = [40, 10, 30, 20];
+
+        cache.put(&keys, &keys, Some(&ttl), 0).unwrap();
+
+        let new_keys = vec![vec![5; 16]];
+        cache.put(&new_keys, &new_keys, None, 0).unwrap();
+
+        let (_, indices) = cache.get(&vec![vec![2; 16]], 0).unwrap();
+        assert_eq!(indices, vec![-1]);
+
+        let (values, _) = cache.get(&new_keys, 0).unwrap();
+        assert_eq!(values, new_keys);
+
+        assert_eq!(cache.print().unwrap().len(0), 4);
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(16, 16, 8, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1; 16]; 4];
+        let values = vec![vec![1; 16]; 3];
+
+        assert_eq!(
+            cache.put(&keys, &values, None, 0),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_ttl_cache_expiry {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn entries_expire_with_the_fake_clock() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(16, 16, 8, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1; 16], vec![2; 16]];
+
+        cache.put(&keys, &keys, Some(&[10, 100]), 0).unwrap();
+
+        let (_, indices) = cache.get(&keys, 9).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let (values, indices) = cache.get(&keys, 10).unwrap();
+        assert_eq!(indices[0], -1);
+        assert_eq!(values[0], vec![i32::cl_default(); 16]);
+        assert_eq!(values[1], vec![2; 16]);
+
+        let live_keys = cache.keys(10).unwrap();
+        assert_eq!(live_keys.len(), 1);
+        assert_eq!(live_keys[0].key, vec![2; 16]);
+        assert_eq!(live_keys[0].expiry, 100);
+    }
+
+    #[test]
+    fn purge_expired_entries() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(16, 16, 8, 100);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_ttl_by_id(0).unwrap();
+        let cache = TTLCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys: Vec<Vec<cl_int>> = (1..=4).map(|x| vec![x; 16]).collect();
+
+        let indices = cache.put(&keys, &keys, Some(&[10, 50, 10, 50]), 0).unwrap();
+
+        let mut purged = cache.purge(20).unwrap();
+        purged.sort();
+
+        let mut expected = vec![indices[0] as usize, indices[2] as usize];
+        expected.sort();
+        assert_eq!(purged, expected);
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs.len(20), 2);
+        assert_eq!(
+            cs.entries.iter().filter(|&&x| x == TTL_ENTRY_EMPTY).count(),
+            6
+        );
+
+        assert!(cache.purge(20).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,559 @@
+use crate::cache::config::{get_two_q_sizes, CacheConfig};
+use crate::cache::handle::lru::{CacheKeys, CacheValues};
+use crate::cache::handle::CacheIndices;
+use crate::cache::kernel::name::{
+    get_cache_kernel_name, TWO_Q_CACHE_DEBUG, TWO_Q_CACHE_GET, TWO_Q_CACHE_PUT, TWO_Q_CACHE_RESET,
+};
+use crate::config::{ClTypeDefault, DEBUG_MODE};
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::OpenclCommonOperation;
+use std::sync::Arc;
+
+pub const TWO_Q_EMPTY: cl_int = 0;
+/// first in first out queue, entries seen once
+pub const TWO_Q_A1IN: cl_int = 1;
+/// least recently used queue, entries seen more than once
+pub const TWO_Q_AM: cl_int = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwoQCacheSnapshot {
+    pub tick: cl_int,
+    pub keys: Vec<Vec<cl_int>>,
+    pub values: Vec<Vec<cl_int>>,
+    pub queues: Vec<cl_int>,
+    pub priorities: Vec<cl_int>,
+    pub ghost_keys: Vec<Vec<cl_int>>,
+    pub ghost_priorities: Vec<cl_int>,
+}
+
+impl TwoQCacheSnapshot {
+    pub fn new(
+        tick: cl_int,
+        keys: Vec<Vec<cl_int>>,
+        values: Vec<Vec<cl_int>>,
+        queues: Vec<cl_int>,
+        priorities: Vec<cl_int>,
+        ghost_keys: Vec<Vec<cl_int>>,
+        ghost_priorities: Vec<cl_int>,
+    ) -> Self {
+        Self {
+            tick,
+            keys,
+            values,
+            queues,
+            priorities,
+            ghost_keys,
+            ghost_priorities,
+        }
+    }
+
+    pub fn create_empty(key_len: usize, value_len: usize, capacity: usize) -> Self {
+        let (_, kout) = get_two_q_sizes(capacity);
+
+        Self::new(
+            0,
+            vec![vec![i32::cl_default(); key_len]; capacity],
+            vec![vec![i32::cl_default(); value_len]; capacity],
+            vec![TWO_Q_EMPTY; capacity],
+            vec![0; capacity],
+            vec![vec![i32::cl_default(); key_len]; kout],
+            vec![0; kout],
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.queues.iter().filter(|&&x| x != TWO_Q_EMPTY).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn has_entry(&self, key: &Vec<cl_int>, value: &Vec<cl_int>) -> bool {
+        match self
+            .keys
+            .iter()
+            .enumerate()
+            .position(|(i, x)| self.queues[i] != TWO_Q_EMPTY && x == key)
+        {
+            None => false,
+            Some(index) => &self.values[index] == value,
+        }
+    }
+
+    /// keys of the queue in eviction order (the next key to be evicted first)
+    pub fn get_queue_keys(&self, queue: cl_int) -> Vec<Vec<cl_int>> {
+        let mut keys: Vec<(&Vec<cl_int>, cl_int)> = self
+            .keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.queues[*i] == queue)
+            .map(|(i, x)| (x, self.priorities[i]))
+            .collect();
+
+        keys.sort_by_key(|(_, priority)| *priority);
+
+        keys.into_iter().map(|(x, _)| x.clone()).collect()
+    }
+
+    /// ghost keys (A1out), the oldest first
+    pub fn get_ghost_keys(&self) -> Vec<Vec<cl_int>> {
+        let mut keys: Vec<(&Vec<cl_int>, cl_int)> = self
+            .ghost_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.ghost_priorities[*i] > 0)
+            .map(|(i, x)| (x, self.ghost_priorities[i]))
+            .collect();
+
+        keys.sort_by_key(|(_, priority)| *priority);
+
+        keys.into_iter().map(|(x, _)| x.clone()).collect()
+    }
+
+    // debug
+    pub fn print_all_entries(&self) {
+        for (i, key) in self.keys.iter().enumerate() {
+            println!("i: {} - key:      {:?}", i, key);
+            println!("     - value:    {:?}", self.values[i]);
+            println!("     - queue:    {:?}", self.queues[i]);
+            println!("     - priority: {:?}", self.priorities[i]);
+        }
+
+        for (i, key) in self.ghost_keys.iter().enumerate() {
+            println!("ghost i: {} - key:      {:?}", i, key);
+            println!("           - priority: {:?}", self.ghost_priorities[i]);
+        }
+    }
+}
+
+/// 2Q (A1in fifo, A1out ghost keys and Am lru), an entry is promoted to Am when its key is
+/// written again after being evicted from A1in, so that one-time accesses (scans) do not
+/// evict the hot entries.
+/// The inputs of a call are processed in order, see `TwoQCacheModel` for the host equivalent
+#[derive(Debug)]
+pub struct TwoQCacheHandle<T: OpenclCommonOperation> {
+    config: CacheConfig,
+    system: Arc<T>,
+}
+
+impl<T: OpenclCommonOperation> TwoQCacheHandle<T> {
+    pub fn new(config: &CacheConfig, system: Arc<T>) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn debug(&self) -> OpenClResult<TwoQCacheSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let (_, kout) = get_two_q_sizes(self.config.capacity);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+        let values_output_capacity = self.config.value_len * global_work_size;
+        let ghost_keys_output_capacity = self.config.key_len * kout;
+        let meta_output_capacity = 1;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+
+        let queues_output_buf = self.system.create_output_buffer(global_work_size)?;
+        let priorities_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let ghost_keys_output_buf = self
+            .system
+            .create_output_buffer(ghost_keys_output_capacity)?;
+        let ghost_priorities_output_buf = self.system.create_output_buffer(kout)?;
+
+        let meta_output_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_cache_kernel_name(TWO_Q_CACHE_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&queues_output_buf.get_cl_mem())?;
+            kernel.set_arg(&priorities_output_buf.get_cl_mem())?;
+            kernel.set_arg(&ghost_keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&ghost_priorities_output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let queues_output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &queues_output_buf, &[])?;
+
+        let priorities_output = self.system.blocking_enqueue_read_buffer(
+            global_work_size,
+            &priorities_output_buf,
+            &[],
+        )?;
+
+        let ghost_keys_output = self.system.blocking_enqueue_read_buffer(
+            ghost_keys_output_capacity,
+            &ghost_keys_output_buf,
+            &[],
+        )?;
+
+        let ghost_priorities_output =
+            self.system
+                .blocking_enqueue_read_buffer(kout, &ghost_priorities_output_buf, &[])?;
+
+        let meta_output = self.system.blocking_enqueue_read_buffer(
+            meta_output_capacity,
+            &meta_output_buf,
+            &[],
+        )?;
+
+        let keys: Vec<Vec<_>> = keys_output
+            .chunks(self.config.key_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        let values: Vec<Vec<_>> = values_output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        let ghost_keys: Vec<Vec<_>> = ghost_keys_output
+            .chunks(self.config.key_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok(TwoQCacheSnapshot::new(
+            meta_output[0],
+            keys,
+            values,
+            queues_output,
+            priorities_output,
+            ghost_keys,
+            ghost_priorities_output,
+        ))
+    }
+
+    pub fn print(&self) -> OpenClResult<TwoQCacheSnapshot> {
+        let cs = self.debug()?;
+        println!(
+            "
+TwoQCacheSnapshot (
+   tick:             {},
+   queues:           {:?}
+   priorities:       {:?}
+   ghost_priorities: {:?}
+)
+        ",
+            cs.tick, cs.queues, cs.priorities, cs.ghost_priorities
+        );
+
+        cs.print_all_entries();
+
+        Ok(cs)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_cache_kernel_name(TWO_Q_CACHE_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        };
+
+        Ok(())
+    }
+
+    pub fn initialize(&self) -> OpenClResult<()> {
+        self.reset()
+    }
+
+    pub fn put(&self, keys: &CacheKeys, values: &CacheValues) -> OpenClResult<CacheIndices> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN,
+            ));
+        }
+
+        let total = keys.len();
+
+        let keys_input_capacity = self.config.key_len * total;
+        let values_input_capacity = self.config.value_len * total;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let mut values_input: Vec<_> = Vec::with_capacity(values_input_capacity);
+
+        for b in values {
+            let mut v = ensure_vec_size(b, self.config.value_len);
+            values_input.append(&mut v);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_input_buf = self.system.blocking_prepare_input_buffer(&values_input)?;
+
+        let indices_output_buf = self.system.create_output_buffer(total)?;
+
+        let total_input = total as cl_int;
+
+        let kernel_name = get_cache_kernel_name(TWO_Q_CACHE_PUT, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&total_input)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let indices_output =
+            self.system
+                .blocking_enqueue_read_buffer(total, &indices_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("indices_output {indices_output:?}");
+        }
+
+        Ok(indices_output)
+    }
+
+    pub fn get(&self, keys: &CacheKeys) -> OpenClResult<(CacheValues, CacheIndices)> {
+        let total = keys.len();
+
+        let keys_input_capacity = self.config.key_len * total;
+        let values_output_capacity = self.config.value_len * total;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut k = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut k);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+
+        let indices_output_buf = self.system.create_output_buffer(total)?;
+
+        let total_input = total as cl_int;
+
+        let kernel_name = get_cache_kernel_name(TWO_Q_CACHE_GET, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&total_input)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let indices_output =
+            self.system
+                .blocking_enqueue_read_buffer(total, &indices_output_buf, &[])?;
+
+        let values: Vec<Vec<_>> = values_output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok((values, indices_output))
+    }
+}
+
+#[cfg(test)]
+mod tests_two_q_cache_reset {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn cache_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_two_q(16, 32, 8);
+        cache_src.add_two_q(64, 64, 16);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_two_q_by_id(0).unwrap();
+        let cache = TwoQCacheHandle::new(config, system);
+
+        let r = cache.reset();
+        assert!(r.is_ok());
+
+        assert_eq!(
+            cache.print().unwrap(),
+            TwoQCacheSnapshot::create_empty(16, 32, 8)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_two_q_cache_put {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn scan_does_not_evict_hot_entries() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_two_q(8, 8, 8);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_two_q_by_id(0).unwrap();
+        let cache = TwoQCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let to_keys = |v: Vec<cl_int>| -> CacheKeys { v.into_iter().map(|x| vec![x; 8]).collect() };
+
+        let hot = to_keys(vec![1, 2]);
+        cache.put(&hot, &hot).unwrap();
+
+        let cold = to_keys((10..18).collect());
+        cache.put(&cold, &cold).unwrap();
+
+        // 1 and 2 were evicted from A1in, the second write promotes them to Am
+        cache.put(&hot, &hot).unwrap();
+
+        let scan = to_keys((100..120).collect());
+        cache.put(&scan, &scan).unwrap();
+
+        let (values, indices) = cache.get(&hot).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(values, hot);
+
+        let cs = cache.print().unwrap();
+        assert_eq!(cs.get_queue_keys(TWO_Q_AM), hot);
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_two_q(16, 16, 8);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_two_q_by_id(0).unwrap();
+        let cache = TwoQCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let keys = vec![vec![1; 16]; 4];
+        let values = vec![vec![1; 16]; 3];
+
+        assert_eq!(
+            cache.put(&keys, &values),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_two_q_cache_model {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::cache::model::TwoQCacheModel;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::skewed_keys;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn skewed_workload() {
+        let cache_capacity = 16;
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_two_q(8, 8, cache_capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_two_q_by_id(0).unwrap();
+        let cache = TwoQCacheHandle::new(config, system);
+        cache.initialize().unwrap();
+
+        let mut model = TwoQCacheModel::new(config);
+
+        for seed in 0..8 {
+            let keys: CacheKeys = skewed_keys(64, 64, seed)
+                .into_iter()
+                .map(|x| vec![x; 8])
+                .collect();
+
+            if seed % 2 == 0 {
+                let values: CacheValues = keys.iter().map(|x| vec![x[0] + 100; 8]).collect();
+
+                let indices = cache.put(&keys, &values).unwrap();
+                assert_eq!(indices, model.put(&keys, &values).unwrap());
+            } else {
+                let result = cache.get(&keys).unwrap();
+                assert_eq!(result, model.get(&keys));
+            }
+
+            assert_eq!(&cache.debug().unwrap(), model.snapshot());
+        }
+    }
+}
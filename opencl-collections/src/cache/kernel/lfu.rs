@@ -0,0 +1,313 @@
+use crate::cache::config::{CacheSrc, CacheType, LFU_AGING_FACTOR};
+use crate::cache::kernel::common_replace;
+use crate::config::ClTypeDefault;
+
+const GLOBALS: &str = r#"
+    __global int lfu_tick__CACHE_ID = 0;
+    __global int lfu_accesses__CACHE_ID = 0;
+
+    __global int lfu_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
+    __global int lfu_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+    __global int lfu_frequencies__CACHE_ID[CACHE_CAPACITY];
+    __global int lfu_last_used__CACHE_ID[CACHE_CAPACITY];
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int lfu_find_key__CACHE_ID(int key_input_index, int* key) {
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+
+            if (lfu_frequencies__CACHE_ID[i] == 0) {
+                continue;
+            }
+
+            int exist_index = i;
+
+            for (int key_index = 0; key_index < KEY_LEN; key_index++) {
+                if (lfu_keys__CACHE_ID[i][key_index] != key[key_index + key_input_index]) {
+                    exist_index = -1;
+                    break;
+                }
+            }
+
+            if (exist_index >= 0) {
+                return exist_index;
+            }
+        }
+
+        return -1;
+    }
+
+    // empty entry or the entry with the lowest frequency (the least recently used on ties)
+    int lfu_victim__CACHE_ID() {
+        int entry_index = 0;
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+            if (lfu_frequencies__CACHE_ID[i] == 0) {
+                return i;
+            }
+
+            if (lfu_frequencies__CACHE_ID[i] < lfu_frequencies__CACHE_ID[entry_index]) {
+                entry_index = i;
+                continue;
+            }
+
+            if (lfu_frequencies__CACHE_ID[i] == lfu_frequencies__CACHE_ID[entry_index]
+                && lfu_last_used__CACHE_ID[i] < lfu_last_used__CACHE_ID[entry_index]) {
+                entry_index = i;
+            }
+        }
+
+        return entry_index;
+    }
+
+    void lfu_touch__CACHE_ID(int entry_index) {
+        lfu_tick__CACHE_ID++;
+
+        if (lfu_frequencies__CACHE_ID[entry_index] < INT_MAX) {
+            lfu_frequencies__CACHE_ID[entry_index]++;
+        }
+
+        lfu_last_used__CACHE_ID[entry_index] = lfu_tick__CACHE_ID;
+    }
+
+    // aging, halve the frequencies (an entry keeps at least 1)
+    void lfu_access__CACHE_ID() {
+        lfu_accesses__CACHE_ID++;
+
+        if (lfu_accesses__CACHE_ID < LFU_AGING_PERIOD) {
+            return;
+        }
+
+        lfu_accesses__CACHE_ID = 0;
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+            int frequency = lfu_frequencies__CACHE_ID[i];
+
+            if (frequency > 0) {
+                lfu_frequencies__CACHE_ID[i] = max(1, frequency >> 1);
+            }
+        }
+    }
+
+    void lfu_set_key__CACHE_ID(int entry_index, int key_input_index, int* key) {
+        for (int index = 0; index < KEY_LEN; index++) {
+            lfu_keys__CACHE_ID[entry_index][index] = key[index + key_input_index];
+        }
+    }
+
+    void lfu_set_value__CACHE_ID(int entry_index, int value_input_index, int* value) {
+        for (int index = 0; index < VALUE_LEN; index++) {
+            lfu_values__CACHE_ID[entry_index][index] = value[index + value_input_index];
+        }
+    }
+
+    void lfu_get_value__CACHE_ID(int entry_index, int output_index, int* value) {
+        for (int index = 0; index < VALUE_LEN; index++) {
+            value[output_index + index] = lfu_values__CACHE_ID[entry_index][index];
+        }
+    }
+
+    void lfu_set_default_output_value__CACHE_ID(int output_index, int* value) {
+        for (int index = 0; index < VALUE_LEN; index++) {
+            value[output_index + index] = CL_DEFAULT_VALUE;
+        }
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void lfu_reset__CACHE_ID() {
+        int i = get_global_id(0);
+
+        if (i == 0) {
+            lfu_tick__CACHE_ID = 0;
+            lfu_accesses__CACHE_ID = 0;
+        }
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            lfu_keys__CACHE_ID[i][index] = CL_DEFAULT_VALUE;
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            lfu_values__CACHE_ID[i][index] = CL_DEFAULT_VALUE;
+        }
+
+        lfu_frequencies__CACHE_ID[i] = 0;
+        lfu_last_used__CACHE_ID[i] = 0;
+    }
+
+    kernel void lfu_debug__CACHE_ID(
+        global int* keys_output,
+        global int* values_output,
+        global int* frequencies_output,
+        global int* last_used_output,
+        global int* meta_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_output_index = i * KEY_LEN;
+        int value_output_index = i * VALUE_LEN;
+
+        if (i == 0) {
+            meta_output[0] = lfu_tick__CACHE_ID;
+            meta_output[1] = lfu_accesses__CACHE_ID;
+        }
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            keys_output[index + key_output_index] = lfu_keys__CACHE_ID[i][index];
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            values_output[index + value_output_index] = lfu_values__CACHE_ID[i][index];
+        }
+
+        frequencies_output[i] = lfu_frequencies__CACHE_ID[i];
+        last_used_output[i] = lfu_last_used__CACHE_ID[i];
+    }
+
+    // global work size = 1, the inputs are processed in order
+    kernel void lfu_put__CACHE_ID(
+        const int total,
+        global int* keys_input,
+        global int* values_input,
+        global int* indices_output
+        ) {
+
+        for (int i = 0; i < total; i++) {
+            int key_input_index = i * KEY_LEN;
+            int value_input_index = i * VALUE_LEN;
+
+            int entry_index = lfu_find_key__CACHE_ID(key_input_index, keys_input);
+
+            if (entry_index < 0) {
+                entry_index = lfu_victim__CACHE_ID();
+
+                lfu_set_key__CACHE_ID(entry_index, key_input_index, keys_input);
+                lfu_frequencies__CACHE_ID[entry_index] = 0;
+            }
+
+            lfu_set_value__CACHE_ID(entry_index, value_input_index, values_input);
+            lfu_touch__CACHE_ID(entry_index);
+            lfu_access__CACHE_ID();
+
+            indices_output[i] = entry_index;
+        }
+    }
+
+    // global work size = 1, the inputs are processed in order
+    kernel void lfu_get__CACHE_ID(
+        const int total,
+        global int* keys_input,
+        global int* values_output,
+        global int* indices_output
+        ) {
+
+        for (int i = 0; i < total; i++) {
+            int key_input_index = i * KEY_LEN;
+            int value_output_index = i * VALUE_LEN;
+
+            int entry_index = lfu_find_key__CACHE_ID(key_input_index, keys_input);
+
+            if (entry_index >= 0) {
+                lfu_get_value__CACHE_ID(entry_index, value_output_index, values_output);
+                lfu_touch__CACHE_ID(entry_index);
+            } else {
+                lfu_set_default_output_value__CACHE_ID(value_output_index, values_output);
+            }
+
+            lfu_access__CACHE_ID();
+
+            indices_output[i] = entry_index;
+        }
+    }
+    "#;
+
+impl CacheSrc {
+    pub fn generate_cache_lfu_program_source(&self) -> String {
+        let blocks = self.get_configs_by_type(CacheType::LFU);
+
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+
+        let mut base_functions = String::new();
+
+        let mut base_kernels = String::new();
+
+        for config in blocks {
+            let aging_period = config.capacity * LFU_AGING_FACTOR;
+
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config)
+                .replace("LFU_AGING_PERIOD", &aging_period.to_string())
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config)
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
+            base_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// *** LFU SRC *** ///
+
+    /// constants
+    // ...
+
+    /// globals
+    {globals}
+
+    /// kernels
+
+    {base_functions}
+
+    {base_kernels}
+
+    /// *** LFU SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(8, 256, 256);
+
+        let program_source = cache_src.generate_cache_lfu_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int lfu_frequencies__0[256];"));
+        assert!(program_source.contains("if (lfu_accesses__0 < 1024)"));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(8, 256, 256);
+        cache_src.add_lfu(32, 512, 64);
+        cache_src.add_lru(16, 256, 128);
+
+        let program_source = cache_src.generate_cache_lfu_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("kernel void lfu_put__1("));
+        assert!(!program_source.contains("lfu_put__2"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 256, 512);
+
+        let program_source = cache_src.generate_cache_lfu_program_source();
+        assert!(program_source.is_empty());
+    }
+}
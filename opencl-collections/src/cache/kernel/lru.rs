@@ -1,4 +1,4 @@
-use crate::cache::config::{CacheSrc, CacheType};
+use crate::cache::config::{CacheSrc, CacheType, LRU_STATS_LEN};
 use crate::cache::kernel::common_replace;
 use crate::config::ClTypeDefault;
 
@@ -17,6 +17,10 @@ const GLOBALS: &str = r#"
     __global int lru_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
     __global int lru_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
     __global int lru_priorities__CACHE_ID[CACHE_CAPACITY];
+
+    // real lengths of the keys and values
+    __global int lru_key_sizes__CACHE_ID[CACHE_CAPACITY];
+    __global int lru_value_sizes__CACHE_ID[CACHE_CAPACITY];
     
     __global int lru_array_set__CACHE_ID[CACHE_CAPACITY];
     __global int lru_array_set_entries__CACHE_ID[CACHE_CAPACITY];
@@ -24,8 +28,13 @@ const GLOBALS: &str = r#"
     // tmp (sort)
     __global int lru_tmp_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
     __global int lru_tmp_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+    __global int lru_tmp_key_sizes__CACHE_ID[CACHE_CAPACITY];
+    __global int lru_tmp_value_sizes__CACHE_ID[CACHE_CAPACITY];
     
     __global struct LruEntry lru_sort_entries__CACHE_ID[CACHE_CAPACITY];
+
+    // hits, misses, inserts, evictions
+    __global int lru_stats__CACHE_ID[LRU_STATS_LEN];
     
     "#;
 
@@ -125,10 +134,15 @@ const BASE_FUNCTIONS: &str = r#"
         return SET_FULL_DEF_2;
     }
 
-    int check_if_lru_key_exists__CACHE_ID(int key_input_index, int* key) {
+    int check_if_lru_key_exists__CACHE_ID(int key_input_index, int* key, int key_size) {
 
         for (int i = 0; i < CACHE_CAPACITY; i++) {
 
+            // [1] and [1, 0] are different keys
+            if (lru_key_sizes__CACHE_ID[i] != key_size) {
+                continue;
+            }
+
             int exist_index = i;
 
             for (int key_index = 0; key_index < KEY_LEN; key_index++) {
@@ -186,15 +200,65 @@ const BASE_FUNCTIONS: &str = r#"
         }
 
     }
+
+    void lru_set_default_output_key__CACHE_ID(int output_index, int* key) {
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            key[output_index + index] = CL_DEFAULT_VALUE;
+        }
+
+    }
+
+    // copy the entry before it is overwritten
+    void lru_log_eviction__CACHE_ID(
+        int entry_index,
+        int input_index,
+        int log_evictions,
+        int* evicted_keys,
+        int* evicted_values,
+        int* evicted_sizes
+        ) {
+
+        atomic_fetch_add(&lru_stats__CACHE_ID[LRU_STATS_EVICTIONS], 1);
+
+        if (log_evictions) {
+            lru_get_key__CACHE_ID(entry_index, input_index * KEY_LEN, evicted_keys);
+            lru_get_value__CACHE_ID(entry_index, input_index * VALUE_LEN, evicted_values);
+
+            evicted_sizes[input_index * 2] = lru_key_sizes__CACHE_ID[entry_index];
+            evicted_sizes[input_index * 2 + 1] = lru_value_sizes__CACHE_ID[entry_index];
+        }
+
+    }
     
+    void lru_set_sizes__CACHE_ID(int entry_index, int key_size, int value_size) {
+        lru_key_sizes__CACHE_ID[entry_index] = key_size;
+        lru_value_sizes__CACHE_ID[entry_index] = value_size;
+    }
+
     int lru_insert__CACHE_ID(
         int key_input_index,
         int value_input_index,
         int* key,
-        int* value
+        int* value,
+        int key_size,
+        int value_size,
+        int input_index,
+        int log_evictions,
+        int* evicted_keys,
+        int* evicted_values,
+        int* evicted_sizes
         ) {
+
+        if (log_evictions) {
+            lru_set_default_output_key__CACHE_ID(input_index * KEY_LEN, evicted_keys);
+            lru_set_default_output_value__CACHE_ID(input_index * VALUE_LEN, evicted_values);
+
+            evicted_sizes[input_index * 2] = 0;
+            evicted_sizes[input_index * 2 + 1] = 0;
+        }
         
-        int entry_index = check_if_lru_key_exists__CACHE_ID(key_input_index, key);
+        int entry_index = check_if_lru_key_exists__CACHE_ID(key_input_index, key, key_size);
 
         // exist in cache
         if (entry_index >= 0) {
@@ -202,6 +266,7 @@ const BASE_FUNCTIONS: &str = r#"
             int current_priority = atomic_fetch_add(&lru_last_priority__CACHE_ID, 1);
 
             lru_set_value__CACHE_ID(entry_index, value_input_index, value);
+            lru_set_sizes__CACHE_ID(entry_index, key_size, value_size);
             lru_priorities__CACHE_ID[entry_index] = current_priority;
 
         } else {
@@ -216,7 +281,10 @@ const BASE_FUNCTIONS: &str = r#"
     
                    lru_set_key__CACHE_ID(entry_index, key_input_index, key);
                    lru_set_value__CACHE_ID(entry_index, value_input_index, value);
+                   lru_set_sizes__CACHE_ID(entry_index, key_size, value_size);
                    lru_priorities__CACHE_ID[entry_index] = current_priority;
+
+                   atomic_fetch_add(&lru_stats__CACHE_ID[LRU_STATS_INSERTS], 1);
                    
                    return entry_index;
                 }
@@ -271,9 +339,24 @@ const BASE_FUNCTIONS: &str = r#"
                
                 if (r == SET_VALUE_NO_EXIST_DEF_2) {
                    int current_priority = atomic_fetch_add(&lru_last_priority__CACHE_ID, 1);
+
+                   // a slot freed by remove has priority 0, there is nothing to evict
+                   if (lru_priorities__CACHE_ID[entry_index] > 0) {
+                       lru_log_eviction__CACHE_ID(
+                           entry_index,
+                           input_index,
+                           log_evictions,
+                           evicted_keys,
+                           evicted_values,
+                           evicted_sizes
+                       );
+                   }
+
+                   atomic_fetch_add(&lru_stats__CACHE_ID[LRU_STATS_INSERTS], 1);
     
                    lru_set_key__CACHE_ID(entry_index, key_input_index, key);
                    lru_set_value__CACHE_ID(entry_index, value_input_index, value);
+                   lru_set_sizes__CACHE_ID(entry_index, key_size, value_size);
                    lru_priorities__CACHE_ID[entry_index] = current_priority;
                    
                    break;
@@ -291,23 +374,67 @@ const BASE_FUNCTIONS: &str = r#"
         return entry_index;
     }
     
+    void lru_remove_entry__CACHE_ID(int entry_index) {
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            lru_keys__CACHE_ID[entry_index][index] = CL_DEFAULT_VALUE;
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            lru_values__CACHE_ID[entry_index][index] = CL_DEFAULT_VALUE;
+        }
+
+        lru_priorities__CACHE_ID[entry_index] = 0;
+        lru_set_sizes__CACHE_ID(entry_index, 0, 0);
+
+        int set_index = lru_array_set_get_index__CACHE_ID(&entry_index);
+
+        if (set_index >= 0) {
+            lru_array_set__CACHE_ID[set_index] = -1;
+            lru_array_set_entries__CACHE_ID[set_index] = 0;
+        }
+
+    }
+
+    int lru_match_priority__CACHE_ID(int priority, int predicate, int value) {
+
+        if (predicate == PRIORITY_LESS_THAN_DEF_2) {
+            return priority < value;
+        }
+
+        if (predicate == PRIORITY_GREATER_THAN_DEF_2) {
+            return priority > value;
+        }
+
+        return 0;
+    }
+
     int lru_get__CACHE_ID(
         int key_input_index,
         int value_output_index,
         int* key,
-        int* value
+        int key_size,
+        int* value,
+        int* value_size
         ) {
-        int entry_index = check_if_lru_key_exists__CACHE_ID(key_input_index, key);
+        int entry_index = check_if_lru_key_exists__CACHE_ID(key_input_index, key, key_size);
+
+        *value_size = 0;
 
         if (entry_index >= 0) {
 
             int current_priority = atomic_fetch_add(&lru_last_priority__CACHE_ID, 1);
 
             lru_get_value__CACHE_ID(entry_index, value_output_index, value);
+            *value_size = lru_value_sizes__CACHE_ID[entry_index];
             lru_priorities__CACHE_ID[entry_index] = current_priority;
 
+            atomic_fetch_add(&lru_stats__CACHE_ID[LRU_STATS_HITS], 1);
+
         } else {
             lru_set_default_output_value__CACHE_ID(value_output_index, value);
+
+            atomic_fetch_add(&lru_stats__CACHE_ID[LRU_STATS_MISSES], 1);
         }
 
         return entry_index;
@@ -327,6 +454,8 @@ const BASE_KERNELS: &str = r#"
         }
         
         lru_priorities__CACHE_ID[i] = 0;
+        lru_key_sizes__CACHE_ID[i] = 0;
+        lru_value_sizes__CACHE_ID[i] = 0;
         
         lru_array_set__CACHE_ID[i] = -1;
         lru_array_set_entries__CACHE_ID[i] = 0;
@@ -334,9 +463,27 @@ const BASE_KERNELS: &str = r#"
         if (i == 0) {
             lru_last_priority__CACHE_ID = 1;
             lru_top__CACHE_ID = 0;
+
+            for (int index = 0; index < LRU_STATS_LEN; index++) {
+                lru_stats__CACHE_ID[index] = 0;
+            }
         }
 
     }
+
+    kernel void lru_reset_stats__CACHE_ID() {
+        int i = get_global_id(0);
+
+        lru_stats__CACHE_ID[i] = 0;
+    }
+
+    kernel void lru_get_stats__CACHE_ID(
+        global int* stats_output
+        ) {
+        int i = get_global_id(0);
+
+        stats_output[i] = lru_stats__CACHE_ID[i];
+    }
     
     kernel void lru_array_set_reset__CACHE_ID() {
         int i = get_global_id(0);
@@ -350,13 +497,17 @@ const BASE_KERNELS: &str = r#"
         global int* values_output,
         global int* priorities_output,
         global int* meta_output,
-        global int* set_items_output
+        global int* set_items_output,
+        global int* sizes_output
         ) {
 
         int i = get_global_id(0);
         int key_output_index = i * KEY_LEN;
         int value_output_index = i * VALUE_LEN;
 
+        sizes_output[i] = lru_key_sizes__CACHE_ID[i];
+        sizes_output[i + CACHE_CAPACITY] = lru_value_sizes__CACHE_ID[i];
+
         for (int index = 0; index < KEY_LEN; index++) {
             keys_output[index + key_output_index] = lru_keys__CACHE_ID[i][index];
         }
@@ -378,12 +529,15 @@ const BASE_KERNELS: &str = r#"
     
     kernel void lru_get_keys__CACHE_ID(
         global int* keys_output,
-        global int* priorities_output
+        global int* priorities_output,
+        global int* key_sizes_output
         ) {
 
         int i = get_global_id(0);
         int key_output_index = i * KEY_LEN;
 
+        key_sizes_output[i] = lru_key_sizes__CACHE_ID[i];
+
         for (int index = 0; index < KEY_LEN; index++) {
             keys_output[index + key_output_index] = lru_keys__CACHE_ID[i][index];
         }
@@ -436,6 +590,10 @@ const BASE_KERNELS: &str = r#"
                        for (int index = 0; index < VALUE_LEN; index++) {
                            lru_tmp_values__CACHE_ID[i][index] = lru_values__CACHE_ID[i][index];
                        }
+
+                       // sizes
+                       lru_tmp_key_sizes__CACHE_ID[i] = lru_key_sizes__CACHE_ID[i];
+                       lru_tmp_value_sizes__CACHE_ID[i] = lru_value_sizes__CACHE_ID[i];
                        
                     }
                 );
@@ -485,6 +643,10 @@ const BASE_KERNELS: &str = r#"
                        lru_values__CACHE_ID[to_entry_index][index] = lru_tmp_values__CACHE_ID[i][index];
                    }
 
+                   // sizes
+                   lru_key_sizes__CACHE_ID[to_entry_index] = lru_tmp_key_sizes__CACHE_ID[i];
+                   lru_value_sizes__CACHE_ID[to_entry_index] = lru_tmp_value_sizes__CACHE_ID[i];
+
                }
                
             }
@@ -510,6 +672,7 @@ const BASE_KERNELS: &str = r#"
         const uint capacity_device_local_work_size,
         global int* keys_output,
         global int* priorities_output,
+        global int* key_sizes_output,
         global int* enqueue_kernel_output
         ) {
         
@@ -553,6 +716,7 @@ const BASE_KERNELS: &str = r#"
                 }
                 
                 priorities_output[i] = lru_priorities__CACHE_ID[i];
+                key_sizes_output[i] = lru_key_sizes__CACHE_ID[i];
             }
         );
     
@@ -562,9 +726,15 @@ const BASE_KERNELS: &str = r#"
     }
     
     kernel void lru_put__CACHE_ID(
+        const int log_evictions,
         global int* keys_input,
         global int* values_input,
-        global int* priorities_input
+        global int* priorities_input,
+        global int* key_sizes_input,
+        global int* value_sizes_input,
+        global int* evicted_keys_output,
+        global int* evicted_values_output,
+        global int* evicted_sizes_output
         ) {
 
         int i = get_global_id(0);
@@ -572,6 +742,37 @@ const BASE_KERNELS: &str = r#"
         int key_input_index = i * KEY_LEN;
         int value_input_index = i * VALUE_LEN;
 
+        if (log_evictions) {
+            lru_set_default_output_key__CACHE_ID(key_input_index, evicted_keys_output);
+            lru_set_default_output_value__CACHE_ID(value_input_index, evicted_values_output);
+
+            evicted_sizes_output[i * 2] = 0;
+            evicted_sizes_output[i * 2 + 1] = 0;
+        }
+
+        int same_key = lru_key_sizes__CACHE_ID[i] == key_sizes_input[i];
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            if (lru_keys__CACHE_ID[i][index] != keys_input[index + key_input_index]) {
+                same_key = 0;
+                break;
+            }
+        }
+
+        // the slot holds another entry
+        if (lru_priorities__CACHE_ID[i] > 0 && !same_key) {
+            lru_log_eviction__CACHE_ID(
+                i,
+                i,
+                log_evictions,
+                evicted_keys_output,
+                evicted_values_output,
+                evicted_sizes_output
+            );
+        }
+
+        atomic_fetch_add(&lru_stats__CACHE_ID[LRU_STATS_INSERTS], 1);
+
         for (int index = 0; index < KEY_LEN; index++) {
             lru_keys__CACHE_ID[i][index] = keys_input[index + key_input_index];
         }
@@ -581,6 +782,7 @@ const BASE_KERNELS: &str = r#"
         }
         
         lru_priorities__CACHE_ID[i] = priorities_input[i];
+        lru_set_sizes__CACHE_ID(i, key_sizes_input[i], value_sizes_input[i]);
 
         if (i == 0) {
             lru_last_priority__CACHE_ID = global_size + 1;
@@ -591,9 +793,15 @@ const BASE_KERNELS: &str = r#"
     kernel void write_in_lru__CACHE_ID(
         const uint key_len,
         const uint value_len,
+        const int log_evictions,
         global int* keys_input,
         global int* values_input,
-        global int* indices_output
+        global int* key_sizes_input,
+        global int* value_sizes_input,
+        global int* indices_output,
+        global int* evicted_keys_output,
+        global int* evicted_values_output,
+        global int* evicted_sizes_output
         ) {
 
         int i = get_global_id(0);
@@ -604,16 +812,76 @@ const BASE_KERNELS: &str = r#"
             key_input_index,
             value_input_index,
             keys_input,
-            values_input
+            values_input,
+            key_sizes_input[i],
+            value_sizes_input[i],
+            i,
+            log_evictions,
+            evicted_keys_output,
+            evicted_values_output,
+            evicted_sizes_output
         );
     }
 
+    kernel void lru_remove__CACHE_ID(
+        global int* keys_input,
+        global int* key_sizes_input,
+        global int* indices_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_input_index = i * KEY_LEN;
+
+        int entry_index = check_if_lru_key_exists__CACHE_ID(
+            key_input_index,
+            keys_input,
+            key_sizes_input[i]
+        );
+
+        // empty slots are not entries
+        if (entry_index >= 0 && lru_priorities__CACHE_ID[entry_index] > 0) {
+            lru_remove_entry__CACHE_ID(entry_index);
+        } else {
+            entry_index = -1;
+        }
+
+        indices_output[i] = entry_index;
+    }
+
+    kernel void lru_remove_where__CACHE_ID(
+        const int predicate,
+        const int value,
+        global int* keys_output,
+        global int* priorities_output,
+        global int* key_sizes_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_output_index = i * KEY_LEN;
+
+        int priority = lru_priorities__CACHE_ID[i];
+
+        lru_set_default_output_key__CACHE_ID(key_output_index, keys_output);
+        priorities_output[i] = 0;
+        key_sizes_output[i] = 0;
+
+        if (priority > 0 && lru_match_priority__CACHE_ID(priority, predicate, value)) {
+            lru_get_key__CACHE_ID(i, key_output_index, keys_output);
+            priorities_output[i] = priority;
+            key_sizes_output[i] = lru_key_sizes__CACHE_ID[i];
+
+            lru_remove_entry__CACHE_ID(i);
+        }
+    }
+
     kernel void read_on_lru__CACHE_ID(
         const uint key_len,
         const uint value_len,
         global int* keys_input,
+        global int* key_sizes_input,
         global int* values_output,
-        global int* indices_output
+        global int* indices_output,
+        global int* value_sizes_output
         ) {
 
         int i = get_global_id(0);
@@ -624,7 +892,9 @@ const BASE_KERNELS: &str = r#"
             key_input_index,
             value_output_index,
             keys_input,
-            values_output
+            key_sizes_input[i],
+            values_output,
+            &value_sizes_output[i]
         );
     }
     "#;
@@ -644,14 +914,17 @@ impl CacheSrc {
         let mut base_kernels = String::new();
 
         for config in blocks {
-            let template = common_replace(GLOBALS, config);
+            let template = common_replace(GLOBALS, config)
+                .replace("LRU_STATS_LEN", &LRU_STATS_LEN.to_string());
             globals.push_str(&template);
 
             let template = common_replace(BASE_FUNCTIONS, config)
                 .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
             base_functions.push_str(&template);
 
-            let template = common_replace(BASE_KERNELS, config);
+            let template = common_replace(BASE_KERNELS, config)
+                .replace("LRU_STATS_LEN", &LRU_STATS_LEN.to_string())
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
             base_kernels.push_str(&template);
         }
 
@@ -667,6 +940,14 @@ impl CacheSrc {
     const int SET_VALUE_NO_EXIST_DEF_2 = 1;
     const int SET_FULL_DEF_2 = 2;
 
+    const int PRIORITY_LESS_THAN_DEF_2 = 0;
+    const int PRIORITY_GREATER_THAN_DEF_2 = 1;
+
+    const int LRU_STATS_HITS = 0;
+    const int LRU_STATS_MISSES = 1;
+    const int LRU_STATS_INSERTS = 2;
+    const int LRU_STATS_EVICTIONS = 3;
+
     /// globals
     {STRUCT_DEF}
 
@@ -710,6 +991,39 @@ mod tests {
         assert!(!program_source.is_empty());
     }
 
+    #[test]
+    fn test_stats() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(8, 256, 256);
+
+        let program_source = cache_src.generate_cache_lru_program_source();
+        assert!(program_source.contains("__global int lru_stats__0[4];"));
+        assert!(program_source.contains("kernel void lru_get_stats__0("));
+        assert!(program_source.contains("kernel void lru_reset_stats__0("));
+        assert!(!program_source.contains("LRU_STATS_LEN"));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(8, 256, 256);
+
+        let program_source = cache_src.generate_cache_lru_program_source();
+        assert!(program_source.contains("kernel void lru_remove__0("));
+        assert!(program_source.contains("kernel void lru_remove_where__0("));
+        assert!(program_source.contains("const int PRIORITY_LESS_THAN_DEF_2 = 0;"));
+    }
+
+    #[test]
+    fn test_sizes() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(8, 256, 128);
+
+        let program_source = cache_src.generate_cache_lru_program_source();
+        assert!(program_source.contains("__global int lru_key_sizes__0[128];"));
+        assert!(program_source.contains("__global int lru_value_sizes__0[128];"));
+    }
+
     #[test]
     fn test_is_empty() {
         let mut cache_src = CacheSrc::new();
@@ -77,7 +77,7 @@ const BASE_FUNCTIONS: &str = r#"
 
         for (int i = 0; i < CACHE_CAPACITY; i++) {
             if ( mini_lru_array_set__CACHE_ID[i] == *k ) {
-                return SET_VALUE_EXIST;
+                return SET_VALUE_EXIST_DEF_1;
             }
         }
 
@@ -90,7 +90,7 @@ const BASE_FUNCTIONS: &str = r#"
             int exist_index = mini_lru_array_set_get_index__CACHE_ID(k);
 
             if (exist_index >= 0) {
-                return SET_VALUE_EXIST;
+                return SET_VALUE_EXIST_DEF_1;
             }
 
             int r = atomic_fetch_add(&mini_lru_array_set_entries__CACHE_ID[i], 1);
@@ -99,13 +99,13 @@ const BASE_FUNCTIONS: &str = r#"
 
                 mini_lru_array_set__CACHE_ID[i] = *k;
 
-                return SET_VALUE_NO_EXIST;
+                return SET_VALUE_NO_EXIST_DEF_1;
 
             }
 
         }
 
-        return SET_FULL;
+        return SET_FULL_DEF_1;
     }
 
     int check_if_mini_lru_key_exists__CACHE_ID(int* k) {
@@ -195,11 +195,11 @@ const BASE_FUNCTIONS: &str = r#"
                            
                 int r = mini_lru_array_set_insert__CACHE_ID(&entry_index);
                 
-                // if (r == SET_VALUE_EXIST) {
+                // if (r == SET_VALUE_EXIST_DEF_1) {
                 //    ...
                 // }
                
-                if (r == SET_VALUE_NO_EXIST) {
+                if (r == SET_VALUE_NO_EXIST_DEF_1) {
                    int current_priority = atomic_fetch_add(&mini_lru_last_priority__CACHE_ID, 1);
     
                    mini_lru_keys__CACHE_ID[entry_index] = *key;
@@ -209,7 +209,7 @@ const BASE_FUNCTIONS: &str = r#"
                    break;
                 }
                 
-                if (r == SET_FULL) {
+                if (r == SET_FULL_DEF_1) {
                   entry_index = -500;
                   break;
                 }
@@ -221,6 +221,34 @@ const BASE_FUNCTIONS: &str = r#"
         return entry_index;
     }
     
+    void mini_lru_remove_entry__CACHE_ID(int entry_index) {
+
+        mini_lru_keys__CACHE_ID[entry_index] = -1;
+        mini_lru_values__CACHE_ID[entry_index] = -1;
+        mini_lru_priorities__CACHE_ID[entry_index] = 0;
+
+        int set_index = mini_lru_array_set_get_index__CACHE_ID(&entry_index);
+
+        if (set_index >= 0) {
+            mini_lru_array_set__CACHE_ID[set_index] = -1;
+            mini_lru_array_set_entries__CACHE_ID[set_index] = 0;
+        }
+
+    }
+
+    int mini_lru_match_priority__CACHE_ID(int priority, int predicate, int value) {
+
+        if (predicate == PRIORITY_LESS_THAN_DEF_1) {
+            return priority < value;
+        }
+
+        if (predicate == PRIORITY_GREATER_THAN_DEF_1) {
+            return priority > value;
+        }
+
+        return 0;
+    }
+
     int mini_lru_get__CACHE_ID(
         int* key,
         int* value
@@ -377,6 +405,45 @@ const BASE_KERNELS: &str = r#"
         );
     }
 
+    kernel void mini_lru_remove__CACHE_ID(
+        global int* keys_input,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        int entry_index = check_if_mini_lru_key_exists__CACHE_ID(&keys_input[i]);
+
+        // empty slots are not entries
+        if (entry_index >= 0 && mini_lru_priorities__CACHE_ID[entry_index] > 0) {
+            mini_lru_remove_entry__CACHE_ID(entry_index);
+        } else {
+            entry_index = -1;
+        }
+
+        indices_output[i] = entry_index;
+    }
+
+    kernel void mini_lru_remove_where__CACHE_ID(
+        const int predicate,
+        const int value,
+        global int* keys_output,
+        global int* priorities_output
+        ) {
+        int i = get_global_id(0);
+
+        int priority = mini_lru_priorities__CACHE_ID[i];
+
+        keys_output[i] = -1;
+        priorities_output[i] = 0;
+
+        if (priority > 0 && mini_lru_match_priority__CACHE_ID(priority, predicate, value)) {
+            keys_output[i] = mini_lru_keys__CACHE_ID[i];
+            priorities_output[i] = priority;
+
+            mini_lru_remove_entry__CACHE_ID(i);
+        }
+    }
+
     kernel void read_on_mini_lru__CACHE_ID(
         global int* keys_input,
         global int* values_output,
@@ -427,9 +494,12 @@ impl CacheSrc {
     const int PUT_MINI_LRU = 0;
     const int GET_MINI_LRU = 1;
 
-    const int SET_VALUE_EXIST = 0;
-    const int SET_VALUE_NO_EXIST = 1;
-    const int SET_FULL = 2;
+    const int SET_VALUE_EXIST_DEF_1 = 0;
+    const int SET_VALUE_NO_EXIST_DEF_1 = 1;
+    const int SET_FULL_DEF_1 = 2;
+
+    const int PRIORITY_LESS_THAN_DEF_1 = 0;
+    const int PRIORITY_GREATER_THAN_DEF_1 = 1;
 
     /// globals
     {globals}
@@ -474,6 +544,17 @@ mod tests {
         assert!(!program_source.is_empty());
     }
 
+    #[test]
+    fn test_remove() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(8);
+
+        let program_source = cache_src.generate_cache_mini_lru_program_source();
+        assert!(program_source.contains("kernel void mini_lru_remove__0("));
+        assert!(program_source.contains("kernel void mini_lru_remove_where__0("));
+        assert!(program_source.contains("const int PRIORITY_LESS_THAN_DEF_1 = 0;"));
+    }
+
     #[test]
     fn test_is_empty() {
         let cache_src = CacheSrc::new();
@@ -1,7 +1,10 @@
 use crate::cache::config::{CacheConfig, CacheSrc};
 
+mod lfu;
 mod lru;
 mod mini_lru;
+mod ttl;
+mod two_q;
 
 pub mod name;
 
@@ -22,6 +25,12 @@ impl CacheSrc {
 
         let lru_cache_src = self.generate_cache_lru_program_source();
 
+        let ttl_cache_src = self.generate_cache_ttl_program_source();
+
+        let lfu_cache_src = self.generate_cache_lfu_program_source();
+
+        let two_q_cache_src = self.generate_cache_two_q_program_source();
+
         format!(
             "
     /// *** CACHE SRC *** ///
@@ -30,6 +39,12 @@ impl CacheSrc {
 
     {lru_cache_src}
 
+    {ttl_cache_src}
+
+    {lfu_cache_src}
+
+    {two_q_cache_src}
+
     /// *** CACHE SRC *** ///
     "
         )
@@ -67,6 +82,9 @@ mod tests {
         cache_src.add_mini_lru(32);
         cache_src.add_lru(16, 16, 16);
         cache_src.add_lru(64, 32, 64);
+        cache_src.add_ttl(16, 16, 32, 1000);
+        cache_src.add_lfu(16, 16, 32);
+        cache_src.add_two_q(16, 16, 32);
 
         let program_source = cache_src.build();
         println!("{program_source}");
@@ -15,6 +15,9 @@ pub const MINI_LRU_CACHE_SORT: &str = "mini_lru_sort__CACHE_ID";
 
 pub const MINI_LRU_CACHE_PUT: &str = "mini_lru_put__CACHE_ID";
 
+pub const MINI_LRU_CACHE_REMOVE: &str = "mini_lru_remove__CACHE_ID";
+pub const MINI_LRU_CACHE_REMOVE_WHERE: &str = "mini_lru_remove_where__CACHE_ID";
+
 pub const WRITE_IN_MINI_LRU_CACHE: &str = "write_in_mini_lru__CACHE_ID";
 pub const READ_ON_MINI_LRU_CACHE: &str = "read_on_mini_lru__CACHE_ID";
 
@@ -23,6 +26,9 @@ pub const READ_ON_MINI_LRU_CACHE: &str = "read_on_mini_lru__CACHE_ID";
 pub const LRU_CACHE_DEBUG: &str = "lru_debug__CACHE_ID";
 pub const LRU_CACHE_RESET: &str = "lru_reset__CACHE_ID";
 pub const LRU_CACHE_ARRAY_SET_RESET: &str = "lru_array_set_reset__CACHE_ID";
+pub const LRU_CACHE_RESET_STATS: &str = "lru_reset_stats__CACHE_ID";
+
+pub const LRU_CACHE_GET_STATS: &str = "lru_get_stats__CACHE_ID";
 
 pub const LRU_CACHE_GET_KEYS: &str = "lru_get_keys__CACHE_ID";
 pub const LRU_CACHE_GET_SORTED_KEYS: &str = "lru_get_sorted_keys__CACHE_ID";
@@ -32,5 +38,35 @@ pub const LRU_CACHE_DEBUG_SORT: &str = "lru_debug_sort__CACHE_ID";
 
 pub const LRU_CACHE_PUT: &str = "lru_put__CACHE_ID";
 
+pub const LRU_CACHE_REMOVE: &str = "lru_remove__CACHE_ID";
+pub const LRU_CACHE_REMOVE_WHERE: &str = "lru_remove_where__CACHE_ID";
+
 pub const WRITE_IN_LRU_CACHE: &str = "write_in_lru__CACHE_ID";
 pub const READ_ON_LRU_CACHE: &str = "read_on_lru__CACHE_ID";
+
+// TTL
+
+pub const TTL_CACHE_DEBUG: &str = "ttl_debug__CACHE_ID";
+pub const TTL_CACHE_RESET: &str = "ttl_reset__CACHE_ID";
+
+pub const TTL_CACHE_GET_KEYS: &str = "ttl_get_keys__CACHE_ID";
+
+pub const TTL_CACHE_PUT: &str = "ttl_put__CACHE_ID";
+pub const TTL_CACHE_GET: &str = "ttl_get__CACHE_ID";
+pub const TTL_CACHE_PURGE: &str = "ttl_purge__CACHE_ID";
+
+// LFU
+
+pub const LFU_CACHE_DEBUG: &str = "lfu_debug__CACHE_ID";
+pub const LFU_CACHE_RESET: &str = "lfu_reset__CACHE_ID";
+
+pub const LFU_CACHE_PUT: &str = "lfu_put__CACHE_ID";
+pub const LFU_CACHE_GET: &str = "lfu_get__CACHE_ID";
+
+// 2Q
+
+pub const TWO_Q_CACHE_DEBUG: &str = "two_q_debug__CACHE_ID";
+pub const TWO_Q_CACHE_RESET: &str = "two_q_reset__CACHE_ID";
+
+pub const TWO_Q_CACHE_PUT: &str = "two_q_put__CACHE_ID";
+pub const TWO_Q_CACHE_GET: &str = "two_q_get__CACHE_ID";
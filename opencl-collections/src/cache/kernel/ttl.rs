@@ -0,0 +1,398 @@
+use crate::cache::config::{CacheSrc, CacheType};
+use crate::cache::kernel::common_replace;
+use crate::config::ClTypeDefault;
+
+const GLOBALS: &str = r#"
+    // ...
+    __global int ttl_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
+    __global int ttl_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+    __global long ttl_expiry__CACHE_ID[CACHE_CAPACITY];
+    __global int ttl_entries__CACHE_ID[CACHE_CAPACITY];
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    bool is_ttl_entry_alive__CACHE_ID(int entry_index, long now) {
+        return ttl_entries__CACHE_ID[entry_index] == TTL_ENTRY_ASSIGNED
+            && ttl_expiry__CACHE_ID[entry_index] > now;
+    }
+
+    int check_if_ttl_key_exists__CACHE_ID(long now, int key_input_index, int* key) {
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+
+            if (!is_ttl_entry_alive__CACHE_ID(i, now)) {
+                continue;
+            }
+
+            int exist_index = i;
+
+            for (int key_index = 0; key_index < KEY_LEN; key_index++) {
+                if (ttl_keys__CACHE_ID[i][key_index] != key[key_index + key_input_index]) {
+                    exist_index = -1;
+                    break;
+                }
+            }
+
+            if (exist_index >= 0) {
+                return exist_index;
+            }
+
+        }
+
+        return -1;
+    }
+
+    void ttl_set_key__CACHE_ID(int entry_index, int key_input_index, int* key) {
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            ttl_keys__CACHE_ID[entry_index][index] = key[index + key_input_index];
+        }
+
+    }
+
+    void ttl_set_value__CACHE_ID(int entry_index, int value_input_index, int* value) {
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            ttl_values__CACHE_ID[entry_index][index] = value[index + value_input_index];
+        }
+
+    }
+
+    void ttl_get_value__CACHE_ID(int entry_index, int output_index, int* value) {
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            value[output_index + index] = ttl_values__CACHE_ID[entry_index][index];
+        }
+
+    }
+
+    void ttl_set_default_output_value__CACHE_ID(int output_index, int* value) {
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            value[output_index + index] = CL_DEFAULT_VALUE;
+        }
+
+    }
+
+    void ttl_clear_entry__CACHE_ID(int entry_index) {
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            ttl_keys__CACHE_ID[entry_index][index] = CL_DEFAULT_VALUE;
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            ttl_values__CACHE_ID[entry_index][index] = CL_DEFAULT_VALUE;
+        }
+
+        ttl_expiry__CACHE_ID[entry_index] = 0;
+    }
+
+    // empty entry, expired entry or (cache is full) the entry closest to expire
+    int ttl_claim_entry__CACHE_ID(long now) {
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+            int r = atomic_cmpxchg(&ttl_entries__CACHE_ID[i], TTL_ENTRY_EMPTY, TTL_ENTRY_WRITING);
+
+            if (r == TTL_ENTRY_EMPTY) {
+                return i;
+            }
+        }
+
+        // limit
+        for (int counter = 0; counter <= CACHE_MAX_CAPACITY; counter++) {
+
+            int entry_index = -1;
+            long min_expiry = LONG_MAX;
+
+            for (int i = 0; i < CACHE_CAPACITY; i++) {
+                if (ttl_entries__CACHE_ID[i] != TTL_ENTRY_ASSIGNED) {
+                    continue;
+                }
+
+                long expiry = ttl_expiry__CACHE_ID[i];
+
+                if (expiry < min_expiry) {
+                    min_expiry = expiry;
+                    entry_index = i;
+                }
+            }
+
+            if (entry_index < 0) {
+                break;
+            }
+
+            int r = atomic_cmpxchg(&ttl_entries__CACHE_ID[entry_index], TTL_ENTRY_ASSIGNED, TTL_ENTRY_WRITING);
+
+            if (r == TTL_ENTRY_ASSIGNED) {
+                return entry_index;
+            }
+        }
+
+        return TTL_CACHE_FULL;
+    }
+
+    int ttl_insert__CACHE_ID(
+        long now,
+        long expiry,
+        int key_input_index,
+        int value_input_index,
+        int* key,
+        int* value
+        ) {
+
+        int entry_index = check_if_ttl_key_exists__CACHE_ID(now, key_input_index, key);
+
+        // exist in cache
+        if (entry_index >= 0) {
+
+            ttl_set_value__CACHE_ID(entry_index, value_input_index, value);
+            ttl_expiry__CACHE_ID[entry_index] = expiry;
+
+            return entry_index;
+        }
+
+        entry_index = ttl_claim_entry__CACHE_ID(now);
+
+        if (entry_index >= 0) {
+
+            ttl_set_key__CACHE_ID(entry_index, key_input_index, key);
+            ttl_set_value__CACHE_ID(entry_index, value_input_index, value);
+            ttl_expiry__CACHE_ID[entry_index] = expiry;
+
+            atomic_xchg(&ttl_entries__CACHE_ID[entry_index], TTL_ENTRY_ASSIGNED);
+        }
+
+        return entry_index;
+    }
+
+    int ttl_lookup__CACHE_ID(
+        long now,
+        int key_input_index,
+        int value_output_index,
+        int* key,
+        int* value
+        ) {
+        int entry_index = check_if_ttl_key_exists__CACHE_ID(now, key_input_index, key);
+
+        if (entry_index >= 0) {
+            ttl_get_value__CACHE_ID(entry_index, value_output_index, value);
+        } else {
+            ttl_set_default_output_value__CACHE_ID(value_output_index, value);
+        }
+
+        return entry_index;
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void ttl_reset__CACHE_ID() {
+        int i = get_global_id(0);
+
+        ttl_clear_entry__CACHE_ID(i);
+        ttl_entries__CACHE_ID[i] = TTL_ENTRY_EMPTY;
+    }
+
+    kernel void ttl_debug__CACHE_ID(
+        global int* keys_output,
+        global int* values_output,
+        global long* expiry_output,
+        global int* entries_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_output_index = i * KEY_LEN;
+        int value_output_index = i * VALUE_LEN;
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            keys_output[index + key_output_index] = ttl_keys__CACHE_ID[i][index];
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            values_output[index + value_output_index] = ttl_values__CACHE_ID[i][index];
+        }
+
+        expiry_output[i] = ttl_expiry__CACHE_ID[i];
+        entries_output[i] = ttl_entries__CACHE_ID[i];
+    }
+
+    kernel void ttl_get_keys__CACHE_ID(
+        const long now,
+        global int* keys_output,
+        global long* expiry_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_output_index = i * KEY_LEN;
+
+        if (is_ttl_entry_alive__CACHE_ID(i, now)) {
+
+            for (int index = 0; index < KEY_LEN; index++) {
+                keys_output[index + key_output_index] = ttl_keys__CACHE_ID[i][index];
+            }
+
+            expiry_output[i] = ttl_expiry__CACHE_ID[i];
+
+        } else {
+
+            for (int index = 0; index < KEY_LEN; index++) {
+                keys_output[index + key_output_index] = CL_DEFAULT_VALUE;
+            }
+
+            expiry_output[i] = 0;
+        }
+    }
+
+    kernel void ttl_put__CACHE_ID(
+        const long now,
+        global int* keys_input,
+        global int* values_input,
+        global long* ttl_input,
+        global int* indices_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_input_index = i * KEY_LEN;
+        int value_input_index = i * VALUE_LEN;
+
+        indices_output[i] = ttl_insert__CACHE_ID(
+            now,
+            now + ttl_input[i], // expiry
+            key_input_index,
+            value_input_index,
+            keys_input,
+            values_input
+        );
+    }
+
+    kernel void ttl_get__CACHE_ID(
+        const long now,
+        global int* keys_input,
+        global int* values_output,
+        global int* indices_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_input_index = i * KEY_LEN;
+        int value_output_index = i * VALUE_LEN;
+
+        indices_output[i] = ttl_lookup__CACHE_ID(
+            now,
+            key_input_index,
+            value_output_index,
+            keys_input,
+            values_output
+        );
+    }
+
+    kernel void ttl_purge__CACHE_ID(
+        const long now,
+        global int* indices_output
+        ) {
+
+        int i = get_global_id(0);
+
+        indices_output[i] = -1;
+
+        if (ttl_entries__CACHE_ID[i] == TTL_ENTRY_ASSIGNED && ttl_expiry__CACHE_ID[i] <= now) {
+
+            int r = atomic_cmpxchg(&ttl_entries__CACHE_ID[i], TTL_ENTRY_ASSIGNED, TTL_ENTRY_WRITING);
+
+            if (r == TTL_ENTRY_ASSIGNED) {
+                ttl_clear_entry__CACHE_ID(i);
+                atomic_xchg(&ttl_entries__CACHE_ID[i], TTL_ENTRY_EMPTY);
+
+                indices_output[i] = i;
+            }
+        }
+    }
+    "#;
+
+impl CacheSrc {
+    pub fn generate_cache_ttl_program_source(&self) -> String {
+        let blocks = self.get_configs_by_type(CacheType::TTL);
+
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+
+        let mut base_functions = String::new();
+
+        let mut base_kernels = String::new();
+
+        for config in blocks {
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config)
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config)
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
+            base_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// *** TTL SRC *** ///
+
+    /// constants
+    const int TTL_ENTRY_EMPTY = 0;
+    const int TTL_ENTRY_ASSIGNED = 1;
+    const int TTL_ENTRY_WRITING = 2;
+
+    const int TTL_CACHE_FULL = -2;
+
+    /// globals
+    {globals}
+
+    /// kernels
+
+    {base_functions}
+
+    {base_kernels}
+
+    /// *** TTL SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(8, 256, 256, 1000);
+
+        let program_source = cache_src.generate_cache_ttl_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global long ttl_expiry__0[256];"));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_ttl(8, 256, 256, 1000);
+        cache_src.add_ttl(32, 512, 256, 1000);
+        cache_src.add_lru(16, 256, 128);
+
+        let program_source = cache_src.generate_cache_ttl_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("kernel void ttl_purge__1("));
+        assert!(!program_source.contains("ttl_purge__2"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 256, 512);
+
+        let program_source = cache_src.generate_cache_ttl_program_source();
+        assert!(program_source.is_empty());
+    }
+}
@@ -0,0 +1,402 @@
+use crate::cache::config::{get_two_q_sizes, CacheSrc, CacheType};
+use crate::cache::kernel::common_replace;
+use crate::config::ClTypeDefault;
+
+const GLOBALS: &str = r#"
+    __global int two_q_tick__CACHE_ID = 0;
+
+    __global int two_q_keys__CACHE_ID[CACHE_CAPACITY][KEY_LEN];
+    __global int two_q_values__CACHE_ID[CACHE_CAPACITY][VALUE_LEN];
+    __global int two_q_queues__CACHE_ID[CACHE_CAPACITY];
+    __global int two_q_priorities__CACHE_ID[CACHE_CAPACITY];
+
+    __global int two_q_ghost_keys__CACHE_ID[TWO_Q_KOUT][KEY_LEN];
+    __global int two_q_ghost_priorities__CACHE_ID[TWO_Q_KOUT];
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int two_q_find_key__CACHE_ID(int key_input_index, int* key) {
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+
+            if (two_q_queues__CACHE_ID[i] == TWO_Q_EMPTY) {
+                continue;
+            }
+
+            int exist_index = i;
+
+            for (int key_index = 0; key_index < KEY_LEN; key_index++) {
+                if (two_q_keys__CACHE_ID[i][key_index] != key[key_index + key_input_index]) {
+                    exist_index = -1;
+                    break;
+                }
+            }
+
+            if (exist_index >= 0) {
+                return exist_index;
+            }
+        }
+
+        return -1;
+    }
+
+    int two_q_find_ghost_key__CACHE_ID(int key_input_index, int* key) {
+
+        for (int i = 0; i < TWO_Q_KOUT; i++) {
+
+            if (two_q_ghost_priorities__CACHE_ID[i] == 0) {
+                continue;
+            }
+
+            int exist_index = i;
+
+            for (int key_index = 0; key_index < KEY_LEN; key_index++) {
+                if (two_q_ghost_keys__CACHE_ID[i][key_index] != key[key_index + key_input_index]) {
+                    exist_index = -1;
+                    break;
+                }
+            }
+
+            if (exist_index >= 0) {
+                return exist_index;
+            }
+        }
+
+        return -1;
+    }
+
+    void two_q_clear_ghost__CACHE_ID(int ghost_index) {
+        for (int index = 0; index < KEY_LEN; index++) {
+            two_q_ghost_keys__CACHE_ID[ghost_index][index] = CL_DEFAULT_VALUE;
+        }
+
+        two_q_ghost_priorities__CACHE_ID[ghost_index] = 0;
+    }
+
+    // the key of the entry is moved to A1out, the oldest ghost key is dropped when A1out is full
+    void two_q_push_ghost__CACHE_ID(int entry_index) {
+        int ghost_index = 0;
+
+        for (int i = 0; i < TWO_Q_KOUT; i++) {
+            if (two_q_ghost_priorities__CACHE_ID[i] == 0) {
+                ghost_index = i;
+                break;
+            }
+
+            if (two_q_ghost_priorities__CACHE_ID[i] < two_q_ghost_priorities__CACHE_ID[ghost_index]) {
+                ghost_index = i;
+            }
+        }
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            two_q_ghost_keys__CACHE_ID[ghost_index][index] = two_q_keys__CACHE_ID[entry_index][index];
+        }
+
+        two_q_tick__CACHE_ID++;
+        two_q_ghost_priorities__CACHE_ID[ghost_index] = two_q_tick__CACHE_ID;
+    }
+
+    // A1in: first in first out, Am: least recently used
+    int two_q_oldest__CACHE_ID(int queue) {
+        int entry_index = -1;
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+            if (two_q_queues__CACHE_ID[i] != queue) {
+                continue;
+            }
+
+            if (entry_index < 0 || two_q_priorities__CACHE_ID[i] < two_q_priorities__CACHE_ID[entry_index]) {
+                entry_index = i;
+            }
+        }
+
+        return entry_index;
+    }
+
+    int two_q_reclaim__CACHE_ID() {
+        int a1in_len = 0;
+
+        for (int i = 0; i < CACHE_CAPACITY; i++) {
+            if (two_q_queues__CACHE_ID[i] == TWO_Q_EMPTY) {
+                return i;
+            }
+
+            if (two_q_queues__CACHE_ID[i] == TWO_Q_A1IN) {
+                a1in_len++;
+            }
+        }
+
+        int entry_index = -1;
+
+        if (a1in_len <= TWO_Q_KIN) {
+            entry_index = two_q_oldest__CACHE_ID(TWO_Q_AM);
+        }
+
+        if (entry_index < 0) {
+            entry_index = two_q_oldest__CACHE_ID(TWO_Q_A1IN);
+            two_q_push_ghost__CACHE_ID(entry_index);
+        }
+
+        return entry_index;
+    }
+
+    void two_q_set_key__CACHE_ID(int entry_index, int key_input_index, int* key) {
+        for (int index = 0; index < KEY_LEN; index++) {
+            two_q_keys__CACHE_ID[entry_index][index] = key[index + key_input_index];
+        }
+    }
+
+    void two_q_set_value__CACHE_ID(int entry_index, int value_input_index, int* value) {
+        for (int index = 0; index < VALUE_LEN; index++) {
+            two_q_values__CACHE_ID[entry_index][index] = value[index + value_input_index];
+        }
+    }
+
+    void two_q_get_value__CACHE_ID(int entry_index, int output_index, int* value) {
+        for (int index = 0; index < VALUE_LEN; index++) {
+            value[output_index + index] = two_q_values__CACHE_ID[entry_index][index];
+        }
+    }
+
+    void two_q_set_default_output_value__CACHE_ID(int output_index, int* value) {
+        for (int index = 0; index < VALUE_LEN; index++) {
+            value[output_index + index] = CL_DEFAULT_VALUE;
+        }
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void two_q_reset__CACHE_ID() {
+        int i = get_global_id(0);
+
+        if (i == 0) {
+            two_q_tick__CACHE_ID = 0;
+        }
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            two_q_keys__CACHE_ID[i][index] = CL_DEFAULT_VALUE;
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            two_q_values__CACHE_ID[i][index] = CL_DEFAULT_VALUE;
+        }
+
+        two_q_queues__CACHE_ID[i] = TWO_Q_EMPTY;
+        two_q_priorities__CACHE_ID[i] = 0;
+
+        if (i < TWO_Q_KOUT) {
+            two_q_clear_ghost__CACHE_ID(i);
+        }
+    }
+
+    kernel void two_q_debug__CACHE_ID(
+        global int* keys_output,
+        global int* values_output,
+        global int* queues_output,
+        global int* priorities_output,
+        global int* ghost_keys_output,
+        global int* ghost_priorities_output,
+        global int* meta_output
+        ) {
+
+        int i = get_global_id(0);
+        int key_output_index = i * KEY_LEN;
+        int value_output_index = i * VALUE_LEN;
+
+        if (i == 0) {
+            meta_output[0] = two_q_tick__CACHE_ID;
+        }
+
+        for (int index = 0; index < KEY_LEN; index++) {
+            keys_output[index + key_output_index] = two_q_keys__CACHE_ID[i][index];
+        }
+
+        for (int index = 0; index < VALUE_LEN; index++) {
+            values_output[index + value_output_index] = two_q_values__CACHE_ID[i][index];
+        }
+
+        queues_output[i] = two_q_queues__CACHE_ID[i];
+        priorities_output[i] = two_q_priorities__CACHE_ID[i];
+
+        if (i < TWO_Q_KOUT) {
+            for (int index = 0; index < KEY_LEN; index++) {
+                ghost_keys_output[index + key_output_index] = two_q_ghost_keys__CACHE_ID[i][index];
+            }
+
+            ghost_priorities_output[i] = two_q_ghost_priorities__CACHE_ID[i];
+        }
+    }
+
+    // global work size = 1, the inputs are processed in order
+    kernel void two_q_put__CACHE_ID(
+        const int total,
+        global int* keys_input,
+        global int* values_input,
+        global int* indices_output
+        ) {
+
+        for (int i = 0; i < total; i++) {
+            int key_input_index = i * KEY_LEN;
+            int value_input_index = i * VALUE_LEN;
+
+            int entry_index = two_q_find_key__CACHE_ID(key_input_index, keys_input);
+
+            if (entry_index >= 0) {
+                two_q_set_value__CACHE_ID(entry_index, value_input_index, values_input);
+
+                if (two_q_queues__CACHE_ID[entry_index] == TWO_Q_AM) {
+                    two_q_tick__CACHE_ID++;
+                    two_q_priorities__CACHE_ID[entry_index] = two_q_tick__CACHE_ID;
+                }
+
+                indices_output[i] = entry_index;
+                continue;
+            }
+
+            int queue = TWO_Q_A1IN;
+
+            // seen recently (A1out), the entry goes to Am
+            int ghost_index = two_q_find_ghost_key__CACHE_ID(key_input_index, keys_input);
+
+            if (ghost_index >= 0) {
+                two_q_clear_ghost__CACHE_ID(ghost_index);
+                queue = TWO_Q_AM;
+            }
+
+            entry_index = two_q_reclaim__CACHE_ID();
+
+            two_q_set_key__CACHE_ID(entry_index, key_input_index, keys_input);
+            two_q_set_value__CACHE_ID(entry_index, value_input_index, values_input);
+
+            two_q_tick__CACHE_ID++;
+            two_q_queues__CACHE_ID[entry_index] = queue;
+            two_q_priorities__CACHE_ID[entry_index] = two_q_tick__CACHE_ID;
+
+            indices_output[i] = entry_index;
+        }
+    }
+
+    // global work size = 1, the inputs are processed in order
+    kernel void two_q_get__CACHE_ID(
+        const int total,
+        global int* keys_input,
+        global int* values_output,
+        global int* indices_output
+        ) {
+
+        for (int i = 0; i < total; i++) {
+            int key_input_index = i * KEY_LEN;
+            int value_output_index = i * VALUE_LEN;
+
+            int entry_index = two_q_find_key__CACHE_ID(key_input_index, keys_input);
+
+            if (entry_index >= 0) {
+                two_q_get_value__CACHE_ID(entry_index, value_output_index, values_output);
+
+                // a hit in A1in does not change the order
+                if (two_q_queues__CACHE_ID[entry_index] == TWO_Q_AM) {
+                    two_q_tick__CACHE_ID++;
+                    two_q_priorities__CACHE_ID[entry_index] = two_q_tick__CACHE_ID;
+                }
+            } else {
+                two_q_set_default_output_value__CACHE_ID(value_output_index, values_output);
+            }
+
+            indices_output[i] = entry_index;
+        }
+    }
+    "#;
+
+impl CacheSrc {
+    pub fn generate_cache_two_q_program_source(&self) -> String {
+        let blocks = self.get_configs_by_type(CacheType::TwoQ);
+
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+
+        let mut base_functions = String::new();
+
+        let mut base_kernels = String::new();
+
+        for config in blocks {
+            let (kin, kout) = get_two_q_sizes(config.capacity);
+
+            let template = common_replace(GLOBALS, config).replace("TWO_Q_KOUT", &kout.to_string());
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config)
+                .replace("TWO_Q_KIN", &kin.to_string())
+                .replace("TWO_Q_KOUT", &kout.to_string())
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config)
+                .replace("TWO_Q_KOUT", &kout.to_string())
+                .replace("CL_DEFAULT_VALUE", &i32::cl_default().to_string());
+            base_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// *** 2Q SRC *** ///
+
+    /// constants
+    const int TWO_Q_EMPTY = 0;
+    const int TWO_Q_A1IN = 1;
+    const int TWO_Q_AM = 2;
+
+    /// globals
+    {globals}
+
+    /// kernels
+
+    {base_functions}
+
+    {base_kernels}
+
+    /// *** 2Q SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_two_q(8, 256, 256);
+
+        let program_source = cache_src.generate_cache_two_q_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int two_q_ghost_priorities__0[128];"));
+        assert!(program_source.contains("if (a1in_len <= 64)"));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_two_q(8, 256, 256);
+        cache_src.add_two_q(32, 512, 64);
+        cache_src.add_lfu(16, 256, 128);
+
+        let program_source = cache_src.generate_cache_two_q_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("kernel void two_q_put__1("));
+        assert!(!program_source.contains("two_q_put__2"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 256, 512);
+
+        let program_source = cache_src.generate_cache_two_q_program_source();
+        assert!(program_source.is_empty());
+    }
+}
@@ -2,3 +2,5 @@ pub mod config;
 
 pub mod handle;
 pub mod kernel;
+pub mod model;
+pub mod store;
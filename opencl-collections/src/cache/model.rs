@@ -0,0 +1,530 @@
+//! # Host reference models
+//!
+//! Host equivalents of the lfu and 2q kernels, used to verify the eviction order
+//! of the gpu caches (the snapshots of a model and a handle must be equal after
+//! the same sequence of calls).
+
+use crate::cache::config::{get_two_q_sizes, CacheConfig, LFU_AGING_FACTOR};
+use crate::cache::handle::lfu::LFUCacheSnapshot;
+use crate::cache::handle::lru::{CacheKeys, CacheValues};
+use crate::cache::handle::two_q::{TwoQCacheSnapshot, TWO_Q_A1IN, TWO_Q_AM, TWO_Q_EMPTY};
+use crate::cache::handle::CacheIndices;
+use crate::config::ClTypeDefault;
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_LFU_INPUT_LEN,
+    CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN,
+};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::cl_int;
+
+#[derive(Debug, Clone)]
+pub struct LFUCacheModel {
+    config: CacheConfig,
+    state: LFUCacheSnapshot,
+    evicted: Vec<Vec<cl_int>>,
+}
+
+impl LFUCacheModel {
+    pub fn new(config: &CacheConfig) -> Self {
+        Self {
+            config: config.clone(),
+            state: LFUCacheSnapshot::create_empty(
+                config.key_len,
+                config.value_len,
+                config.capacity,
+            ),
+            evicted: vec![],
+        }
+    }
+
+    pub fn snapshot(&self) -> &LFUCacheSnapshot {
+        &self.state
+    }
+
+    /// evicted keys, in eviction order
+    pub fn evicted(&self) -> &Vec<Vec<cl_int>> {
+        &self.evicted
+    }
+
+    fn find_key(&self, key: &[cl_int]) -> Option<usize> {
+        (0..self.config.capacity)
+            .find(|&i| self.state.frequencies[i] > 0 && self.state.keys[i] == key)
+    }
+
+    fn victim(&self) -> usize {
+        let s = &self.state;
+        let mut entry_index = 0;
+
+        for i in 0..self.config.capacity {
+            if s.frequencies[i] == 0 {
+                return i;
+            }
+
+            if s.frequencies[i] < s.frequencies[entry_index]
+                || (s.frequencies[i] == s.frequencies[entry_index]
+                    && s.last_used[i] < s.last_used[entry_index])
+            {
+                entry_index = i;
+            }
+        }
+
+        entry_index
+    }
+
+    fn touch(&mut self, entry_index: usize) {
+        self.state.tick += 1;
+
+        if self.state.frequencies[entry_index] < cl_int::MAX {
+            self.state.frequencies[entry_index] += 1;
+        }
+
+        self.state.last_used[entry_index] = self.state.tick;
+    }
+
+    fn access(&mut self) {
+        self.state.accesses += 1;
+
+        if (self.state.accesses as usize) < self.config.capacity * LFU_AGING_FACTOR {
+            return;
+        }
+
+        self.state.accesses = 0;
+
+        for frequency in self.state.frequencies.iter_mut() {
+            if *frequency > 0 {
+                *frequency = (*frequency >> 1).max(1);
+            }
+        }
+    }
+
+    pub fn put(&mut self, keys: &CacheKeys, values: &CacheValues) -> OpenClResult<CacheIndices> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LFU_INPUT_LEN,
+            ));
+        }
+
+        let mut indices = Vec::with_capacity(keys.len());
+
+        for (key, value) in keys.iter().zip(values) {
+            let key = ensure_vec_size(key, self.config.key_len);
+
+            let entry_index = match self.find_key(&key) {
+                Some(i) => i,
+                None => {
+                    let i = self.victim();
+
+                    if self.state.frequencies[i] > 0 {
+                        self.evicted.push(self.state.keys[i].clone());
+                    }
+
+                    self.state.keys[i] = key;
+                    self.state.frequencies[i] = 0;
+                    i
+                }
+            };
+
+            self.state.values[entry_index] = ensure_vec_size(value, self.config.value_len);
+            self.touch(entry_index);
+            self.access();
+
+            indices.push(entry_index as cl_int);
+        }
+
+        Ok(indices)
+    }
+
+    pub fn get(&mut self, keys: &CacheKeys) -> (CacheValues, CacheIndices) {
+        let mut values = Vec::with_capacity(keys.len());
+        let mut indices = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            let key = ensure_vec_size(key, self.config.key_len);
+
+            match self.find_key(&key) {
+                Some(i) => {
+                    values.push(self.state.values[i].clone());
+                    self.touch(i);
+                    indices.push(i as cl_int);
+                }
+                None => {
+                    values.push(vec![i32::cl_default(); self.config.value_len]);
+                    indices.push(-1);
+                }
+            }
+
+            self.access();
+        }
+
+        (values, indices)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TwoQCacheModel {
+    config: CacheConfig,
+    kin: usize,
+    state: TwoQCacheSnapshot,
+    evicted: Vec<Vec<cl_int>>,
+}
+
+impl TwoQCacheModel {
+    pub fn new(config: &CacheConfig) -> Self {
+        let (kin, _) = get_two_q_sizes(config.capacity);
+
+        Self {
+            config: config.clone(),
+            kin,
+            state: TwoQCacheSnapshot::create_empty(
+                config.key_len,
+                config.value_len,
+                config.capacity,
+            ),
+            evicted: vec![],
+        }
+    }
+
+    pub fn snapshot(&self) -> &TwoQCacheSnapshot {
+        &self.state
+    }
+
+    /// evicted keys (A1in and Am), in eviction order
+    pub fn evicted(&self) -> &Vec<Vec<cl_int>> {
+        &self.evicted
+    }
+
+    fn find_key(&self, key: &[cl_int]) -> Option<usize> {
+        (0..self.config.capacity)
+            .find(|&i| self.state.queues[i] != TWO_Q_EMPTY && self.state.keys[i] == key)
+    }
+
+    fn find_ghost_key(&self, key: &[cl_int]) -> Option<usize> {
+        (0..self.state.ghost_keys.len())
+            .find(|&i| self.state.ghost_priorities[i] > 0 && self.state.ghost_keys[i] == key)
+    }
+
+    fn clear_ghost(&mut self, ghost_index: usize) {
+        self.state.ghost_keys[ghost_index] = vec![i32::cl_default(); self.config.key_len];
+        self.state.ghost_priorities[ghost_index] = 0;
+    }
+
+    fn push_ghost(&mut self, entry_index: usize) {
+        let s = &self.state;
+        let mut ghost_index = 0;
+
+        for i in 0..s.ghost_keys.len() {
+            if s.ghost_priorities[i] == 0 {
+                ghost_index = i;
+                break;
+            }
+
+            if s.ghost_priorities[i] < s.ghost_priorities[ghost_index] {
+                ghost_index = i;
+            }
+        }
+
+        self.state.ghost_keys[ghost_index] = self.state.keys[entry_index].clone();
+
+        self.state.tick += 1;
+        self.state.ghost_priorities[ghost_index] = self.state.tick;
+    }
+
+    fn oldest(&self, queue: cl_int) -> Option<usize> {
+        (0..self.config.capacity)
+            .filter(|&i| self.state.queues[i] == queue)
+            .min_by_key(|&i| self.state.priorities[i])
+    }
+
+    fn reclaim(&mut self) -> usize {
+        if let Some(i) = self.state.queues.iter().position(|&x| x == TWO_Q_EMPTY) {
+            return i;
+        }
+
+        let a1in_len = self
+            .state
+            .queues
+            .iter()
+            .filter(|&&x| x == TWO_Q_A1IN)
+            .count();
+
+        let mut entry_index = None;
+
+        if a1in_len <= self.kin {
+            entry_index = self.oldest(TWO_Q_AM);
+        }
+
+        let entry_index = match entry_index {
+            Some(i) => i,
+            None => {
+                let i = self.oldest(TWO_Q_A1IN).unwrap();
+                self.push_ghost(i);
+                i
+            }
+        };
+
+        self.evicted.push(self.state.keys[entry_index].clone());
+
+        entry_index
+    }
+
+    fn touch(&mut self, entry_index: usize) {
+        if self.state.queues[entry_index] == TWO_Q_AM {
+            self.state.tick += 1;
+            self.state.priorities[entry_index] = self.state.tick;
+        }
+    }
+
+    pub fn put(&mut self, keys: &CacheKeys, values: &CacheValues) -> OpenClResult<CacheIndices> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN,
+            ));
+        }
+
+        let mut indices = Vec::with_capacity(keys.len());
+
+        for (key, value) in keys.iter().zip(values) {
+            let key = ensure_vec_size(key, self.config.key_len);
+            let value = ensure_vec_size(value, self.config.value_len);
+
+            if let Some(i) = self.find_key(&key) {
+                self.state.values[i] = value;
+                self.touch(i);
+                indices.push(i as cl_int);
+                continue;
+            }
+
+            let mut queue = TWO_Q_A1IN;
+
+            if let Some(ghost_index) = self.find_ghost_key(&key) {
+                self.clear_ghost(ghost_index);
+                queue = TWO_Q_AM;
+            }
+
+            let i = self.reclaim();
+
+            self.state.keys[i] = key;
+            self.state.values[i] = value;
+
+            self.state.tick += 1;
+            self.state.queues[i] = queue;
+            self.state.priorities[i] = self.state.tick;
+
+            indices.push(i as cl_int);
+        }
+
+        Ok(indices)
+    }
+
+    pub fn get(&mut self, keys: &CacheKeys) -> (CacheValues, CacheIndices) {
+        let mut values = Vec::with_capacity(keys.len());
+        let mut indices = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            let key = ensure_vec_size(key, self.config.key_len);
+
+            match self.find_key(&key) {
+                Some(i) => {
+                    values.push(self.state.values[i].clone());
+                    self.touch(i);
+                    indices.push(i as cl_int);
+                }
+                None => {
+                    values.push(vec![i32::cl_default(); self.config.value_len]);
+                    indices.push(-1);
+                }
+            }
+        }
+
+        (values, indices)
+    }
+}
+
+#[cfg(test)]
+mod tests_lfu_cache_model {
+    use super::*;
+    use crate::cache::config::CacheType;
+
+    fn keys(v: &[cl_int]) -> CacheKeys {
+        v.iter().map(|&x| vec![x]).collect()
+    }
+
+    #[test]
+    fn evict_lowest_frequency() {
+        let config = CacheConfig::new(0, 1, 1, 3, CacheType::LFU);
+        let mut model = LFUCacheModel::new(&config);
+
+        model.put(&keys(&[1, 2, 3]), &keys(&[1, 2, 3])).unwrap();
+        model.get(&keys(&[1, 1, 3]));
+
+        // 2 is the least frequently used
+        model.put(&keys(&[4]), &keys(&[4])).unwrap();
+        // 4 (frequency 1)
+        model.put(&keys(&[5]), &keys(&[5])).unwrap();
+
+        assert_eq!(model.evicted(), &keys(&[2, 4]));
+
+        let sorted: Vec<_> = model
+            .snapshot()
+            .get_sorted_keys()
+            .into_iter()
+            .map(|x| x.key)
+            .collect();
+        assert_eq!(sorted, keys(&[1, 3, 5]));
+    }
+
+    #[test]
+    fn ties_evict_least_recently_used() {
+        let config = CacheConfig::new(0, 1, 1, 3, CacheType::LFU);
+        let mut model = LFUCacheModel::new(&config);
+
+        model.put(&keys(&[1, 2, 3]), &keys(&[1, 2, 3])).unwrap();
+        model.get(&keys(&[3, 1, 2]));
+
+        model.put(&keys(&[4, 5]), &keys(&[4, 5])).unwrap();
+
+        // 3 then 4 (4 has frequency 1)
+        assert_eq!(model.evicted(), &keys(&[3, 4]));
+    }
+
+    #[test]
+    fn aging() {
+        let config = CacheConfig::new(0, 1, 1, 2, CacheType::LFU);
+        let mut model = LFUCacheModel::new(&config);
+
+        // aging period = 2 * LFU_AGING_FACTOR
+        let period = 2 * LFU_AGING_FACTOR;
+
+        model.put(&keys(&[1]), &keys(&[1])).unwrap();
+        model.get(&keys(&vec![1; period - 2]));
+
+        assert_eq!(model.snapshot().frequencies[0], (period - 1) as cl_int);
+
+        model.get(&keys(&[1]));
+
+        assert_eq!(model.snapshot().frequencies[0], (period / 2) as cl_int);
+        assert_eq!(model.snapshot().accesses, 0);
+    }
+
+    #[test]
+    fn update_value() {
+        let config = CacheConfig::new(0, 2, 2, 4, CacheType::LFU);
+        let mut model = LFUCacheModel::new(&config);
+
+        let i1 = model.put(&vec![vec![1]], &vec![vec![1, 1]]).unwrap();
+        let i2 = model.put(&vec![vec![1]], &vec![vec![2]]).unwrap();
+        assert_eq!(i1, i2);
+
+        let (values, indices) = model.get(&vec![vec![1], vec![2]]);
+        assert_eq!(
+            values,
+            vec![vec![2, i32::cl_default()], vec![i32::cl_default(); 2]]
+        );
+        assert_eq!(indices, vec![0, -1]);
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        let config = CacheConfig::new(0, 1, 1, 4, CacheType::LFU);
+        let mut model = LFUCacheModel::new(&config);
+
+        assert_eq!(
+            model.put(&keys(&[1, 2]), &keys(&[1])),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LFU_INPUT_LEN
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_two_q_cache_model {
+    use super::*;
+    use crate::cache::config::CacheType;
+
+    fn keys(v: &[cl_int]) -> CacheKeys {
+        v.iter().map(|&x| vec![x]).collect()
+    }
+
+    #[test]
+    fn scan_does_not_evict_hot_entries() {
+        // kin = 2, kout = 4
+        let config = CacheConfig::new(0, 1, 1, 8, CacheType::TwoQ);
+        let mut model = TwoQCacheModel::new(&config);
+
+        // 1 and 2 are promoted to Am after being evicted from A1in
+        let hot = keys(&[1, 2]);
+        model.put(&hot, &hot).unwrap();
+        model
+            .put(
+                &keys(&[10, 11, 12, 13, 14, 15]),
+                &keys(&[10, 11, 12, 13, 14, 15]),
+            )
+            .unwrap();
+        model.put(&keys(&[16, 17]), &keys(&[16, 17])).unwrap();
+        assert_eq!(model.evicted(), &keys(&[1, 2]));
+
+        model.put(&hot, &hot).unwrap();
+
+        let s = model.snapshot();
+        assert_eq!(s.get_queue_keys(TWO_Q_AM), hot);
+
+        // scan
+        let scan: Vec<cl_int> = (100..120).collect();
+        model.put(&keys(&scan), &keys(&scan)).unwrap();
+
+        let (_, indices) = model.get(&hot);
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let s = model.snapshot();
+        assert_eq!(s.get_queue_keys(TWO_Q_AM), hot);
+        assert_eq!(s.len(), 8);
+        assert_eq!(s.get_ghost_keys().len(), 4);
+    }
+
+    #[test]
+    fn am_is_lru() {
+        let config = CacheConfig::new(0, 1, 1, 4, CacheType::TwoQ);
+        let mut model = TwoQCacheModel::new(&config);
+
+        // kin = 1, kout = 2
+        model
+            .put(&keys(&[1, 2, 3, 4]), &keys(&[1, 2, 3, 4]))
+            .unwrap();
+        model.put(&keys(&[5, 6]), &keys(&[5, 6])).unwrap();
+        assert_eq!(model.evicted(), &keys(&[1, 2]));
+        assert_eq!(model.snapshot().get_ghost_keys(), keys(&[1, 2]));
+
+        // 1 and 2 -> Am
+        model.put(&keys(&[1, 2]), &keys(&[1, 2])).unwrap();
+        assert_eq!(model.snapshot().get_queue_keys(TWO_Q_AM), keys(&[1, 2]));
+
+        model.get(&keys(&[1]));
+        assert_eq!(model.snapshot().get_queue_keys(TWO_Q_AM), keys(&[2, 1]));
+    }
+
+    #[test]
+    fn capacity_1() {
+        let config = CacheConfig::new(0, 1, 1, 1, CacheType::TwoQ);
+        let mut model = TwoQCacheModel::new(&config);
+
+        model.put(&keys(&[1, 2, 1]), &keys(&[1, 2, 1])).unwrap();
+
+        let s = model.snapshot();
+        assert_eq!(s.get_queue_keys(TWO_Q_AM), keys(&[1]));
+        assert_eq!(s.get_ghost_keys(), keys(&[2]));
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        let config = CacheConfig::new(0, 1, 1, 4, CacheType::TwoQ);
+        let mut model = TwoQCacheModel::new(&config);
+
+        assert_eq!(
+            model.put(&keys(&[1, 2]), &keys(&[1])),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN
+            ))
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! # Cached store
+//!
+//! Read-through / write-back adapter, an lru cache in front of a slower store.
+//!
+//! The keys and values handed to the store keep their real length, limited
+//! to the cache key_len / value_len.
+
+use crate::cache::handle::lru::{CacheEvictions, CacheKeys, CacheValues, LRUCacheHandle};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_LRU_INPUT_LEN,
+    CL_COLLECTION_LRU_DIRTY_ENTRY_NOT_FOUND,
+};
+use crate::utils::limit_vec_size;
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::OpenclCommonOperation;
+use std::collections::{HashMap, HashSet};
+
+pub trait BackingStore {
+    fn load(&mut self, key: &[cl_int]) -> OpenClResult<Option<Vec<cl_int>>>;
+
+    fn store(&mut self, key: &[cl_int], value: &[cl_int]) -> OpenClResult<()>;
+}
+
+/// in-memory backing store
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStore {
+    pub entries: HashMap<Vec<cl_int>, Vec<cl_int>>,
+    pub loads: usize,
+    pub stores: usize,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl BackingStore for MemoryStore {
+    fn load(&mut self, key: &[cl_int]) -> OpenClResult<Option<Vec<cl_int>>> {
+        self.loads += 1;
+        Ok(self.entries.get(key).cloned())
+    }
+
+    fn store(&mut self, key: &[cl_int], value: &[cl_int]) -> OpenClResult<()> {
+        self.stores += 1;
+        self.entries.insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct CachedStore<T: OpenclCommonOperation, S: BackingStore> {
+    cache: LRUCacheHandle<T>,
+    store: S,
+    key_len: usize,
+    value_len: usize,
+    // entries written in the cache and not yet in the store
+    dirty: HashSet<Vec<cl_int>>,
+}
+
+impl<T: OpenclCommonOperation, S: BackingStore> CachedStore<T, S> {
+    pub fn new(cache: LRUCacheHandle<T>, store: S) -> Self {
+        let config = cache.get_config();
+        Self {
+            key_len: config.key_len,
+            value_len: config.value_len,
+            cache,
+            store,
+            dirty: HashSet::new(),
+        }
+    }
+
+    pub fn cache(&self) -> &LRUCacheHandle<T> {
+        &self.cache
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn dirty_len(&self) -> usize {
+        self.dirty.len()
+    }
+
+    pub fn initialize(&mut self) -> OpenClResult<()> {
+        self.dirty.clear();
+        self.cache.initialize()
+    }
+
+    /// read-through, the misses are loaded from the store and inserted in the cache
+    pub fn get(&mut self, keys: &CacheKeys) -> OpenClResult<Vec<Option<Vec<cl_int>>>> {
+        let (values, indices) = self.cache.get(keys)?;
+
+        let mut output: Vec<Option<Vec<cl_int>>> = Vec::with_capacity(keys.len());
+
+        let mut loaded_keys: CacheKeys = vec![];
+        let mut loaded_values: CacheValues = vec![];
+
+        for (i, value) in values.into_iter().enumerate() {
+            if indices[i] >= 0 {
+                output.push(Some(value));
+                continue;
+            }
+
+            let key = limit_vec_size(&keys[i], self.key_len).to_vec();
+
+            if let Some(index) = loaded_keys.iter().position(|x| x == &key) {
+                output.push(Some(loaded_values[index].clone()));
+                continue;
+            }
+
+            match self.store.load(&key)? {
+                None => output.push(None),
+                Some(value) => {
+                    let value = limit_vec_size(&value, self.value_len).to_vec();
+                    output.push(Some(value.clone()));
+                    loaded_keys.push(key);
+                    loaded_values.push(value);
+                }
+            }
+        }
+
+        if !loaded_keys.is_empty() {
+            let (_, evictions) = self
+                .cache
+                .insert_with_evictions(&loaded_keys, &loaded_values)?;
+            self.cache.reset_array_set()?;
+            self.write_back(evictions)?;
+        }
+
+        Ok(output)
+    }
+
+    /// write-back, the entries reach the store when they are evicted or flushed
+    pub fn put(&mut self, keys: &CacheKeys, values: &CacheValues) -> OpenClResult<()> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_LRU_INPUT_LEN,
+            ));
+        }
+
+        let (indices, evictions) = self.cache.insert_with_evictions(keys, values)?;
+        self.cache.reset_array_set()?;
+        self.write_back(evictions)?;
+
+        for (i, &entry_index) in indices.iter().enumerate() {
+            let key = limit_vec_size(&keys[i], self.key_len).to_vec();
+
+            // not cached, write-through
+            if entry_index < 0 {
+                let value = limit_vec_size(&values[i], self.value_len).to_vec();
+                self.store.store(&key, &value)?;
+                continue;
+            }
+
+            self.dirty.insert(key);
+        }
+
+        Ok(())
+    }
+
+    /// writes all the dirty entries to the store, the entries that are no longer
+    /// in the cache (e.g. removed with cache().remove) stay dirty and return an error
+    pub fn flush(&mut self) -> OpenClResult<()> {
+        if self.dirty.is_empty() {
+            return Ok(());
+        }
+
+        let cs = self.cache.debug()?;
+
+        for i in 0..cs.keys.len() {
+            let key = cs.get_key(i);
+
+            if self.dirty.remove(&key) {
+                self.store.store(&key, &cs.get_value(i))?;
+            }
+        }
+
+        if !self.dirty.is_empty() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_LRU_DIRTY_ENTRY_NOT_FOUND,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn write_back(&mut self, evictions: CacheEvictions) -> OpenClResult<()> {
+        for entry in evictions {
+            if self.dirty.remove(&entry.key) {
+                self.store.store(&entry.key, &entry.value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_memory_store {
+    use super::*;
+
+    #[test]
+    fn load_and_store() {
+        let mut store = MemoryStore::new();
+        assert!(store.is_empty());
+
+        assert_eq!(store.load(&[1, 2]), Ok(None));
+
+        store.store(&[1, 2], &[10, 20]).unwrap();
+        store.store(&[1, 2], &[30]).unwrap();
+
+        assert_eq!(store.load(&[1, 2]), Ok(Some(vec![30])));
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.loads, 2);
+        assert_eq!(store.stores, 2);
+    }
+}
+
+#[cfg(test)]
+mod tests_cached_store {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+    use std::sync::Arc;
+
+    fn create_cached_store(
+        key_len: usize,
+        value_len: usize,
+        capacity: usize,
+        store: MemoryStore,
+    ) -> CachedStore<System, MemoryStore> {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(key_len, value_len, capacity);
+
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &cache_src.build()).unwrap());
+
+        let config = cache_src.get_lru_by_id(0).unwrap();
+        let cache = LRUCacheHandle::new(config, system);
+
+        let mut cached_store = CachedStore::new(cache, store);
+        cached_store.initialize().unwrap();
+        cached_store
+    }
+
+    #[test]
+    fn read_through() {
+        let key_len = 16;
+        let value_len = 16;
+
+        let test_matrix = TestMatrix::new(8, key_len, value_len, 1, 10);
+
+        let mut store = MemoryStore::new();
+        for (i, key) in test_matrix.keys.iter().enumerate() {
+            store.store(key, &test_matrix.values[i]).unwrap();
+        }
+
+        let mut cached_store = create_cached_store(key_len, value_len, 32, store);
+
+        let mut keys = test_matrix.keys.clone();
+        keys.push(vec![-5; key_len]);
+
+        let values = cached_store.get(&keys).unwrap();
+
+        for (i, value) in test_matrix.values.iter().enumerate() {
+            assert_eq!(values[i], Some(value.clone()));
+        }
+        assert_eq!(values[8], None);
+        assert_eq!(cached_store.store().loads, 9);
+
+        // hits
+        let values = cached_store.get(&test_matrix.keys).unwrap();
+        assert!(values.iter().all(|x| x.is_some()));
+        assert_eq!(cached_store.store().loads, 9);
+
+        let stats = cached_store.cache().stats().unwrap();
+        assert_eq!(stats.hits, 8);
+        assert_eq!(stats.misses, 9);
+    }
+
+    #[test]
+    fn write_back_on_eviction() {
+        let key_len = 16;
+        let value_len = 16;
+        let capacity = 8;
+
+        let mut cached_store =
+            create_cached_store(key_len, value_len, capacity, MemoryStore::new());
+
+        let test_matrix = TestMatrix::new(capacity, key_len, value_len, 1, 10);
+        cached_store
+            .put(&test_matrix.keys, &test_matrix.values)
+            .unwrap();
+
+        assert!(cached_store.store().is_empty());
+        assert_eq!(cached_store.dirty_len(), capacity);
+
+        let test_matrix_2 = TestMatrix::new(2, key_len, value_len, 100, 100);
+        cached_store
+            .put(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+
+        assert_eq!(cached_store.store().len(), 2);
+        assert_eq!(cached_store.dirty_len(), capacity);
+
+        for (key, value) in cached_store.store().entries.iter() {
+            let i = test_matrix.keys.iter().position(|x| x == key).unwrap();
+            assert_eq!(value, &test_matrix.values[i]);
+        }
+
+        cached_store.flush().unwrap();
+
+        assert_eq!(cached_store.store().len(), capacity + 2);
+        assert_eq!(cached_store.dirty_len(), 0);
+
+        // clean entries are not written again
+        let stores = cached_store.store().stores;
+
+        let test_matrix_3 = TestMatrix::new(capacity, key_len, value_len, 200, 200);
+        let values = cached_store.get(&test_matrix_3.keys).unwrap();
+        assert!(values.iter().all(|x| x.is_none()));
+
+        cached_store
+            .put(&test_matrix_3.keys, &test_matrix_3.values)
+            .unwrap();
+        assert_eq!(cached_store.store().stores, stores);
+    }
+
+    #[test]
+    fn flush_keeps_the_dirty_entries_not_in_the_cache() {
+        let key_len = 16;
+        let value_len = 16;
+        let capacity = 8;
+
+        let mut cached_store =
+            create_cached_store(key_len, value_len, capacity, MemoryStore::new());
+
+        let test_matrix = TestMatrix::new(4, key_len, value_len, 1, 10);
+        cached_store
+            .put(&test_matrix.keys, &test_matrix.values)
+            .unwrap();
+
+        cached_store
+            .cache()
+            .remove(&vec![test_matrix.keys[0].clone()])
+            .unwrap();
+
+        assert_eq!(
+            cached_store.flush(),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_LRU_DIRTY_ENTRY_NOT_FOUND
+            ))
+        );
+
+        assert_eq!(cached_store.store().len(), 3);
+        assert_eq!(cached_store.dirty_len(), 1);
+        assert!(!cached_store
+            .store()
+            .entries
+            .contains_key(&test_matrix.keys[0]));
+
+        // the key is stored when it is written again
+        cached_store
+            .put(
+                &vec![test_matrix.keys[0].clone()],
+                &vec![vec![7; value_len]],
+            )
+            .unwrap();
+        cached_store.flush().unwrap();
+
+        assert_eq!(cached_store.store().len(), 4);
+        assert_eq!(cached_store.dirty_len(), 0);
+        assert_eq!(
+            cached_store.store().entries.get(&test_matrix.keys[0]),
+            Some(&vec![7; value_len])
+        );
+    }
+}
@@ -0,0 +1,963 @@
+use crate::cache::config::{CacheConfig, CacheSrc, CacheType};
+use crate::config::ClTypeTrait;
+use crate::dictionary::config::{DictConfig, DictSrc};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_DUPLICATE_NAMESPACE,
+    CL_COLLECTION_INCOMPATIBLE_MAP_SRC, CL_COLLECTION_INVALID_NAMESPACE,
+    CL_COLLECTION_SYMBOL_COLLISION,
+};
+use crate::map::config::MapSrc;
+use crate::queue::config::{QueueConfig, QueueSrc, QueueType};
+use crate::set::config::{ArraySetVersion, SetConfig, SetSrc, SetType};
+use crate::stack::config::{StackConfig, StackSrc, StackType};
+use humansize::{format_size, DECIMAL};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CollectionKind {
+    Map,
+    Cache,
+    Dictionary,
+    Queue,
+    Set,
+    Stack,
+    /// kernels of the user (add_user_src)
+    User,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BlockType {
+    Map,
+    Cache(CacheType),
+    Dictionary,
+    Queue(QueueType),
+    Set(SetType),
+    Stack(StackType),
+}
+
+/// id of a block inside the src that was added (id) and inside the combined program (program_id)
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamespaceBlock {
+    pub block_type: BlockType,
+    pub id: usize,
+    pub program_id: usize,
+}
+
+impl NamespaceBlock {
+    pub fn new(block_type: BlockType, id: usize, program_id: usize) -> Self {
+        Self {
+            block_type,
+            id,
+            program_id,
+        }
+    }
+}
+
+/// ...
+///
+/// every src added to the combined program is registered with a name, the ids of
+/// its blocks are renumbered so that the symbols (stack__STACK_ID, lq__QUEUE_ID, ...)
+/// do not collide with those of other src of the same kind.
+///
+/// ```text
+/// add_stack("jobs", [stack 0, stack 1])    -> stack__0, stack__1
+/// add_stack("workers", [stack 0])          -> stack__2
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionNamespace {
+    pub name: String,
+    pub kind: CollectionKind,
+    pub blocks: Vec<NamespaceBlock>,
+}
+
+impl CollectionNamespace {
+    pub fn new(name: &str, kind: CollectionKind) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn get_program_id(&self, block_type: BlockType, id: usize) -> OpenClResult<usize> {
+        match self
+            .blocks
+            .iter()
+            .find(|x| x.block_type == block_type && x.id == id)
+        {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE,
+            )),
+            Some(b) => Ok(b.program_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollectionSection {
+    pub kind: CollectionKind,
+    pub source: String,
+}
+
+/// symbol declared in more than one section of the combined program
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolCollision {
+    pub name: String,
+    pub kinds: Vec<CollectionKind>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CollectionSummary {
+    pub kind: CollectionKind,
+    pub blocks: usize,
+    pub kernels: usize,
+    /// memory required in bytes
+    pub memory_required: usize,
+    pub memory_required_text: String,
+}
+
+impl CollectionSummary {
+    pub fn new(
+        kind: CollectionKind,
+        blocks: usize,
+        kernels: usize,
+        memory_required: usize,
+    ) -> Self {
+        Self {
+            kind,
+            blocks,
+            kernels,
+            memory_required,
+            memory_required_text: format_size(memory_required, DECIMAL),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CollectionsSummary {
+    pub kernels: usize,
+    /// memory required in bytes
+    pub total_memory_required: usize,
+    pub total_memory_required_text: String,
+    pub collections: Vec<CollectionSummary>,
+}
+
+impl CollectionsSummary {
+    pub fn new(collections: Vec<CollectionSummary>) -> Self {
+        let total_memory_required = collections.iter().map(|x| x.memory_required).sum();
+
+        Self {
+            kernels: collections.iter().map(|x| x.kernels).sum(),
+            total_memory_required,
+            total_memory_required_text: format_size(total_memory_required, DECIMAL),
+            collections,
+        }
+    }
+
+    pub fn get_by_kind(&self, kind: CollectionKind) -> Option<&CollectionSummary> {
+        self.collections.iter().find(|x| x.kind == kind)
+    }
+}
+
+/// Several collection src combined in a single program, so that a single context
+/// (and a single compilation) serves all the collections of an application.
+///
+/// The map and the dictionaries share the same CL_TYPE.
+///
+/// With enable_device_api each section includes the device-callable functions of
+/// its collections (stack_push__ID, lq_enqueue__ID, map_get__BLOCK_NAME, ...), the
+/// sources of the user (add_user_src) are added at the end of the program and can
+/// call them from their own kernels.
+#[derive(Debug, Clone)]
+pub struct CollectionsSrc<T: ClTypeTrait> {
+    namespaces: Vec<CollectionNamespace>,
+    map: Option<MapSrc<T>>,
+    dict: DictSrc<T>,
+    cache: CacheSrc,
+    queue: QueueSrc,
+    set: SetSrc,
+    set_version: ArraySetVersion,
+    stack: StackSrc,
+    device_api: bool,
+    user_sources: Vec<String>,
+}
+
+impl<T: ClTypeTrait> CollectionsSrc<T> {
+    pub fn new() -> Self {
+        Self {
+            namespaces: Vec::new(),
+            map: None,
+            dict: DictSrc::new(),
+            cache: CacheSrc::new(),
+            queue: QueueSrc::new(),
+            set: SetSrc::new(),
+            set_version: ArraySetVersion::V2,
+            stack: StackSrc::new(),
+            device_api: false,
+            user_sources: Vec::new(),
+        }
+    }
+
+    pub fn get_namespaces(&self) -> &Vec<CollectionNamespace> {
+        &self.namespaces
+    }
+
+    pub fn get_namespace(
+        &self,
+        name: &str,
+        kind: CollectionKind,
+    ) -> OpenClResult<&CollectionNamespace> {
+        match self
+            .namespaces
+            .iter()
+            .find(|x| x.name == name && x.kind == kind)
+        {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE,
+            )),
+            Some(n) => Ok(n),
+        }
+    }
+
+    pub fn get_program_id(
+        &self,
+        name: &str,
+        kind: CollectionKind,
+        block_type: BlockType,
+        id: usize,
+    ) -> OpenClResult<usize> {
+        self.get_namespace(name, kind)?
+            .get_program_id(block_type, id)
+    }
+
+    fn register_namespace(&mut self, namespace: CollectionNamespace) -> OpenClResult<()> {
+        if self.get_namespace(&namespace.name, namespace.kind).is_ok() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_DUPLICATE_NAMESPACE,
+            ));
+        }
+
+        self.namespaces.push(namespace);
+        Ok(())
+    }
+
+    fn check_namespace(&self, name: &str, kind: CollectionKind) -> OpenClResult<()> {
+        if self.get_namespace(name, kind).is_ok() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_DUPLICATE_NAMESPACE,
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_none()
+            && self.dict.is_empty()
+            && self.cache.is_empty()
+            && self.queue.is_empty()
+            && self.set.is_empty()
+            && self.stack.is_empty()
+            && self.user_sources.is_empty()
+    }
+
+    // device api
+
+    pub fn enable_device_api(&mut self) {
+        self.device_api = true;
+    }
+
+    pub fn has_device_api(&self) -> bool {
+        self.device_api
+    }
+
+    // user
+
+    /// the source is added after the collections, the symbols must not collide with them
+    pub fn add_user_src(&mut self, src: &str) {
+        self.user_sources.push(src.to_string());
+    }
+
+    pub fn get_user_sources(&self) -> &Vec<String> {
+        &self.user_sources
+    }
+
+    // map
+
+    /// The map blocks are identified by value_len (map_keys__BLOCK_NAME), the maps of
+    /// each namespace are renumbered after those of the previous namespaces (total_maps
+    /// is the sum), the blocks with the same value_len must have the same config.
+    /// The optional sources (map_insert, map_get, ...) must be added after combining
+    /// them with get_map_src_mut.
+    ///
+    /// ```text
+    /// add_map("a", [total_maps 2])    -> map_id 0, 1
+    /// add_map("b", [total_maps 1])    -> map_id 2
+    /// ```
+    pub fn add_map(&mut self, namespace: &str, map_src: &MapSrc<T>) -> OpenClResult<()> {
+        self.check_namespace(namespace, CollectionKind::Map)?;
+
+        let mut collection_namespace = CollectionNamespace::new(namespace, CollectionKind::Map);
+
+        let offset = match &mut self.map {
+            None => {
+                self.map = Some(map_src.clone());
+                0
+            }
+            Some(current) => {
+                if !current.optional_sources.is_empty() || !map_src.optional_sources.is_empty() {
+                    return Err(OpenclError::OpenclCollection(
+                        CL_COLLECTION_INCOMPATIBLE_MAP_SRC,
+                    ));
+                }
+
+                for config in map_src.get_configs() {
+                    if let Ok(c) = current.get_config_by_value_len(config.value_len) {
+                        if c != config {
+                            return Err(OpenclError::OpenclCollection(
+                                CL_COLLECTION_SYMBOL_COLLISION,
+                            ));
+                        }
+                    }
+                }
+
+                for config in map_src.get_configs() {
+                    if current.get_config_by_value_len(config.value_len).is_err() {
+                        current.get_configs_mut().push(config.clone());
+                    }
+                }
+
+                let offset = current.get_total_maps();
+                current.set_total_maps(offset + map_src.get_total_maps())?;
+                offset
+            }
+        };
+
+        for map_id in 0..map_src.get_total_maps() {
+            collection_namespace.blocks.push(NamespaceBlock::new(
+                BlockType::Map,
+                map_id,
+                offset + map_id,
+            ));
+        }
+
+        self.register_namespace(collection_namespace)
+    }
+
+    /// map_id of the combined program (MapHandle::new)
+    pub fn get_map_id(&self, namespace: &str, map_id: usize) -> OpenClResult<usize> {
+        self.get_program_id(namespace, CollectionKind::Map, BlockType::Map, map_id)
+    }
+
+    pub fn get_map_src(&self) -> Option<&MapSrc<T>> {
+        self.map.as_ref()
+    }
+
+    pub fn get_map_src_mut(&mut self) -> Option<&mut MapSrc<T>> {
+        self.map.as_mut()
+    }
+
+    // dict
+
+    pub fn add_dict(&mut self, namespace: &str, dict_src: &DictSrc<T>) -> OpenClResult<()> {
+        self.check_namespace(namespace, CollectionKind::Dictionary)?;
+
+        let mut collection_namespace =
+            CollectionNamespace::new(namespace, CollectionKind::Dictionary);
+
+        for config in dict_src.get_configs() {
+            let program_id = self.dict.len();
+            self.dict
+                .add(config.key_len, config.value_len, config.capacity);
+
+            collection_namespace.blocks.push(NamespaceBlock::new(
+                BlockType::Dictionary,
+                config.id,
+                program_id,
+            ));
+        }
+
+        self.register_namespace(collection_namespace)
+    }
+
+    pub fn get_dict_src(&self) -> &DictSrc<T> {
+        &self.dict
+    }
+
+    pub(crate) fn get_dict_src_mut(&mut self) -> &mut DictSrc<T> {
+        &mut self.dict
+    }
+
+    pub fn get_dict_config(&self, namespace: &str, id: usize) -> OpenClResult<&DictConfig<T>> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Dictionary,
+            BlockType::Dictionary,
+            id,
+        )?;
+        self.dict.get_config_by_id(program_id)
+    }
+
+    // cache
+
+    pub fn add_cache(&mut self, namespace: &str, cache_src: &CacheSrc) -> OpenClResult<()> {
+        self.check_namespace(namespace, CollectionKind::Cache)?;
+
+        let mut collection_namespace = CollectionNamespace::new(namespace, CollectionKind::Cache);
+
+        for config in cache_src.get_configs() {
+            let program_id = self.cache.add_config(config);
+
+            collection_namespace.blocks.push(NamespaceBlock::new(
+                BlockType::Cache(config.cache_type),
+                config.id,
+                program_id,
+            ));
+        }
+
+        self.register_namespace(collection_namespace)
+    }
+
+    pub fn get_cache_src(&self) -> &CacheSrc {
+        &self.cache
+    }
+
+    pub(crate) fn get_cache_src_mut(&mut self) -> &mut CacheSrc {
+        &mut self.cache
+    }
+
+    pub fn get_mini_lru_config(&self, namespace: &str, id: usize) -> OpenClResult<&CacheConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Cache,
+            BlockType::Cache(CacheType::MiniLRU),
+            id,
+        )?;
+        self.cache.get_mini_lru_by_id(program_id)
+    }
+
+    pub fn get_lru_config(&self, namespace: &str, id: usize) -> OpenClResult<&CacheConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Cache,
+            BlockType::Cache(CacheType::LRU),
+            id,
+        )?;
+        self.cache.get_lru_by_id(program_id)
+    }
+
+    pub fn get_ttl_cache_config(&self, namespace: &str, id: usize) -> OpenClResult<&CacheConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Cache,
+            BlockType::Cache(CacheType::TTL),
+            id,
+        )?;
+        self.cache.get_ttl_by_id(program_id)
+    }
+
+    pub fn get_lfu_config(&self, namespace: &str, id: usize) -> OpenClResult<&CacheConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Cache,
+            BlockType::Cache(CacheType::LFU),
+            id,
+        )?;
+        self.cache.get_lfu_by_id(program_id)
+    }
+
+    pub fn get_two_q_config(&self, namespace: &str, id: usize) -> OpenClResult<&CacheConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Cache,
+            BlockType::Cache(CacheType::TwoQ),
+            id,
+        )?;
+        self.cache.get_two_q_by_id(program_id)
+    }
+
+    // queue
+
+    pub fn add_queue(&mut self, namespace: &str, queue_src: &QueueSrc) -> OpenClResult<()> {
+        self.check_namespace(namespace, CollectionKind::Queue)?;
+
+        let mut collection_namespace = CollectionNamespace::new(namespace, CollectionKind::Queue);
+
+        for config in queue_src.get_configs() {
+            let program_id = self.queue.add_config(config);
+
+            collection_namespace.blocks.push(NamespaceBlock::new(
+                BlockType::Queue(config.queue_type),
+                config.id,
+                program_id,
+            ));
+        }
+
+        self.register_namespace(collection_namespace)
+    }
+
+    pub fn get_queue_src(&self) -> &QueueSrc {
+        &self.queue
+    }
+
+    pub(crate) fn get_queue_src_mut(&mut self) -> &mut QueueSrc {
+        &mut self.queue
+    }
+
+    pub fn get_queue_config(
+        &self,
+        namespace: &str,
+        id: usize,
+        queue_type: QueueType,
+    ) -> OpenClResult<&QueueConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Queue,
+            BlockType::Queue(queue_type),
+            id,
+        )?;
+        self.queue.get_config_by_id(program_id, queue_type)
+    }
+
+    // set
+
+    pub fn set_array_set_version(&mut self, version: ArraySetVersion) {
+        self.set_version = version;
+    }
+
+    pub fn get_array_set_version(&self) -> ArraySetVersion {
+        self.set_version
+    }
+
+    pub fn add_set(&mut self, namespace: &str, set_src: &SetSrc) -> OpenClResult<()> {
+        self.check_namespace(namespace, CollectionKind::Set)?;
+
+        let mut collection_namespace = CollectionNamespace::new(namespace, CollectionKind::Set);
+
+        for config in set_src.get_configs() {
+            let program_id = self.set.add_config(config);
+
+            collection_namespace.blocks.push(NamespaceBlock::new(
+                BlockType::Set(config.set_type),
+                config.id,
+                program_id,
+            ));
+        }
+
+        self.register_namespace(collection_namespace)
+    }
+
+    pub fn get_set_src(&self) -> &SetSrc {
+        &self.set
+    }
+
+    pub(crate) fn get_set_src_mut(&mut self) -> &mut SetSrc {
+        &mut self.set
+    }
+
+    pub fn get_set_config(&self, namespace: &str, id: usize) -> OpenClResult<&SetConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Set,
+            BlockType::Set(SetType::ArraySet),
+            id,
+        )?;
+        self.set.get_config_by_id(program_id)
+    }
+
+    pub fn get_hash_set_config(&self, namespace: &str, id: usize) -> OpenClResult<&SetConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Set,
+            BlockType::Set(SetType::HashSet),
+            id,
+        )?;
+        self.set.get_hash_set_by_id(program_id)
+    }
+
+    // stack
+
+    pub fn add_stack(&mut self, namespace: &str, stack_src: &StackSrc) -> OpenClResult<()> {
+        self.check_namespace(namespace, CollectionKind::Stack)?;
+
+        let mut collection_namespace = CollectionNamespace::new(namespace, CollectionKind::Stack);
+
+        for config in stack_src.get_configs() {
+            let program_id = self.stack.add_config(config);
+
+            collection_namespace.blocks.push(NamespaceBlock::new(
+                BlockType::Stack(config.stack_type),
+                config.id,
+                program_id,
+            ));
+        }
+
+        self.register_namespace(collection_namespace)
+    }
+
+    pub fn get_stack_src(&self) -> &StackSrc {
+        &self.stack
+    }
+
+    pub(crate) fn get_stack_src_mut(&mut self) -> &mut StackSrc {
+        &mut self.stack
+    }
+
+    pub fn get_stack_config(&self, namespace: &str, id: usize) -> OpenClResult<&StackConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Stack,
+            BlockType::Stack(StackType::Int),
+            id,
+        )?;
+        self.stack.get_config_by_id(program_id)
+    }
+
+    pub fn get_value_stack_config(&self, namespace: &str, id: usize) -> OpenClResult<&StackConfig> {
+        let program_id = self.get_program_id(
+            namespace,
+            CollectionKind::Stack,
+            BlockType::Stack(StackType::Value),
+            id,
+        )?;
+        self.stack.get_value_stack_by_id(program_id)
+    }
+}
+
+impl<T: ClTypeTrait> Default for CollectionsSrc<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{BYTE_256, BYTE_512, KB};
+
+    #[test]
+    fn test_collections_src_add_stack() {
+        let mut stack_src_a = StackSrc::new();
+        stack_src_a.add(8);
+        stack_src_a.add(16);
+
+        let mut stack_src_b = StackSrc::new();
+        stack_src_b.add(32);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_stack("a", &stack_src_a).unwrap();
+        collections_src.add_stack("b", &stack_src_b).unwrap();
+
+        let ids: Vec<usize> = collections_src
+            .get_stack_src()
+            .get_configs()
+            .iter()
+            .map(|x| x.id)
+            .collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+
+        assert_eq!(
+            collections_src.get_stack_config("a", 1),
+            Ok(&StackConfig::new(1, 16, 1, StackType::Int))
+        );
+        assert_eq!(
+            collections_src.get_stack_config("b", 0),
+            Ok(&StackConfig::new(2, 32, 1, StackType::Int))
+        );
+        assert_eq!(
+            collections_src.get_stack_config("b", 1),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE
+            ))
+        );
+        assert_eq!(
+            collections_src.get_stack_config("c", 0),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_collections_src_add_value_stack() {
+        let mut stack_src_a = StackSrc::new();
+        stack_src_a.add(8);
+
+        let mut stack_src_b = StackSrc::new();
+        stack_src_b.add_value_stack(4, 16);
+        stack_src_b.add(32);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_stack("a", &stack_src_a).unwrap();
+        collections_src.add_stack("b", &stack_src_b).unwrap();
+
+        assert_eq!(
+            collections_src.get_value_stack_config("b", 0),
+            Ok(&StackConfig::new(1, 16, 4, StackType::Value))
+        );
+        assert_eq!(
+            collections_src.get_stack_config("b", 1),
+            Ok(&StackConfig::new(2, 32, 1, StackType::Int))
+        );
+        assert_eq!(
+            collections_src.get_stack_config("b", 0),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_collections_src_duplicate_namespace() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_stack("a", &stack_src).unwrap();
+
+        assert_eq!(
+            collections_src.add_stack("a", &stack_src),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_DUPLICATE_NAMESPACE
+            ))
+        );
+
+        // the same name in another kind of collection
+        assert!(collections_src.add_queue("a", &queue_src).is_ok());
+        assert_eq!(collections_src.get_stack_src().len(), 1);
+    }
+
+    #[test]
+    fn test_collections_src_add_cache_and_queue() {
+        let mut cache_src_a = CacheSrc::new();
+        cache_src_a.add_mini_lru(8);
+        cache_src_a.add_lru(16, 16, 16);
+
+        let mut cache_src_b = CacheSrc::new();
+        cache_src_b.add_lru(32, 32, 32);
+        cache_src_b.add_mini_lru(32);
+
+        let mut queue_src_a = QueueSrc::new();
+        queue_src_a.add_lq(8);
+        queue_src_a.add_pq(8);
+
+        let mut queue_src_b = QueueSrc::new();
+        queue_src_b.add_pq(16);
+        queue_src_b.add_cq(16);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_cache("a", &cache_src_a).unwrap();
+        collections_src.add_cache("b", &cache_src_b).unwrap();
+        collections_src.add_queue("a", &queue_src_a).unwrap();
+        collections_src.add_queue("b", &queue_src_b).unwrap();
+
+        assert_eq!(
+            collections_src.get_lru_config("b", 0),
+            Ok(&CacheConfig::new(1, 32, 32, 32, CacheType::LRU))
+        );
+        assert_eq!(
+            collections_src.get_mini_lru_config("b", 0),
+            Ok(&CacheConfig::new(1, 1, 1, 32, CacheType::MiniLRU))
+        );
+        assert_eq!(
+            collections_src.get_queue_config("b", 0, QueueType::Priority),
+            Ok(&QueueConfig::new(1, 16, QueueType::Priority))
+        );
+        assert_eq!(
+            collections_src.get_queue_config("b", 0, QueueType::Circular),
+            Ok(&QueueConfig::new(0, 16, QueueType::Circular))
+        );
+    }
+
+    #[test]
+    fn test_collections_src_add_dict_and_set() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 8, 8);
+
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_dict("a", &dict_src).unwrap();
+        collections_src.add_dict("b", &dict_src).unwrap();
+        collections_src.add_set("a", &set_src).unwrap();
+        collections_src.add_set("b", &set_src).unwrap();
+
+        assert_eq!(
+            collections_src.get_dict_config("b", 0),
+            Ok(&DictConfig::new(1, 8, 8, 8))
+        );
+        assert_eq!(
+            collections_src.get_set_config("b", 0),
+            Ok(&SetConfig::new(1, 8, SetType::ArraySet))
+        );
+    }
+
+    #[test]
+    fn test_collections_src_add_hash_set() {
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+        set_src.add_hash_set(16);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_set("a", &set_src).unwrap();
+        collections_src.add_set("b", &set_src).unwrap();
+
+        assert_eq!(
+            collections_src.get_hash_set_config("b", 1),
+            Ok(&SetConfig::new(3, 16, SetType::HashSet))
+        );
+        assert_eq!(
+            collections_src.get_hash_set_config("b", 0),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE
+            ))
+        );
+
+        assert!(collections_src.check_symbols().is_empty());
+    }
+
+    #[test]
+    fn test_collections_src_add_map() {
+        let mut map_src_a: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src_a.add(BYTE_256, 8);
+
+        let mut map_src_b: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src_b.add(BYTE_512, 8);
+        map_src_b.add(KB, 8);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_map("a", &map_src_a).unwrap();
+        collections_src.add_map("b", &map_src_b).unwrap();
+
+        let block_sizes: Vec<usize> = collections_src
+            .get_map_src()
+            .unwrap()
+            .get_configs()
+            .iter()
+            .map(|x| x.value_len)
+            .collect();
+        assert_eq!(block_sizes, vec![BYTE_256, BYTE_512, KB]);
+        assert_eq!(collections_src.get_map_src().unwrap().get_total_maps(), 4);
+    }
+
+    #[test]
+    fn test_collections_src_map_namespaces_are_isolated() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 8);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_map("a", &map_src).unwrap();
+        collections_src.add_map("b", &map_src).unwrap();
+
+        // the same map id of both namespaces
+        assert_eq!(collections_src.get_map_id("a", 0), Ok(0));
+        assert_eq!(collections_src.get_map_id("a", 1), Ok(1));
+        assert_eq!(collections_src.get_map_id("b", 0), Ok(2));
+        assert_eq!(collections_src.get_map_id("b", 1), Ok(3));
+        assert_eq!(
+            collections_src.get_map_id("b", 2),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_NAMESPACE
+            ))
+        );
+
+        let combined = collections_src.get_map_src().unwrap();
+        assert_eq!(combined.get_total_maps(), 4);
+        assert_eq!(combined.get_configs().len(), 1);
+
+        let program_source = collections_src.build().unwrap();
+        assert!(program_source.contains("__global int map_keys__256_byte[4][8][256];"));
+    }
+
+    #[test]
+    fn test_collections_src_add_incompatible_map() {
+        let mut map_src_a: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src_a.add(BYTE_256, 8);
+
+        let mut map_src_b: MapSrc<i32> = MapSrc::new(4).unwrap();
+        map_src_b.add(BYTE_512, 8);
+        map_src_b.add_map_add_program_src();
+
+        let mut map_src_c: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src_c.add(BYTE_256, 16);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_map("a", &map_src_a).unwrap();
+
+        assert_eq!(
+            collections_src.add_map("b", &map_src_b),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INCOMPATIBLE_MAP_SRC
+            ))
+        );
+        assert_eq!(
+            collections_src.add_map("c", &map_src_c),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_SYMBOL_COLLISION
+            ))
+        );
+        assert_eq!(
+            collections_src.get_map_src().unwrap().get_configs().len(),
+            1
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_collections_map_namespaces {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::map::handle::{Handle, MapHandle};
+    use crate::utils::BYTE_256;
+    use opencl::wrapper::system::System;
+    use std::sync::Arc;
+
+    #[test]
+    fn same_map_id_in_two_namespaces() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(1).unwrap();
+        map_src.add(BYTE_256, 8);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_map("a", &map_src).unwrap();
+        collections_src.add_map("b", &map_src).unwrap();
+
+        let program_source = collections_src.build().unwrap();
+        let system = Arc::new(System::new(DEFAULT_DEVICE_INDEX, &program_source).unwrap());
+
+        let combined_map_src = collections_src.get_map_src().unwrap();
+        Handle::new(combined_map_src, system.clone())
+            .initialize_all_maps()
+            .unwrap();
+
+        let map_a = MapHandle::new(
+            collections_src.get_map_id("a", 0).unwrap(),
+            combined_map_src,
+            system.clone(),
+        );
+        let map_b = MapHandle::new(
+            collections_src.get_map_id("b", 0).unwrap(),
+            combined_map_src,
+            system,
+        );
+
+        map_a
+            .put(BYTE_256, &vec![vec![1]], &vec![vec![10]])
+            .unwrap();
+        map_b
+            .put(BYTE_256, &vec![vec![1]], &vec![vec![20]])
+            .unwrap();
+
+        let pairs_a = map_a.read(BYTE_256).unwrap();
+        let pairs_b = map_b.read(BYTE_256).unwrap();
+
+        assert_eq!(pairs_a.len(), 1);
+        assert_eq!(pairs_a[0].get_key(), vec![1]);
+        assert_eq!(pairs_a[0].get_value(), vec![10]);
+
+        assert_eq!(pairs_b.len(), 1);
+        assert_eq!(pairs_b[0].get_key(), vec![1]);
+        assert_eq!(pairs_b[0].get_value(), vec![20]);
+    }
+}
@@ -0,0 +1,1117 @@
+//! # Config file (toml, json)
+//!
+//! Declarative layout of the collection src, so that the capacities and blocks can be
+//! tuned without recompiling, the ids are assigned in the order of the file.
+//!
+//! ```toml
+//! total_maps = 2
+//!
+//! [[blocks]]
+//! value_len = 256
+//! capacity = 1024
+//!
+//! [[blocks]]
+//! value_len = 1024
+//! capacity = 512
+//! ```
+//!
+//! The optional sources of the map (map_insert, map_get, ...) are not part of the file,
+//! they must be added after loading it.
+
+use crate::cache::config::{CacheConfig, CacheSrc, CacheType};
+use crate::config::{ClTypeTrait, DEBUG_MODE};
+use crate::dictionary::config::DictSrc;
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_DUPLICATE_MAP_VALUE_LEN,
+    CL_COLLECTION_INVALID_BLOCK_LEN, CL_COLLECTION_INVALID_CACHE_TTL,
+    CL_COLLECTION_INVALID_CAPACITY, CL_COLLECTION_INVALID_CONFIG_FILE,
+};
+use crate::map::config::MapSrc;
+use crate::queue::config::{
+    PriorityQueueType, QueueConfig, QueueOverflowPolicy, QueueSrc, QueueType,
+};
+use crate::set::config::{SetSrc, SetType};
+use crate::stack::config::{StackSrc, StackType};
+use opencl::opencl_sys::bindings::cl_long;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CapacityBlockFile {
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapBlockFile {
+    pub value_len: usize,
+    pub capacity: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ttl: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MapSrcFile {
+    pub total_maps: usize,
+    #[serde(default)]
+    pub blocks: Vec<MapBlockFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LruBlockFile {
+    pub key_len: usize,
+    pub value_len: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TtlBlockFile {
+    pub key_len: usize,
+    pub value_len: usize,
+    pub capacity: usize,
+    pub default_ttl: cl_long,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheSrcFile {
+    #[serde(default)]
+    pub mini_lru: Vec<CapacityBlockFile>,
+    #[serde(default)]
+    pub lru: Vec<LruBlockFile>,
+    #[serde(default)]
+    pub ttl: Vec<TtlBlockFile>,
+    #[serde(default)]
+    pub lfu: Vec<LruBlockFile>,
+    #[serde(default)]
+    pub two_q: Vec<LruBlockFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DictBlockFile {
+    pub key_len: usize,
+    pub value_len: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DictSrcFile {
+    #[serde(default)]
+    pub blocks: Vec<DictBlockFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriorityQueueBlockFile {
+    pub capacity: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub drop_lowest_priority: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub heap: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircularQueueBlockFile {
+    pub capacity: usize,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overwrite_oldest: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueueSrcFile {
+    #[serde(default)]
+    pub lineal: Vec<CapacityBlockFile>,
+    #[serde(default)]
+    pub priority: Vec<PriorityQueueBlockFile>,
+    #[serde(default)]
+    pub circular: Vec<CircularQueueBlockFile>,
+    #[serde(default)]
+    pub mpmc: Vec<CapacityBlockFile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetBlockType {
+    #[default]
+    Array,
+    Hash,
+}
+
+/// the set ids are shared by all the set types, the blocks are kept in one list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetBlockFile {
+    pub capacity: usize,
+    #[serde(rename = "type", default, skip_serializing_if = "is_default")]
+    pub set_type: SetBlockType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SetSrcFile {
+    #[serde(default)]
+    pub blocks: Vec<SetBlockFile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StackBlockType {
+    #[default]
+    Int,
+    Value,
+}
+
+/// the stack ids are shared by all the stack types, the blocks are kept in one list,
+/// value_len is required by the value stacks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StackBlockFile {
+    pub capacity: usize,
+    #[serde(rename = "type", default, skip_serializing_if = "is_default")]
+    pub stack_type: StackBlockType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_len: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StackSrcFile {
+    #[serde(default)]
+    pub blocks: Vec<StackBlockFile>,
+}
+
+fn is_default<T: Default + PartialEq>(x: &T) -> bool {
+    x == &T::default()
+}
+
+fn cache_blocks(configs: Vec<&CacheConfig>) -> Vec<LruBlockFile> {
+    configs
+        .iter()
+        .map(|x| LruBlockFile {
+            key_len: x.key_len,
+            value_len: x.value_len,
+            capacity: x.capacity,
+        })
+        .collect()
+}
+
+fn check_capacity(capacity: usize) -> OpenClResult<()> {
+    if capacity == 0 {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_CAPACITY,
+        ));
+    }
+    Ok(())
+}
+
+fn check_block_len(len: usize) -> OpenClResult<()> {
+    if len == 0 {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_BLOCK_LEN,
+        ));
+    }
+    Ok(())
+}
+
+fn capacity_blocks(capacities: impl Iterator<Item = usize>) -> Vec<CapacityBlockFile> {
+    capacities
+        .map(|capacity| CapacityBlockFile { capacity })
+        .collect()
+}
+
+fn invalid_config_file<E: std::fmt::Display>(e: E) -> OpenclError {
+    if DEBUG_MODE {
+        println!("invalid config file: {e}");
+    }
+    OpenclError::OpenclCollection(CL_COLLECTION_INVALID_CONFIG_FILE)
+}
+
+pub trait SrcFile: Sized {
+    type File: Serialize + DeserializeOwned;
+
+    fn to_src_file(&self) -> Self::File;
+
+    /// validate the file and create the src
+    fn from_src_file(file: Self::File) -> OpenClResult<Self>;
+
+    fn from_toml(toml_str: &str) -> OpenClResult<Self> {
+        let file: Self::File = toml::from_str(toml_str).map_err(invalid_config_file)?;
+        Self::from_src_file(file)
+    }
+
+    fn to_toml(&self) -> OpenClResult<String> {
+        toml::to_string(&self.to_src_file()).map_err(invalid_config_file)
+    }
+
+    fn from_json(json_str: &str) -> OpenClResult<Self> {
+        let file: Self::File = serde_json::from_str(json_str).map_err(invalid_config_file)?;
+        Self::from_src_file(file)
+    }
+
+    fn to_json(&self) -> OpenClResult<String> {
+        serde_json::to_string_pretty(&self.to_src_file()).map_err(invalid_config_file)
+    }
+
+    /// the format is selected by the extension of the file (.toml, .json)
+    fn read_file<P: AsRef<Path>>(path: P) -> OpenClResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            Some("json") => Self::from_json(&content),
+            _ => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE,
+            )),
+        }
+    }
+
+    fn write_file<P: AsRef<Path>>(&self, path: P) -> OpenClResult<()> {
+        let path = path.as_ref();
+
+        let content = match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => self.to_toml()?,
+            Some("json") => self.to_json()?,
+            _ => {
+                return Err(OpenclError::OpenclCollection(
+                    CL_COLLECTION_INVALID_CONFIG_FILE,
+                ))
+            }
+        };
+
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+impl<T: ClTypeTrait> SrcFile for MapSrc<T> {
+    type File = MapSrcFile;
+
+    fn to_src_file(&self) -> Self::File {
+        MapSrcFile {
+            total_maps: self.get_total_maps(),
+            blocks: self
+                .get_configs()
+                .iter()
+                .map(|x| MapBlockFile {
+                    value_len: x.value_len,
+                    capacity: x.capacity,
+                    ttl: x.ttl,
+                })
+                .collect(),
+        }
+    }
+
+    fn from_src_file(file: Self::File) -> OpenClResult<Self> {
+        let mut map_src = MapSrc::new(file.total_maps)?;
+
+        for block in file.blocks {
+            check_block_len(block.value_len)?;
+            check_capacity(block.capacity)?;
+            if block.ttl {
+                map_src.add_with_ttl(block.value_len, block.capacity);
+            } else {
+                map_src.add(block.value_len, block.capacity);
+            }
+        }
+
+        if !map_src.check() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_DUPLICATE_MAP_VALUE_LEN,
+            ));
+        }
+
+        Ok(map_src)
+    }
+}
+
+impl SrcFile for CacheSrc {
+    type File = CacheSrcFile;
+
+    fn to_src_file(&self) -> Self::File {
+        CacheSrcFile {
+            mini_lru: capacity_blocks(
+                self.get_configs_by_type(CacheType::MiniLRU)
+                    .iter()
+                    .map(|x| x.capacity),
+            ),
+            lru: cache_blocks(self.get_configs_by_type(CacheType::LRU)),
+            ttl: self
+                .get_configs_by_type(CacheType::TTL)
+                .iter()
+                .map(|x| TtlBlockFile {
+                    key_len: x.key_len,
+                    value_len: x.value_len,
+                    capacity: x.capacity,
+                    default_ttl: x.default_ttl,
+                })
+                .collect(),
+            lfu: cache_blocks(self.get_configs_by_type(CacheType::LFU)),
+            two_q: cache_blocks(self.get_configs_by_type(CacheType::TwoQ)),
+        }
+    }
+
+    fn from_src_file(file: Self::File) -> OpenClResult<Self> {
+        let mut cache_src = CacheSrc::new();
+
+        for block in file.mini_lru {
+            check_capacity(block.capacity)?;
+            cache_src.add_mini_lru(block.capacity);
+        }
+
+        for block in file.lru {
+            check_block_len(block.key_len)?;
+            check_block_len(block.value_len)?;
+            check_capacity(block.capacity)?;
+            cache_src.add_lru(block.key_len, block.value_len, block.capacity);
+        }
+
+        for block in file.ttl {
+            check_block_len(block.key_len)?;
+            check_block_len(block.value_len)?;
+            check_capacity(block.capacity)?;
+
+            if block.default_ttl <= 0 {
+                return Err(OpenclError::OpenclCollection(
+                    CL_COLLECTION_INVALID_CACHE_TTL,
+                ));
+            }
+
+            cache_src.add_ttl(
+                block.key_len,
+                block.value_len,
+                block.capacity,
+                block.default_ttl,
+            );
+        }
+
+        for block in file.lfu {
+            check_block_len(block.key_len)?;
+            check_block_len(block.value_len)?;
+            check_capacity(block.capacity)?;
+            cache_src.add_lfu(block.key_len, block.value_len, block.capacity);
+        }
+
+        for block in file.two_q {
+            check_block_len(block.key_len)?;
+            check_block_len(block.value_len)?;
+            check_capacity(block.capacity)?;
+            cache_src.add_two_q(block.key_len, block.value_len, block.capacity);
+        }
+
+        Ok(cache_src)
+    }
+}
+
+impl<T: ClTypeTrait> SrcFile for DictSrc<T> {
+    type File = DictSrcFile;
+
+    fn to_src_file(&self) -> Self::File {
+        DictSrcFile {
+            blocks: self
+                .get_configs()
+                .iter()
+                .map(|x| DictBlockFile {
+                    key_len: x.key_len,
+                    value_len: x.value_len,
+                    capacity: x.capacity,
+                })
+                .collect(),
+        }
+    }
+
+    fn from_src_file(file: Self::File) -> OpenClResult<Self> {
+        let mut dict_src = DictSrc::new();
+
+        for block in file.blocks {
+            check_block_len(block.key_len)?;
+            check_block_len(block.value_len)?;
+            check_capacity(block.capacity)?;
+            dict_src.add(block.key_len, block.value_len, block.capacity);
+        }
+
+        Ok(dict_src)
+    }
+}
+
+impl SrcFile for QueueSrc {
+    type File = QueueSrcFile;
+
+    fn to_src_file(&self) -> Self::File {
+        let has_policy =
+            |config: &QueueConfig, policy: QueueOverflowPolicy| config.overflow_policy == policy;
+
+        QueueSrcFile {
+            lineal: capacity_blocks(
+                self.get_configs_by_type(QueueType::Lineal)
+                    .iter()
+                    .map(|x| x.capacity),
+            ),
+            priority: self
+                .get_configs_by_type(QueueType::Priority)
+                .iter()
+                .map(|x| PriorityQueueBlockFile {
+                    capacity: x.capacity,
+                    drop_lowest_priority: has_policy(x, QueueOverflowPolicy::DropLowestPriority),
+                    heap: x.priority_queue_type == PriorityQueueType::Heap,
+                })
+                .collect(),
+            circular: self
+                .get_configs_by_type(QueueType::Circular)
+                .iter()
+                .map(|x| CircularQueueBlockFile {
+                    capacity: x.capacity,
+                    overwrite_oldest: has_policy(x, QueueOverflowPolicy::OverwriteOldest),
+                })
+                .collect(),
+            mpmc: capacity_blocks(
+                self.get_configs_by_type(QueueType::Mpmc)
+                    .iter()
+                    .map(|x| x.capacity),
+            ),
+        }
+    }
+
+    fn from_src_file(file: Self::File) -> OpenClResult<Self> {
+        let mut queue_src = QueueSrc::new();
+
+        let get_policy = |enabled: bool, policy: QueueOverflowPolicy| {
+            if enabled {
+                policy
+            } else {
+                QueueOverflowPolicy::Reject
+            }
+        };
+
+        for block in file.lineal {
+            check_capacity(block.capacity)?;
+            queue_src.add(block.capacity, QueueType::Lineal);
+        }
+
+        for block in file.priority {
+            check_capacity(block.capacity)?;
+
+            let priority_queue_type = if block.heap {
+                PriorityQueueType::Heap
+            } else {
+                PriorityQueueType::Ordered
+            };
+
+            queue_src.add_config(&QueueConfig {
+                overflow_policy: get_policy(
+                    block.drop_lowest_priority,
+                    QueueOverflowPolicy::DropLowestPriority,
+                ),
+                priority_queue_type,
+                ..QueueConfig::new(0, block.capacity, QueueType::Priority)
+            });
+        }
+
+        for block in file.circular {
+            check_capacity(block.capacity)?;
+            queue_src.add_with_overflow_policy(
+                block.capacity,
+                QueueType::Circular,
+                get_policy(block.overwrite_oldest, QueueOverflowPolicy::OverwriteOldest),
+            )?;
+        }
+
+        for block in file.mpmc {
+            check_capacity(block.capacity)?;
+            queue_src.add(block.capacity, QueueType::Mpmc);
+        }
+
+        Ok(queue_src)
+    }
+}
+
+impl SrcFile for SetSrc {
+    type File = SetSrcFile;
+
+    fn to_src_file(&self) -> Self::File {
+        SetSrcFile {
+            blocks: self
+                .get_configs()
+                .iter()
+                .map(|x| SetBlockFile {
+                    capacity: x.capacity,
+                    set_type: match x.set_type {
+                        SetType::ArraySet => SetBlockType::Array,
+                        SetType::HashSet => SetBlockType::Hash,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn from_src_file(file: Self::File) -> OpenClResult<Self> {
+        let mut set_src = SetSrc::new();
+
+        for block in file.blocks {
+            check_capacity(block.capacity)?;
+            match block.set_type {
+                SetBlockType::Array => set_src.add(block.capacity),
+                SetBlockType::Hash => set_src.add_hash_set(block.capacity),
+            }
+        }
+
+        Ok(set_src)
+    }
+}
+
+impl SrcFile for StackSrc {
+    type File = StackSrcFile;
+
+    fn to_src_file(&self) -> Self::File {
+        StackSrcFile {
+            blocks: self
+                .get_configs()
+                .iter()
+                .map(|x| match x.stack_type {
+                    StackType::Int => StackBlockFile {
+                        capacity: x.capacity,
+                        stack_type: StackBlockType::Int,
+                        value_len: None,
+                    },
+                    StackType::Value => StackBlockFile {
+                        capacity: x.capacity,
+                        stack_type: StackBlockType::Value,
+                        value_len: Some(x.value_len),
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn from_src_file(file: Self::File) -> OpenClResult<Self> {
+        let mut stack_src = StackSrc::new();
+
+        for block in file.blocks {
+            check_capacity(block.capacity)?;
+
+            match (block.stack_type, block.value_len) {
+                (StackBlockType::Int, None) => stack_src.add(block.capacity),
+                (StackBlockType::Value, Some(value_len)) => {
+                    check_block_len(value_len)?;
+                    stack_src.add_value_stack(value_len, block.capacity);
+                }
+                _ => {
+                    return Err(OpenclError::OpenclCollection(
+                        CL_COLLECTION_INVALID_CONFIG_FILE,
+                    ))
+                }
+            }
+        }
+
+        Ok(stack_src)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::CL_COLLECTION_INVALID_TOTAL_MAPS;
+    use crate::map::config::MapConfig;
+    use crate::queue::config::QueueConfig;
+    use crate::set::config::SetConfig;
+    use crate::stack::config::StackConfig;
+    use crate::utils::{BYTE_256, KB};
+
+    const MAP_TOML: &str = r#"
+        total_maps = 2
+
+        [[blocks]]
+        value_len = 256
+        capacity = 1024
+
+        [[blocks]]
+        value_len = 1024
+        capacity = 512
+    "#;
+
+    #[test]
+    fn test_map_src_from_toml() {
+        let map_src: MapSrc<i32> = MapSrc::from_toml(MAP_TOML).unwrap();
+
+        assert_eq!(map_src.get_total_maps(), 2);
+        assert_eq!(
+            map_src.get_configs(),
+            &vec![MapConfig::new(BYTE_256, 1024), MapConfig::new(KB, 512)]
+        );
+    }
+
+    #[test]
+    fn test_map_src_toml_round_trip() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(4).unwrap();
+        map_src.add(BYTE_256, 16);
+        map_src.add(KB, 8);
+
+        let toml_str = map_src.to_toml().unwrap();
+        println!("{toml_str}");
+
+        let result: MapSrc<i32> = MapSrc::from_toml(&toml_str).unwrap();
+        assert_eq!(result.get_total_maps(), 4);
+        assert_eq!(result.get_configs(), map_src.get_configs());
+    }
+
+    #[test]
+    fn test_map_src_with_ttl_round_trip() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(1).unwrap();
+        map_src.add(BYTE_256, 16);
+        map_src.add_with_ttl(KB, 8);
+
+        let toml_str = map_src.to_toml().unwrap();
+        println!("{toml_str}");
+        assert_eq!(toml_str.matches("ttl = true").count(), 1);
+
+        let result: MapSrc<i32> = MapSrc::from_toml(&toml_str).unwrap();
+        assert_eq!(result.get_configs(), map_src.get_configs());
+    }
+
+    #[test]
+    fn test_map_src_json_round_trip() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(1).unwrap();
+        map_src.add(BYTE_256, 16);
+
+        let json_str = map_src.to_json().unwrap();
+        println!("{json_str}");
+
+        let result: MapSrc<i32> = MapSrc::from_json(&json_str).unwrap();
+        assert_eq!(result.get_configs(), map_src.get_configs());
+    }
+
+    #[test]
+    fn test_map_src_duplicate_value_len() {
+        let json_str = r#"{
+            "total_maps": 1,
+            "blocks": [
+                { "value_len": 256, "capacity": 8 },
+                { "value_len": 256, "capacity": 16 }
+            ]
+        }"#;
+
+        let result: OpenClResult<MapSrc<i32>> = MapSrc::from_json(json_str);
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_DUPLICATE_MAP_VALUE_LEN
+            ))
+        );
+    }
+
+    #[test]
+    fn test_map_src_total_maps_zero() {
+        let result: OpenClResult<MapSrc<i32>> = MapSrc::from_toml("total_maps = 0");
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TOTAL_MAPS
+            ))
+        );
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let toml_str = r#"
+            [[blocks]]
+            capacity = 0
+        "#;
+
+        let result = StackSrc::from_toml(toml_str);
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CAPACITY
+            ))
+        );
+
+        let result = SetSrc::from_toml(toml_str);
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CAPACITY
+            ))
+        );
+    }
+
+    #[test]
+    fn test_zero_block_len() {
+        let toml_str = r#"
+            [[lru]]
+            key_len = 256
+            value_len = 0
+            capacity = 8
+        "#;
+
+        let result = CacheSrc::from_toml(toml_str);
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BLOCK_LEN
+            ))
+        );
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        // unknown field
+        let result = StackSrc::from_toml("[[blocks]]\ncapacity = 8\nkey_len = 8");
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE
+            ))
+        );
+
+        // negative capacity
+        let result = QueueSrc::from_json(r#"{ "lineal": [{ "capacity": -1 }] }"#);
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_cache_src_round_trip() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(8);
+        cache_src.add_lru(16, 32, 64);
+        cache_src.add_mini_lru(128);
+
+        let toml_str = cache_src.to_toml().unwrap();
+        println!("{toml_str}");
+
+        let result = CacheSrc::from_toml(&toml_str).unwrap();
+
+        assert_eq!(result.get_mini_lru_by_id(0).unwrap().capacity, 8);
+        assert_eq!(result.get_mini_lru_by_id(1).unwrap().capacity, 128);
+        assert_eq!(
+            result.get_lru_by_id(0).unwrap(),
+            cache_src.get_lru_by_id(0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cache_src_with_ttl_round_trip() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 32, 64);
+        cache_src.add_ttl(16, 32, 64, 1000);
+
+        let json_str = cache_src.to_json().unwrap();
+        let result = CacheSrc::from_json(&json_str).unwrap();
+
+        assert_eq!(
+            result.get_ttl_by_id(0).unwrap(),
+            cache_src.get_ttl_by_id(0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_cache_src_with_lfu_and_two_q_round_trip() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lfu(16, 32, 64);
+        cache_src.add_two_q(8, 8, 16);
+        cache_src.add_lfu(8, 8, 8);
+
+        let toml_str = cache_src.to_toml().unwrap();
+        println!("{toml_str}");
+
+        let result = CacheSrc::from_toml(&toml_str).unwrap();
+
+        for cache_type in [CacheType::LFU, CacheType::TwoQ] {
+            assert_eq!(
+                result.get_configs_by_type(cache_type),
+                cache_src.get_configs_by_type(cache_type)
+            );
+        }
+    }
+
+    #[test]
+    fn test_cache_src_invalid_ttl() {
+        let toml_str = r#"
+            [[ttl]]
+            key_len = 16
+            value_len = 32
+            capacity = 64
+            default_ttl = 0
+        "#;
+
+        assert_eq!(
+            CacheSrc::from_toml(toml_str).err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CACHE_TTL
+            ))
+        );
+    }
+
+    #[test]
+    fn test_dict_src_round_trip() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 16, 32);
+        dict_src.add(64, 128, 256);
+
+        let json_str = dict_src.to_json().unwrap();
+        let result: DictSrc<i32> = DictSrc::from_json(&json_str).unwrap();
+
+        assert_eq!(result.get_configs(), dict_src.get_configs());
+    }
+
+    #[test]
+    fn test_queue_src_from_toml() {
+        let toml_str = r#"
+            [[lineal]]
+            capacity = 8
+
+            [[priority]]
+            capacity = 16
+
+            [[circular]]
+            capacity = 32
+
+            [[lineal]]
+            capacity = 64
+
+            [[mpmc]]
+            capacity = 128
+        "#;
+
+        let queue_src = QueueSrc::from_toml(toml_str).unwrap();
+
+        assert_eq!(
+            queue_src.get_configs(),
+            &vec![
+                QueueConfig::new(0, 8, QueueType::Lineal),
+                QueueConfig::new(1, 64, QueueType::Lineal),
+                QueueConfig::new(0, 16, QueueType::Priority),
+                QueueConfig::new(0, 32, QueueType::Circular),
+                QueueConfig::new(0, 128, QueueType::Mpmc),
+            ]
+        );
+
+        let result = QueueSrc::from_toml(&queue_src.to_toml().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), queue_src.get_configs());
+    }
+
+    #[test]
+    fn test_queue_src_overflow_policy() {
+        let toml_str = r#"
+            [[priority]]
+            capacity = 16
+            drop_lowest_priority = true
+
+            [[priority]]
+            capacity = 16
+            drop_lowest_priority = true
+            heap = true
+
+            [[circular]]
+            capacity = 32
+            overwrite_oldest = true
+
+            [[circular]]
+            capacity = 8
+        "#;
+
+        let queue_src = QueueSrc::from_toml(toml_str).unwrap();
+
+        let policies: Vec<QueueOverflowPolicy> = queue_src
+            .get_configs()
+            .iter()
+            .map(|x| x.overflow_policy)
+            .collect();
+
+        assert_eq!(
+            policies,
+            vec![
+                QueueOverflowPolicy::DropLowestPriority,
+                QueueOverflowPolicy::DropLowestPriority,
+                QueueOverflowPolicy::OverwriteOldest,
+                QueueOverflowPolicy::Reject,
+            ]
+        );
+
+        let config = queue_src.get_pq_config_by_id(1).unwrap();
+        assert_eq!(config.priority_queue_type, PriorityQueueType::Heap);
+
+        let result = QueueSrc::from_json(&queue_src.to_json().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), queue_src.get_configs());
+
+        // the policy is not supported by the queue type
+        let toml_str = r#"
+            [[lineal]]
+            capacity = 16
+            overwrite_oldest = true
+        "#;
+
+        assert_eq!(
+            QueueSrc::from_toml(toml_str).err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_set_and_stack_round_trip() {
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+        set_src.add(16);
+
+        let result = SetSrc::from_json(&set_src.to_json().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), set_src.get_configs());
+
+        set_src.add_hash_set(32);
+
+        let result = SetSrc::from_toml(&set_src.to_toml().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), set_src.get_configs());
+
+        let mut stack_src = StackSrc::new();
+        stack_src.add(32);
+        stack_src.add_value_stack(8, 16);
+
+        let result = StackSrc::from_toml(&stack_src.to_toml().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), stack_src.get_configs());
+    }
+
+    #[test]
+    fn test_interleaved_stack_round_trip() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(8, 16);
+        stack_src.add(32);
+        stack_src.add_value_stack(4, 8);
+        stack_src.add(64);
+
+        let toml_str = stack_src.to_toml().unwrap();
+        println!("{toml_str}");
+        assert_eq!(toml_str.matches("type = \"value\"").count(), 2);
+
+        let result = StackSrc::from_toml(&toml_str).unwrap();
+        assert_eq!(result.get_configs(), stack_src.get_configs());
+
+        let result = StackSrc::from_json(&stack_src.to_json().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), stack_src.get_configs());
+
+        // the ids follow the order of the file
+        let toml_str = r#"
+            [[blocks]]
+            capacity = 16
+            type = "value"
+            value_len = 8
+
+            [[blocks]]
+            capacity = 32
+        "#;
+
+        let result = StackSrc::from_toml(toml_str).unwrap();
+        assert_eq!(
+            result.get_configs(),
+            &vec![
+                StackConfig::new(0, 16, 8, StackType::Value),
+                StackConfig::new(1, 32, 1, StackType::Int),
+            ]
+        );
+
+        // value stack without value_len
+        let result = StackSrc::from_toml("[[blocks]]\ncapacity = 8\ntype = \"value\"");
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE
+            ))
+        );
+
+        let result =
+            StackSrc::from_toml("[[blocks]]\ncapacity = 8\ntype = \"value\"\nvalue_len = 0");
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_BLOCK_LEN
+            ))
+        );
+    }
+
+    #[test]
+    fn test_interleaved_set_round_trip() {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(32);
+        set_src.add(8);
+        set_src.add_hash_set(64);
+        set_src.add(16);
+
+        let toml_str = set_src.to_toml().unwrap();
+        println!("{toml_str}");
+        assert_eq!(toml_str.matches("type = \"hash\"").count(), 2);
+
+        let result = SetSrc::from_toml(&toml_str).unwrap();
+        assert_eq!(result.get_configs(), set_src.get_configs());
+
+        let result = SetSrc::from_json(&set_src.to_json().unwrap()).unwrap();
+        assert_eq!(result.get_configs(), set_src.get_configs());
+
+        // the ids follow the order of the file
+        let toml_str = r#"
+            [[blocks]]
+            capacity = 8
+            type = "hash"
+
+            [[blocks]]
+            capacity = 16
+        "#;
+
+        let result = SetSrc::from_toml(toml_str).unwrap();
+        assert_eq!(
+            result.get_configs(),
+            &vec![
+                SetConfig::new(0, 8, SetType::HashSet),
+                SetConfig::new(1, 16, SetType::ArraySet),
+            ]
+        );
+
+        let result = SetSrc::from_toml("[[blocks]]\ncapacity = 8\ntype = \"tree\"");
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE
+            ))
+        );
+    }
+
+    #[test]
+    fn test_read_and_write_file() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(32);
+        stack_src.add(64);
+
+        let dir = std::env::temp_dir();
+
+        for name in ["stack_src_test.toml", "stack_src_test.json"] {
+            let path = dir.join(name);
+            stack_src.write_file(&path).unwrap();
+
+            let result = StackSrc::read_file(&path).unwrap();
+            assert_eq!(result.get_configs(), stack_src.get_configs());
+
+            fs::remove_file(&path).unwrap();
+        }
+
+        let result = stack_src.write_file(dir.join("stack_src_test.yaml"));
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_CONFIG_FILE
+            ))
+        );
+    }
+}
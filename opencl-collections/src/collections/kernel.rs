@@ -0,0 +1,449 @@
+use crate::collections::config::{
+    CollectionKind, CollectionSection, CollectionSummary, CollectionsSrc, CollectionsSummary,
+    SymbolCollision,
+};
+use crate::collections::symbol::scan_program_symbols;
+use crate::config::{ClTypeTrait, DEBUG_MODE};
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_SYMBOL_COLLISION};
+
+impl<T: ClTypeTrait> CollectionsSrc<T> {
+    fn get_blocks_by_kind(&self, kind: CollectionKind) -> usize {
+        match kind {
+            CollectionKind::Map => self
+                .get_map_src()
+                .map(|x| x.get_configs().len())
+                .unwrap_or(0),
+            CollectionKind::Cache => self.get_cache_src().len(),
+            CollectionKind::Dictionary => self.get_dict_src().len(),
+            CollectionKind::Queue => self.get_queue_src().len(),
+            CollectionKind::Set => self.get_set_src().len(),
+            CollectionKind::Stack => self.get_stack_src().len(),
+            CollectionKind::User => self.get_user_sources().len(),
+        }
+    }
+
+    fn with_device_api(&self, source: String, device_api: String) -> String {
+        if !self.has_device_api() {
+            return source;
+        }
+
+        format!(
+            "{source}
+    /// device api
+    {device_api}
+    "
+        )
+    }
+
+    /// source of each kind of collection included in the program
+    pub fn sections(&self) -> Vec<CollectionSection> {
+        let mut sections = Vec::new();
+
+        if let Some(map_src) = self.get_map_src() {
+            if !map_src.get_configs().is_empty() {
+                sections.push(CollectionSection {
+                    kind: CollectionKind::Map,
+                    source: self.with_device_api(map_src.build(), map_src.build_device_api()),
+                });
+            }
+        }
+
+        if !self.get_cache_src().is_empty() {
+            sections.push(CollectionSection {
+                kind: CollectionKind::Cache,
+                source: self.get_cache_src().build(),
+            });
+        }
+
+        if !self.get_dict_src().is_empty() {
+            sections.push(CollectionSection {
+                kind: CollectionKind::Dictionary,
+                source: self.with_device_api(
+                    self.get_dict_src().build(),
+                    self.get_dict_src().build_device_api(),
+                ),
+            });
+        }
+
+        if !self.get_queue_src().is_empty() {
+            sections.push(CollectionSection {
+                kind: CollectionKind::Queue,
+                source: self.with_device_api(
+                    self.get_queue_src().build(),
+                    self.get_queue_src().build_device_api(),
+                ),
+            });
+        }
+
+        if !self.get_set_src().is_empty() {
+            sections.push(CollectionSection {
+                kind: CollectionKind::Set,
+                source: self.with_device_api(
+                    self.get_set_src().build(self.get_array_set_version()),
+                    self.get_set_src().build_device_api(),
+                ),
+            });
+        }
+
+        if !self.get_stack_src().is_empty() {
+            sections.push(CollectionSection {
+                kind: CollectionKind::Stack,
+                source: self.with_device_api(
+                    self.get_stack_src().build(),
+                    self.get_stack_src().build_device_api(),
+                ),
+            });
+        }
+
+        if !self.get_user_sources().is_empty() {
+            sections.push(CollectionSection {
+                kind: CollectionKind::User,
+                source: self.get_user_sources().join("\n"),
+            });
+        }
+
+        sections
+    }
+
+    fn find_collisions(sections: &[CollectionSection]) -> Vec<SymbolCollision> {
+        let mut declared: Vec<(String, CollectionKind)> = Vec::new();
+
+        for section in sections {
+            let program_symbols = scan_program_symbols(&section.source);
+
+            for symbol in program_symbols.symbols {
+                declared.push((symbol.name, section.kind));
+            }
+        }
+
+        let mut collisions: Vec<SymbolCollision> = Vec::new();
+
+        for (name, _) in declared.iter() {
+            let kinds: Vec<CollectionKind> = declared
+                .iter()
+                .filter(|(n, _)| n == name)
+                .map(|(_, k)| *k)
+                .collect();
+
+            if kinds.len() > 1 && !collisions.iter().any(|x| &x.name == name) {
+                collisions.push(SymbolCollision {
+                    name: name.clone(),
+                    kinds,
+                });
+            }
+        }
+
+        collisions
+    }
+
+    /// symbols (kernels, functions, globals, ...) declared more than once in the combined program
+    pub fn check_symbols(&self) -> Vec<SymbolCollision> {
+        Self::find_collisions(&self.sections())
+    }
+
+    pub fn build(&self) -> OpenClResult<String> {
+        let sections = self.sections();
+
+        if sections.is_empty() {
+            return Ok(String::new());
+        }
+
+        let collisions = Self::find_collisions(&sections);
+
+        if !collisions.is_empty() {
+            if DEBUG_MODE {
+                println!("symbol collisions {collisions:?}");
+            }
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_SYMBOL_COLLISION,
+            ));
+        }
+
+        let mut collections_src = String::new();
+
+        for section in sections {
+            collections_src.push_str(&section.source);
+        }
+
+        Ok(format!(
+            "
+    /// *** COLLECTIONS SRC START *** ///
+
+    {collections_src}
+
+    /// *** COLLECTIONS SRC END *** ///
+    "
+        ))
+    }
+
+    /// Memory required by the global variables of each kind of collection in the program
+    pub fn summary(&self) -> CollectionsSummary {
+        let collections: Vec<CollectionSummary> = self
+            .sections()
+            .iter()
+            .map(|section| {
+                let program_symbols = scan_program_symbols(&section.source);
+
+                CollectionSummary::new(
+                    section.kind,
+                    self.get_blocks_by_kind(section.kind),
+                    program_symbols.kernel_names().len(),
+                    program_symbols.memory_required(),
+                )
+            })
+            .collect();
+
+        CollectionsSummary::new(collections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::config::CacheSrc;
+    use crate::dictionary::config::DictSrc;
+    use crate::map::config::{MapSrc, MAX_FIND_WORK_SIZE};
+    use crate::queue::config::QueueSrc;
+    use crate::set::config::{ArraySetVersion, SetSrc};
+    use crate::stack::config::StackSrc;
+    use crate::utils::{BYTE_256, BYTE_512};
+
+    fn create_collections_src() -> CollectionsSrc<i32> {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 8);
+        map_src.add(BYTE_512, 8);
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(8);
+        cache_src.add_lru(16, 16, 8);
+
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 8, 8);
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+        queue_src.add_pq(8);
+        queue_src.add_cq(8);
+
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+
+        let mut collections_src = CollectionsSrc::new();
+        collections_src.add_map("map", &map_src).unwrap();
+        collections_src.add_cache("cache", &cache_src).unwrap();
+        collections_src.add_dict("dict", &dict_src).unwrap();
+        collections_src.add_queue("queue", &queue_src).unwrap();
+        collections_src.add_set("set", &set_src).unwrap();
+        collections_src.add_stack("stack", &stack_src).unwrap();
+
+        collections_src
+    }
+
+    #[test]
+    fn test_all_kinds() {
+        let collections_src = create_collections_src();
+
+        let collisions = collections_src.check_symbols();
+        println!("{collisions:#?}");
+        assert!(collisions.is_empty());
+
+        let program_source = collections_src.build().unwrap();
+        assert!(!program_source.is_empty());
+    }
+
+    #[test]
+    fn test_all_kinds_with_array_set_v1() {
+        let mut collections_src = create_collections_src();
+        collections_src.set_array_set_version(ArraySetVersion::V1);
+
+        assert!(collections_src.check_symbols().is_empty());
+        assert!(collections_src.build().is_ok());
+    }
+
+    #[test]
+    fn test_with_map_optional_sources() {
+        let mut collections_src = create_collections_src();
+        collections_src
+            .get_map_src_mut()
+            .unwrap()
+            .add_map_insert_program_src(MAX_FIND_WORK_SIZE)
+            .add_map_get_program_src(MAX_FIND_WORK_SIZE);
+
+        assert!(collections_src.check_symbols().is_empty());
+        assert!(collections_src.build().is_ok());
+    }
+
+    #[test]
+    fn test_same_kind_is_merged() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+        stack_src.add(16);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_stack("a", &stack_src).unwrap();
+        collections_src.add_stack("b", &stack_src).unwrap();
+
+        let program_source = collections_src.build().unwrap();
+
+        for id in 0..4 {
+            assert!(program_source.contains(&format!("stack__{id}[")));
+        }
+    }
+
+    #[test]
+    fn test_symbol_collision() {
+        let collections_src = create_collections_src();
+        let sections = collections_src.sections();
+
+        let mut duplicated = sections.clone();
+        duplicated.push(sections[0].clone());
+
+        let collisions = CollectionsSrc::<i32>::find_collisions(&duplicated);
+        assert!(!collisions.is_empty());
+        assert!(collisions
+            .iter()
+            .all(|x| x.kinds == vec![CollectionKind::Map, CollectionKind::Map]));
+    }
+
+    #[test]
+    fn test_summary() {
+        let collections_src = create_collections_src();
+
+        let summary = collections_src.summary();
+        println!("{summary:#?}");
+
+        assert_eq!(summary.collections.len(), 6);
+
+        // __global int stack__0[8];
+        // __global int stack_top__0 = -1;
+        let stack_summary = summary.get_by_kind(CollectionKind::Stack).unwrap();
+        assert_eq!(stack_summary.memory_required, (8 * 2 + 1) * 4);
+        assert_eq!(stack_summary.blocks, 1);
+
+        // __global int dict_keys__0[8][8];
+        // __global int dict_values__0[8][8];
+        // __global int dict_entries__0[8];
+        // __global int dict_write_stack__0[8];
+        // __global int dict_write_stack_top__0 = -1;
+        // __global int dict_remove_stack__0[8];
+        // __global int dict_remove_stack_top__0 = -1;
+        let dict_summary = summary.get_by_kind(CollectionKind::Dictionary).unwrap();
+        assert_eq!(
+            dict_summary.memory_required,
+            (8 * 8 * 2 + 8 + (8 + 1) * 2) * 4
+        );
+
+        // __global int map_keys__BLOCK_NAME[2][8][256];
+        // __global int map_values__BLOCK_NAME[2][8][VALUE_LEN];
+        let map_summary = summary.get_by_kind(CollectionKind::Map).unwrap();
+        assert!(map_summary.memory_required >= 2 * 8 * (256 * 2 + 256 + 512) * 4);
+
+        let total: usize = summary.collections.iter().map(|x| x.memory_required).sum();
+        assert_eq!(summary.total_memory_required, total);
+    }
+
+    const USER_SRC: &str = r#"
+    kernel void move_stack_to_queue(
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        int v = -1;
+        int pi = stack_pop__0(&v);
+
+        if (pi >= 0) {
+            pi = lq_enqueue__0(v);
+        }
+
+        int map_value[256];
+        map_get__256_byte(0, map_value, map_value);
+
+        output[i] = pi;
+    }
+    "#;
+
+    #[test]
+    fn test_with_device_api() {
+        let mut collections_src = create_collections_src();
+
+        let program_source = collections_src.build().unwrap();
+        assert!(!program_source.contains("int stack_push__0(int v) {"));
+
+        collections_src.enable_device_api();
+        collections_src.add_user_src(USER_SRC);
+
+        let collisions = collections_src.check_symbols();
+        println!("{collisions:#?}");
+        assert!(collisions.is_empty());
+
+        let program_source = collections_src.build().unwrap();
+        println!("{program_source}");
+        assert!(program_source.contains("int stack_push__0(int v) {"));
+        assert!(program_source.contains("int lq_enqueue__0(int v) {"));
+        assert!(!program_source.contains("int cq_enqueue__2("));
+        assert!(program_source.contains("int set_insert__0(int k) {"));
+        assert!(program_source.contains("int dict_lookup__0(int* key, int* value_output) {"));
+        assert!(program_source
+            .contains("int map_get__512_byte(uint map_id, int* key, int* value_output) {"));
+
+        // the user source is added after the collections
+        let user_index = program_source
+            .find("kernel void move_stack_to_queue(")
+            .unwrap();
+        assert!(program_source.find("int stack_pop__0(int* v) {").unwrap() < user_index);
+
+        let summary = collections_src.summary();
+        let user_summary = summary.get_by_kind(CollectionKind::User).unwrap();
+        assert_eq!(user_summary.blocks, 1);
+        assert_eq!(user_summary.kernels, 1);
+    }
+
+    #[test]
+    fn test_user_src_symbol_collision() {
+        let mut collections_src = create_collections_src();
+        collections_src.add_user_src(
+            r#"
+    kernel void stack_reset__0() {}
+    "#,
+        );
+
+        let collisions = collections_src.check_symbols();
+        assert_eq!(
+            collisions,
+            vec![SymbolCollision {
+                name: "stack_reset__0".to_string(),
+                kinds: vec![CollectionKind::Stack, CollectionKind::User],
+            }]
+        );
+        assert_eq!(
+            collections_src.build(),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_SYMBOL_COLLISION
+            ))
+        );
+    }
+
+    #[test]
+    fn test_only_user_src() {
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_user_src(USER_SRC);
+
+        assert!(!collections_src.is_empty());
+        assert!(collections_src
+            .build()
+            .unwrap()
+            .contains("kernel void move_stack_to_queue("));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+
+        let program_source = collections_src.build().unwrap();
+        assert!(program_source.is_empty());
+        assert_eq!(collections_src.summary().total_memory_required, 0);
+    }
+}
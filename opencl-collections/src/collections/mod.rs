@@ -0,0 +1,11 @@
+//! # Opencl Collections (single program)
+//!
+//! Combine the src of several collections (map, cache, dictionary, queue, set, stack)
+//! in a single program, so that one context and one compilation serves the whole application.
+//!
+
+pub mod config;
+pub mod file;
+pub mod kernel;
+pub mod planner;
+pub mod symbol;
@@ -0,0 +1,774 @@
+//! # Memory budget planner
+//!
+//! The memory required by a program is the sum of its program scope global variables,
+//! it is read from the generated source (symbol::scan_program_symbols), so that the
+//! keys of the maps, the array sets of the caches or the temporary buffers of the optional
+//! sources (tmp_for_map_insert, lru_tmp_keys__CACHE_ID, ...) are always taken into account.
+//!
+//! ```text
+//! let plan = map_src.memory_plan();
+//! let budget = MemoryBudget::from_device_info(&device.info()?);
+//!
+//! if !plan.fits(&budget) {
+//!     let suggestion = map_src.fit_to_budget(&budget)?;
+//! }
+//! ```
+//!
+//! Host side only, no device is required.
+
+use crate::cache::config::CacheSrc;
+use crate::collections::config::{CollectionKind, CollectionSection, CollectionsSrc};
+use crate::collections::symbol::scan_program_symbols;
+use crate::config::ClTypeTrait;
+use crate::dictionary::config::DictSrc;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_MEMORY_BUDGET_EXCEEDED};
+use crate::map::config::MapSrc;
+use crate::queue::config::QueueSrc;
+use crate::set::config::{ArraySetVersion, SetSrc};
+use crate::stack::config::StackSrc;
+use humansize::{format_size, DECIMAL};
+use opencl::unsafe_wrapper::DeviceInfo;
+
+/// precision of the capacity reduction (capacity * PERMILLE / 1000)
+const PERMILLE: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedAllocation {
+    pub kind: CollectionKind,
+    pub name: String,
+    /// memory required in bytes
+    pub memory_required: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MemoryBudget {
+    /// bytes
+    pub global_mem_size: usize,
+    /// bytes, largest global variable allowed
+    pub max_mem_alloc_size: usize,
+}
+
+impl MemoryBudget {
+    pub fn new(global_mem_size: usize, max_mem_alloc_size: usize) -> Self {
+        Self {
+            global_mem_size,
+            max_mem_alloc_size,
+        }
+    }
+
+    /// a byte budget without a limit per allocation
+    pub fn with_bytes(bytes: usize) -> Self {
+        Self::new(bytes, bytes)
+    }
+
+    pub fn from_device_info(device_info: &DeviceInfo) -> Self {
+        Self::new(
+            device_info.global_mem_size as usize,
+            device_info.max_mem_alloc_size as usize,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemoryBudgetIssue {
+    GlobalMemoryExceeded {
+        memory_required: usize,
+        available: usize,
+    },
+    MaxAllocationExceeded {
+        name: String,
+        memory_required: usize,
+        available: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryPlan {
+    pub allocations: Vec<PlannedAllocation>,
+    /// memory required in bytes
+    pub total_memory_required: usize,
+    pub total_memory_required_text: String,
+    /// bytes of the largest global variable
+    pub largest_allocation: usize,
+}
+
+impl MemoryPlan {
+    pub fn new(allocations: Vec<PlannedAllocation>) -> Self {
+        let total_memory_required = allocations.iter().map(|x| x.memory_required).sum();
+        let largest_allocation = allocations
+            .iter()
+            .map(|x| x.memory_required)
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            allocations,
+            total_memory_required,
+            total_memory_required_text: format_size(total_memory_required, DECIMAL),
+            largest_allocation,
+        }
+    }
+
+    pub fn from_sections(sections: &[CollectionSection]) -> Self {
+        let allocations = sections
+            .iter()
+            .flat_map(|section| {
+                scan_program_symbols(&section.source)
+                    .globals
+                    .into_iter()
+                    .map(|x| PlannedAllocation {
+                        kind: section.kind,
+                        name: x.name,
+                        memory_required: x.memory_required,
+                    })
+            })
+            .collect();
+
+        Self::new(allocations)
+    }
+
+    pub fn get_memory_required_by_kind(&self, kind: CollectionKind) -> usize {
+        self.allocations
+            .iter()
+            .filter(|x| x.kind == kind)
+            .map(|x| x.memory_required)
+            .sum()
+    }
+
+    pub fn check(&self, budget: &MemoryBudget) -> Vec<MemoryBudgetIssue> {
+        let mut issues = Vec::new();
+
+        if self.total_memory_required > budget.global_mem_size {
+            issues.push(MemoryBudgetIssue::GlobalMemoryExceeded {
+                memory_required: self.total_memory_required,
+                available: budget.global_mem_size,
+            });
+        }
+
+        for allocation in self.allocations.iter() {
+            if allocation.memory_required > budget.max_mem_alloc_size {
+                issues.push(MemoryBudgetIssue::MaxAllocationExceeded {
+                    name: allocation.name.clone(),
+                    memory_required: allocation.memory_required,
+                    available: budget.max_mem_alloc_size,
+                });
+            }
+        }
+
+        issues
+    }
+
+    pub fn fits(&self, budget: &MemoryBudget) -> bool {
+        self.check(budget).is_empty()
+    }
+}
+
+/// capacity of a block, index is the position in get_configs() of the src of that kind
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BlockCapacity {
+    pub kind: CollectionKind,
+    pub index: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CapacityAdjustment {
+    pub kind: CollectionKind,
+    pub index: usize,
+    pub capacity: usize,
+    pub suggested_capacity: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct BudgetSuggestion<S> {
+    /// copy of the src with the suggested capacities
+    pub src: S,
+    pub adjustments: Vec<CapacityAdjustment>,
+    pub plan: MemoryPlan,
+}
+
+fn scale_capacities(capacities: &[BlockCapacity], permille: usize) -> Vec<BlockCapacity> {
+    capacities
+        .iter()
+        .map(|x| BlockCapacity {
+            capacity: (x.capacity * permille / PERMILLE).max(1),
+            ..*x
+        })
+        .collect()
+}
+
+pub trait MemoryPlanner: Clone {
+    fn plan_sections(&self) -> Vec<CollectionSection>;
+
+    fn get_capacities(&self) -> Vec<BlockCapacity>;
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]);
+
+    fn memory_plan(&self) -> MemoryPlan {
+        MemoryPlan::from_sections(&self.plan_sections())
+    }
+
+    /// Reduce the capacity of all blocks in the same proportion until the program fits
+    /// in the budget, if it does not fit even with capacity 1 the number of blocks
+    /// (or total_maps) must be reduced, CL_COLLECTION_MEMORY_BUDGET_EXCEEDED is returned.
+    fn fit_to_budget(&self, budget: &MemoryBudget) -> OpenClResult<BudgetSuggestion<Self>> {
+        let capacities = self.get_capacities();
+
+        let with_permille = |permille: usize| -> (Self, MemoryPlan) {
+            let mut src = self.clone();
+            src.set_capacities(&scale_capacities(&capacities, permille));
+            let plan = src.memory_plan();
+            (src, plan)
+        };
+
+        let (src, plan) = with_permille(PERMILLE);
+        if plan.fits(budget) {
+            return Ok(BudgetSuggestion {
+                src,
+                adjustments: vec![],
+                plan,
+            });
+        }
+
+        let (src, plan) = with_permille(0);
+        if !plan.fits(budget) {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_MEMORY_BUDGET_EXCEEDED,
+            ));
+        }
+
+        // the memory required grows with the capacity, largest permille that fits
+        let mut best = (src, plan);
+        let mut low = 0;
+        let mut high = PERMILLE;
+
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            let (src, plan) = with_permille(middle);
+
+            if plan.fits(budget) {
+                low = middle;
+                best = (src, plan);
+            } else {
+                high = middle;
+            }
+        }
+
+        let (src, plan) = best;
+
+        let adjustments = capacities
+            .iter()
+            .zip(src.get_capacities())
+            .filter(|(current, suggested)| current.capacity != suggested.capacity)
+            .map(|(current, suggested)| CapacityAdjustment {
+                kind: current.kind,
+                index: current.index,
+                capacity: current.capacity,
+                suggested_capacity: suggested.capacity,
+            })
+            .collect();
+
+        Ok(BudgetSuggestion {
+            src,
+            adjustments,
+            plan,
+        })
+    }
+}
+
+fn block_capacities<'a>(
+    kind: CollectionKind,
+    capacities: impl Iterator<Item = &'a usize>,
+) -> Vec<BlockCapacity> {
+    capacities
+        .enumerate()
+        .map(|(index, capacity)| BlockCapacity {
+            kind,
+            index,
+            capacity: *capacity,
+        })
+        .collect()
+}
+
+fn find_capacity(
+    capacities: &[BlockCapacity],
+    kind: CollectionKind,
+    index: usize,
+) -> Option<usize> {
+    capacities
+        .iter()
+        .find(|x| x.kind == kind && x.index == index)
+        .map(|x| x.capacity)
+}
+
+impl<T: ClTypeTrait> MemoryPlanner for MapSrc<T> {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        vec![CollectionSection {
+            kind: CollectionKind::Map,
+            source: self.build(),
+        }]
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        block_capacities(
+            CollectionKind::Map,
+            self.get_configs().iter().map(|x| &x.capacity),
+        )
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        for (index, config) in self.get_configs_mut().iter_mut().enumerate() {
+            if let Some(c) = find_capacity(capacities, CollectionKind::Map, index) {
+                config.capacity = c;
+            }
+        }
+    }
+}
+
+impl MemoryPlanner for CacheSrc {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        vec![CollectionSection {
+            kind: CollectionKind::Cache,
+            source: self.build(),
+        }]
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        block_capacities(
+            CollectionKind::Cache,
+            self.get_configs().iter().map(|x| &x.capacity),
+        )
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        for (index, config) in self.get_configs_mut().iter_mut().enumerate() {
+            if let Some(c) = find_capacity(capacities, CollectionKind::Cache, index) {
+                config.capacity = c;
+            }
+        }
+    }
+}
+
+impl<T: ClTypeTrait> MemoryPlanner for DictSrc<T> {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        vec![CollectionSection {
+            kind: CollectionKind::Dictionary,
+            source: self.build(),
+        }]
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        block_capacities(
+            CollectionKind::Dictionary,
+            self.get_configs().iter().map(|x| &x.capacity),
+        )
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        for (index, config) in self.get_configs_mut().iter_mut().enumerate() {
+            if let Some(c) = find_capacity(capacities, CollectionKind::Dictionary, index) {
+                config.capacity = c;
+            }
+        }
+    }
+}
+
+impl MemoryPlanner for QueueSrc {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        vec![CollectionSection {
+            kind: CollectionKind::Queue,
+            source: self.build(),
+        }]
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        block_capacities(
+            CollectionKind::Queue,
+            self.get_configs().iter().map(|x| &x.capacity),
+        )
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        for (index, config) in self.get_configs_mut().iter_mut().enumerate() {
+            if let Some(c) = find_capacity(capacities, CollectionKind::Queue, index) {
+                config.capacity = c;
+            }
+        }
+    }
+}
+
+/// the array set v2 requires more memory than v1, it is used to plan
+impl MemoryPlanner for SetSrc {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        vec![CollectionSection {
+            kind: CollectionKind::Set,
+            source: self.build(ArraySetVersion::V2),
+        }]
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        block_capacities(
+            CollectionKind::Set,
+            self.get_configs().iter().map(|x| &x.capacity),
+        )
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        for (index, config) in self.get_configs_mut().iter_mut().enumerate() {
+            if let Some(c) = find_capacity(capacities, CollectionKind::Set, index) {
+                config.capacity = c;
+            }
+        }
+    }
+}
+
+impl MemoryPlanner for StackSrc {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        vec![CollectionSection {
+            kind: CollectionKind::Stack,
+            source: self.build(),
+        }]
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        block_capacities(
+            CollectionKind::Stack,
+            self.get_configs().iter().map(|x| &x.capacity),
+        )
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        for (index, config) in self.get_configs_mut().iter_mut().enumerate() {
+            if let Some(c) = find_capacity(capacities, CollectionKind::Stack, index) {
+                config.capacity = c;
+            }
+        }
+    }
+}
+
+impl<T: ClTypeTrait> MemoryPlanner for CollectionsSrc<T> {
+    fn plan_sections(&self) -> Vec<CollectionSection> {
+        self.sections()
+    }
+
+    fn get_capacities(&self) -> Vec<BlockCapacity> {
+        let mut capacities = Vec::new();
+
+        if let Some(map_src) = self.get_map_src() {
+            capacities.append(&mut map_src.get_capacities());
+        }
+        capacities.append(&mut self.get_cache_src().get_capacities());
+        capacities.append(&mut self.get_dict_src().get_capacities());
+        capacities.append(&mut self.get_queue_src().get_capacities());
+        capacities.append(&mut self.get_set_src().get_capacities());
+        capacities.append(&mut self.get_stack_src().get_capacities());
+
+        capacities
+    }
+
+    fn set_capacities(&mut self, capacities: &[BlockCapacity]) {
+        if let Some(map_src) = self.get_map_src_mut() {
+            map_src.set_capacities(capacities);
+        }
+        self.get_cache_src_mut().set_capacities(capacities);
+        self.get_dict_src_mut().set_capacities(capacities);
+        self.get_queue_src_mut().set_capacities(capacities);
+        self.get_set_src_mut().set_capacities(capacities);
+        self.get_stack_src_mut().set_capacities(capacities);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::config::MAX_FIND_WORK_SIZE;
+    use crate::utils::{BYTE_256, BYTE_512, KB, MB};
+
+    #[test]
+    fn test_map_plan_includes_keys() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 8);
+        map_src.add(BYTE_512, 16);
+
+        let plan = map_src.memory_plan();
+        println!("{plan:#?}");
+
+        // __global int map_keys__256_byte[2][8][256];
+        // __global int map_values__256_byte[2][8][256];
+        // __global int map_keys__512_byte[2][16][256];
+        // __global int map_values__512_byte[2][16][512];
+        let keys_and_values = (2 * 8 * (256 + 256) + 2 * 16 * (256 + 512)) * 4;
+        assert_eq!(keys_and_values, map_src.summary().total_memory_required);
+
+        // global int tmp_for_map_get_summary[TOTAL_MAPS][TMP_LEN];
+        let tmp = plan
+            .allocations
+            .iter()
+            .find(|x| x.name == "tmp_for_map_get_summary")
+            .unwrap();
+
+        let expected = keys_and_values + tmp.memory_required;
+        assert_eq!(plan.total_memory_required, expected);
+        assert_eq!(plan.largest_allocation, 2 * 16 * 512 * 4);
+        assert_eq!(
+            plan.get_memory_required_by_kind(CollectionKind::Map),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_map_plan_includes_optional_sources() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 8);
+        let base = map_src.memory_plan().total_memory_required;
+
+        map_src.add_map_insert_program_src(MAX_FIND_WORK_SIZE);
+        let plan = map_src.memory_plan();
+
+        // global int tmp_for_map_insert[TOTAL_MAPS][TMP_LEN];
+        let tmp = plan
+            .allocations
+            .iter()
+            .find(|x| x.name == "tmp_for_map_insert")
+            .unwrap();
+        assert!(tmp.memory_required > 0);
+        assert_eq!(plan.total_memory_required, base + tmp.memory_required);
+    }
+
+    #[test]
+    fn test_lru_plan() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_lru(16, 32, 8);
+
+        let plan = cache_src.memory_plan();
+        println!("{plan:#?}");
+
+        // lru_last_priority, lru_top, lru_stats[4]
+        // lru_keys[8][16], lru_values[8][32], lru_priorities[8]
+        // lru_key_sizes[8], lru_value_sizes[8]
+        // lru_array_set[8], lru_array_set_entries[8]
+        // lru_tmp_keys[8][16], lru_tmp_values[8][32]
+        // lru_tmp_key_sizes[8], lru_tmp_value_sizes[8]
+        // struct LruEntry lru_sort_entries[8]
+        let expected = (2 + 4 + 8 * 16 + 8 * 32 + 8 * 7 + 8 * 16 + 8 * 32) * 4 + 8 * 2 * 4;
+        assert_eq!(plan.total_memory_required, expected);
+    }
+
+    #[test]
+    fn test_queue_plan() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+        queue_src.add_pq(16);
+        queue_src.add_cq(32);
+
+        let plan = queue_src.memory_plan();
+
+        let lq = (8 + 2) * 4;
+        let pq = (2 + 16 * 2) * 4;
+        let cq = (32 * 2 + 4) * 4;
+        assert_eq!(plan.total_memory_required, lq + pq + cq);
+    }
+
+    #[test]
+    fn test_dict_and_stack_plan() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 16, 4);
+
+        let plan = dict_src.memory_plan();
+        assert_eq!(
+            plan.total_memory_required,
+            (4 * 8 + 4 * 16 + 4 + (4 + 1) * 2) * 4
+        );
+
+        let mut stack_src = StackSrc::new();
+        stack_src.add(32);
+        stack_src.add(64);
+
+        let plan = stack_src.memory_plan();
+        // the items, the slot states and the top
+        assert_eq!(plan.total_memory_required, (32 * 2 + 1 + 64 * 2 + 1) * 4);
+    }
+
+    #[test]
+    fn test_collections_plan_is_sum_of_srcs() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(1).unwrap();
+        map_src.add(BYTE_256, 8);
+
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(8);
+
+        let mut set_src = SetSrc::new();
+        set_src.add(16);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_map("map", &map_src).unwrap();
+        collections_src.add_cache("cache", &cache_src).unwrap();
+        collections_src.add_set("set", &set_src).unwrap();
+
+        let plan = collections_src.memory_plan();
+
+        assert_eq!(
+            plan.total_memory_required,
+            map_src.memory_plan().total_memory_required
+                + cache_src.memory_plan().total_memory_required
+                + set_src.memory_plan().total_memory_required
+        );
+        assert_eq!(
+            plan.total_memory_required,
+            collections_src.summary().total_memory_required
+        );
+    }
+
+    #[test]
+    fn test_check_budget() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(KB);
+
+        let plan = stack_src.memory_plan();
+
+        assert!(plan.fits(&MemoryBudget::with_bytes(MB)));
+        assert!(plan.fits(&MemoryBudget::with_bytes(plan.total_memory_required)));
+
+        assert_eq!(
+            plan.check(&MemoryBudget::new(KB, MB)),
+            vec![MemoryBudgetIssue::GlobalMemoryExceeded {
+                memory_required: (KB * 2 + 1) * 4,
+                available: KB,
+            }]
+        );
+
+        assert_eq!(
+            plan.check(&MemoryBudget::new(MB, 2 * KB)),
+            vec![
+                MemoryBudgetIssue::MaxAllocationExceeded {
+                    name: "stack__0".to_string(),
+                    memory_required: KB * 4,
+                    available: 2 * KB,
+                },
+                MemoryBudgetIssue::MaxAllocationExceeded {
+                    name: "stack_slot__0".to_string(),
+                    memory_required: KB * 4,
+                    available: 2 * KB,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_budget_from_device_info() {
+        let device_info = DeviceInfo {
+            board_name: "",
+            name: "gfx1032".to_string(),
+            vendor: "Advanced Micro Devices, Inc.".to_string(),
+            max_work_group_size: 256,
+            global_mem_size: 8 * 1024 * MB as u64,
+            max_mem_alloc_size: 7 * 1024 * MB as u64,
+        };
+
+        let budget = MemoryBudget::from_device_info(&device_info);
+        assert_eq!(budget, MemoryBudget::new(8 * 1024 * MB, 7 * 1024 * MB));
+    }
+
+    #[test]
+    fn test_fit_to_budget_without_changes() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(64);
+
+        let suggestion = stack_src
+            .fit_to_budget(&MemoryBudget::with_bytes(MB))
+            .unwrap();
+
+        assert!(suggestion.adjustments.is_empty());
+        assert_eq!(suggestion.plan, stack_src.memory_plan());
+    }
+
+    #[test]
+    fn test_fit_to_budget_reduce_capacity() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 1024);
+        map_src.add(KB, 512);
+
+        let budget = MemoryBudget::with_bytes(2 * MB);
+        assert!(!map_src.memory_plan().fits(&budget));
+
+        let suggestion = map_src.fit_to_budget(&budget).unwrap();
+        println!("{:#?}", suggestion.adjustments);
+
+        assert!(suggestion.plan.fits(&budget));
+        assert_eq!(suggestion.plan, suggestion.src.memory_plan());
+        assert_eq!(suggestion.adjustments.len(), 2);
+
+        for adjustment in suggestion.adjustments.iter() {
+            assert_eq!(adjustment.kind, CollectionKind::Map);
+            assert!(adjustment.suggested_capacity < adjustment.capacity);
+        }
+
+        // the same proportion in all blocks
+        let configs = suggestion.src.get_configs();
+        assert!(configs[0].capacity.abs_diff(configs[1].capacity * 2) <= 2);
+
+        // a little more capacity no longer fits
+        let mut larger = suggestion.src.clone();
+        for config in larger.get_configs_mut().iter_mut() {
+            config.capacity += config.capacity / 100 + 1;
+        }
+        assert!(!larger.memory_plan().fits(&budget));
+    }
+
+    #[test]
+    fn test_fit_to_budget_max_allocation() {
+        let mut cache_src = CacheSrc::new();
+        cache_src.add_mini_lru(1024);
+        cache_src.add_lru(256, 256, 256);
+
+        // lru_keys__0[256][256] int
+        let budget = MemoryBudget::new(MB * 4, 64 * KB);
+
+        let suggestion = cache_src.fit_to_budget(&budget).unwrap();
+
+        assert!(suggestion.plan.fits(&budget));
+        assert!(suggestion.plan.largest_allocation <= 64 * KB);
+        // all blocks are reduced in the same proportion
+        assert!(suggestion.src.get_configs()[0].capacity < 1024);
+        assert_eq!(suggestion.src.get_configs()[1].capacity, 64);
+    }
+
+    #[test]
+    fn test_fit_to_budget_collections() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(256, 256, 256);
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(KB);
+
+        let mut collections_src: CollectionsSrc<i32> = CollectionsSrc::new();
+        collections_src.add_dict("dict", &dict_src).unwrap();
+        collections_src.add_queue("queue", &queue_src).unwrap();
+
+        let budget = MemoryBudget::with_bytes(256 * KB);
+        let suggestion = collections_src.fit_to_budget(&budget).unwrap();
+
+        assert!(suggestion.plan.fits(&budget));
+
+        let kinds: Vec<CollectionKind> = suggestion.adjustments.iter().map(|x| x.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![CollectionKind::Dictionary, CollectionKind::Queue]
+        );
+    }
+
+    #[test]
+    fn test_fit_to_budget_impossible() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_many(8, 64);
+
+        let result = stack_src.fit_to_budget(&MemoryBudget::with_bytes(64));
+
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_MEMORY_BUDGET_EXCEEDED
+            ))
+        );
+    }
+}
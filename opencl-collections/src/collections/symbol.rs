@@ -0,0 +1,404 @@
+//! Minimal scanner of the program scope declarations of the generated opencl c source.
+//!
+//! It does not try to be a c parser, it only understands the subset of declarations
+//! used by the templates of this crate:
+//!
+//! ```c
+//! struct LruEntry { int priority; int to_index; };
+//!
+//! __global int stack__0[32];
+//! __global int stack_top__0 = -1;
+//!
+//! const int SET_FULL_DEF_2 = 2;
+//!
+//! int read_pipe_st(uint stack_id, int* v) { ... }
+//!
+//! kernel void stack_debug__0(global int* items_output) { ... }
+//! ```
+
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SymbolKind {
+    Kernel,
+    Function,
+    Global,
+    Constant,
+    Struct,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramSymbol {
+    pub kind: SymbolKind,
+    pub name: String,
+}
+
+impl ProgramSymbol {
+    pub fn new(kind: SymbolKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// ...
+///
+/// ```c
+/// __global CL_TYPE map_keys__256_byte[TOTAL_MAPS][MAP_CAPACITY][MAP_KEY_LEN];
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalVariable {
+    pub name: String,
+    pub cl_type: String,
+    pub dims: Vec<usize>,
+    /// memory required in bytes
+    pub memory_required: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProgramSymbols {
+    pub symbols: Vec<ProgramSymbol>,
+    pub globals: Vec<GlobalVariable>,
+}
+
+impl ProgramSymbols {
+    /// total memory required (bytes) of all program scope global variables
+    pub fn memory_required(&self) -> usize {
+        self.globals.iter().map(|x| x.memory_required).sum()
+    }
+
+    pub fn kernel_names(&self) -> Vec<&str> {
+        self.symbols
+            .iter()
+            .filter(|x| x.kind == SymbolKind::Kernel)
+            .map(|x| x.name.as_str())
+            .collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.symbols.iter().any(|x| x.name == name)
+    }
+
+    /// names declared more than once in the same program source
+    pub fn duplicates(&self) -> Vec<String> {
+        let mut counter: HashMap<&str, usize> = HashMap::new();
+
+        for symbol in self.symbols.iter() {
+            *counter.entry(&symbol.name).or_insert(0) += 1;
+        }
+
+        let mut names: Vec<String> = counter
+            .into_iter()
+            .filter(|(_, c)| *c > 1)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+pub fn get_cl_type_size(cl_type: &str) -> Option<usize> {
+    let size = match cl_type {
+        "bool" | "char" | "uchar" => 1,
+        "short" | "ushort" | "half" => 2,
+        "int" | "uint" | "float" => 4,
+        "long" | "ulong" | "double" => 8,
+        _ => return None,
+    };
+    Some(size)
+}
+
+fn remove_comments(src: &str) -> String {
+    let mut output = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '/' {
+            match chars.peek() {
+                Some('/') => {
+                    for n in chars.by_ref() {
+                        if n == '\n' {
+                            output.push('\n');
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                Some('*') => {
+                    chars.next();
+                    let mut last = ' ';
+                    for n in chars.by_ref() {
+                        if last == '*' && n == '/' {
+                            break;
+                        }
+                        last = n;
+                    }
+                    output.push(' ');
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        output.push(c);
+    }
+
+    output
+}
+
+fn is_identifier(token: &str) -> bool {
+    !token.is_empty()
+        && token.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !token.chars().next().unwrap().is_numeric()
+}
+
+fn last_identifier(text: &str) -> Option<&str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .rfind(|x| is_identifier(x))
+}
+
+fn parse_dims(text: &str) -> Vec<usize> {
+    text.split('[')
+        .skip(1)
+        .filter_map(|x| x.split(']').next())
+        .map(|x| x.trim().parse().unwrap_or(0))
+        .collect()
+}
+
+fn parse_variable(statement: &str, structs: &HashMap<String, usize>) -> Option<GlobalVariable> {
+    let declaration = statement.split('=').next()?.trim();
+
+    let (head, dims) = match declaration.find('[') {
+        None => (declaration, vec![]),
+        Some(i) => (&declaration[..i], parse_dims(&declaration[i..])),
+    };
+
+    let name = last_identifier(head)?;
+
+    let qualifiers = [
+        "__global",
+        "global",
+        "volatile",
+        "const",
+        "__constant",
+        "constant",
+    ];
+    let cl_type: Vec<&str> = head
+        .split_whitespace()
+        .filter(|x| !qualifiers.contains(x) && *x != name)
+        .collect();
+    let cl_type = cl_type.join(" ");
+
+    let type_size = match cl_type.strip_prefix("struct ") {
+        None => get_cl_type_size(cl_type.trim_start_matches("atomic_")).unwrap_or(4),
+        Some(struct_name) => structs.get(struct_name.trim()).copied().unwrap_or(0),
+    };
+
+    let elements: usize = dims.iter().product();
+
+    Some(GlobalVariable {
+        name: name.to_string(),
+        cl_type,
+        dims,
+        memory_required: type_size * elements,
+    })
+}
+
+fn parse_struct_size(body: &str, structs: &HashMap<String, usize>) -> usize {
+    body.split(';')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .filter_map(|x| parse_variable(x, structs))
+        .map(|x| {
+            x.memory_required
+                .max(get_cl_type_size(&x.cl_type).unwrap_or(0))
+        })
+        .sum()
+}
+
+/// Read the program scope declarations (kernels, functions, globals, constants and structs)
+pub fn scan_program_symbols(src: &str) -> ProgramSymbols {
+    let src = remove_comments(src);
+
+    let mut program_symbols = ProgramSymbols::default();
+    let mut structs: HashMap<String, usize> = HashMap::new();
+
+    let mut depth = 0;
+    let mut statement = String::new();
+    let mut struct_name: Option<String> = None;
+    let mut struct_body = String::new();
+
+    for c in src.chars() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    let header = statement.trim();
+
+                    if let Some(name) = header.strip_prefix("struct ") {
+                        let name = name.trim().to_string();
+                        program_symbols
+                            .symbols
+                            .push(ProgramSymbol::new(SymbolKind::Struct, &name));
+                        struct_name = Some(name);
+                        struct_body.clear();
+                    } else if let Some(i) = header.find('(') {
+                        if let Some(name) = last_identifier(&header[..i]) {
+                            let is_kernel = header[..i]
+                                .split_whitespace()
+                                .any(|x| x == "kernel" || x == "__kernel");
+
+                            let kind = if is_kernel {
+                                SymbolKind::Kernel
+                            } else {
+                                SymbolKind::Function
+                            };
+                            program_symbols.symbols.push(ProgramSymbol::new(kind, name));
+                        }
+                    }
+
+                    statement.clear();
+                } else if struct_name.is_some() {
+                    struct_body.push(c);
+                }
+                depth += 1;
+            }
+            '}' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    if let Some(name) = struct_name.take() {
+                        let size = parse_struct_size(&struct_body, &structs);
+                        structs.insert(name, size);
+                    }
+                    statement.clear();
+                } else if struct_name.is_some() {
+                    struct_body.push(c);
+                }
+            }
+            ';' if depth == 0 => {
+                let s = statement.trim();
+
+                // function prototypes are not definitions
+                if !s.is_empty() && !s.contains('(') {
+                    let is_global = s.starts_with("__global ") || s.starts_with("global ");
+                    let is_constant = s.starts_with("const ")
+                        || s.starts_with("constant ")
+                        || s.starts_with("__constant ");
+
+                    if let Some(variable) = parse_variable(s, &structs) {
+                        if is_global {
+                            program_symbols
+                                .symbols
+                                .push(ProgramSymbol::new(SymbolKind::Global, &variable.name));
+                            program_symbols.globals.push(variable);
+                        } else if is_constant {
+                            program_symbols
+                                .symbols
+                                .push(ProgramSymbol::new(SymbolKind::Constant, &variable.name));
+                        }
+                    }
+                }
+
+                statement.clear();
+            }
+            _ => {
+                if depth == 0 {
+                    statement.push(c);
+                } else if struct_name.is_some() {
+                    struct_body.push(c);
+                }
+            }
+        }
+    }
+
+    program_symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_SRC: &str = r#"
+    /// *** EXAMPLE *** ///
+
+    struct LruEntry {
+        int priority;
+        int to_index;
+    };
+
+    const int SET_FULL_DEF_2 = 2;
+
+    __global int stack__0[32];
+    __global int stack_top__0 = -1;
+    __global uchar map_keys__256_byte[2][8][256];
+    __global struct LruEntry lru_sort_entries__0[16];
+
+    /* int ignored_fn() {} */
+    int read_pipe_st(uint stack_id, int* v) {
+        int front_i = -1;
+
+        switch (stack_id) {
+            case 0:
+                break;
+        }
+
+        return front_i;
+    }
+
+    kernel void stack_debug__0(
+        global int* items_output
+        ) {
+        int i = get_global_id(0);
+        items_output[i] = stack__0[i];
+    }
+    "#;
+
+    #[test]
+    fn test_scan_symbols() {
+        let program_symbols = scan_program_symbols(PROGRAM_SRC);
+        println!("{:#?}", program_symbols);
+
+        assert_eq!(
+            program_symbols.symbols,
+            vec![
+                ProgramSymbol::new(SymbolKind::Struct, "LruEntry"),
+                ProgramSymbol::new(SymbolKind::Constant, "SET_FULL_DEF_2"),
+                ProgramSymbol::new(SymbolKind::Global, "stack__0"),
+                ProgramSymbol::new(SymbolKind::Global, "stack_top__0"),
+                ProgramSymbol::new(SymbolKind::Global, "map_keys__256_byte"),
+                ProgramSymbol::new(SymbolKind::Global, "lru_sort_entries__0"),
+                ProgramSymbol::new(SymbolKind::Function, "read_pipe_st"),
+                ProgramSymbol::new(SymbolKind::Kernel, "stack_debug__0"),
+            ]
+        );
+        assert_eq!(program_symbols.kernel_names(), vec!["stack_debug__0"]);
+        assert!(program_symbols.duplicates().is_empty());
+    }
+
+    #[test]
+    fn test_scan_globals_memory() {
+        let program_symbols = scan_program_symbols(PROGRAM_SRC);
+
+        let sizes: Vec<usize> = program_symbols
+            .globals
+            .iter()
+            .map(|x| x.memory_required)
+            .collect();
+
+        assert_eq!(sizes, vec![32 * 4, 4, 2 * 8 * 256, 16 * 8]);
+        assert_eq!(program_symbols.globals[2].dims, vec![2, 8, 256]);
+        assert_eq!(program_symbols.globals[2].cl_type, "uchar");
+        assert_eq!(
+            program_symbols.memory_required(),
+            32 * 4 + 4 + 2 * 8 * 256 + 16 * 8
+        );
+    }
+
+    #[test]
+    fn test_scan_duplicates() {
+        let src = format!("{PROGRAM_SRC} const int SET_FULL_DEF_2 = 2;");
+        let program_symbols = scan_program_symbols(&src);
+
+        assert_eq!(program_symbols.duplicates(), vec!["SET_FULL_DEF_2"]);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_DEQUE_ID};
+
+/// the items are stored in a circular buffer, front and back are positions
+/// (not indices), the deque holds the items in [front, back)
+///
+/// ```c
+/// __global int deque__DEQUE_ID[DEQUE_CAPACITY];
+/// __global int deque_front__DEQUE_ID = 0;
+/// __global int deque_back__DEQUE_ID = 0;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DequeConfig {
+    pub id: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DequeSrc {
+    blocks: Vec<DequeConfig>,
+}
+
+impl DequeSrc {
+    pub fn new() -> Self {
+        Self { blocks: Vec::new() }
+    }
+
+    pub fn get_configs(&self) -> &Vec<DequeConfig> {
+        &self.blocks
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn add(&mut self, capacity: usize) {
+        let id = self.blocks.len();
+
+        self.blocks.push(DequeConfig { id, capacity });
+    }
+
+    pub fn add_many(&mut self, capacity: usize, quantity: usize) {
+        for _ in 0..quantity {
+            self.add(capacity);
+        }
+    }
+
+    pub fn get_config_by_id(&self, id: usize) -> OpenClResult<&DequeConfig> {
+        match self.blocks.iter().find(|x| x.id == id) {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DEQUE_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
+}
+
+impl Default for DequeSrc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deque_src_add() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(8);
+        deque_src.add_many(32, 2);
+
+        assert_eq!(deque_src.len(), 3);
+        let ids: Vec<usize> = deque_src.get_configs().iter().map(|x| x.id).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_deque_src_get_config_by_id() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(8);
+        deque_src.add(16);
+
+        assert_eq!(
+            deque_src.get_config_by_id(1),
+            Ok(&DequeConfig {
+                id: 1,
+                capacity: 16,
+            })
+        );
+
+        assert_eq!(
+            deque_src.get_config_by_id(5),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DEQUE_ID
+            ))
+        );
+    }
+}
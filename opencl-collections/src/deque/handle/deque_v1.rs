@@ -0,0 +1,653 @@
+use crate::config::DEBUG_MODE;
+use crate::deque::config::DequeConfig;
+use crate::deque::kernel::name::{
+    get_deque_kernel_name, DEQUE_DEBUG, DEQUE_RESET, GET_DEQUE_LEN, POP_BACK_AND_STEAL_ON_DEQUE,
+    POP_BACK_ON_DEQUE, POP_FRONT_ON_DEQUE, PUSH_BACK_TO_DEQUE, PUSH_FRONT_TO_DEQUE, STEAL_ON_DEQUE,
+};
+use crate::error::OpenClResult;
+use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+use opencl::wrapper::system::OpenclCommonOperation;
+
+#[derive(Debug, PartialEq)]
+pub struct DequeSnapshot {
+    pub front: cl_int,
+    pub back: cl_int,
+    pub items: Vec<cl_int>,
+}
+
+impl DequeSnapshot {
+    pub fn new(front: cl_int, back: cl_int, items: Vec<cl_int>) -> Self {
+        Self { front, back, items }
+    }
+
+    pub fn create_empty(capacity: usize) -> Self {
+        Self::new(0, 0, vec![0; capacity])
+    }
+
+    /// items from front to back
+    pub fn get_ordered_items(&self) -> Vec<cl_int> {
+        let capacity = self.items.len() as cl_int;
+
+        (self.front..self.back)
+            .map(|position| self.items[position.rem_euclid(capacity) as usize])
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct DequeHandle<T: OpenclCommonOperation> {
+    config: DequeConfig,
+    system: T,
+}
+
+impl<T: OpenclCommonOperation> DequeHandle<T> {
+    pub fn new(config: &DequeConfig, system: T) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn debug(&self) -> OpenClResult<DequeSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let meta_output_capacity = 2;
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_deque_kernel_name(DEQUE_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        Ok(DequeSnapshot::new(meta_output[0], meta_output[1], output))
+    }
+
+    pub fn print(&self) -> OpenClResult<DequeSnapshot> {
+        let sn = self.debug()?;
+        println!("{sn:?}");
+        Ok(sn)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_deque_kernel_name(DEQUE_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_deque_kernel_name(GET_DEQUE_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    fn push(&self, kernel_name: &str, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        let global_work_size = input.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let mut kernel = self.system.create_kernel(kernel_name)?;
+
+        let deque_id = self.get_id() as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&deque_id)?;
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("{kernel_name} output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    fn pop(&self, kernel_name: &str, take: usize) -> OpenClResult<Vec<cl_int>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let mut kernel = self.system.create_kernel(kernel_name)?;
+
+        let deque_id = self.get_id() as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&deque_id)?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("{kernel_name} output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// index of the item in the deque, or -1 if the deque is full
+    pub fn push_front(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        self.push(PUSH_FRONT_TO_DEQUE, input)
+    }
+
+    /// index of the item in the deque, or -1 if the deque is full
+    pub fn push_back(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        self.push(PUSH_BACK_TO_DEQUE, input)
+    }
+
+    /// up to take values, -1 if the deque is empty
+    pub fn pop_front(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        self.pop(POP_FRONT_ON_DEQUE, take)
+    }
+
+    /// up to take values, -1 if the deque is empty
+    pub fn pop_back(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        self.pop(POP_BACK_ON_DEQUE, take)
+    }
+
+    /// moves up to take values from the front of the victim deque to the back of this deque,
+    /// -1 if the victim is empty or this deque is full
+    pub fn steal(&self, victim_id: usize, take: usize) -> OpenClResult<Vec<cl_int>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let mut kernel = self.system.create_kernel(STEAL_ON_DEQUE)?;
+
+        let thief_id = self.get_id() as cl_uint;
+        let victim_id = victim_id as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&thief_id)?;
+            kernel.set_arg(&victim_id)?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("steal output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// the owner pops the back of this deque while the thief deque steals its front
+    /// (device enqueued kernels), (pop_back values, stolen values) are returned
+    pub fn pop_back_and_steal(
+        &self,
+        thief_id: usize,
+        pop_back_take: usize,
+        steal_take: usize,
+    ) -> OpenClResult<(Vec<cl_int>, Vec<cl_int>)> {
+        let global_work_size = 1;
+        let local_work_size = 1;
+
+        let enqueue_kernel_output_capacity = 2;
+
+        let pop_back_output_buf = self.system.create_output_buffer(pop_back_take)?;
+        let steal_output_buf = self.system.create_output_buffer(steal_take)?;
+
+        let enqueue_kernel_output_buf = self
+            .system
+            .create_output_buffer(enqueue_kernel_output_capacity)?;
+
+        let pop_back_global_work_size = pop_back_take as cl_uint;
+        let pop_back_local_work_size =
+            self.system
+                .first_device_check_local_work_size(pop_back_take) as cl_uint;
+
+        let q0 = self
+            .system
+            .get_device_command_queue_0()
+            .get_cl_command_queue();
+
+        let mut kernel = self.system.create_kernel(POP_BACK_AND_STEAL_ON_DEQUE)?;
+
+        let thief_id = thief_id as cl_uint;
+        let victim_id = self.get_id() as cl_uint;
+        let steal_take_arg = steal_take as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&q0)?;
+            kernel.set_arg(&thief_id)?;
+            kernel.set_arg(&victim_id)?;
+            kernel.set_arg(&pop_back_global_work_size)?;
+            kernel.set_arg(&pop_back_local_work_size)?;
+            kernel.set_arg(&steal_take_arg)?;
+            kernel.set_arg(&pop_back_output_buf.get_cl_mem())?;
+            kernel.set_arg(&steal_output_buf.get_cl_mem())?;
+            kernel.set_arg(&enqueue_kernel_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let pop_back_output =
+            self.system
+                .blocking_enqueue_read_buffer(pop_back_take, &pop_back_output_buf, &[])?;
+
+        let steal_output =
+            self.system
+                .blocking_enqueue_read_buffer(steal_take, &steal_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("pop_back output {pop_back_output:?}");
+            println!("steal output {steal_output:?}");
+        }
+
+        self.system.assert_device_enqueue_kernel(
+            enqueue_kernel_output_capacity,
+            enqueue_kernel_output_buf,
+            &[],
+        )?;
+
+        Ok((pop_back_output, steal_output))
+    }
+}
+
+#[cfg(test)]
+mod tests_deque_snapshot {
+    use super::*;
+
+    #[test]
+    fn ordered_items() {
+        let snapshot = DequeSnapshot::new(-2, 2, vec![1, 2, 0, 0, 3, 4]);
+        assert_eq!(snapshot.get_ordered_items(), vec![3, 4, 1, 2]);
+
+        let snapshot = DequeSnapshot::create_empty(8);
+        assert_eq!(snapshot.get_ordered_items(), Vec::<cl_int>::new());
+    }
+}
+
+#[cfg(test)]
+mod tests_deque_debug {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::deque::config::DequeSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn deque_is_empty() {
+        let deque_capacity = 32;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(deque_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let config = deque_src.get_config_by_id(0).unwrap();
+        let deque = DequeHandle::new(config, system);
+
+        let snapshot = deque.print().unwrap();
+
+        assert_eq!(snapshot, DequeSnapshot::create_empty(deque_capacity));
+        assert_eq!(deque.len().unwrap(), 0);
+        assert!(deque.is_empty().unwrap());
+    }
+
+    #[test]
+    fn deque_reset() {
+        let deque_capacity = 32;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(deque_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let config = deque_src.get_config_by_id(0).unwrap();
+        let deque = DequeHandle::new(config, system);
+
+        let input = vec![10; deque_capacity / 2];
+        deque.push_front(&input).unwrap();
+        deque.push_back(&input).unwrap();
+
+        deque.reset().unwrap();
+        let snapshot = deque.print().unwrap();
+
+        assert_eq!(snapshot, DequeSnapshot::create_empty(deque_capacity));
+    }
+}
+
+#[cfg(test)]
+mod tests_deque_push_pop {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::deque::config::DequeSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn both_ends() {
+        let deque_capacity = 32;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(deque_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let config = deque_src.get_config_by_id(0).unwrap();
+        let deque = DequeHandle::new(config, system);
+
+        // a single item per kernel, the order is known
+        deque.push_back(&[1]).unwrap();
+        deque.push_back(&[2]).unwrap();
+        deque.push_front(&[0]).unwrap();
+        deque.push_front(&[-5]).unwrap();
+
+        let snapshot = deque.print().unwrap();
+        assert_eq!(snapshot.front, -2);
+        assert_eq!(snapshot.back, 2);
+        assert_eq!(snapshot.get_ordered_items(), vec![-5, 0, 1, 2]);
+        assert_eq!(deque.len().unwrap(), 4);
+
+        assert_eq!(deque.pop_front(1).unwrap(), vec![-5]);
+        assert_eq!(deque.pop_back(1).unwrap(), vec![2]);
+        assert_eq!(deque.pop_back(1).unwrap(), vec![1]);
+        assert_eq!(deque.pop_front(1).unwrap(), vec![0]);
+
+        assert!(deque.is_empty().unwrap());
+        assert_eq!(deque.pop_front(2).unwrap(), vec![-1, -1]);
+        assert_eq!(deque.pop_back(2).unwrap(), vec![-1, -1]);
+    }
+
+    #[test]
+    fn deque_is_full() {
+        let deque_capacity = 32;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(deque_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let config = deque_src.get_config_by_id(0).unwrap();
+        let deque = DequeHandle::new(config, system);
+
+        let input: Vec<cl_int> = (0..deque_capacity as cl_int).collect();
+
+        let indices = deque.push_front(&input[0..8]).unwrap();
+        assert!(indices.iter().all(|x| *x >= 0));
+
+        let indices = deque.push_back(&input[8..]).unwrap();
+        assert!(indices.iter().all(|x| *x >= 0));
+
+        assert_eq!(deque.push_back(&[100, 200]).unwrap(), vec![-1, -1]);
+        assert_eq!(deque.push_front(&[100, 200]).unwrap(), vec![-1, -1]);
+
+        let snapshot = deque.print().unwrap();
+        assert_eq!(snapshot.front, -8);
+        assert_eq!(snapshot.back, 24);
+
+        let mut items = snapshot.items.clone();
+        items.sort();
+        assert_eq!(items, input);
+    }
+
+    #[test]
+    fn many_work_items() {
+        let deque_capacity = 1024;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(deque_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let config = deque_src.get_config_by_id(0).unwrap();
+        let deque = DequeHandle::new(config, system);
+
+        let input: Vec<cl_int> = (0..deque_capacity as cl_int).collect();
+
+        deque.push_back(&input[0..512]).unwrap();
+        deque.push_front(&input[512..]).unwrap();
+
+        let mut output = deque.pop_back(600).unwrap();
+        output.append(&mut deque.pop_front(600).unwrap());
+
+        assert_eq!(output.iter().filter(|x| **x == -1).count(), 176);
+
+        output.retain(|x| *x != -1);
+        output.sort();
+        assert_eq!(output, input);
+
+        let snapshot = deque.print().unwrap();
+        assert_eq!(snapshot.front, snapshot.back);
+    }
+}
+
+#[cfg(test)]
+mod tests_deque_steal {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::deque::config::DequeSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn steal_from_front() {
+        let deque_capacity = 32;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add_many(deque_capacity, 2);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let victim = DequeHandle::new(deque_src.get_config_by_id(0).unwrap(), &system);
+        let thief = DequeHandle::new(deque_src.get_config_by_id(1).unwrap(), &system);
+
+        for value in 0..6 {
+            victim.push_back(&[value]).unwrap();
+        }
+
+        // the oldest items
+        assert_eq!(thief.steal(victim.get_id(), 4).unwrap(), vec![0, 1, 2, 3]);
+
+        assert_eq!(victim.print().unwrap().get_ordered_items(), vec![4, 5]);
+        assert_eq!(thief.print().unwrap().get_ordered_items(), vec![0, 1, 2, 3]);
+
+        assert_eq!(thief.steal(victim.get_id(), 4).unwrap(), vec![4, 5, -1, -1]);
+        assert!(victim.is_empty().unwrap());
+        assert_eq!(thief.len().unwrap(), 6);
+    }
+
+    #[test]
+    fn thief_is_full() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(32);
+        deque_src.add(4);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let victim = DequeHandle::new(deque_src.get_config_by_id(0).unwrap(), &system);
+        let thief = DequeHandle::new(deque_src.get_config_by_id(1).unwrap(), &system);
+
+        for value in 0..8 {
+            victim.push_back(&[value]).unwrap();
+        }
+
+        assert_eq!(
+            thief.steal(victim.get_id(), 6).unwrap(),
+            vec![0, 1, 2, 3, -1, -1]
+        );
+
+        // the victim keeps the items that were not stolen
+        assert_eq!(
+            victim.print().unwrap().get_ordered_items(),
+            vec![4, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn invalid_victim() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let thief = DequeHandle::new(deque_src.get_config_by_id(0).unwrap(), &system);
+
+        assert_eq!(thief.steal(3, 2).unwrap(), vec![-1, -1]);
+        assert!(thief.is_empty().unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests_deque_stress {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::deque::config::DequeSrc;
+    use opencl::wrapper::system::System;
+
+    fn without_empty(values: &[cl_int]) -> Vec<cl_int> {
+        let mut values: Vec<cl_int> = values.iter().filter(|&&x| x != -1).copied().collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn concurrent_pop_back_and_steal() {
+        let deque_capacity = 512;
+
+        let mut deque_src = DequeSrc::new();
+        deque_src.add_many(deque_capacity, 2);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &deque_src.build()).unwrap();
+
+        let owner = DequeHandle::new(deque_src.get_config_by_id(0).unwrap(), &system);
+        let thief = DequeHandle::new(deque_src.get_config_by_id(1).unwrap(), &system);
+
+        for round in 0..16 {
+            let input: Vec<cl_int> = (0..256).map(|x| round * 1000 + x).collect();
+            owner.push_back(&input).unwrap();
+
+            // more pops than items, the last items are wanted by both sides
+            let (popped, stolen) = owner.pop_back_and_steal(thief.get_id(), 192, 128).unwrap();
+
+            let remaining = owner.pop_front(deque_capacity).unwrap();
+            let thief_items = thief.pop_front(deque_capacity).unwrap();
+
+            // no item is returned twice and no item is lost
+            let mut all = popped.clone();
+            all.extend(&stolen);
+            all.extend(&remaining);
+            assert_eq!(without_empty(&all), input);
+
+            assert_eq!(without_empty(&stolen), without_empty(&thief_items));
+
+            assert!(owner.is_empty().unwrap());
+            assert!(thief.is_empty().unwrap());
+        }
+    }
+}
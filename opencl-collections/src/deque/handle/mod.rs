@@ -0,0 +1 @@
+pub mod deque_v1;
@@ -0,0 +1,483 @@
+// work items of the same kernel can push or pop on the same end concurrently,
+// the position of that end is reserved with atomic_cmpxchg
+//
+// the pops on both ends (pop_back of the owner, pop_front / steal of the thieves) can run
+// concurrently, the last item is arbitrated as in the Chase-Lev deque:
+//   pop_back reserves the back position first and reads the front after it,
+//   if the position is the last item the owner and the thieves race on the front
+//   with atomic_cmpxchg, the owner restores the back after it
+//
+// the pushes are not synchronized with the pops (push in its own kernel)
+//
+// SOURCE: David Chase, Yossi Lev, Dynamic Circular Work-Stealing Deque
+//
+// the positions are int, front decreases on push_front, the index of a position
+// is always in [0, DEQUE_CAPACITY)
+
+use crate::deque::config::DequeSrc;
+use crate::deque::kernel::common_replace;
+
+const GLOBALS: &str = r#"
+    __global int deque__DEQUE_ID[DEQUE_CAPACITY];
+    __global int deque_front__DEQUE_ID = 0;
+    __global int deque_back__DEQUE_ID = 0;
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int deque_get_index__DEQUE_ID(int position) {
+        int i = position % DEQUE_CAPACITY;
+        return (i < 0) ? i + DEQUE_CAPACITY : i;
+    }
+
+    int deque_get_len__DEQUE_ID() {
+        return deque_back__DEQUE_ID - deque_front__DEQUE_ID;
+    }
+
+    int deque_push_front__DEQUE_ID(int v) {
+        int front = atomic_add(&deque_front__DEQUE_ID, 0);
+
+        for (;;) {
+            if (deque_back__DEQUE_ID - front >= DEQUE_CAPACITY) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&deque_front__DEQUE_ID, front, front - 1);
+
+            if (r == front) {
+                int i = deque_get_index__DEQUE_ID(front - 1);
+                deque__DEQUE_ID[i] = v;
+                return i;
+            }
+
+            front = r;
+        }
+    }
+
+    int deque_push_back__DEQUE_ID(int v) {
+        int back = atomic_add(&deque_back__DEQUE_ID, 0);
+
+        for (;;) {
+            if (back - deque_front__DEQUE_ID >= DEQUE_CAPACITY) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&deque_back__DEQUE_ID, back, back + 1);
+
+            if (r == back) {
+                int i = deque_get_index__DEQUE_ID(back);
+                deque__DEQUE_ID[i] = v;
+                return i;
+            }
+
+            back = r;
+        }
+    }
+
+    int deque_pop_front__DEQUE_ID(int* v) {
+        int front = atomic_add(&deque_front__DEQUE_ID, 0);
+
+        for (;;) {
+            // the back is read after the front (a pop_back reserves the back first)
+            atomic_work_item_fence(
+                CLK_GLOBAL_MEM_FENCE,
+                memory_order_seq_cst,
+                memory_scope_device
+            );
+
+            if (atomic_add(&deque_back__DEQUE_ID, 0) <= front) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&deque_front__DEQUE_ID, front, front + 1);
+
+            if (r == front) {
+                int i = deque_get_index__DEQUE_ID(front);
+                *v = deque__DEQUE_ID[i];
+                return i;
+            }
+
+            front = r;
+        }
+    }
+
+    int deque_pop_back__DEQUE_ID(int* v) {
+        int back = atomic_add(&deque_back__DEQUE_ID, 0);
+
+        for (;;) {
+            if (back <= atomic_add(&deque_front__DEQUE_ID, 0)) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&deque_back__DEQUE_ID, back, back - 1);
+
+            if (r == back) {
+                break;
+            }
+
+            back = r;
+        }
+
+        int position = back - 1;
+        int i = deque_get_index__DEQUE_ID(position);
+
+        // the front is read after the back is reserved
+        atomic_work_item_fence(
+            CLK_GLOBAL_MEM_FENCE,
+            memory_order_seq_cst,
+            memory_scope_device
+        );
+
+        int front = atomic_add(&deque_front__DEQUE_ID, 0);
+
+        // the thieves can not reach the position
+        if (position > front) {
+            *v = deque__DEQUE_ID[i];
+            return i;
+        }
+
+        // last item, the owner and the thieves race on the front
+        int taken = position == front
+            && atomic_cmpxchg(&deque_front__DEQUE_ID, front, front + 1) == front;
+
+        // the position is empty now (taken, stolen or never there)
+        atomic_max(&deque_back__DEQUE_ID, position + 1);
+
+        if (taken) {
+            *v = deque__DEQUE_ID[i];
+            return i;
+        }
+
+        return -1;
+    }
+    "#;
+
+// the functions are called with the deque id, similar to the stack pipe functions
+const ID_FUNCTIONS: &str = r#"
+    int deque_is_full(uint deque_id) {
+        switch (deque_id) {
+            IS_FULL_BODY_CASE
+        }
+
+        return 1;
+    }
+
+    int deque_push_front(uint deque_id, int v) {
+        switch (deque_id) {
+            PUSH_FRONT_BODY_CASE
+        }
+
+        return -1;
+    }
+
+    int deque_push_back(uint deque_id, int v) {
+        switch (deque_id) {
+            PUSH_BACK_BODY_CASE
+        }
+
+        return -1;
+    }
+
+    int deque_pop_front(uint deque_id, int* v) {
+        switch (deque_id) {
+            POP_FRONT_BODY_CASE
+        }
+
+        return -1;
+    }
+
+    int deque_pop_back(uint deque_id, int* v) {
+        switch (deque_id) {
+            POP_BACK_BODY_CASE
+        }
+
+        return -1;
+    }
+
+    // the thief takes the items on the front of the victim and pushes them on its back
+    void deque_steal(uint thief_id, uint victim_id, int take, global int* output) {
+        for (int j = 0; j < take; j++) {
+            int pi = -1;
+
+            if (!deque_is_full(thief_id) && deque_pop_front(victim_id, &pi) >= 0) {
+                deque_push_back(thief_id, pi);
+            }
+
+            output[j] = pi;
+        }
+    }
+    "#;
+
+const IS_FULL_BODY_CASE: &str = r#"
+            case DEQUE_ID:
+                return deque_get_len__DEQUE_ID() >= DEQUE_CAPACITY;
+    "#;
+
+const PUSH_FRONT_BODY_CASE: &str = r#"
+            case DEQUE_ID:
+                return deque_push_front__DEQUE_ID(v);
+    "#;
+
+const PUSH_BACK_BODY_CASE: &str = r#"
+            case DEQUE_ID:
+                return deque_push_back__DEQUE_ID(v);
+    "#;
+
+const POP_FRONT_BODY_CASE: &str = r#"
+            case DEQUE_ID:
+                return deque_pop_front__DEQUE_ID(v);
+    "#;
+
+const POP_BACK_BODY_CASE: &str = r#"
+            case DEQUE_ID:
+                return deque_pop_back__DEQUE_ID(v);
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void deque_reset__DEQUE_ID() {
+        int i = get_global_id(0);
+
+        deque__DEQUE_ID[i] = 0;
+
+        if (i == 0) {
+            deque_front__DEQUE_ID = 0;
+            deque_back__DEQUE_ID = 0;
+        }
+    }
+
+    kernel void deque_debug__DEQUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        items_output[i] = deque__DEQUE_ID[i];
+
+        if (i == 0) {
+            meta_output[0] = deque_front__DEQUE_ID;
+            meta_output[1] = deque_back__DEQUE_ID;
+        }
+    }
+
+    kernel void get_deque_len__DEQUE_ID(
+        global int* len_output
+        ) {
+        len_output[0] = deque_get_len__DEQUE_ID();
+    }
+    "#;
+
+const BASIC_KERNELS: &str = r#"
+    kernel void push_front_to_deque(
+        const uint deque_id,
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = deque_push_front(deque_id, input[i]);
+    }
+
+    kernel void push_back_to_deque(
+        const uint deque_id,
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = deque_push_back(deque_id, input[i]);
+    }
+
+    kernel void pop_front_on_deque(
+        const uint deque_id,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        int pi = -1;
+        deque_pop_front(deque_id, &pi);
+
+        output[i] = pi;
+    }
+
+    kernel void pop_back_on_deque(
+        const uint deque_id,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        int pi = -1;
+        deque_pop_back(deque_id, &pi);
+
+        output[i] = pi;
+    }
+
+    // a single work item moves all the items, the victim keeps its back end
+    kernel void steal_on_deque(
+        const uint thief_id,
+        const uint victim_id,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        if (i != 0) {
+            return;
+        }
+
+        deque_steal(thief_id, victim_id, get_global_size(0), output);
+    }
+
+    // the owner pops the back of the victim while the thief steals its front,
+    // the kernels are enqueued without waiting each other
+    kernel void pop_back_and_steal_on_deque(
+        queue_t q0,
+        const uint thief_id,
+        const uint victim_id,
+        const uint pop_back_global_work_size,
+        const uint pop_back_local_work_size,
+        const uint steal_take,
+        global int* pop_back_output,
+        global int* steal_output,
+        global int* enqueue_kernel_output
+        ) {
+
+        enqueue_kernel_output[0] = enqueue_kernel(
+            q0,
+            CLK_ENQUEUE_FLAGS_NO_WAIT,
+            ndrange_1D(pop_back_global_work_size, pop_back_local_work_size),
+            ^{
+                int i = get_global_id(0);
+
+                int pi = -1;
+                deque_pop_back(victim_id, &pi);
+
+                pop_back_output[i] = pi;
+            }
+        );
+
+        enqueue_kernel_output[1] = enqueue_kernel(
+            q0,
+            CLK_ENQUEUE_FLAGS_NO_WAIT,
+            ndrange_1D(1, 1),
+            ^{
+                deque_steal(thief_id, victim_id, steal_take, steal_output);
+            }
+        );
+    }
+    "#;
+
+impl DequeSrc {
+    pub(crate) fn generate_deque_program_source_v1(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+        let mut base_functions = String::new();
+
+        let mut fn_is_full_cases = String::new();
+        let mut fn_push_front_cases = String::new();
+        let mut fn_push_back_cases = String::new();
+        let mut fn_pop_front_cases = String::new();
+        let mut fn_pop_back_cases = String::new();
+
+        let mut base_kernels = String::new();
+
+        for config in self.get_configs() {
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config);
+            base_functions.push_str(&template);
+
+            let template = common_replace(IS_FULL_BODY_CASE, config);
+            fn_is_full_cases.push_str(&template);
+
+            let template = common_replace(PUSH_FRONT_BODY_CASE, config);
+            fn_push_front_cases.push_str(&template);
+
+            let template = common_replace(PUSH_BACK_BODY_CASE, config);
+            fn_push_back_cases.push_str(&template);
+
+            let template = common_replace(POP_FRONT_BODY_CASE, config);
+            fn_pop_front_cases.push_str(&template);
+
+            let template = common_replace(POP_BACK_BODY_CASE, config);
+            fn_pop_back_cases.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config);
+            base_kernels.push_str(&template);
+        }
+
+        let id_functions = ID_FUNCTIONS
+            .replace("IS_FULL_BODY_CASE", &fn_is_full_cases)
+            .replace("PUSH_FRONT_BODY_CASE", &fn_push_front_cases)
+            .replace("PUSH_BACK_BODY_CASE", &fn_push_back_cases)
+            .replace("POP_FRONT_BODY_CASE", &fn_pop_front_cases)
+            .replace("POP_BACK_BODY_CASE", &fn_pop_back_cases);
+
+        format!(
+            "
+    /// *** DEQUE V1 SRC *** ///
+
+    /// constants
+    // ...
+
+    /// globals
+    {globals}
+
+    /// kernels
+    {base_functions}
+
+    {id_functions}
+
+    {base_kernels}
+
+    {BASIC_KERNELS}
+
+    /// *** DEQUE V1 SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(8);
+
+        let program_source = deque_src.generate_deque_program_source_v1();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int deque__0[8];"));
+        assert!(program_source.contains("return deque_push_back__0(v);"));
+        assert!(program_source.contains("kernel void steal_on_deque("));
+        assert!(program_source.contains("kernel void pop_back_and_steal_on_deque("));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(8);
+        deque_src.add(32);
+        deque_src.add(16);
+
+        let program_source = deque_src.generate_deque_program_source_v1();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int deque__1[32];"));
+        assert!(program_source.contains("return deque_pop_front__2(v);"));
+        assert_eq!(
+            program_source
+                .matches("kernel void steal_on_deque(")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let deque_src = DequeSrc::new();
+
+        let program_source = deque_src.generate_deque_program_source_v1();
+        assert!(program_source.is_empty());
+    }
+}
@@ -0,0 +1,38 @@
+use crate::deque::config::{DequeConfig, DequeSrc};
+
+pub mod deque_v1;
+pub mod name;
+
+fn common_replace(src: &str, config: &DequeConfig) -> String {
+    src.replace("DEQUE_CAPACITY", &config.capacity.to_string())
+        .replace("DEQUE_ID", &config.id.to_string())
+}
+
+impl DequeSrc {
+    pub fn build(&self) -> String {
+        self.generate_deque_program_source_v1()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut deque_src = DequeSrc::new();
+        deque_src.add(8);
+
+        let program_source = deque_src.build();
+        println!("{program_source}");
+        assert!(!program_source.is_empty());
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let deque_src = DequeSrc::new();
+
+        let program_source = deque_src.build();
+        assert!(program_source.is_empty());
+    }
+}
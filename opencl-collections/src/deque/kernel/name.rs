@@ -0,0 +1,15 @@
+pub fn get_deque_kernel_name(kernel_name: &str, id: usize) -> String {
+    kernel_name.replace("DEQUE_ID", &id.to_string())
+}
+
+// deque v1
+pub const DEQUE_DEBUG: &str = "deque_debug__DEQUE_ID";
+pub const DEQUE_RESET: &str = "deque_reset__DEQUE_ID";
+pub const GET_DEQUE_LEN: &str = "get_deque_len__DEQUE_ID";
+
+pub const PUSH_FRONT_TO_DEQUE: &str = "push_front_to_deque";
+pub const PUSH_BACK_TO_DEQUE: &str = "push_back_to_deque";
+pub const POP_FRONT_ON_DEQUE: &str = "pop_front_on_deque";
+pub const POP_BACK_ON_DEQUE: &str = "pop_back_on_deque";
+pub const STEAL_ON_DEQUE: &str = "steal_on_deque";
+pub const POP_BACK_AND_STEAL_ON_DEQUE: &str = "pop_back_and_steal_on_deque";
@@ -0,0 +1,8 @@
+//! # Opencl Deque
+//!
+//! ...
+//!
+
+pub mod config;
+pub mod handle;
+pub mod kernel;
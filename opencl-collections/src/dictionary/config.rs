@@ -49,6 +49,10 @@ impl<T: ClTypeTrait> DictSrc<T> {
         &self.blocks
     }
 
+    pub(crate) fn get_configs_mut(&mut self) -> &mut Vec<DictConfig<T>> {
+        &mut self.blocks
+    }
+
     pub fn len(&self) -> usize {
         self.blocks.len()
     }
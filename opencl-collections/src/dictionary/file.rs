@@ -0,0 +1,367 @@
+//! # Dict file
+//!
+//! Portable binary export of the dict entries (little endian).
+//!
+//! ```text
+//! magic     [u8; 8] = "OCLDICT\0"
+//! version   u32
+//! cl_type   u32
+//! id        u64
+//! key_len   u64
+//! value_len u64
+//! capacity  u64
+//! entries   u64
+//! entries * (key [key_len] + value [value_len]), each element as u64 / i64
+//! ```
+
+use crate::config::{ClType, ClTypeTrait};
+use crate::dictionary::config::DictConfig;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_DICT_FILE};
+use num::NumCast;
+use std::fs;
+use std::path::Path;
+
+const DICT_FILE_MAGIC: &[u8; 8] = b"OCLDICT\0";
+const DICT_FILE_VERSION: u32 = 1;
+
+// magic + version + cl_type + (id, key_len, value_len, capacity, entries)
+const DICT_FILE_HEADER_LEN: usize = 8 + 4 + 4 + 8 * 5;
+
+fn invalid_file<R>() -> OpenClResult<R> {
+    Err(OpenclError::OpenclCollection(
+        CL_COLLECTION_INVALID_DICT_FILE,
+    ))
+}
+
+fn get_cl_type_code(cl_type: ClType) -> u32 {
+    match cl_type {
+        ClType::U8 => 0,
+        ClType::U16 => 1,
+        ClType::U32 => 2,
+        ClType::U64 => 3,
+        ClType::I16 => 4,
+        ClType::I32 => 5,
+        ClType::I64 => 6,
+    }
+}
+
+fn is_unsigned(cl_type: ClType) -> bool {
+    matches!(
+        cl_type,
+        ClType::U8 | ClType::U16 | ClType::U32 | ClType::U64
+    )
+}
+
+fn write_elements<T: ClTypeTrait + NumCast>(buf: &mut Vec<u8>, elements: &[T]) {
+    let unsigned = is_unsigned(T::cl_enum());
+
+    for x in elements {
+        let bytes = if unsigned {
+            x.to_u64().unwrap_or_default().to_le_bytes()
+        } else {
+            x.to_i64().unwrap_or_default().to_le_bytes()
+        };
+        buf.extend_from_slice(&bytes);
+    }
+}
+
+struct DictFileReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> DictFileReader<'a> {
+    fn take(&mut self, len: usize) -> OpenClResult<&'a [u8]> {
+        let end = match self.position.checked_add(len) {
+            Some(end) if end <= self.bytes.len() => end,
+            _ => return invalid_file(),
+        };
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    fn read_u32(&mut self) -> OpenClResult<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> OpenClResult<u64> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_usize(&mut self) -> OpenClResult<usize> {
+        match usize::try_from(self.read_u64()?) {
+            Ok(x) => Ok(x),
+            Err(_) => invalid_file(),
+        }
+    }
+
+    fn read_elements<T: ClTypeTrait + NumCast>(&mut self, len: usize) -> OpenClResult<Vec<T>> {
+        let unsigned = is_unsigned(T::cl_enum());
+
+        let mut elements = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            let bytes: [u8; 8] = self.take(8)?.try_into().unwrap();
+
+            let element = if unsigned {
+                T::from(u64::from_le_bytes(bytes))
+            } else {
+                T::from(i64::from_le_bytes(bytes))
+            };
+
+            match element {
+                None => return invalid_file(),
+                Some(x) => elements.push(x),
+            }
+        }
+
+        Ok(elements)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictFile<T: ClTypeTrait> {
+    pub config: DictConfig<T>,
+    pub keys: Vec<Vec<T>>,
+    pub values: Vec<Vec<T>>,
+}
+
+impl<T: ClTypeTrait + NumCast> DictFile<T> {
+    pub fn new(config: &DictConfig<T>, keys: Vec<Vec<T>>, values: Vec<Vec<T>>) -> Self {
+        Self {
+            config: config.clone(),
+            keys,
+            values,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let config = &self.config;
+
+        let mut buf = Vec::with_capacity(
+            DICT_FILE_HEADER_LEN + self.len() * (config.key_len + config.value_len) * 8,
+        );
+
+        buf.extend_from_slice(DICT_FILE_MAGIC);
+        buf.extend_from_slice(&DICT_FILE_VERSION.to_le_bytes());
+        buf.extend_from_slice(&get_cl_type_code(T::cl_enum()).to_le_bytes());
+
+        for x in [
+            config.id,
+            config.key_len,
+            config.value_len,
+            config.capacity,
+            self.len(),
+        ] {
+            buf.extend_from_slice(&(x as u64).to_le_bytes());
+        }
+
+        for (i, key) in self.keys.iter().enumerate() {
+            write_elements(&mut buf, key);
+            write_elements(&mut buf, &self.values[i]);
+        }
+
+        buf
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> OpenClResult<Self> {
+        let mut reader = DictFileReader { bytes, position: 0 };
+
+        if reader.take(DICT_FILE_MAGIC.len())? != DICT_FILE_MAGIC {
+            return invalid_file();
+        }
+
+        if reader.read_u32()? != DICT_FILE_VERSION {
+            return invalid_file();
+        }
+
+        if reader.read_u32()? != get_cl_type_code(T::cl_enum()) {
+            return invalid_file();
+        }
+
+        let id = reader.read_usize()?;
+        let key_len = reader.read_usize()?;
+        let value_len = reader.read_usize()?;
+        let capacity = reader.read_usize()?;
+        let entries = reader.read_usize()?;
+
+        if key_len == 0 {
+            return invalid_file();
+        }
+
+        // the entries must be in the file, this also limits the allocations below
+        let entries_len = key_len
+            .checked_add(value_len)
+            .and_then(|x| x.checked_mul(8))
+            .and_then(|x| x.checked_mul(entries));
+
+        if entries_len != Some(reader.remaining()) {
+            return invalid_file();
+        }
+
+        let mut keys = Vec::with_capacity(entries);
+        let mut values = Vec::with_capacity(entries);
+
+        for _ in 0..entries {
+            keys.push(reader.read_elements(key_len)?);
+            values.push(reader.read_elements(value_len)?);
+        }
+
+        let config = DictConfig::new(id, key_len, value_len, capacity);
+
+        Ok(Self::new(&config, keys, values))
+    }
+
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> OpenClResult<()> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn read_file<P: AsRef<Path>>(path: P) -> OpenClResult<Self> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let config: DictConfig<i16> = DictConfig::new(2, 4, 3, 16);
+
+        let keys = vec![vec![1, 2, -1, -1], vec![i16::MIN, i16::MAX, 0, -1]];
+        let values = vec![vec![10, -1, -1], vec![-20, 30, 40]];
+
+        let dict_file = DictFile::new(&config, keys, values);
+
+        let bytes = dict_file.to_bytes();
+        assert_eq!(bytes.len(), DICT_FILE_HEADER_LEN + 2 * (4 + 3) * 8);
+
+        assert_eq!(DictFile::from_bytes(&bytes), Ok(dict_file));
+    }
+
+    #[test]
+    fn round_trip_unsigned() {
+        let config: DictConfig<u64> = DictConfig::new(0, 2, 2, 8);
+
+        let dict_file = DictFile::new(&config, vec![vec![u64::MAX, 0]], vec![vec![1, 2]]);
+
+        let bytes = dict_file.to_bytes();
+        assert_eq!(DictFile::from_bytes(&bytes), Ok(dict_file));
+    }
+
+    #[test]
+    fn empty_dict() {
+        let config: DictConfig<i32> = DictConfig::new(0, 8, 8, 8);
+
+        let dict_file = DictFile::new(&config, vec![], vec![]);
+        assert!(dict_file.is_empty());
+
+        let bytes = dict_file.to_bytes();
+        assert_eq!(bytes.len(), DICT_FILE_HEADER_LEN);
+        assert_eq!(DictFile::from_bytes(&bytes), Ok(dict_file));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let config: DictConfig<i32> = DictConfig::new(0, 2, 2, 8);
+
+        let dict_file = DictFile::new(&config, vec![vec![1, 2]], vec![vec![3, 4]]);
+        let bytes = dict_file.to_bytes();
+
+        let error = Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_DICT_FILE,
+        ));
+
+        // truncated
+        assert_eq!(
+            DictFile::<i32>::from_bytes(&bytes[..bytes.len() - 1]),
+            error
+        );
+        assert_eq!(DictFile::<i32>::from_bytes(&bytes[..10]), error);
+
+        // magic
+        let mut invalid = bytes.clone();
+        invalid[0] = b'X';
+        assert_eq!(DictFile::<i32>::from_bytes(&invalid), error);
+
+        // cl type
+        assert_eq!(
+            DictFile::<i16>::from_bytes(&bytes),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_FILE
+            ))
+        );
+    }
+
+    fn set_header_field(bytes: &mut [u8], field: usize, value: u64) {
+        // id, key_len, value_len, capacity, entries
+        let start = 8 + 4 + 4 + field * 8;
+        bytes[start..(start + 8)].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn invalid_header_lengths() {
+        let config: DictConfig<i32> = DictConfig::new(0, 2, 2, 8);
+
+        let dict_file = DictFile::new(&config, vec![vec![1, 2]], vec![vec![3, 4]]);
+        let bytes = dict_file.to_bytes();
+
+        let error = Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_DICT_FILE,
+        ));
+
+        // key_len 0
+        let empty = DictFile::new(&DictConfig::<i32>::new(0, 0, 2, 8), vec![], vec![]);
+        assert_eq!(DictFile::<i32>::from_bytes(&empty.to_bytes()), error);
+
+        // (key_len + value_len) * 8 * entries overflows
+        let mut invalid = bytes.clone();
+        set_header_field(&mut invalid, 1, u64::MAX);
+        assert_eq!(DictFile::<i32>::from_bytes(&invalid), error);
+
+        let mut invalid = bytes.clone();
+        set_header_field(&mut invalid, 2, u64::MAX / 8);
+        assert_eq!(DictFile::<i32>::from_bytes(&invalid), error);
+
+        let mut invalid = bytes.clone();
+        set_header_field(&mut invalid, 4, u64::MAX / 16 + 1);
+        assert_eq!(DictFile::<i32>::from_bytes(&invalid), error);
+
+        // more entries than bytes
+        let mut invalid = bytes.clone();
+        set_header_field(&mut invalid, 4, 1 << 40);
+        assert_eq!(DictFile::<i32>::from_bytes(&invalid), error);
+    }
+
+    #[test]
+    fn write_and_read_file() {
+        let config: DictConfig<i32> = DictConfig::new(0, 2, 2, 8);
+        let dict_file = DictFile::new(&config, vec![vec![1, 2]], vec![vec![3, 4]]);
+
+        let path = std::env::temp_dir().join("dict_file_test.bin");
+
+        dict_file.write_file(&path).unwrap();
+        let result = DictFile::read_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result, Ok(dict_file));
+    }
+}
@@ -1,16 +1,26 @@
 use crate::config::{ClTypeTrait, DEBUG_MODE};
-use crate::dictionary::config::DictConfig;
-use crate::dictionary::handle::{DictSnapshotSummary, DictSummary};
+use crate::dictionary::config::{DictConfig, DictSrc};
+use crate::dictionary::file::DictFile;
+use crate::dictionary::handle::{
+    DictEntry, DictGrowPolicy, DictKeySize, DictMergeMode, DictSnapshotSummary, DictSummary,
+    DictUpsert,
+};
 use crate::dictionary::kernel::name::{
-    get_dict_kernel_name, DICT_DEBUG, DICT_GET_KEYS, DICT_GET_SUMMARY, DICT_RESET, READ_ON_DICT,
-    READ_VALUE_SIZE_ON_DICT, REMOVE_FROM_DICT, VERIFY_AND_REMOVE_IN_DICT, VERIFY_AND_WRITE_IN_DICT,
-    WRITE_TO_DICT,
+    get_dict_kernel_name, DICT_DEBUG, DICT_FILTER_BY_VALUE_SIZE, DICT_GET_ENTRIES, DICT_GET_KEYS,
+    DICT_GET_SUMMARY, DICT_RESET, READ_ON_DICT, READ_VALUE_SIZE_ON_DICT, REMOVE_FROM_DICT,
+    UPSERT_ON_DICT, VERIFY_AND_REMOVE_IN_DICT, VERIFY_AND_WRITE_IN_DICT, WRITE_TO_DICT,
+};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_DICT_GROW_FAILED,
+    CL_COLLECTION_DICT_GROW_SHARED_PROGRAM, CL_COLLECTION_INVALID_DICT_CAPACITY,
+    CL_COLLECTION_INVALID_DICT_FILE, CL_COLLECTION_INVALID_DICT_INPUT_LEN,
 };
-use crate::error::OpenClResult;
 use crate::utils::ensure_vec_size;
+use num::NumCast;
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
-use opencl::wrapper::system::OpenclCommonOperation;
+use opencl::wrapper::system::{OpenclCommonOperation, System};
 use std::marker::PhantomData;
+use std::path::Path;
 
 #[derive(Debug, PartialEq)]
 pub struct DictSnapshot<T: ClTypeTrait> {
@@ -88,6 +98,9 @@ impl<T: ClTypeTrait> DictSnapshot<T> {
 pub struct DictHandle<T: ClTypeTrait, D: OpenclCommonOperation> {
     config: DictConfig<T>,
     system: D,
+    grow_policy: Option<DictGrowPolicy>,
+    // device of the program, only known when the handle built the program itself
+    device_index: Option<usize>,
     phantom: PhantomData<T>,
 }
 
@@ -96,6 +109,8 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
         Self {
             config: config.clone(),
             system,
+            grow_policy: None,
+            device_index: None,
             phantom: Default::default(),
         }
     }
@@ -108,6 +123,18 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
         self.config.value_len
     }
 
+    pub fn get_capacity(&self) -> usize {
+        self.config.capacity
+    }
+
+    pub fn get_grow_policy(&self) -> Option<DictGrowPolicy> {
+        self.grow_policy
+    }
+
+    pub fn set_grow_policy(&mut self, grow_policy: Option<DictGrowPolicy>) {
+        self.grow_policy = grow_policy;
+    }
+
     pub fn debug(&self) -> OpenClResult<DictSnapshot<T>> {
         let global_work_size = self.config.capacity;
         let local_work_size = self
@@ -219,7 +246,9 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
 
     pub fn insert(&self, keys: &Vec<Vec<T>>, values: &Vec<Vec<T>>) -> OpenClResult<Vec<cl_int>> {
         if keys.len() != values.len() {
-            panic!("error handle keys & values error len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+            ));
         }
 
         let global_work_size = keys.len();
@@ -290,11 +319,15 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
         values: &Vec<Vec<T>>,
     ) -> OpenClResult<Vec<cl_int>> {
         if keys.len() <= 1 {
-            panic!("error handle input len 1");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+            ));
         }
 
         if keys.len() != values.len() {
-            panic!("error handle keys & values error len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+            ));
         }
 
         let global_work_size = 1;
@@ -378,6 +411,99 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
         Ok(indices_output)
     }
 
+    /// insert or merge (existing keys), duplicated input keys are merged in the input order
+    pub fn upsert(
+        &self,
+        keys: &Vec<Vec<T>>,
+        values: &Vec<Vec<T>>,
+        merge_mode: DictMergeMode,
+    ) -> OpenClResult<Vec<DictUpsert>> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+            ));
+        }
+
+        let global_work_size = keys.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_input_capacity = global_work_size * self.config.key_len;
+        let values_input_capacity = global_work_size * self.config.value_len;
+        let output_capacity = global_work_size;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(keys_input_capacity);
+
+        for key in keys {
+            let mut id_input = ensure_vec_size(key, self.config.key_len);
+            keys_input.append(&mut id_input);
+        }
+
+        let mut values_input: Vec<_> = Vec::with_capacity(values_input_capacity);
+
+        for b in values {
+            let mut v = ensure_vec_size(b, self.config.value_len);
+            values_input.append(&mut v);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+        let values_input_buf = self.system.blocking_prepare_input_buffer(&values_input)?;
+
+        let indices_output_buf = self.system.create_output_buffer(output_capacity)?;
+        let statuses_output_buf = self.system.create_output_buffer(output_capacity)?;
+        let sizes_output_buf = self.system.create_output_buffer(output_capacity)?;
+
+        let merge_mode = merge_mode.to_kernel_arg();
+        let keys_global_work_size = global_work_size as cl_int;
+
+        let kernel_name = get_dict_kernel_name(UPSERT_ON_DICT, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&merge_mode)?;
+            kernel.set_arg(&keys_global_work_size)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+            kernel.set_arg(&statuses_output_buf.get_cl_mem())?;
+            kernel.set_arg(&sizes_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(output_capacity, &indices_output_buf, &[])?;
+
+        let statuses_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(output_capacity, &statuses_output_buf, &[])?;
+
+        let sizes_output: Vec<cl_uint> =
+            self.system
+                .blocking_enqueue_read_buffer(output_capacity, &sizes_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("upsert indices_output  {indices_output:?}");
+            println!("upsert statuses_output {statuses_output:?}");
+            println!("upsert sizes_output    {sizes_output:?}");
+        }
+
+        let output = indices_output
+            .into_iter()
+            .enumerate()
+            .map(|(i, index)| DictUpsert::new(index, statuses_output[i], sizes_output[i]))
+            .collect();
+
+        Ok(output)
+    }
+
     pub fn remove(&self, keys: &Vec<Vec<T>>) -> OpenClResult<Vec<cl_int>> {
         let global_work_size = keys.len();
         let local_work_size = self
@@ -431,7 +557,9 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
 
     pub fn remove_with_verification(&self, keys: &Vec<Vec<T>>) -> OpenClResult<Vec<cl_int>> {
         if keys.len() <= 1 {
-            panic!("error handle input len 1");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+            ));
         }
 
         let global_work_size = 1;
@@ -548,6 +676,164 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
         Ok(keys)
     }
 
+    /// live entries of the slots offset..(offset + limit)
+    pub fn entries(&self, offset: usize, limit: usize) -> OpenClResult<Vec<DictEntry<T>>> {
+        if offset >= self.config.capacity || limit == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = limit.min(self.config.capacity - offset);
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+        let values_output_capacity = self.config.value_len * global_work_size;
+        let entries_output_capacity = global_work_size;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+        let entries_output_buf = self.system.create_output_buffer(entries_output_capacity)?;
+
+        let offset_arg = offset as cl_uint;
+
+        let kernel_name = get_dict_kernel_name(DICT_GET_ENTRIES, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&offset_arg)?;
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&entries_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let values_output = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let entries_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            entries_output_capacity,
+            &entries_output_buf,
+            &[],
+        )?;
+
+        let entries: Vec<DictEntry<T>> = keys_output
+            .chunks(self.config.key_len)
+            .zip(values_output.chunks(self.config.value_len))
+            .enumerate()
+            .filter(|(i, _)| entries_output[*i] != 0)
+            .map(|(i, (key, value))| DictEntry {
+                index: (offset + i) as cl_int,
+                key: key.to_vec(),
+                value: value.to_vec(),
+            })
+            .collect();
+
+        if DEBUG_MODE {
+            println!(
+                "entries {offset}..{} ({})",
+                offset + global_work_size,
+                entries.len()
+            );
+        }
+
+        Ok(entries)
+    }
+
+    /// keys of the entries with min_size <= value size <= max_size
+    pub fn filter_by_value_size(
+        &self,
+        min_size: usize,
+        max_size: usize,
+    ) -> OpenClResult<Vec<DictKeySize<T>>> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let keys_output_capacity = self.config.key_len * global_work_size;
+        let sizes_output_capacity = global_work_size;
+        let indices_output_capacity = global_work_size;
+
+        let keys_output_buf = self.system.create_output_buffer(keys_output_capacity)?;
+        let sizes_output_buf = self.system.create_output_buffer(sizes_output_capacity)?;
+        let indices_output_buf = self.system.create_output_buffer(indices_output_capacity)?;
+
+        let min_size = min_size as cl_uint;
+        let max_size = max_size.min(cl_uint::MAX as usize) as cl_uint;
+
+        let kernel_name = get_dict_kernel_name(DICT_FILTER_BY_VALUE_SIZE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&min_size)?;
+            kernel.set_arg(&max_size)?;
+            kernel.set_arg(&keys_output_buf.get_cl_mem())?;
+            kernel.set_arg(&sizes_output_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let keys_output = self.system.blocking_enqueue_read_buffer(
+            keys_output_capacity,
+            &keys_output_buf,
+            &[],
+        )?;
+
+        let sizes_output: Vec<cl_uint> = self.system.blocking_enqueue_read_buffer(
+            sizes_output_capacity,
+            &sizes_output_buf,
+            &[],
+        )?;
+
+        let indices_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            indices_output_capacity,
+            &indices_output_buf,
+            &[],
+        )?;
+
+        let keys: Vec<DictKeySize<T>> = keys_output
+            .chunks(self.config.key_len)
+            .enumerate()
+            .filter(|(i, _)| indices_output[*i] >= 0)
+            .map(|(i, key)| DictKeySize {
+                index: indices_output[i],
+                key: key.to_vec(),
+                value_size: sizes_output[i],
+            })
+            .collect();
+
+        if DEBUG_MODE {
+            println!(
+                "filter_by_value_size {min_size}..={max_size} ({})",
+                keys.len()
+            );
+        }
+
+        Ok(keys)
+    }
+
     pub fn get(&self, keys: &Vec<Vec<T>>) -> OpenClResult<(Vec<Vec<T>>, Vec<cl_int>)> {
         let global_work_size = self.config.capacity;
         let local_work_size = self
@@ -763,6 +1049,162 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> DictHandle<T, D> {
     }
 }
 
+impl<T: ClTypeTrait + NumCast, D: OpenclCommonOperation> DictHandle<T, D> {
+    /// writes the live entries and the config to a binary file (see DictFile)
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> OpenClResult<()> {
+        let ds = self.debug()?;
+
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+
+        for (i, &entry) in ds.entries.iter().enumerate() {
+            if entry == 0 {
+                continue;
+            }
+            keys.push(ds.keys[i].clone());
+            values.push(ds.values[i].clone());
+        }
+
+        DictFile::new(&self.config, keys, values).write_file(path)
+    }
+
+    /// replaces the entries of the dict with the entries of the file,
+    /// key_len and value_len must be equal, the capacity may be different
+    pub fn import<P: AsRef<Path>>(&self, path: P) -> OpenClResult<Vec<cl_int>> {
+        let dict_file: DictFile<T> = DictFile::read_file(path)?;
+
+        if dict_file.config.key_len != self.config.key_len
+            || dict_file.config.value_len != self.config.value_len
+            || dict_file.len() > self.config.capacity
+        {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_FILE,
+            ));
+        }
+
+        self.reset()?;
+
+        if dict_file.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.insert(&dict_file.keys, &dict_file.values)
+    }
+}
+
+impl<T: ClTypeTrait> DictHandle<T, System> {
+    /// builds a program with the dict only on the device, only these handles can grow
+    pub fn new_growable(config: &DictConfig<T>, device_index: usize) -> OpenClResult<Self> {
+        let mut dict_src: DictSrc<T> = DictSrc::new();
+        dict_src.get_configs_mut().push(config.clone());
+
+        let system = System::new(device_index, &dict_src.build())?;
+
+        let mut dict = DictHandle::new(config, system);
+        dict.device_index = Some(device_index);
+        Ok(dict)
+    }
+
+    pub fn get_device_index(&self) -> Option<usize> {
+        self.device_index
+    }
+
+    /// builds a new program with the dict only on the same device and copies the live entries,
+    /// a dict that shares its program with other collections (DictHandle::new) can not grow
+    pub fn grow(&mut self, new_capacity: usize) -> OpenClResult<()> {
+        let device_index = match self.device_index {
+            None => {
+                return Err(OpenclError::OpenclCollection(
+                    CL_COLLECTION_DICT_GROW_SHARED_PROGRAM,
+                ))
+            }
+            Some(index) => index,
+        };
+
+        if new_capacity <= self.config.capacity {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_CAPACITY,
+            ));
+        }
+
+        let ds = self.debug()?;
+
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+
+        for (i, &entry) in ds.entries.iter().enumerate() {
+            if entry == 0 {
+                continue;
+            }
+            keys.push(ds.keys[i].clone());
+            values.push(ds.values[i].clone());
+        }
+
+        let config = DictConfig::new(
+            self.config.id,
+            self.config.key_len,
+            self.config.value_len,
+            new_capacity,
+        );
+
+        let dict = DictHandle::new_growable(&config, device_index)?;
+        dict.initialize()?;
+
+        if !keys.is_empty() {
+            let indices = dict.insert(&keys, &values)?;
+
+            if indices.iter().any(|&x| x < 0) {
+                return Err(OpenclError::OpenclCollection(
+                    CL_COLLECTION_DICT_GROW_FAILED,
+                ));
+            }
+        }
+
+        if DEBUG_MODE {
+            println!(
+                "dict {} grow {} -> {new_capacity} ({} entries)",
+                self.config.id,
+                self.config.capacity,
+                keys.len()
+            );
+        }
+
+        self.config = dict.config;
+        self.system = dict.system;
+
+        Ok(())
+    }
+
+    /// grows the dict if the grow policy requires it for the new entries,
+    /// returns true if the dict grew
+    pub fn grow_if_needed(&mut self, new_entries: usize) -> OpenClResult<bool> {
+        let grow_policy = match self.grow_policy {
+            None => return Ok(false),
+            Some(p) => p,
+        };
+
+        let summary = self.summary()?;
+
+        match grow_policy.next_capacity(self.config.capacity, summary.used() + new_entries) {
+            None => Ok(false),
+            Some(new_capacity) => {
+                self.grow(new_capacity)?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// insert with auto-grow (the grow policy must be defined)
+    pub fn insert_with_grow(
+        &mut self,
+        keys: &Vec<Vec<T>>,
+        values: &Vec<Vec<T>>,
+    ) -> OpenClResult<Vec<cl_int>> {
+        self.grow_if_needed(keys.len())?;
+        self.insert(keys, values)
+    }
+}
+
 #[cfg(test)]
 mod tests_dict_reset {
     use super::*;
@@ -1416,6 +1858,192 @@ mod tests_dict_insert_with_verification {
     }
 }
 
+#[cfg(test)]
+mod tests_dict_upsert {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::dictionary::config::DictSrc;
+    use crate::dictionary::handle::{KEYS_NOT_AVAILABLE, UPSERT_INSERTED, UPSERT_MERGED};
+    use opencl::wrapper::system::System;
+
+    fn create_dict(capacity: usize) -> DictHandle<cl_int, System> {
+        let mut dict_src: DictSrc<cl_int> = DictSrc::new();
+        dict_src.add(8, 8, capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let config = dict_src.get_config_by_id(0).unwrap();
+        let dict = DictHandle::new(config, system);
+        dict.initialize().unwrap();
+        dict
+    }
+
+    #[test]
+    fn invalid_input_len() {
+        let dict = create_dict(8);
+
+        assert_eq!(
+            dict.upsert(&vec![vec![1]], &vec![], DictMergeMode::Overwrite),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN
+            ))
+        );
+    }
+
+    #[test]
+    fn overwrite() {
+        let dict = create_dict(8);
+
+        let output = dict
+            .upsert(
+                &vec![vec![1], vec![2]],
+                &vec![vec![10, 10], vec![20]],
+                DictMergeMode::Overwrite,
+            )
+            .unwrap();
+
+        assert!(output.iter().all(|x| x.status == UPSERT_INSERTED));
+        assert_eq!(output[0].value_size, 2);
+        assert_eq!(output[1].value_size, 1);
+
+        let output_2 = dict
+            .upsert(&vec![vec![1]], &vec![vec![5]], DictMergeMode::Overwrite)
+            .unwrap();
+
+        assert_eq!(
+            output_2,
+            vec![DictUpsert::new(output[0].index, UPSERT_MERGED, 1)]
+        );
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0], ensure_vec_size(&[5], 8));
+    }
+
+    #[test]
+    fn append() {
+        let dict = create_dict(8);
+
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![1, 2, 3]], DictMergeMode::Append)
+            .unwrap();
+
+        let output = dict
+            .upsert(
+                &vec![vec![1], vec![1]],
+                &vec![vec![4, 5], vec![6, 7, 8, 9]],
+                DictMergeMode::Append,
+            )
+            .unwrap();
+
+        assert_eq!(output[0].status, UPSERT_MERGED);
+        assert_eq!(output[0].value_size, 5);
+        // bounded by value_len
+        assert_eq!(output[1].status, UPSERT_MERGED);
+        assert_eq!(output[1].value_size, 8);
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0], vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn add_counters() {
+        let dict = create_dict(8);
+
+        let keys = vec![vec![1], vec![2], vec![1], vec![1], vec![2]];
+        let values = vec![vec![1]; 5];
+
+        let output = dict.upsert(&keys, &values, DictMergeMode::Add).unwrap();
+
+        let statuses: Vec<cl_int> = output.iter().map(|x| x.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                UPSERT_INSERTED,
+                UPSERT_INSERTED,
+                UPSERT_MERGED,
+                UPSERT_MERGED,
+                UPSERT_MERGED
+            ]
+        );
+
+        let (values, _) = dict.get(&vec![vec![1], vec![2]]).unwrap();
+        assert_eq!(values[0][0], 3);
+        assert_eq!(values[1][0], 2);
+    }
+
+    #[test]
+    fn max_and_min() {
+        let dict = create_dict(8);
+
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![5, 1, 5]], DictMergeMode::Max)
+            .unwrap();
+
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![1, 9, 5]], DictMergeMode::Max)
+            .unwrap();
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0][0..3], vec![5, 9, 5]);
+
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![2, 2, 2]], DictMergeMode::Min)
+            .unwrap();
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0][0..3], vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn max_and_min_with_zero_and_negative_values() {
+        let dict = create_dict(8);
+
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![0, 3, 1]], DictMergeMode::Max)
+            .unwrap();
+
+        // max(0, -5) keeps 0, the padding of the input keeps 1
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![-5, -2]], DictMergeMode::Max)
+            .unwrap();
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0][0..4], vec![0, 3, 1, 0]);
+
+        // min(3, 0) is 0
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![-1, 0, 1]], DictMergeMode::Min)
+            .unwrap();
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0][0..4], vec![-1, 0, 1, 0]);
+
+        // the elements after the current value size take the input
+        let _ = dict
+            .upsert(&vec![vec![1]], &vec![vec![0, 0, 0, -4]], DictMergeMode::Min)
+            .unwrap();
+
+        let (values, _) = dict.get(&vec![vec![1]]).unwrap();
+        assert_eq!(values[0][0..4], vec![-1, 0, 0, -4]);
+    }
+
+    #[test]
+    fn keys_not_available() {
+        let dict = create_dict(2);
+
+        let keys = vec![vec![1], vec![2], vec![3]];
+        let values = vec![vec![1]; 3];
+
+        let output = dict.upsert(&keys, &values, DictMergeMode::Add).unwrap();
+
+        let failed: Vec<&DictUpsert> = output.iter().filter(|x| x.index < 0).collect();
+        assert_eq!(
+            failed,
+            vec![&DictUpsert::new(KEYS_NOT_AVAILABLE, KEYS_NOT_AVAILABLE, 0)]
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests_dict_remove {
     use super::*;
@@ -1935,6 +2563,170 @@ mod tests_dict_get {
     }
 }
 
+#[cfg(test)]
+mod tests_dict_entries {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::dictionary::config::DictSrc;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn dict_is_empty() {
+        let mut dict_src: DictSrc<i16> = DictSrc::new();
+        dict_src.add(16, 16, 32);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let config = dict_src.get_config_by_id(0).unwrap();
+        let dict = DictHandle::new(config, system);
+        dict.initialize().unwrap();
+
+        assert_eq!(dict.entries(0, 32).unwrap(), vec![]);
+        assert_eq!(dict.entries(64, 32).unwrap(), vec![]);
+        assert_eq!(dict.filter_by_value_size(0, 16).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn paged_entries() {
+        let dict_capacity = 64;
+        let dict_key_len = 16;
+        let dict_value_len = 16;
+
+        let mut dict_src: DictSrc<i16> = DictSrc::new();
+        dict_src.add(dict_key_len, dict_value_len, dict_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let config = dict_src.get_config_by_id(0).unwrap();
+        let dict = DictHandle::new(config, system);
+        dict.initialize().unwrap();
+
+        let test_matrix = TestMatrix::new(40, dict_key_len, dict_value_len, 1, 10);
+        let _ = dict.insert(&test_matrix.keys, &test_matrix.values).unwrap();
+
+        let mut entries = Vec::new();
+        let mut offset = 0;
+
+        while offset < dict.get_capacity() {
+            let page = dict.entries(offset, 16).unwrap();
+            assert!(page.len() <= 16);
+            entries.extend(page);
+            offset += 16;
+        }
+
+        assert_eq!(entries.len(), 40);
+
+        let ds = dict.debug().unwrap();
+        for entry in entries {
+            assert!(ds.has_entry(&entry.key, &entry.value));
+            assert_eq!(ds.keys[entry.index as usize], entry.key);
+        }
+    }
+
+    #[test]
+    fn filter_by_value_size() {
+        let mut dict_src: DictSrc<i16> = DictSrc::new();
+        dict_src.add(8, 8, 16);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let config = dict_src.get_config_by_id(0).unwrap();
+        let dict = DictHandle::new(config, system);
+        dict.initialize().unwrap();
+
+        let keys = vec![vec![1], vec![2], vec![3], vec![4]];
+        let values = vec![
+            vec![1],
+            vec![1, 2],
+            vec![1, 2, 3],
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+        ];
+
+        let _ = dict.insert(&keys, &values).unwrap();
+
+        let result = dict.filter_by_value_size(2, 3).unwrap();
+
+        let mut sizes: Vec<cl_uint> = result.iter().map(|x| x.value_size).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![2, 3]);
+
+        let mut result_keys: Vec<Vec<i16>> = result.into_iter().map(|x| x.key).collect();
+        result_keys.sort();
+        assert_eq!(
+            result_keys,
+            vec![ensure_vec_size(&[2], 8), ensure_vec_size(&[3], 8)]
+        );
+
+        assert_eq!(dict.filter_by_value_size(0, usize::MAX).unwrap().len(), 4);
+        assert_eq!(dict.filter_by_value_size(9, 10).unwrap().len(), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests_dict_export {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::dictionary::config::DictSrc;
+    use crate::error::CL_COLLECTION_INVALID_DICT_FILE;
+    use crate::test_utils::TestMatrix;
+    use opencl::wrapper::system::System;
+
+    fn create_dict(dict_src: &DictSrc<i16>, id: usize) -> DictHandle<i16, System> {
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let config = dict_src.get_config_by_id(id).unwrap();
+        let dict = DictHandle::new(config, system);
+        dict.initialize().unwrap();
+        dict
+    }
+
+    #[test]
+    fn export_and_import() {
+        let dict_key_len = 16;
+        let dict_value_len = 16;
+
+        let mut dict_src: DictSrc<i16> = DictSrc::new();
+        dict_src.add(dict_key_len, dict_value_len, 32);
+        dict_src.add(dict_key_len, dict_value_len, 64);
+        dict_src.add(8, dict_value_len, 64);
+
+        let dict_0 = create_dict(&dict_src, 0);
+        let dict_1 = create_dict(&dict_src, 1);
+        let dict_2 = create_dict(&dict_src, 2);
+
+        let test_matrix = TestMatrix::new(20, dict_key_len, dict_value_len, 1, 10);
+        let _ = dict_0
+            .insert(&test_matrix.keys, &test_matrix.values)
+            .unwrap();
+
+        let path = std::env::temp_dir().join("dict_export_test.bin");
+
+        dict_0.export(&path).unwrap();
+
+        let indices = dict_1.import(&path).unwrap();
+        assert_eq!(indices.len(), 20);
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let ds = dict_1.debug().unwrap();
+        assert_eq!(ds.summary(), DictSnapshotSummary::with(20));
+
+        for (i, key) in test_matrix.keys.iter().enumerate() {
+            assert!(ds.has_entry(key, &test_matrix.values[i]));
+        }
+
+        // different key_len
+        assert_eq!(
+            dict_2.import(&path),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_FILE
+            ))
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
+
 #[cfg(test)]
 mod tests_dict_get_size {
     use super::*;
@@ -2250,3 +3042,135 @@ mod tests_dict_summary {
         );
     }
 }
+
+#[cfg(test)]
+mod tests_dict_grow {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::dictionary::config::DictSrc;
+    use crate::error::CL_COLLECTION_INVALID_DICT_CAPACITY;
+    use crate::test_utils::TestMatrix;
+    use opencl::opencl_sys::bindings::cl_short;
+
+    fn create_dict(
+        key_len: usize,
+        value_len: usize,
+        capacity: usize,
+    ) -> DictHandle<cl_short, System> {
+        let mut dict_src: DictSrc<cl_short> = DictSrc::new();
+        dict_src.add(key_len, value_len, capacity);
+
+        let config = dict_src.get_config_by_id(0).unwrap();
+        let dict = DictHandle::new_growable(config, DEFAULT_DEVICE_INDEX).unwrap();
+        dict.initialize().unwrap();
+        dict
+    }
+
+    #[test]
+    fn shared_program() {
+        let mut dict_src: DictSrc<cl_short> = DictSrc::new();
+        dict_src.add(16, 16, 32);
+        dict_src.add(16, 16, 32);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let mut dict = DictHandle::new(dict_src.get_config_by_id(0).unwrap(), system);
+        dict.initialize().unwrap();
+
+        assert_eq!(
+            dict.grow(64),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_DICT_GROW_SHARED_PROGRAM
+            ))
+        );
+        assert_eq!(dict.get_capacity(), 32);
+    }
+
+    #[test]
+    fn invalid_capacity() {
+        let mut dict = create_dict(16, 16, 32);
+
+        assert_eq!(
+            dict.grow(32),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_CAPACITY
+            ))
+        );
+        assert_eq!(dict.get_capacity(), 32);
+    }
+
+    #[test]
+    fn grow_keeps_entries() {
+        let dict_capacity = 32;
+        let dict_key_len = 16;
+        let dict_value_len = 16;
+
+        let mut dict = create_dict(dict_key_len, dict_value_len, dict_capacity);
+
+        let test_matrix = TestMatrix::new(dict_capacity, dict_key_len, dict_value_len, 1, 10);
+        let indices = dict.insert(&test_matrix.keys, &test_matrix.values).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        dict.grow(dict_capacity * 2).unwrap();
+        assert_eq!(dict.get_capacity(), dict_capacity * 2);
+        assert_eq!(dict.get_device_index(), Some(DEFAULT_DEVICE_INDEX));
+
+        let ds = dict.debug().unwrap();
+        assert_eq!(ds.summary(), DictSnapshotSummary::with(dict_capacity));
+
+        for (i, key) in test_matrix.keys.iter().enumerate() {
+            assert!(ds.has_entry(key, &test_matrix.values[i]));
+        }
+
+        // new entries use the new capacity
+        let test_matrix_2 = TestMatrix::new(
+            dict_capacity,
+            dict_key_len,
+            dict_value_len,
+            1 + dict_capacity as cl_short,
+            10 + dict_capacity as cl_short,
+        );
+        let indices = dict
+            .insert(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let summary = dict.summary().unwrap();
+        assert_eq!(summary.available, 0);
+    }
+
+    #[test]
+    fn insert_with_grow() {
+        let dict_capacity = 16;
+        let dict_key_len = 16;
+        let dict_value_len = 16;
+
+        let mut dict = create_dict(dict_key_len, dict_value_len, dict_capacity);
+        dict.set_grow_policy(Some(DictGrowPolicy::new(0.75, 2)));
+
+        let test_matrix = TestMatrix::new(12, dict_key_len, dict_value_len, 1, 10);
+        let indices = dict
+            .insert_with_grow(&test_matrix.keys, &test_matrix.values)
+            .unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(dict.get_capacity(), dict_capacity);
+
+        let test_matrix_2 = TestMatrix::new(20, dict_key_len, dict_value_len, 100, 100);
+        let indices = dict
+            .insert_with_grow(&test_matrix_2.keys, &test_matrix_2.values)
+            .unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(dict.get_capacity(), dict_capacity * 4);
+
+        let ds = dict.debug().unwrap();
+        assert_eq!(ds.summary(), DictSnapshotSummary::with(32));
+    }
+
+    #[test]
+    fn without_grow_policy() {
+        let mut dict = create_dict(16, 16, 8);
+
+        assert_eq!(dict.grow_if_needed(100), Ok(false));
+        assert_eq!(dict.get_capacity(), 8);
+    }
+}
@@ -3,11 +3,72 @@ use crate::dictionary::config::DictConfig;
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
 
 pub mod dict_v1;
+pub mod transaction;
 
 pub const KEYS_NOT_AVAILABLE: cl_int = -1;
 pub const KEY_NOT_EXIST: cl_int = -2;
 pub const DUPLICATE_KEY: cl_int = -3;
 
+pub const UPSERT_INSERTED: cl_int = 1;
+pub const UPSERT_MERGED: cl_int = 2;
+
+/// how upsert combines the input value with the value of an existing key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DictMergeMode {
+    Overwrite,
+    /// appended after the current value size, bounded by value_len
+    Append,
+    /// element-wise up to the value sizes, the trailing default elements (padding)
+    /// are not merged
+    Add,
+    Max,
+    Min,
+}
+
+impl DictMergeMode {
+    pub fn to_kernel_arg(&self) -> cl_int {
+        match self {
+            DictMergeMode::Overwrite => 0,
+            DictMergeMode::Append => 1,
+            DictMergeMode::Add => 2,
+            DictMergeMode::Max => 3,
+            DictMergeMode::Min => 4,
+        }
+    }
+}
+
+/// per key upsert result, status is UPSERT_INSERTED, UPSERT_MERGED or KEYS_NOT_AVAILABLE
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictUpsert {
+    pub index: cl_int,
+    pub status: cl_int,
+    pub value_size: cl_uint,
+}
+
+impl DictUpsert {
+    pub fn new(index: cl_int, status: cl_int, value_size: cl_uint) -> Self {
+        Self {
+            index,
+            status,
+            value_size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictEntry<T: ClTypeTrait> {
+    pub index: cl_int,
+    pub key: Vec<T>,
+    pub value: Vec<T>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictKeySize<T: ClTypeTrait> {
+    pub index: cl_int,
+    pub key: Vec<T>,
+    pub value_size: cl_uint,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct DictSnapshotSummary {
     pub keys: usize,
@@ -55,4 +116,124 @@ impl<T: ClTypeTrait> DictSummary<T> {
             config.capacity,
         )
     }
+
+    pub fn used(&self) -> usize {
+        self.config.capacity.saturating_sub(self.available)
+    }
+
+    pub fn occupancy(&self) -> f32 {
+        get_occupancy(self.used(), self.config.capacity)
+    }
+}
+
+fn get_occupancy(entries: usize, capacity: usize) -> f32 {
+    if capacity == 0 {
+        return 1.0;
+    }
+    entries as f32 / capacity as f32
+}
+
+/// the dict grows (capacity * factor) when an insert would leave
+/// the occupancy above max_occupancy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DictGrowPolicy {
+    pub max_occupancy: f32,
+    pub factor: usize,
+}
+
+impl DictGrowPolicy {
+    pub fn new(max_occupancy: f32, factor: usize) -> Self {
+        Self {
+            max_occupancy,
+            factor,
+        }
+    }
+
+    /// returns the new capacity, or None if the entries fit in the current capacity
+    pub fn next_capacity(&self, capacity: usize, entries: usize) -> Option<usize> {
+        // a non positive max_occupancy only grows when the entries do not fit
+        let max_occupancy = if self.max_occupancy > 0.0 {
+            self.max_occupancy.min(1.0)
+        } else {
+            1.0
+        };
+
+        if entries <= capacity && get_occupancy(entries, capacity) <= max_occupancy {
+            return None;
+        }
+
+        let factor = self.factor.max(2);
+        let mut new_capacity = capacity.max(1);
+
+        while new_capacity < entries || get_occupancy(entries, new_capacity) > max_occupancy {
+            new_capacity *= factor;
+        }
+
+        Some(new_capacity)
+    }
+}
+
+impl Default for DictGrowPolicy {
+    fn default() -> Self {
+        Self::new(0.75, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests_dict_merge_mode {
+    use super::*;
+
+    #[test]
+    fn to_kernel_arg() {
+        assert_eq!(DictMergeMode::Overwrite.to_kernel_arg(), 0);
+        assert_eq!(DictMergeMode::Append.to_kernel_arg(), 1);
+        assert_eq!(DictMergeMode::Add.to_kernel_arg(), 2);
+        assert_eq!(DictMergeMode::Max.to_kernel_arg(), 3);
+        assert_eq!(DictMergeMode::Min.to_kernel_arg(), 4);
+    }
+}
+
+#[cfg(test)]
+mod tests_dict_grow_policy {
+    use super::*;
+
+    #[test]
+    fn summary_occupancy() {
+        let config: DictConfig<i32> = DictConfig::new(0, 8, 8, 16);
+
+        let summary = DictSummary::create_empty(&config);
+        assert_eq!(summary.used(), 0);
+        assert_eq!(summary.occupancy(), 0.0);
+
+        let summary = DictSummary::new(&config, &[], &[], 4);
+        assert_eq!(summary.used(), 12);
+        assert_eq!(summary.occupancy(), 0.75);
+    }
+
+    #[test]
+    fn next_capacity() {
+        let policy = DictGrowPolicy::new(0.75, 2);
+
+        assert_eq!(policy.next_capacity(16, 0), None);
+        assert_eq!(policy.next_capacity(16, 12), None);
+        assert_eq!(policy.next_capacity(16, 13), Some(32));
+        assert_eq!(policy.next_capacity(16, 40), Some(64));
+        assert_eq!(policy.next_capacity(0, 1), Some(2));
+    }
+
+    #[test]
+    fn next_capacity_full() {
+        let policy = DictGrowPolicy::new(1.0, 4);
+
+        assert_eq!(policy.next_capacity(16, 16), None);
+        assert_eq!(policy.next_capacity(16, 17), Some(64));
+    }
+
+    #[test]
+    fn next_capacity_invalid_policy() {
+        let policy = DictGrowPolicy::new(0.0, 0);
+
+        assert_eq!(policy.next_capacity(8, 0), None);
+        assert_eq!(policy.next_capacity(8, 9), Some(16));
+    }
 }
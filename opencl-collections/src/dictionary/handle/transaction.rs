@@ -0,0 +1,620 @@
+//! # Dict transaction
+//!
+//! Inserts and removes staged across the dicts of the same program, all the keys
+//! are verified in one kernel and the operations are committed only if all of them
+//! can be applied (removes first, then inserts).
+//!
+//! A key can be staged once per dict, or twice as a remove followed by an insert
+//! (replace), any other repetition is a DUPLICATE_KEY.
+//!
+//! The dicts must not be modified by other handles while the transaction is committed.
+
+use crate::config::{ClTypeTrait, DEBUG_MODE};
+use crate::dictionary::config::{DictConfig, DictSrc};
+use crate::dictionary::handle::{DUPLICATE_KEY, KEYS_NOT_AVAILABLE, KEY_NOT_EXIST};
+use crate::dictionary::kernel::name::{DICT_TRANSACTION_COMMIT, DICT_TRANSACTION_VERIFY};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_DICT_ID, CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::OpenclCommonOperation;
+
+pub const DICT_TRANSACTION_INSERT: cl_int = 0;
+pub const DICT_TRANSACTION_REMOVE: cl_int = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DictOperation<T: ClTypeTrait> {
+    Insert {
+        dict_id: usize,
+        key: Vec<T>,
+        value: Vec<T>,
+    },
+    Remove {
+        dict_id: usize,
+        key: Vec<T>,
+    },
+}
+
+impl<T: ClTypeTrait> DictOperation<T> {
+    pub fn get_dict_id(&self) -> usize {
+        match self {
+            DictOperation::Insert { dict_id, .. } => *dict_id,
+            DictOperation::Remove { dict_id, .. } => *dict_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictTransaction<T: ClTypeTrait> {
+    operations: Vec<DictOperation<T>>,
+}
+
+impl<T: ClTypeTrait> DictTransaction<T> {
+    pub fn new() -> Self {
+        Self { operations: vec![] }
+    }
+
+    pub fn get_operations(&self) -> &Vec<DictOperation<T>> {
+        &self.operations
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.operations.clear();
+    }
+
+    pub fn insert(
+        &mut self,
+        dict_id: usize,
+        keys: &[Vec<T>],
+        values: &[Vec<T>],
+    ) -> OpenClResult<()> {
+        if keys.len() != values.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN,
+            ));
+        }
+
+        for (i, key) in keys.iter().enumerate() {
+            self.operations.push(DictOperation::Insert {
+                dict_id,
+                key: key.clone(),
+                value: values[i].clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, dict_id: usize, keys: &[Vec<T>]) {
+        for key in keys {
+            self.operations.push(DictOperation::Remove {
+                dict_id,
+                key: key.clone(),
+            });
+        }
+    }
+}
+
+impl<T: ClTypeTrait> Default for DictTransaction<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// indices, one per operation (in the staging order):
+/// - committed: entry index of the insert / remove
+/// - not committed: verification result, the negative values are the operations that failed
+///   (DUPLICATE_KEY, KEY_NOT_EXIST for removes, KEYS_NOT_AVAILABLE for inserts)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictTransactionReport {
+    pub committed: bool,
+    pub indices: Vec<cl_int>,
+}
+
+impl DictTransactionReport {
+    pub fn new(committed: bool, indices: Vec<cl_int>) -> Self {
+        Self { committed, indices }
+    }
+
+    /// positions of the operations that failed
+    pub fn get_failed(&self) -> Vec<usize> {
+        self.indices
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| x < 0)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// transactions on the dicts of a DictSrc program
+#[derive(Debug)]
+pub struct DictTransactionHandle<T: ClTypeTrait, D: OpenclCommonOperation> {
+    configs: Vec<DictConfig<T>>,
+    system: D,
+}
+
+struct TransactionInput<T: ClTypeTrait> {
+    operations: Vec<cl_int>,
+    keys: Vec<T>,
+    values: Vec<T>,
+}
+
+impl<T: ClTypeTrait, D: OpenclCommonOperation> DictTransactionHandle<T, D> {
+    pub fn new(dict_src: &DictSrc<T>, system: D) -> Self {
+        Self {
+            configs: dict_src.get_configs().clone(),
+            system,
+        }
+    }
+
+    fn get_config(&self, dict_id: usize) -> OpenClResult<&DictConfig<T>> {
+        match self.configs.iter().find(|x| x.id == dict_id) {
+            None => Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_DICT_ID)),
+            Some(c) => Ok(c),
+        }
+    }
+
+    fn prepare_input(&self, transaction: &DictTransaction<T>) -> OpenClResult<TransactionInput<T>> {
+        let mut operations = Vec::with_capacity(transaction.len() * 4);
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+
+        for operation in transaction.get_operations() {
+            let config = self.get_config(operation.get_dict_id())?;
+
+            let key_input_index = keys.len() as cl_int;
+            let value_input_index = values.len() as cl_int;
+
+            let operation_type = match operation {
+                DictOperation::Insert { key, value, .. } => {
+                    keys.append(&mut ensure_vec_size(key, config.key_len));
+                    values.append(&mut ensure_vec_size(value, config.value_len));
+                    DICT_TRANSACTION_INSERT
+                }
+                DictOperation::Remove { key, .. } => {
+                    keys.append(&mut ensure_vec_size(key, config.key_len));
+                    DICT_TRANSACTION_REMOVE
+                }
+            };
+
+            operations.push(config.id as cl_int);
+            operations.push(operation_type);
+            operations.push(key_input_index);
+            operations.push(value_input_index);
+        }
+
+        // the buffers can not be empty
+        if values.is_empty() {
+            values.push(T::cl_default());
+        }
+
+        Ok(TransactionInput {
+            operations,
+            keys,
+            values,
+        })
+    }
+
+    /// verification result (per operation) and available entries (per dict)
+    fn verify(&self, input: &TransactionInput<T>) -> OpenClResult<(Vec<cl_int>, Vec<cl_int>)> {
+        let operations_len = input.operations.len() / 4;
+
+        let global_work_size = operations_len.max(self.configs.len());
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let operations_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&input.operations)?;
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&input.keys)?;
+
+        let statuses_output_buf = self.system.create_output_buffer(operations_len)?;
+        let available_output_buf = self.system.create_output_buffer(self.configs.len())?;
+
+        let operations_len_arg = operations_len as cl_int;
+
+        let mut kernel = self.system.create_kernel(DICT_TRANSACTION_VERIFY)?;
+
+        unsafe {
+            kernel.set_arg(&operations_len_arg)?;
+            kernel.set_arg(&operations_input_buf.get_cl_mem())?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&statuses_output_buf.get_cl_mem())?;
+            kernel.set_arg(&available_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let statuses_output =
+            self.system
+                .blocking_enqueue_read_buffer(operations_len, &statuses_output_buf, &[])?;
+
+        let available_output = self.system.blocking_enqueue_read_buffer(
+            self.configs.len(),
+            &available_output_buf,
+            &[],
+        )?;
+
+        Ok((statuses_output, available_output))
+    }
+
+    pub fn commit(&self, transaction: &DictTransaction<T>) -> OpenClResult<DictTransactionReport> {
+        if transaction.is_empty() {
+            return Ok(DictTransactionReport::new(true, vec![]));
+        }
+
+        let input = self.prepare_input(transaction)?;
+
+        let (mut statuses, available) = self.verify(&input)?;
+
+        if DEBUG_MODE {
+            println!("transaction statuses  {statuses:?}");
+            println!("transaction available {available:?}");
+        }
+
+        let mut is_valid = true;
+
+        // new entries per dict = inserts of new keys - removes
+        let mut new_entries = vec![0; self.configs.len()];
+
+        for (i, operation) in transaction.get_operations().iter().enumerate() {
+            let dict_index = self
+                .configs
+                .iter()
+                .position(|x| x.id == operation.get_dict_id())
+                .unwrap();
+
+            match (operation, statuses[i]) {
+                (_, DUPLICATE_KEY) => is_valid = false,
+                (DictOperation::Remove { .. }, KEY_NOT_EXIST) => is_valid = false,
+                (DictOperation::Remove { .. }, _) => new_entries[dict_index] -= 1,
+                (DictOperation::Insert { .. }, KEY_NOT_EXIST) => new_entries[dict_index] += 1,
+                (DictOperation::Insert { .. }, _) => {}
+            }
+        }
+
+        for (dict_index, &entries) in new_entries.iter().enumerate() {
+            if entries <= available[dict_index] {
+                continue;
+            }
+
+            is_valid = false;
+
+            let dict_id = self.configs[dict_index].id;
+
+            for (i, operation) in transaction.get_operations().iter().enumerate() {
+                if let DictOperation::Insert { .. } = operation {
+                    if operation.get_dict_id() == dict_id && statuses[i] == KEY_NOT_EXIST {
+                        statuses[i] = KEYS_NOT_AVAILABLE;
+                    }
+                }
+            }
+        }
+
+        if !is_valid {
+            return Ok(DictTransactionReport::new(false, statuses));
+        }
+
+        let global_work_size = transaction.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let operations_input_buf = self
+            .system
+            .blocking_prepare_input_buffer(&input.operations)?;
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&input.keys)?;
+        let values_input_buf = self.system.blocking_prepare_input_buffer(&input.values)?;
+
+        let indices_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        // removes first, the inserts can use the released entries
+        for phase in [0, 1] {
+            let phase: cl_int = phase;
+
+            let mut kernel = self.system.create_kernel(DICT_TRANSACTION_COMMIT)?;
+
+            unsafe {
+                kernel.set_arg(&phase)?;
+                kernel.set_arg(&operations_input_buf.get_cl_mem())?;
+                kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+                kernel.set_arg(&values_input_buf.get_cl_mem())?;
+                kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+                kernel.enqueue_nd_range_kernel_dim_1(
+                    self.system.get_host_command_queue(),
+                    global_work_size,
+                    local_work_size,
+                    &[],
+                )?;
+            }
+        }
+
+        let indices_output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &indices_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("transaction indices {indices_output:?}");
+        }
+
+        Ok(DictTransactionReport::new(true, indices_output))
+    }
+}
+
+#[cfg(test)]
+mod tests_dict_transaction_staging {
+    use super::*;
+
+    #[test]
+    fn stage_operations() {
+        let mut transaction: DictTransaction<i32> = DictTransaction::new();
+        assert!(transaction.is_empty());
+
+        transaction
+            .insert(0, &[vec![1], vec![2]], &[vec![10], vec![20]])
+            .unwrap();
+        transaction.remove(1, &[vec![3]]);
+
+        assert_eq!(
+            transaction.get_operations(),
+            &vec![
+                DictOperation::Insert {
+                    dict_id: 0,
+                    key: vec![1],
+                    value: vec![10]
+                },
+                DictOperation::Insert {
+                    dict_id: 0,
+                    key: vec![2],
+                    value: vec![20]
+                },
+                DictOperation::Remove {
+                    dict_id: 1,
+                    key: vec![3]
+                },
+            ]
+        );
+
+        assert_eq!(
+            transaction.insert(0, &[vec![1]], &[]),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_DICT_INPUT_LEN
+            ))
+        );
+        assert_eq!(transaction.len(), 3);
+
+        transaction.clear();
+        assert!(transaction.is_empty());
+    }
+
+    #[test]
+    fn report_failed() {
+        let report = DictTransactionReport::new(false, vec![0, KEY_NOT_EXIST, 2, DUPLICATE_KEY]);
+        assert_eq!(report.get_failed(), vec![1, 3]);
+    }
+}
+
+#[cfg(test)]
+mod tests_dict_transaction {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::dictionary::handle::dict_v1::DictHandle;
+    use crate::dictionary::handle::DictSnapshotSummary;
+    use opencl::wrapper::system::System;
+
+    fn create_dict_src() -> DictSrc<i32> {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        // forward index
+        dict_src.add(8, 8, 8);
+        // reverse index
+        dict_src.add(8, 8, 4);
+        dict_src
+    }
+
+    #[test]
+    fn invalid_dict_id() {
+        let dict_src = create_dict_src();
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let handle = DictTransactionHandle::new(&dict_src, &system);
+
+        let mut transaction = DictTransaction::new();
+        transaction.remove(5, &[vec![1]]);
+
+        assert_eq!(
+            handle.commit(&transaction),
+            Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_DICT_ID))
+        );
+    }
+
+    #[test]
+    fn commit_all() {
+        let dict_src = create_dict_src();
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let forward = DictHandle::new(dict_src.get_config_by_id(0).unwrap(), &system);
+        let reverse = DictHandle::new(dict_src.get_config_by_id(1).unwrap(), &system);
+        forward.initialize().unwrap();
+        reverse.initialize().unwrap();
+
+        let handle = DictTransactionHandle::new(&dict_src, &system);
+
+        let mut transaction = DictTransaction::new();
+        transaction
+            .insert(0, &[vec![1], vec![2]], &[vec![10], vec![20]])
+            .unwrap();
+        transaction
+            .insert(1, &[vec![10], vec![20]], &[vec![1], vec![2]])
+            .unwrap();
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(report.committed);
+        assert!(report.get_failed().is_empty());
+
+        let (values, _) = forward.get(&vec![vec![1], vec![2]]).unwrap();
+        assert_eq!(
+            values,
+            vec![ensure_vec_size(&[10], 8), ensure_vec_size(&[20], 8)]
+        );
+
+        let (values, _) = reverse.get(&vec![vec![10], vec![20]]).unwrap();
+        assert_eq!(
+            values,
+            vec![ensure_vec_size(&[1], 8), ensure_vec_size(&[2], 8)]
+        );
+
+        // the remove releases the entry used by the insert
+        let mut transaction = DictTransaction::new();
+        transaction.remove(1, &[vec![10], vec![20]]);
+        transaction
+            .insert(
+                1,
+                &[vec![30], vec![40], vec![50], vec![60]],
+                &vec![vec![3]; 4],
+            )
+            .unwrap();
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(report.committed);
+        assert!(report.get_failed().is_empty());
+        assert_eq!(
+            reverse.debug().unwrap().summary(),
+            DictSnapshotSummary::with(4)
+        );
+    }
+
+    #[test]
+    fn nothing_is_committed() {
+        let dict_src = create_dict_src();
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let forward = DictHandle::new(dict_src.get_config_by_id(0).unwrap(), &system);
+        let reverse = DictHandle::new(dict_src.get_config_by_id(1).unwrap(), &system);
+        forward.initialize().unwrap();
+        reverse.initialize().unwrap();
+
+        let handle = DictTransactionHandle::new(&dict_src, &system);
+
+        // the reverse index is full
+        let mut transaction = DictTransaction::new();
+        transaction
+            .insert(0, &[vec![1], vec![2]], &[vec![10], vec![20]])
+            .unwrap();
+        transaction
+            .insert(
+                1,
+                &(10..15).map(|x| vec![x]).collect::<Vec<_>>(),
+                &vec![vec![1]; 5],
+            )
+            .unwrap();
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(!report.committed);
+        assert_eq!(report.get_failed(), vec![2, 3, 4, 5, 6]);
+        assert!(report.indices[2..].iter().all(|&x| x == KEYS_NOT_AVAILABLE));
+
+        // missing key and duplicate key
+        let mut transaction = DictTransaction::new();
+        transaction
+            .insert(0, &[vec![1], vec![1]], &[vec![10], vec![20]])
+            .unwrap();
+        transaction.remove(1, &[vec![99]]);
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(!report.committed);
+        assert_eq!(
+            report.indices,
+            vec![DUPLICATE_KEY, DUPLICATE_KEY, KEY_NOT_EXIST]
+        );
+
+        assert_eq!(
+            forward.debug().unwrap().summary(),
+            DictSnapshotSummary::with(0)
+        );
+        assert_eq!(
+            reverse.debug().unwrap().summary(),
+            DictSnapshotSummary::with(0)
+        );
+    }
+
+    #[test]
+    fn remove_then_insert_the_same_key() {
+        let dict_src = create_dict_src();
+        let system = System::new(DEFAULT_DEVICE_INDEX, &dict_src.build()).unwrap();
+
+        let reverse = DictHandle::new(dict_src.get_config_by_id(1).unwrap(), &system);
+        reverse.initialize().unwrap();
+
+        let handle = DictTransactionHandle::new(&dict_src, &system);
+
+        // the reverse index is full
+        let mut transaction = DictTransaction::new();
+        transaction
+            .insert(
+                1,
+                &(10..14).map(|x| vec![x]).collect::<Vec<_>>(),
+                &vec![vec![1]; 4],
+            )
+            .unwrap();
+        assert!(handle.commit(&transaction).unwrap().committed);
+
+        // replace
+        let mut transaction = DictTransaction::new();
+        transaction.remove(1, &[vec![10]]);
+        transaction.insert(1, &[vec![10]], &[vec![2]]).unwrap();
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(report.committed);
+        assert!(report.get_failed().is_empty());
+
+        let (values, _) = reverse.get(&vec![vec![10]]).unwrap();
+        assert_eq!(values, vec![ensure_vec_size(&[2], 8)]);
+        assert_eq!(
+            reverse.debug().unwrap().summary(),
+            DictSnapshotSummary::with(4)
+        );
+
+        // insert followed by remove
+        let mut transaction = DictTransaction::new();
+        transaction.insert(1, &[vec![11]], &[vec![3]]).unwrap();
+        transaction.remove(1, &[vec![11]]);
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(!report.committed);
+        assert_eq!(report.indices, vec![DUPLICATE_KEY, DUPLICATE_KEY]);
+
+        // a second insert
+        let mut transaction = DictTransaction::new();
+        transaction.remove(1, &[vec![12]]);
+        transaction
+            .insert(1, &[vec![12], vec![12]], &[vec![3], vec![4]])
+            .unwrap();
+
+        let report = handle.commit(&transaction).unwrap();
+        assert!(!report.committed);
+        assert_eq!(
+            report.indices,
+            vec![DUPLICATE_KEY, DUPLICATE_KEY, DUPLICATE_KEY]
+        );
+
+        let (values, _) = reverse.get(&vec![vec![11], vec![12]]).unwrap();
+        assert_eq!(values, vec![ensure_vec_size(&[1], 8); 2]);
+    }
+}
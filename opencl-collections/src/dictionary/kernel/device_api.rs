@@ -0,0 +1,62 @@
+// device-callable functions, the kernels of the user can operate on the dictionaries
+// (CollectionsSrc::enable_device_api or DictSrc::build_device_api)
+//
+//   int dict_lookup__DICT_ID(CL_TYPE* key, CL_TYPE* value_output)    index of the entry, -1 if the key does not exist
+//   int dict_put__DICT_ID(CL_TYPE* key, CL_TYPE* value)              index of the entry, KEYS_NOT_AVAILABLE if the dict is full
+//   int dict_delete__DICT_ID(CL_TYPE* key)                           index of the entry, -1 if the key does not exist
+//
+// key is DICT_KEY_LEN items and value DICT_VALUE_LEN items
+
+use crate::config::ClTypeTrait;
+use crate::dictionary::config::DictSrc;
+use crate::dictionary::kernel::common_replace;
+
+const DICT_API: &str = r#"
+    int dict_lookup__DICT_ID(CL_TYPE* key, CL_TYPE* value_output) {
+        return dict_get__DICT_ID(0, 0, key, value_output);
+    }
+
+    int dict_put__DICT_ID(CL_TYPE* key, CL_TYPE* value) {
+        return dict_insert__DICT_ID(0, 0, key, value);
+    }
+
+    int dict_delete__DICT_ID(CL_TYPE* key) {
+        return dict_remove__DICT_ID(0, key);
+    }
+    "#;
+
+impl<T: ClTypeTrait> DictSrc<T> {
+    /// the source must be added after the source of the dictionaries (build)
+    pub fn build_device_api(&self) -> String {
+        let mut device_api = String::new();
+
+        for config in self.get_configs() {
+            device_api.push_str(&common_replace(DICT_API, config));
+        }
+
+        device_api
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut dict_src: DictSrc<i16> = DictSrc::new();
+        dict_src.add(8, 16, 32);
+        dict_src.add(32, 64, 64);
+
+        let device_api = dict_src.build_device_api();
+        println!("{device_api}");
+        assert!(device_api.contains("int dict_lookup__0(short* key, short* value_output) {"));
+        assert!(device_api.contains("return dict_insert__1(0, 0, key, value);"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let dict_src: DictSrc<i32> = DictSrc::new();
+        assert!(dict_src.build_device_api().is_empty());
+    }
+}
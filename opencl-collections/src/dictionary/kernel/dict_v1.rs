@@ -155,6 +155,18 @@ const BASE_FUNCTIONS: &str = r#"
 
         return KEYS_NOT_AVAILABLE;
     }
+
+    int get_dict_available_entries__DICT_ID() {
+        int available = 0;
+
+        for (int index = 0; index < DICT_CAPACITY; index++) {
+            if (dict_entries__DICT_ID[index] == 0) {
+               available++;
+            }
+        }
+
+        return available;
+    }
     "#;
 
 const DICT_FUNCTIONS: &str = r#"
@@ -247,6 +259,87 @@ const DICT_FUNCTIONS: &str = r#"
 
         return entry_index;
     }
+
+    int get_size_input_value__DICT_ID(int value_input_index, CL_TYPE* value) {
+
+        for (int index = (DICT_VALUE_LEN - 1); index >= 0; index--) {
+            if (value[value_input_index + index] != CL_DEFAULT_VALUE) {
+                return index + 1;
+            }
+        }
+
+        return 0;
+    }
+
+    void dict_merge_value__DICT_ID(int merge_mode, int entry_index, int value_input_index, CL_TYPE* value) {
+
+        int value_size = get_size_dict_value__DICT_ID(entry_index);
+        int input_size = get_size_input_value__DICT_ID(value_input_index, value);
+
+        if (merge_mode == DICT_MERGE_APPEND) {
+            // bounded by DICT_VALUE_LEN
+            for (int index = 0; index < input_size && (value_size + index) < DICT_VALUE_LEN; index++) {
+                dict_values__DICT_ID[entry_index][value_size + index] = value[value_input_index + index];
+            }
+
+            return;
+        }
+
+        for (int index = 0; index < DICT_VALUE_LEN; index++) {
+            CL_TYPE current = dict_values__DICT_ID[entry_index][index];
+            CL_TYPE input = value[value_input_index + index];
+
+            // the elements after the value size are padding (not merged),
+            // the default elements inside the value are merged as any other
+            if (merge_mode == DICT_MERGE_OVERWRITE) {
+                current = input;
+            } else if (index >= input_size) {
+                // padding of the input, the current element is kept
+            } else if (index >= value_size) {
+                current = input;
+            } else if (merge_mode == DICT_MERGE_ADD) {
+                current = current + input;
+            } else if (merge_mode == DICT_MERGE_MAX) {
+                current = max(current, input);
+            } else if (merge_mode == DICT_MERGE_MIN) {
+                current = min(current, input);
+            } else {
+                current = input;
+            }
+
+            dict_values__DICT_ID[entry_index][index] = current;
+        }
+
+    }
+
+    int dict_upsert__DICT_ID(
+        int merge_mode,
+        int key_input_index,
+        int value_input_index,
+        CL_TYPE* key,
+        CL_TYPE* value,
+        int* status
+        ) {
+        int entry_index = check_if_dict_key_exists__DICT_ID(key_input_index, key);
+
+        if (entry_index >= 0) {
+
+            dict_merge_value__DICT_ID(merge_mode, entry_index, value_input_index, value);
+            *status = DICT_UPSERT_MERGED;
+
+            return entry_index;
+        }
+
+        entry_index = dict_insert__DICT_ID(key_input_index, value_input_index, key, value);
+
+        if (entry_index >= 0) {
+            *status = DICT_UPSERT_INSERTED;
+        } else {
+            *status = entry_index;
+        }
+
+        return entry_index;
+    }
     "#;
 
 const BASE_KERNELS: &str = r#"
@@ -295,6 +388,42 @@ const BASE_KERNELS: &str = r#"
         }
     }
 
+    kernel void dict_get_entries__DICT_ID(
+        const uint offset,
+        global CL_TYPE* keys_output,
+        global CL_TYPE* values_output,
+        global int* entries_output
+        ) {
+        int i = get_global_id(0);
+        int entry_index = offset + i;
+
+        get_dict_key__DICT_ID(entry_index, i * DICT_KEY_LEN, keys_output);
+        get_dict_value__DICT_ID(entry_index, i * DICT_VALUE_LEN, values_output);
+
+        entries_output[i] = dict_entries__DICT_ID[entry_index];
+    }
+
+    kernel void dict_filter_by_value_size__DICT_ID(
+        const uint min_size,
+        const uint max_size,
+        global CL_TYPE* keys_output,
+        global uint* sizes_output,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        uint value_size = get_size_dict_value__DICT_ID(i);
+
+        if (dict_entries__DICT_ID[i] != 0 && value_size >= min_size && value_size <= max_size) {
+            get_dict_key__DICT_ID(i, i * DICT_KEY_LEN, keys_output);
+            sizes_output[i] = value_size;
+            indices_output[i] = i;
+        } else {
+            sizes_output[i] = 0;
+            indices_output[i] = KEY_NOT_EXIST;
+        }
+    }
+
     // kernel void dict_get_summary__DICT_ID(
     //     global uint* sizes_output,
     //     global int* meta_output
@@ -417,6 +546,53 @@ const BASE_KERNELS: &str = r#"
         );
     }
 
+    kernel void upsert_on_dict__DICT_ID(
+        const int merge_mode,
+        const int keys_global_work_size,
+        global CL_TYPE* keys_input,
+        global CL_TYPE* values_input,
+        global int* indices_output,
+        global int* statuses_output,
+        global uint* sizes_output
+        ) {
+        int i = get_global_id(0);
+        int key_input_index = i * DICT_KEY_LEN;
+
+        // the first occurrence of a key merges all its duplicates (input order)
+        for (int index = 0; index < i; index++) {
+            if (is_dict_key_input_equal_to__DICT_ID(index * DICT_KEY_LEN, key_input_index, keys_input)) {
+                return;
+            }
+        }
+
+        for (int index = i; index < keys_global_work_size; index++) {
+
+            if (index != i && !is_dict_key_input_equal_to__DICT_ID(key_input_index, index * DICT_KEY_LEN, keys_input)) {
+                continue;
+            }
+
+            int status = 0;
+
+            int entry_index = dict_upsert__DICT_ID(
+                merge_mode,
+                key_input_index,
+                index * DICT_VALUE_LEN,
+                keys_input,
+                values_input,
+                &status
+            );
+
+            indices_output[index] = entry_index;
+            statuses_output[index] = status;
+
+            if (entry_index >= 0) {
+                sizes_output[index] = get_size_dict_value__DICT_ID(entry_index);
+            } else {
+                sizes_output[index] = 0;
+            }
+        }
+    }
+
     kernel void remove_from_dict__DICT_ID(
         const uint key_len,
         global CL_TYPE* keys_input,
@@ -790,7 +966,198 @@ const VERIFY_AND_REMOVE_KERNEL: &str = r#"
     }
     "#;
 
+// ops_input: dict_id, operation, key_input_index, value_input_index
+const TRANSACTION_KERNELS: &str = r#"
+    const int DICT_TRANSACTION_INSERT = 0;
+    const int DICT_TRANSACTION_REMOVE = 1;
+
+    int dict_transaction_get_key_len(int dict_id) {
+        DICT_TRANSACTION_KEY_LEN_CASES
+        return 0;
+    }
+
+    int dict_transaction_get_available(int dict_index) {
+        DICT_TRANSACTION_AVAILABLE_CASES
+        return 0;
+    }
+
+    int dict_transaction_key_exists(int dict_id, int key_input_index, CL_TYPE* keys_input) {
+        DICT_TRANSACTION_EXISTS_CASES
+        return KEY_NOT_EXIST;
+    }
+
+    int dict_transaction_insert(
+        int dict_id,
+        int key_input_index,
+        int value_input_index,
+        CL_TYPE* keys_input,
+        CL_TYPE* values_input
+        ) {
+        DICT_TRANSACTION_INSERT_CASES
+        return KEYS_NOT_AVAILABLE;
+    }
+
+    int dict_transaction_remove(int dict_id, int key_input_index, CL_TYPE* keys_input) {
+        DICT_TRANSACTION_REMOVE_CASES
+        return KEY_NOT_EXIST;
+    }
+
+    kernel void dict_transaction_verify(
+        const int operations_len,
+        global int* operations_input,
+        global CL_TYPE* keys_input,
+        global int* statuses_output,
+        global int* available_output
+        ) {
+        int i = get_global_id(0);
+
+        if (i < DICT_TRANSACTION_TOTAL_DICTS) {
+            available_output[i] = dict_transaction_get_available(i);
+        }
+
+        if (i >= operations_len) {
+            return;
+        }
+
+        int dict_id = operations_input[i * 4];
+        int operation = operations_input[i * 4 + 1];
+        int key_input_index = operations_input[i * 4 + 2];
+        int key_len = dict_transaction_get_key_len(dict_id);
+
+        int duplicates = 0;
+        bool is_remove_then_insert = false;
+
+        // the same key staged more than once, only one remove followed by one insert is allowed
+        for (int index = 0; index < operations_len; index++) {
+
+            if (index == i || operations_input[index * 4] != dict_id) {
+                continue;
+            }
+
+            int second_key_input_index = operations_input[index * 4 + 2];
+
+            bool is_equal = true;
+
+            for (int key_index = 0; key_index < key_len; key_index++) {
+                if (keys_input[key_input_index + key_index] != keys_input[second_key_input_index + key_index]) {
+                    is_equal = false;
+                    break;
+                }
+            }
+
+            if (is_equal == true) {
+                int second_operation = operations_input[index * 4 + 1];
+
+                duplicates++;
+
+                is_remove_then_insert = (operation == DICT_TRANSACTION_REMOVE && second_operation == DICT_TRANSACTION_INSERT && index > i) ||
+                    (operation == DICT_TRANSACTION_INSERT && second_operation == DICT_TRANSACTION_REMOVE && index < i);
+            }
+        }
+
+        if (duplicates > 1 || (duplicates == 1 && !is_remove_then_insert)) {
+            statuses_output[i] = DUPLICATE_KEY;
+            return;
+        }
+
+        // the removes are committed first, the key does not exist for the insert
+        if (duplicates == 1 && operation == DICT_TRANSACTION_INSERT) {
+            statuses_output[i] = KEY_NOT_EXIST;
+            return;
+        }
+
+        statuses_output[i] = dict_transaction_key_exists(dict_id, key_input_index, keys_input);
+    }
+
+    // phase 0 = removes, phase 1 = inserts
+    kernel void dict_transaction_commit(
+        const int phase,
+        global int* operations_input,
+        global CL_TYPE* keys_input,
+        global CL_TYPE* values_input,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        int dict_id = operations_input[i * 4];
+        int operation = operations_input[i * 4 + 1];
+        int key_input_index = operations_input[i * 4 + 2];
+        int value_input_index = operations_input[i * 4 + 3];
+
+        if (phase == 0 && operation == DICT_TRANSACTION_REMOVE) {
+            indices_output[i] = dict_transaction_remove(dict_id, key_input_index, keys_input);
+        }
+
+        if (phase == 1 && operation == DICT_TRANSACTION_INSERT) {
+            indices_output[i] = dict_transaction_insert(
+                dict_id,
+                key_input_index,
+                value_input_index,
+                keys_input,
+                values_input
+            );
+        }
+    }
+    "#;
+
 impl<T: ClTypeTrait> DictSrc<T> {
+    fn generate_transaction_kernels(&self) -> String {
+        let mut key_len_cases = String::new();
+        let mut available_cases = String::new();
+        let mut exists_cases = String::new();
+        let mut insert_cases = String::new();
+        let mut remove_cases = String::new();
+
+        for (dict_index, config) in self.get_configs().iter().enumerate() {
+            let id = config.id;
+
+            key_len_cases.push_str(&format!(
+                "
+        if (dict_id == {id}) {{
+            return {};
+        }}",
+                config.key_len
+            ));
+
+            available_cases.push_str(&format!(
+                "
+        if (dict_index == {dict_index}) {{
+            return get_dict_available_entries__{id}();
+        }}"
+            ));
+
+            exists_cases.push_str(&format!(
+                "
+        if (dict_id == {id}) {{
+            return check_if_dict_key_exists__{id}(key_input_index, keys_input);
+        }}"
+            ));
+
+            insert_cases.push_str(&format!(
+                "
+        if (dict_id == {id}) {{
+            return dict_insert__{id}(key_input_index, value_input_index, keys_input, values_input);
+        }}"
+            ));
+
+            remove_cases.push_str(&format!(
+                "
+        if (dict_id == {id}) {{
+            return dict_remove__{id}(key_input_index, keys_input);
+        }}"
+            ));
+        }
+
+        TRANSACTION_KERNELS
+            .replace("DICT_TRANSACTION_KEY_LEN_CASES", &key_len_cases)
+            .replace("DICT_TRANSACTION_AVAILABLE_CASES", &available_cases)
+            .replace("DICT_TRANSACTION_EXISTS_CASES", &exists_cases)
+            .replace("DICT_TRANSACTION_INSERT_CASES", &insert_cases)
+            .replace("DICT_TRANSACTION_REMOVE_CASES", &remove_cases)
+            .replace("DICT_TRANSACTION_TOTAL_DICTS", &self.len().to_string())
+            .replace("CL_TYPE", T::cl_enum().to_cl_type_name())
+    }
+
     pub fn generate_dict_program_source_v1(&self) -> String {
         if self.is_empty() {
             return String::new();
@@ -825,6 +1192,8 @@ impl<T: ClTypeTrait> DictSrc<T> {
             verify_and_remove_kernels.push_str(&template);
         }
 
+        let transaction_kernels = self.generate_transaction_kernels();
+
         format!(
             "
     /// *** DICT SRC *** ///
@@ -835,6 +1204,15 @@ impl<T: ClTypeTrait> DictSrc<T> {
     const int KEY_NOT_EXIST = -2;
     const int DUPLICATE_KEY = -3;
 
+    const int DICT_UPSERT_INSERTED = 1;
+    const int DICT_UPSERT_MERGED = 2;
+
+    const int DICT_MERGE_OVERWRITE = 0;
+    const int DICT_MERGE_APPEND = 1;
+    const int DICT_MERGE_ADD = 2;
+    const int DICT_MERGE_MAX = 3;
+    const int DICT_MERGE_MIN = 4;
+
     /// globals
     {globals}
 
@@ -847,6 +1225,8 @@ impl<T: ClTypeTrait> DictSrc<T> {
     {verify_and_write_kernels}
     {verify_and_remove_kernels}
 
+    {transaction_kernels}
+
     /// *** DICT SRC *** ///
     "
         )
@@ -878,4 +1258,39 @@ mod tests {
         println!("{program_source}");
         assert!(!program_source.is_empty());
     }
+
+    #[test]
+    fn test_upsert() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 16, 32);
+
+        let program_source = dict_src.generate_dict_program_source_v1();
+        assert!(program_source.contains("kernel void upsert_on_dict__0("));
+        assert!(program_source.contains("const int DICT_MERGE_MIN = 4;"));
+        assert!(!program_source.contains("DICT_VALUE_LEN"));
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 16, 32);
+        dict_src.add(4, 8, 16);
+
+        let program_source = dict_src.generate_dict_program_source_v1();
+        assert!(program_source.contains("kernel void dict_transaction_verify("));
+        assert!(program_source.contains("kernel void dict_transaction_commit("));
+        assert!(program_source.contains("return get_dict_available_entries__1();"));
+        assert!(program_source.contains("if (i < 2) {"));
+        assert!(!program_source.contains("_CASES"));
+    }
+
+    #[test]
+    fn test_entries_and_filter() {
+        let mut dict_src: DictSrc<i32> = DictSrc::new();
+        dict_src.add(8, 16, 32);
+
+        let program_source = dict_src.generate_dict_program_source_v1();
+        assert!(program_source.contains("kernel void dict_get_entries__0("));
+        assert!(program_source.contains("kernel void dict_filter_by_value_size__0("));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::config::ClTypeTrait;
 use crate::dictionary::config::{DictConfig, DictSrc};
 
+pub mod device_api;
 pub mod name;
 
 pub mod dict_v1;
@@ -7,12 +7,18 @@ pub const DICT_RESET: &str = "dict_reset__DICT_ID";
 
 pub const DICT_GET_KEYS: &str = "dict_get_keys__DICT_ID";
 pub const DICT_GET_SUMMARY: &str = "dict_get_summary__DICT_ID";
+pub const DICT_GET_ENTRIES: &str = "dict_get_entries__DICT_ID";
+pub const DICT_FILTER_BY_VALUE_SIZE: &str = "dict_filter_by_value_size__DICT_ID";
 
 pub const READ_ON_DICT: &str = "read_on_dict__DICT_ID";
 pub const READ_VALUE_SIZE_ON_DICT: &str = "read_value_size_on_dict__DICT_ID";
 
 pub const WRITE_TO_DICT: &str = "write_to_dict__DICT_ID";
+pub const UPSERT_ON_DICT: &str = "upsert_on_dict__DICT_ID";
 pub const REMOVE_FROM_DICT: &str = "remove_from_dict__DICT_ID";
 
 pub const VERIFY_AND_WRITE_IN_DICT: &str = "verify_and_write_in_dict__DICT_ID";
 pub const VERIFY_AND_REMOVE_IN_DICT: &str = "verify_and_remove_in_dict__DICT_ID";
+
+pub const DICT_TRANSACTION_VERIFY: &str = "dict_transaction_verify";
+pub const DICT_TRANSACTION_COMMIT: &str = "dict_transaction_commit";
@@ -1,4 +1,5 @@
 pub mod config;
+pub mod file;
 
 pub mod handle;
 pub mod kernel;
@@ -1,5 +1,5 @@
 use io::Error as IoError;
-use opencl::error::OclError;
+use opencl::error::{get_wrapper_error_message, OclError};
 use opencl::opencl_sys::bindings::cl_int;
 use std::io;
 
@@ -7,22 +7,149 @@ use std::io;
 
 // stack
 pub const CL_COLLECTION_INVALID_STACK_ID: cl_int = -200;
+pub const CL_COLLECTION_INVALID_VALUE_STACK_ID: cl_int = -201;
+
+// deque
+pub const CL_COLLECTION_INVALID_DEQUE_ID: cl_int = -1400;
+
+// bitset
+pub const CL_COLLECTION_INVALID_BITSET_ID: cl_int = -1500;
+
+// bloom filter
+pub const CL_COLLECTION_INVALID_BLOOM_FILTER_ID: cl_int = -1600;
+pub const CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH: cl_int = -1601;
+pub const CL_COLLECTION_INVALID_BLOOM_FILTER_CONFIG: cl_int = -1602;
 
 // set
 pub const CL_COLLECTION_INVALID_ARRAY_SET_ID: cl_int = -300;
+pub const CL_COLLECTION_INVALID_HASH_SET_ID: cl_int = -301;
 
 // dict
 pub const CL_COLLECTION_INVALID_DICT_ID: cl_int = -400;
+pub const CL_COLLECTION_INVALID_DICT_INPUT_LEN: cl_int = -401;
+pub const CL_COLLECTION_INVALID_DICT_CAPACITY: cl_int = -402;
+pub const CL_COLLECTION_DICT_GROW_FAILED: cl_int = -403;
+pub const CL_COLLECTION_INVALID_DICT_FILE: cl_int = -404;
+pub const CL_COLLECTION_DICT_GROW_SHARED_PROGRAM: cl_int = -405;
 
 // queue
 pub const CL_COLLECTION_INVALID_QUEUE_ID: cl_int = -500;
+pub const CL_COLLECTION_INVALID_QUEUE_INPUT_LEN: cl_int = -501;
+pub const CL_COLLECTION_INVALID_QUEUE_OVERFLOW_POLICY: cl_int = -502;
+pub const CL_COLLECTION_INVALID_QUEUE_CAPACITY: cl_int = -503;
 
 // cache
 pub const CL_COLLECTION_INVALID_MINI_LRU_ID: cl_int = -600;
+pub const CL_COLLECTION_INVALID_MINI_LRU_INPUT_LEN: cl_int = -601;
 pub const CL_COLLECTION_INVALID_LRU_ID: cl_int = -700;
+pub const CL_COLLECTION_INVALID_LRU_INPUT_LEN: cl_int = -701;
+pub const CL_COLLECTION_LRU_DIRTY_ENTRY_NOT_FOUND: cl_int = -702;
+pub const CL_COLLECTION_LRU_INPUT_TOO_LONG: cl_int = -703;
+pub const CL_COLLECTION_INVALID_TTL_CACHE_ID: cl_int = -1100;
+pub const CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN: cl_int = -1101;
+pub const CL_COLLECTION_INVALID_CACHE_TTL: cl_int = -1102;
+pub const CL_COLLECTION_INVALID_LFU_ID: cl_int = -1200;
+pub const CL_COLLECTION_INVALID_LFU_INPUT_LEN: cl_int = -1201;
+pub const CL_COLLECTION_INVALID_TWO_Q_ID: cl_int = -1300;
+pub const CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN: cl_int = -1301;
 
 // map
 pub const CL_COLLECTION_INVALID_MAP_VALUE_LEN: cl_int = -800;
+pub const CL_COLLECTION_INVALID_TOTAL_MAPS: cl_int = -801;
+pub const CL_COLLECTION_DUPLICATE_MAP_VALUE_LEN: cl_int = -802;
+pub const CL_COLLECTION_MAP_CAPACITY_EXCEEDED: cl_int = -803;
+pub const CL_COLLECTION_MAX_FIND_WORK_SIZE_EXCEEDED: cl_int = -804;
+pub const CL_COLLECTION_EMPTY_MAP_SRC: cl_int = -805;
+pub const CL_COLLECTION_INVALID_MAP_INPUT_LEN: cl_int = -806;
+pub const CL_COLLECTION_MAP_TTL_NOT_ENABLED: cl_int = -807;
+pub const CL_COLLECTION_INVALID_MAP_TTL: cl_int = -808;
+
+// collections (single program)
+pub const CL_COLLECTION_INVALID_NAMESPACE: cl_int = -900;
+pub const CL_COLLECTION_DUPLICATE_NAMESPACE: cl_int = -901;
+pub const CL_COLLECTION_SYMBOL_COLLISION: cl_int = -902;
+pub const CL_COLLECTION_INCOMPATIBLE_MAP_SRC: cl_int = -903;
+pub const CL_COLLECTION_MEMORY_BUDGET_EXCEEDED: cl_int = -904;
+
+// config file
+pub const CL_COLLECTION_INVALID_CONFIG_FILE: cl_int = -1000;
+pub const CL_COLLECTION_INVALID_CAPACITY: cl_int = -1001;
+pub const CL_COLLECTION_INVALID_BLOCK_LEN: cl_int = -1002;
+
+pub fn get_collection_error_message(code: cl_int) -> &'static str {
+    match code {
+        CL_COLLECTION_INVALID_STACK_ID => "invalid stack id",
+        CL_COLLECTION_INVALID_VALUE_STACK_ID => "invalid value stack id",
+        CL_COLLECTION_INVALID_DEQUE_ID => "invalid deque id",
+        CL_COLLECTION_INVALID_BITSET_ID => "invalid bitset id",
+        CL_COLLECTION_INVALID_BLOOM_FILTER_ID => "invalid bloom filter id",
+        CL_COLLECTION_BLOOM_FILTER_CONFIG_MISMATCH => {
+            "the bloom filters do not have the same bits, hashes and key_len"
+        }
+        CL_COLLECTION_INVALID_BLOOM_FILTER_CONFIG => {
+            "bits, hashes and key_len of the bloom filter must be greater than 0"
+        }
+        CL_COLLECTION_INVALID_ARRAY_SET_ID => "invalid array set id",
+        CL_COLLECTION_INVALID_HASH_SET_ID => "invalid hash set id",
+        CL_COLLECTION_INVALID_DICT_ID => "invalid dict id",
+        CL_COLLECTION_INVALID_DICT_INPUT_LEN => "invalid dict input len (keys, values)",
+        CL_COLLECTION_INVALID_DICT_CAPACITY => {
+            "the new dict capacity must be greater than the current capacity"
+        }
+        CL_COLLECTION_DICT_GROW_FAILED => "dict entries could not be copied to the new capacity",
+        CL_COLLECTION_INVALID_DICT_FILE => {
+            "invalid dict file or it does not fit in the dict config"
+        }
+        CL_COLLECTION_DICT_GROW_SHARED_PROGRAM => {
+            "the dict can grow only if its program was built with the dict only"
+        }
+        CL_COLLECTION_INVALID_QUEUE_ID => "invalid queue id",
+        CL_COLLECTION_INVALID_QUEUE_INPUT_LEN => "invalid queue input len (values, priorities)",
+        CL_COLLECTION_INVALID_QUEUE_OVERFLOW_POLICY => {
+            "the overflow policy is not supported by the queue type"
+        }
+        CL_COLLECTION_INVALID_QUEUE_CAPACITY => "the mpmc queue capacity must be at least 2",
+        CL_COLLECTION_INVALID_MINI_LRU_ID => "invalid mini lru id",
+        CL_COLLECTION_INVALID_MINI_LRU_INPUT_LEN => {
+            "invalid mini lru input len (keys, values, priorities)"
+        }
+        CL_COLLECTION_INVALID_LRU_ID => "invalid lru id",
+        CL_COLLECTION_INVALID_LRU_INPUT_LEN => "invalid lru input len (keys, values, priorities)",
+        CL_COLLECTION_LRU_DIRTY_ENTRY_NOT_FOUND => {
+            "dirty entries are no longer in the lru cache, they were not stored"
+        }
+        CL_COLLECTION_LRU_INPUT_TOO_LONG => "lru key or value longer than the key_len / value_len",
+        CL_COLLECTION_INVALID_TTL_CACHE_ID => "invalid ttl cache id",
+        CL_COLLECTION_INVALID_TTL_CACHE_INPUT_LEN => {
+            "invalid ttl cache input len (keys, values, ttl)"
+        }
+        CL_COLLECTION_INVALID_CACHE_TTL => {
+            "ttl must be greater than 0 and now must not be negative"
+        }
+        CL_COLLECTION_INVALID_LFU_ID => "invalid lfu id",
+        CL_COLLECTION_INVALID_LFU_INPUT_LEN => "invalid lfu input len (keys, values)",
+        CL_COLLECTION_INVALID_TWO_Q_ID => "invalid 2q id",
+        CL_COLLECTION_INVALID_TWO_Q_INPUT_LEN => "invalid 2q input len (keys, values)",
+        CL_COLLECTION_INVALID_MAP_VALUE_LEN => "there is no map block for the value_len",
+        CL_COLLECTION_INVALID_TOTAL_MAPS => "total_maps must be greater than 0",
+        CL_COLLECTION_DUPLICATE_MAP_VALUE_LEN => "two map blocks with the same value_len",
+        CL_COLLECTION_MAP_CAPACITY_EXCEEDED => "input len is greater than the map capacity",
+        CL_COLLECTION_MAX_FIND_WORK_SIZE_EXCEEDED => "input len is greater than MAX_FIND_WORK_SIZE",
+        CL_COLLECTION_EMPTY_MAP_SRC => "map src without blocks",
+        CL_COLLECTION_INVALID_MAP_INPUT_LEN => "invalid map input len (keys, values, indices)",
+        CL_COLLECTION_MAP_TTL_NOT_ENABLED => "map block without ttl",
+        CL_COLLECTION_INVALID_MAP_TTL => "ttl must be greater than 0 and now must not be negative",
+        CL_COLLECTION_INVALID_NAMESPACE => "namespace not found",
+        CL_COLLECTION_DUPLICATE_NAMESPACE => "namespace already exists",
+        CL_COLLECTION_SYMBOL_COLLISION => "symbol declared more than once in the program",
+        CL_COLLECTION_INCOMPATIBLE_MAP_SRC => "map src can not be combined",
+        CL_COLLECTION_MEMORY_BUDGET_EXCEEDED => "memory required exceeds the budget",
+        CL_COLLECTION_INVALID_CONFIG_FILE => "invalid config file",
+        CL_COLLECTION_INVALID_CAPACITY => "capacity must be greater than 0",
+        CL_COLLECTION_INVALID_BLOCK_LEN => "key_len and value_len must be greater than 0",
+        _ => "unknown collection error",
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub enum OpenclError {
@@ -43,8 +170,16 @@ impl std::fmt::Display for OpenclError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             OpenclError::OpenCl(v) => write!(f, "opencl error code: {v}"),
-            OpenclError::OpenClWrapper(v) => write!(f, "opencl wrapper error code: {v}"),
-            OpenclError::OpenclCollection(v) => write!(f, "opencl collection error code: {v}"),
+            OpenclError::OpenClWrapper(v) => write!(
+                f,
+                "opencl wrapper error code: {v} ({})",
+                get_wrapper_error_message(*v)
+            ),
+            OpenclError::OpenclCollection(v) => write!(
+                f,
+                "opencl collection error code: {v} ({})",
+                get_collection_error_message(*v)
+            ),
             OpenclError::Unknown(message) => write!(f, "{message}"),
         }
     }
@@ -67,12 +202,19 @@ impl From<OpenclError> for IoError {
             OpenclError::OpenCl(code) => {
                 Self::new(error_kind, format!("opencl error code: {code}"))
             }
-            OpenclError::OpenClWrapper(code) => {
-                Self::new(error_kind, format!("opencl wrapper error code: {code}"))
-            }
+            OpenclError::OpenClWrapper(code) => Self::new(
+                error_kind,
+                format!(
+                    "opencl wrapper error code: {code} ({})",
+                    get_wrapper_error_message(code)
+                ),
+            ),
             OpenclError::OpenclCollection(code) => Self::new(
                 error_kind,
-                format!("opencl collection error code: {}", code),
+                format!(
+                    "opencl collection error code: {code} ({})",
+                    get_collection_error_message(code)
+                ),
             ),
             OpenclError::Unknown(message) => {
                 Self::new(error_kind, format!("cl unknown error: {}", message))
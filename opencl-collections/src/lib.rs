@@ -3,7 +3,11 @@ pub mod error;
 pub mod test_utils;
 pub mod utils;
 
+pub mod bitset;
+pub mod bloom_filter;
 pub mod cache;
+pub mod collections;
+pub mod deque;
 pub mod dictionary;
 pub mod map;
 pub mod queue;
@@ -1,6 +1,11 @@
 use crate::config::{ClType, ClTypeTrait};
-use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_MAP_VALUE_LEN};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_EMPTY_MAP_SRC, CL_COLLECTION_INVALID_MAP_VALUE_LEN,
+    CL_COLLECTION_INVALID_TOTAL_MAPS, CL_COLLECTION_MAP_CAPACITY_EXCEEDED,
+    CL_COLLECTION_MAX_FIND_WORK_SIZE_EXCEEDED,
+};
 use humansize::{format_size, DECIMAL};
+use opencl::opencl_sys::bindings::cl_long;
 use std::marker::PhantomData;
 
 pub const DEFAULT_MAP_KEY_LENGTH: usize = 256;
@@ -13,10 +18,13 @@ pub fn get_map_block_name(value_len: usize) -> String {
 pub const MAX_FIND_WORK_SIZE: usize = 32;
 // pub const MAX_FIND_WORK_SIZE: usize = 64; // fatal error
 
-pub fn check_max_find_work_size(len: usize) {
+pub fn check_max_find_work_size(len: usize) -> OpenClResult<()> {
     if len > MAX_FIND_WORK_SIZE {
-        panic!("TODO handle MAX_FIND_WORK_SIZE")
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_MAX_FIND_WORK_SIZE_EXCEEDED,
+        ));
     }
+    Ok(())
 }
 
 pub const MAX_LOCAL_WORK_SIZE: usize = 256;
@@ -77,6 +85,13 @@ pub struct MapConfig<T: ClTypeTrait> {
     /// __global int map_values__256_byte[64][MAP_CAPACITY][256];
     /// ```
     pub capacity: usize,
+
+    /// ...
+    ///
+    /// ```c
+    /// __global long map_expiry__256_byte[64][MAP_CAPACITY];
+    /// ```
+    pub ttl: bool,
 }
 
 impl<T: ClTypeTrait> MapConfig<T> {
@@ -87,13 +102,24 @@ impl<T: ClTypeTrait> MapConfig<T> {
             key_len: DEFAULT_MAP_KEY_LENGTH,
             value_len,
             capacity,
+            ttl: false,
         }
     }
 
-    pub fn can_hold(&self, len: usize) {
+    pub fn with_ttl(value_len: usize, capacity: usize) -> Self {
+        Self {
+            ttl: true,
+            ..Self::new(value_len, capacity)
+        }
+    }
+
+    pub fn can_hold(&self, len: usize) -> OpenClResult<()> {
         if len > self.capacity {
-            panic!("TODO complete input len error")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_MAP_CAPACITY_EXCEEDED,
+            ));
         }
+        Ok(())
     }
 }
 
@@ -119,7 +145,15 @@ impl<T: ClTypeTrait> MapBlockSummary<T> {
             ClType::I64 => 8,
         };
 
-        let bytes = (map_block_config.capacity * map_block_config.value_len) * type_mul;
+        // map_keys__BLOCK_NAME + map_values__BLOCK_NAME
+        let mut bytes = map_block_config.capacity
+            * (map_block_config.key_len + map_block_config.value_len)
+            * type_mul;
+
+        // map_expiry__BLOCK_NAME
+        if map_block_config.ttl {
+            bytes += map_block_config.capacity * size_of::<cl_long>();
+        }
 
         Self {
             block: map_block_config.clone(),
@@ -187,36 +221,57 @@ pub struct MapSrc<T: ClTypeTrait> {
 }
 
 impl<T: ClTypeTrait> MapSrc<T> {
-    pub fn new(total_maps: usize) -> Self {
+    pub fn new(total_maps: usize) -> OpenClResult<Self> {
         if total_maps == 0 {
-            // TODO update to result
-            panic!("Handle error map_src total_maps = 0")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TOTAL_MAPS,
+            ));
         }
 
-        Self {
+        Ok(Self {
             cl_type: Default::default(),
             total_maps,
             blocks: Vec::new(),
             optional_sources: Vec::new(),
-        }
+        })
     }
 
     pub fn get_total_maps(&self) -> usize {
         self.total_maps
     }
 
-    pub fn set_total_maps(&mut self, total_maps: usize) {
+    pub fn set_total_maps(&mut self, total_maps: usize) -> OpenClResult<()> {
+        if total_maps == 0 {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TOTAL_MAPS,
+            ));
+        }
+
         self.total_maps = total_maps;
+        Ok(())
     }
 
     pub fn get_configs(&self) -> &Vec<MapConfig<T>> {
         &self.blocks
     }
 
+    pub(crate) fn get_configs_mut(&mut self) -> &mut Vec<MapConfig<T>> {
+        &mut self.blocks
+    }
+
     pub fn add(&mut self, value_len: usize, capacity: usize) {
         self.blocks.push(MapConfig::new(value_len, capacity));
     }
 
+    /// Block whose entries can expire, see MapHandle::put_with_ttl
+    pub fn add_with_ttl(&mut self, value_len: usize, capacity: usize) {
+        self.blocks.push(MapConfig::with_ttl(value_len, capacity));
+    }
+
+    pub fn has_ttl(&self) -> bool {
+        self.blocks.iter().any(|x| x.ttl)
+    }
+
     pub fn reorder_by_capacity(&mut self) {
         self.blocks.sort_by(|a, b| a.value_len.cmp(&b.value_len));
     }
@@ -244,15 +299,19 @@ impl<T: ClTypeTrait> MapSrc<T> {
     }
 
     /// The highest value (capacity) of all map settings.
-    pub fn get_max_capacity(&self) -> usize {
-        let values = self.blocks.iter().map(|x| x.capacity).max().unwrap();
-        values
+    pub fn get_max_capacity(&self) -> OpenClResult<usize> {
+        match self.blocks.iter().map(|x| x.capacity).max() {
+            None => Err(OpenclError::OpenclCollection(CL_COLLECTION_EMPTY_MAP_SRC)),
+            Some(v) => Ok(v),
+        }
     }
 
     // The highest value (value_len) of all map settings.
-    pub fn get_max_value_len(&self) -> usize {
-        let values = self.blocks.iter().map(|x| x.value_len).max().unwrap();
-        values
+    pub fn get_max_value_len(&self) -> OpenClResult<usize> {
+        match self.blocks.iter().map(|x| x.value_len).max() {
+            None => Err(OpenclError::OpenclCollection(CL_COLLECTION_EMPTY_MAP_SRC)),
+            Some(v) => Ok(v),
+        }
     }
 
     // the total items that can be saved on all maps.
@@ -278,9 +337,8 @@ impl<T: ClTypeTrait> MapSrc<T> {
         true
     }
 
-    /// Approximate memory required for the program
-    /// FIXME correct sum of required memory, add value of keys in the results.
-    /// Not all values are taken into account when calculating the required memory.
+    /// Memory required by the keys and values of all maps, the temporary buffers of the
+    /// optional sources are not included (see collections::planner).
     pub fn summary(&self) -> MapSummary<T> {
         let summaries: Vec<MapBlockSummary<T>> = self
             .blocks
@@ -294,7 +352,12 @@ impl<T: ClTypeTrait> MapSrc<T> {
 
 impl<T: ClTypeTrait> Default for MapSrc<T> {
     fn default() -> Self {
-        Self::new(1)
+        Self {
+            cl_type: Default::default(),
+            total_maps: 1,
+            blocks: Vec::new(),
+            optional_sources: Vec::new(),
+        }
     }
 }
 
@@ -305,7 +368,7 @@ mod tests {
 
     #[test]
     fn test_map_src_add() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 8);
         map_src.add(BYTE_512, 16);
 
@@ -319,9 +382,61 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn test_map_src_invalid_total_maps() {
-        let _map_src: MapSrc<i32> = MapSrc::new(0);
+        let result: OpenClResult<MapSrc<i32>> = MapSrc::new(0);
+        assert_eq!(
+            result.err(),
+            Some(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TOTAL_MAPS
+            ))
+        );
+    }
+
+    #[test]
+    fn test_map_src_set_invalid_total_maps() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+
+        let result = map_src.set_total_maps(0);
+        assert_eq!(
+            result,
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_TOTAL_MAPS
+            ))
+        );
+        assert_eq!(map_src.get_total_maps(), 2);
+    }
+
+    #[test]
+    fn test_map_config_can_hold() {
+        let config: MapConfig<i32> = MapConfig::new(BYTE_256, 8);
+
+        assert_eq!(config.can_hold(8), Ok(()));
+        assert_eq!(
+            config.can_hold(9),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_MAP_CAPACITY_EXCEEDED
+            ))
+        );
+    }
+
+    #[test]
+    fn test_check_max_find_work_size() {
+        assert_eq!(check_max_find_work_size(MAX_FIND_WORK_SIZE), Ok(()));
+        assert_eq!(
+            check_max_find_work_size(MAX_FIND_WORK_SIZE + 1),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_MAX_FIND_WORK_SIZE_EXCEEDED
+            ))
+        );
+    }
+
+    #[test]
+    fn test_map_src_empty_get_max() {
+        let map_src: MapSrc<i32> = MapSrc::default();
+
+        let error = Err(OpenclError::OpenclCollection(CL_COLLECTION_EMPTY_MAP_SRC));
+        assert_eq!(map_src.get_max_capacity(), error);
+        assert_eq!(map_src.get_max_value_len(), error);
     }
 
     #[test]
@@ -365,7 +480,7 @@ mod tests {
         map_src.add(KB, 32);
         map_src.add(MB, 16);
 
-        let result = map_src.get_max_capacity();
+        let result = map_src.get_max_capacity().unwrap();
         assert_eq!(result, 32);
     }
 
@@ -376,7 +491,7 @@ mod tests {
         map_src.add(KB, 32);
         map_src.add(MB, 16);
 
-        let result = map_src.get_max_value_len();
+        let result = map_src.get_max_value_len().unwrap();
         assert_eq!(result, MB);
     }
 
@@ -404,7 +519,7 @@ mod tests {
 
     #[test]
     fn test_map_src_get_summary() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 8);
         map_src.add(KB, 32);
         map_src.add(MB, 16);
@@ -412,8 +527,31 @@ mod tests {
         let summary = map_src.summary();
         println!("{:#?}", summary);
 
-        // TODO assert test
         assert_eq!(summary.reserved, 0);
         assert_eq!(summary.capacity, map_src.get_maximum_assignable_keys());
+
+        // i32 keys + values
+        let map_memory_required = (8 * (256 + BYTE_256) + 32 * (256 + KB) + 16 * (256 + MB)) * 4;
+        assert_eq!(summary.map_memory_required, map_memory_required);
+        assert_eq!(summary.total_memory_required, map_memory_required * 2);
+    }
+
+    #[test]
+    fn test_map_src_with_ttl() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 8);
+        assert!(!map_src.has_ttl());
+
+        map_src.add_with_ttl(KB, 32);
+        assert!(map_src.has_ttl());
+
+        let configs = map_src.get_configs();
+        assert!(!configs[0].ttl);
+        assert_eq!(configs[1], MapConfig::with_ttl(KB, 32));
+
+        // i32 keys + values, i64 map_expiry
+        let summary = map_src.summary();
+        let map_memory_required = (8 * (256 + BYTE_256) + 32 * (256 + KB)) * 4 + 32 * 8;
+        assert_eq!(summary.map_memory_required, map_memory_required);
     }
 }
@@ -9,6 +9,7 @@ use std::sync::Arc;
 // pub mod loader;
 pub mod reset;
 pub mod tmp;
+pub mod ttl;
 
 pub mod read;
 pub mod write;
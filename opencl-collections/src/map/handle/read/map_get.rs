@@ -14,14 +14,14 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         &self,
         keys: &MapKeys<T>,
     ) -> OpenClResult<(EntryIndices, MapBlockSizes, MapValues<T>)> {
-        check_max_find_work_size(keys.len());
+        check_max_find_work_size(keys.len())?;
 
         let map_config = &self.map_src;
 
         let global_work_size = keys.len();
         let local_work_size = check_local_work_size(global_work_size);
 
-        let max_value_len = map_config.get_max_value_len();
+        let max_value_len = map_config.get_max_value_len()?;
 
         let key_input_capacity = DEFAULT_MAP_KEY_LENGTH * global_work_size;
         let value_output_capacity = max_value_len * global_work_size;
@@ -137,7 +137,7 @@ mod tests_map_get {
 
     #[test]
     fn no_index_found() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY);
@@ -170,7 +170,7 @@ mod tests_map_get {
 
     #[test]
     fn index_found() {
-        let mut map_src = MapSrc::new(TOTAL_MAPS);
+        let mut map_src = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -234,7 +234,7 @@ mod tests_map_get {
 
     #[test]
     fn multiple_matches() {
-        let mut map_src = MapSrc::new(TOTAL_MAPS);
+        let mut map_src = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -172,7 +172,7 @@ mod tests_map_get_empty_keys_for_block {
 
     #[test]
     fn no_indexes_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -195,7 +195,7 @@ mod tests_map_get_empty_keys_for_block {
     // FIXME test name
     #[test]
     fn no_indexes_available_2() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -218,7 +218,7 @@ mod tests_map_get_empty_keys_for_block {
 
     #[test]
     fn all_indices_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -241,7 +241,7 @@ mod tests_map_get_empty_keys_for_block {
 
     #[test]
     fn some_indices_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -294,7 +294,7 @@ mod tests_map_get_empty_keys {
 
     #[test]
     fn no_indexes_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, CAPACITY);
         map_src.add(BYTE_512, CAPACITY);
         map_src.add(KB, CAPACITY);
@@ -321,7 +321,7 @@ mod tests_map_get_empty_keys {
 
     #[test]
     fn all_indices_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, CAPACITY);
         map_src.add(BYTE_512, CAPACITY);
         map_src.add(KB, CAPACITY);
@@ -346,7 +346,7 @@ mod tests_map_get_empty_keys {
 
     #[test]
     fn some_indices_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, CAPACITY);
         map_src.add(BYTE_512, CAPACITY);
         map_src.add(KB, CAPACITY);
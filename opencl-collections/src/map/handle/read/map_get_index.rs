@@ -11,7 +11,7 @@ use opencl::wrapper::system::OpenclCommonOperation;
 impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
     // For now, only searches for a few simultaneous elements
     pub fn map_get_index(&self, keys: &MapKeys<T>) -> OpenClResult<(EntryIndices, MapBlockSizes)> {
-        check_max_find_work_size(keys.len());
+        check_max_find_work_size(keys.len())?;
 
         let global_work_size = keys.len();
         let local_work_size = check_local_work_size(global_work_size);
@@ -110,7 +110,7 @@ mod tests_map_get_index {
 
     #[test]
     fn no_index_found() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY);
@@ -149,7 +149,7 @@ mod tests_map_get_index {
 
     #[test]
     fn index_found() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -201,7 +201,7 @@ mod tests_map_get_index {
 
     #[test]
     fn multiple_matches() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY);
@@ -258,7 +258,7 @@ mod tests_map_get_index {
         let input_len = 2;
         let total_blocks = 10;
 
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
 
         for i in 0..total_blocks {
             map_src.add(BYTE_256 * (i + 1), MAP_CAPACITY);
@@ -315,7 +315,7 @@ mod tests_map_get_index {
 //     const MAP_CAPACITY: usize = 1024;
 //
 //     fn map_get_index(input_len: usize, total_blocks: usize) {
-//         let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+//         let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
 //
 //         for i in 0..total_blocks {
 //             map_src.add(BYTE_256 * (i + 1), MAP_CAPACITY);
@@ -115,7 +115,7 @@ mod tests_map_get_summary {
     fn is_empty() {
         let map_capacity = 32;
 
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, map_capacity * 2);
         map_src.add(BYTE_512, map_capacity);
         map_src.add(KB, map_capacity / 2);
@@ -151,7 +151,7 @@ mod tests_map_get_summary {
     fn with_records() {
         let map_capacity = 32;
 
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, map_capacity * 2);
         map_src.add(BYTE_512, map_capacity);
         map_src.add(KB, map_capacity / 2);
@@ -200,7 +200,7 @@ mod tests_map_get_summary {
     fn with_large_records() {
         let map_capacity = 1024;
 
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, map_capacity * 2);
         map_src.add(BYTE_512, map_capacity);
         map_src.add(KB, map_capacity * 4);
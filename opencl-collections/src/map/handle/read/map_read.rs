@@ -465,7 +465,7 @@ mod tests_map_read_keys_for_block {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -481,7 +481,7 @@ mod tests_map_read_keys_for_block {
 
     #[test]
     fn map_is_full() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -498,7 +498,7 @@ mod tests_map_read_keys_for_block {
 
     #[test]
     fn map_is_not_full() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -544,7 +544,7 @@ mod tests_map_read {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -560,7 +560,7 @@ mod tests_map_read {
 
     #[test]
     fn map_is_full() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -580,7 +580,7 @@ mod tests_map_read {
 
     #[test]
     fn map_is_not_full() {
-        let mut map_src = MapSrc::new(TOTAL_MAPS);
+        let mut map_src = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -632,7 +632,7 @@ mod tests_map_read_with_cmq {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -648,7 +648,7 @@ mod tests_map_read_with_cmq {
 
     #[test]
     fn map_is_full() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -668,7 +668,7 @@ mod tests_map_read_with_cmq {
 
     #[test]
     fn map_is_not_full() {
-        let mut map_src = MapSrc::new(TOTAL_MAPS);
+        let mut map_src = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -720,7 +720,7 @@ mod tests_map_read_with_index {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -740,7 +740,7 @@ mod tests_map_read_with_index {
 
     #[test]
     fn all_indices() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -764,7 +764,7 @@ mod tests_map_read_with_index {
 
     #[test]
     fn some_indices() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -801,7 +801,7 @@ mod tests_map_read_with_index_and_cmq {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -821,7 +821,7 @@ mod tests_map_read_with_index_and_cmq {
 
     #[test]
     fn all_indices() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -845,7 +845,7 @@ mod tests_map_read_with_index_and_cmq {
 
     #[test]
     fn some_indices() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -881,7 +881,7 @@ mod tests_map_read_keys {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY / 2);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -920,7 +920,7 @@ mod tests_map_read_keys {
 
     #[test]
     fn map_is_full() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY * 2);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -962,7 +962,7 @@ mod tests_map_read_keys {
 
     #[test]
     fn map_is_not_full() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -161,7 +161,7 @@ mod tests_map_read_assigned_keys {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -183,7 +183,7 @@ mod tests_map_read_assigned_keys {
 
     #[test]
     fn map_is_full() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY * 2);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -246,7 +246,7 @@ mod tests_map_read_assigned_keys {
 
     #[test]
     fn map_is_not_full() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -137,7 +137,7 @@ mod tests_map_read_sizes_for_block {
 
     #[test]
     fn simple_cases() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 32);
@@ -197,7 +197,7 @@ mod tests_map_read_sizes {
 
     #[test]
     fn simple_cases() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 32);
@@ -271,7 +271,7 @@ mod tests_map_read_sizes {
         let mb_8 = MB * 8;
         let mb_20 = MB * 20;
 
-        let mut map_src: MapSrc<i32> = MapSrc::new(1);
+        let mut map_src: MapSrc<i32> = MapSrc::new(1).unwrap();
         map_src.add(KB, 16);
         map_src.add(MB, 32);
         map_src.add(mb_8, 8);
@@ -124,7 +124,7 @@ mod tests_map_reset {
 
     #[test]
     fn test_case_i16() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY * 2);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -148,7 +148,7 @@ mod tests_map_reset {
 
     #[test]
     fn test_case_u8() {
-        let mut map_src: MapSrc<u8> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<u8> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY * 2);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -186,7 +186,7 @@ mod tests_map_handle_reset_all_maps {
 
     #[test]
     fn test_case_i16() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY * 2);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -212,7 +212,7 @@ mod tests_map_handle_reset_all_maps {
 
     #[test]
     fn test_case_u8() {
-        let mut map_src: MapSrc<u8> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<u8> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY * 2);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY / 2);
@@ -69,7 +69,7 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         kernel_name: &str,
         elements: usize,
     ) -> OpenClResult<TmpMultiple<T>> {
-        check_max_find_work_size(elements);
+        check_max_find_work_size(elements)?;
 
         let map_config = &self.map_src;
         let total_indices = map_config.get_maximum_assignable_keys();
@@ -151,7 +151,7 @@ mod tests_get_tmp_multiple {
 
     #[test]
     fn get_all_tmp() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -172,7 +172,7 @@ mod tests_get_tmp_multiple {
 
     #[test]
     fn get_slice_of_tmp() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -0,0 +1,514 @@
+use crate::config::ClTypeTrait;
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_MAP_INPUT_LEN, CL_COLLECTION_INVALID_MAP_TTL,
+    CL_COLLECTION_MAP_TTL_NOT_ENABLED,
+};
+use crate::map::config::{check_local_work_size, MapConfig, DEFAULT_MAP_KEY_LENGTH};
+use crate::map::handle::{MapHandle, MapKeys, MapValues};
+use crate::map::kernel::name::{
+    get_map_kernel_name, MAP_PUT_WITH_TTL, MAP_READ_EXPIRY, MAP_SET_CLOCK,
+    MAP_SET_EXPIRY_WITH_INDEX, MAP_SWEEP_EXPIRED,
+};
+use crate::utils::{ensure_vec_size, from_buf_usize_to_vec_i32};
+use opencl::opencl_sys::bindings::{cl_int, cl_long, cl_uint};
+use opencl::wrapper::system::OpenclCommonOperation;
+
+/// The entry does not expire
+pub const MAP_NO_EXPIRY: cl_long = 0;
+
+/// expiry timestamp of an entry, the time (now) is supplied by the host
+pub fn get_expiry(now: cl_long, ttl: cl_long) -> OpenClResult<cl_long> {
+    if now < 0 || ttl <= 0 {
+        return Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_MAP_TTL));
+    }
+
+    now.checked_add(ttl)
+        .ok_or(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_MAP_TTL))
+}
+
+fn check_ttl_enabled<T: ClTypeTrait>(config: &MapConfig<T>) -> OpenClResult<()> {
+    if !config.ttl {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_MAP_TTL_NOT_ENABLED,
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapExpiredEntries<T: ClTypeTrait> {
+    pub config: MapConfig<T>,
+    pub indices: Vec<usize>,
+}
+
+impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
+    /// Entries with an expiry timestamp lower or equal than now are treated as missing
+    /// by the key searches (map_get, map_get_index, map_insert, map_remove, ...)
+    pub fn set_clock(&self, now: cl_long) -> OpenClResult<()> {
+        if now < 0 {
+            return Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_MAP_TTL));
+        }
+
+        if !self.map_src.has_ttl() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_MAP_TTL_NOT_ENABLED,
+            ));
+        }
+
+        let map_id = self.map_id as cl_uint;
+
+        let mut kernel = self.system.create_kernel(MAP_SET_CLOCK)?;
+
+        unsafe {
+            kernel.set_arg(&map_id)?;
+            kernel.set_arg(&now)?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn put_with_ttl(
+        &self,
+        map_value_len: usize,
+        keys: &MapKeys<T>,
+        values: &MapValues<T>,
+        ttl: &[cl_long],
+        now: cl_long,
+    ) -> OpenClResult<()> {
+        let config = self.map_src.get_config_by_value_len(map_value_len)?;
+        check_ttl_enabled(config)?;
+        config.can_hold(keys.len())?;
+
+        let global_work_size = keys.len();
+
+        if global_work_size != values.len() || global_work_size != ttl.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
+        }
+
+        let expiry_input = ttl
+            .iter()
+            .map(|&x| get_expiry(now, x))
+            .collect::<OpenClResult<Vec<cl_long>>>()?;
+
+        let local_work_size = check_local_work_size(global_work_size);
+
+        let key_input_capacity = DEFAULT_MAP_KEY_LENGTH * global_work_size;
+        let value_input_capacity = map_value_len * global_work_size;
+
+        let mut keys_input: Vec<_> = Vec::with_capacity(key_input_capacity);
+
+        for k in keys {
+            let mut v = ensure_vec_size(k, DEFAULT_MAP_KEY_LENGTH);
+            keys_input.append(&mut v);
+        }
+
+        let mut values_input: Vec<_> = Vec::with_capacity(value_input_capacity);
+
+        for b in values {
+            let mut v = ensure_vec_size(b, map_value_len);
+            values_input.append(&mut v);
+        }
+
+        let keys_input_buf = self.system.blocking_prepare_input_buffer(&keys_input)?;
+
+        let values_input_buf = self.system.blocking_prepare_input_buffer(&values_input)?;
+
+        let expiry_input_buf = self.system.blocking_prepare_input_buffer(&expiry_input)?;
+
+        let map_id = self.map_id as cl_uint;
+
+        let kernel_name = get_map_kernel_name(MAP_PUT_WITH_TTL, map_value_len);
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&map_id)?;
+            kernel.set_arg(&keys_input_buf.get_cl_mem())?;
+            kernel.set_arg(&values_input_buf.get_cl_mem())?;
+            kernel.set_arg(&expiry_input_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Assign the ttl to entries already written (map_insert, put_with_index, ...)
+    pub fn set_ttl_with_index(
+        &self,
+        map_value_len: usize,
+        indices: &[usize],
+        ttl: &[cl_long],
+        now: cl_long,
+    ) -> OpenClResult<()> {
+        let config = self.map_src.get_config_by_value_len(map_value_len)?;
+        check_ttl_enabled(config)?;
+        config.can_hold(indices.len())?;
+
+        let global_work_size = indices.len();
+
+        if global_work_size != ttl.len() {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
+        }
+
+        let expiry_input = ttl
+            .iter()
+            .map(|&x| get_expiry(now, x))
+            .collect::<OpenClResult<Vec<cl_long>>>()?;
+
+        let local_work_size = check_local_work_size(global_work_size);
+
+        let indices_input = from_buf_usize_to_vec_i32(indices);
+
+        let indices_input_buf = self.system.blocking_prepare_input_buffer(&indices_input)?;
+
+        let expiry_input_buf = self.system.blocking_prepare_input_buffer(&expiry_input)?;
+
+        let map_id = self.map_id as cl_uint;
+
+        let kernel_name = get_map_kernel_name(MAP_SET_EXPIRY_WITH_INDEX, map_value_len);
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&map_id)?;
+            kernel.set_arg(&indices_input_buf.get_cl_mem())?;
+            kernel.set_arg(&expiry_input_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read_expiry(&self, map_value_len: usize) -> OpenClResult<Vec<cl_long>> {
+        let config = self.map_src.get_config_by_value_len(map_value_len)?;
+        check_ttl_enabled(config)?;
+
+        let global_work_size = config.capacity;
+        let local_work_size = check_local_work_size(global_work_size);
+
+        let expiry_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let map_id = self.map_id as cl_uint;
+
+        let kernel_name = get_map_kernel_name(MAP_READ_EXPIRY, map_value_len);
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&map_id)?;
+            kernel.set_arg(&expiry_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let expiry_output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &expiry_output_buf, &[])?;
+
+        Ok(expiry_output)
+    }
+
+    fn sweep_expired_entries(&self, config: &MapConfig<T>) -> OpenClResult<Vec<usize>> {
+        let global_work_size = config.capacity;
+        let local_work_size = check_local_work_size(global_work_size);
+
+        let indices_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let map_id = self.map_id as cl_uint;
+
+        let kernel_name = get_map_kernel_name(MAP_SWEEP_EXPIRED, config.value_len);
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&map_id)?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &indices_output_buf, &[])?;
+
+        Ok(indices_output
+            .into_iter()
+            .filter(|&x| x >= 0)
+            .map(|x| x as usize)
+            .collect())
+    }
+
+    /// Clear the expired entries of the block, the indices of the cleared entries are returned
+    pub fn sweep_expired_for_block(
+        &self,
+        map_value_len: usize,
+        now: cl_long,
+    ) -> OpenClResult<Vec<usize>> {
+        let config = self.map_src.get_config_by_value_len(map_value_len)?;
+        check_ttl_enabled(config)?;
+
+        self.set_clock(now)?;
+        self.sweep_expired_entries(config)
+    }
+
+    pub fn sweep_expired(&self, now: cl_long) -> OpenClResult<Vec<MapExpiredEntries<T>>> {
+        self.set_clock(now)?;
+
+        let mut output = Vec::new();
+
+        for config in self.map_src.get_configs().iter().filter(|x| x.ttl) {
+            let indices = self.sweep_expired_entries(config)?;
+
+            output.push(MapExpiredEntries {
+                config: config.clone(),
+                indices,
+            });
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests_map_ttl_expiry {
+    use super::*;
+
+    #[test]
+    fn expiry_is_now_plus_ttl() {
+        assert_eq!(get_expiry(0, 10), Ok(10));
+        assert_eq!(get_expiry(1_000, 250), Ok(1_250));
+    }
+
+    #[test]
+    fn invalid_ttl() {
+        let error = Err(OpenclError::OpenclCollection(CL_COLLECTION_INVALID_MAP_TTL));
+
+        assert_eq!(get_expiry(10, 0), error);
+        assert_eq!(get_expiry(10, -1), error);
+        assert_eq!(get_expiry(-1, 10), error);
+        assert_eq!(get_expiry(cl_long::MAX, 1), error);
+    }
+}
+
+#[cfg(test)]
+mod tests_map_put_with_ttl {
+    use super::*;
+    use crate::map::config::{MapSrc, MAX_FIND_WORK_SIZE};
+    use crate::map::handle::test_utils::generate_arc_opencl_block_default;
+    use crate::map::handle::KEY_NOT_EXISTS;
+    use crate::test_utils::TestMatrix;
+    use crate::utils::{BYTE_256, BYTE_512};
+
+    const TOTAL_MAPS: usize = 2;
+    const MAP_CAPACITY: usize = 16;
+
+    #[test]
+    fn entries_expire_with_the_fake_clock() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
+        map_src.add_with_ttl(BYTE_256, MAP_CAPACITY);
+        map_src.add_map_get_index_program_src(MAX_FIND_WORK_SIZE);
+
+        let system = generate_arc_opencl_block_default(&map_src);
+        let m = MapHandle::new(0, &map_src, system);
+
+        let test_matrix = TestMatrix::new(MAP_CAPACITY, DEFAULT_MAP_KEY_LENGTH, BYTE_256, 1, 10);
+
+        // even entries expire at 110, odd entries at 200
+        let ttl: Vec<cl_long> = (0..MAP_CAPACITY)
+            .map(|i| if i % 2 == 0 { 10 } else { 100 })
+            .collect();
+
+        m.put_with_ttl(BYTE_256, &test_matrix.keys, &test_matrix.values, &ttl, 100)
+            .unwrap();
+
+        let expected_expiry: Vec<cl_long> = ttl.iter().map(|x| x + 100).collect();
+        assert_eq!(m.read_expiry(BYTE_256).unwrap(), expected_expiry);
+
+        m.set_clock(105).unwrap();
+        let (indices, _) = m.map_get_index(&test_matrix.keys).unwrap();
+        let expected_indices: Vec<cl_int> = (0..MAP_CAPACITY as cl_int).collect();
+        assert_eq!(indices, expected_indices);
+
+        m.set_clock(110).unwrap();
+        let (indices, _) = m.map_get_index(&test_matrix.keys).unwrap();
+        let expected_indices: Vec<cl_int> = (0..MAP_CAPACITY as cl_int)
+            .map(|i| if i % 2 == 0 { KEY_NOT_EXISTS } else { i })
+            .collect();
+        assert_eq!(indices, expected_indices);
+    }
+
+    #[test]
+    fn sweep_expired_entries() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
+        map_src.add(BYTE_256, MAP_CAPACITY);
+        map_src.add_with_ttl(BYTE_512, MAP_CAPACITY);
+
+        let system = generate_arc_opencl_block_default(&map_src);
+        let m = MapHandle::new(0, &map_src, system);
+
+        let test_matrix = TestMatrix::new(4, DEFAULT_MAP_KEY_LENGTH, BYTE_512, 1, 10);
+
+        m.put_with_ttl(
+            BYTE_512,
+            &test_matrix.keys,
+            &test_matrix.values,
+            &[5, 50, 5, 50],
+            0,
+        )
+        .unwrap();
+
+        let result = m.sweep_expired(10).unwrap();
+        assert_eq!(
+            result,
+            vec![MapExpiredEntries {
+                config: MapConfig::with_ttl(BYTE_512, MAP_CAPACITY),
+                indices: vec![0, 2],
+            }]
+        );
+
+        let mut expected_expiry = vec![MAP_NO_EXPIRY; MAP_CAPACITY];
+        expected_expiry[1] = 50;
+        expected_expiry[3] = 50;
+        assert_eq!(m.read_expiry(BYTE_512).unwrap(), expected_expiry);
+
+        let pairs = m.read(BYTE_512).unwrap();
+        assert_eq!(pairs[0].get_key(), Vec::<i32>::new());
+        assert_eq!(pairs[1].get_key(), test_matrix.keys[1]);
+        assert_eq!(pairs[2].get_key(), Vec::<i32>::new());
+        assert_eq!(pairs[3].get_key(), test_matrix.keys[3]);
+
+        let indices = m.sweep_expired_for_block(BYTE_512, 50).unwrap();
+        assert_eq!(indices, vec![1, 3]);
+        assert_eq!(
+            m.read_expiry(BYTE_512).unwrap(),
+            vec![MAP_NO_EXPIRY; MAP_CAPACITY]
+        );
+    }
+
+    #[test]
+    fn read_treats_expired_entries_as_empty() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
+        map_src.add_with_ttl(BYTE_256, MAP_CAPACITY);
+        map_src.add_map_read_sizes_program_src();
+
+        let system = generate_arc_opencl_block_default(&map_src);
+        let m = MapHandle::new(0, &map_src, system);
+
+        let test_matrix = TestMatrix::new(4, DEFAULT_MAP_KEY_LENGTH, BYTE_256, 1, 10);
+
+        m.put_with_ttl(
+            BYTE_256,
+            &test_matrix.keys,
+            &test_matrix.values,
+            &[5, 50, 5, 50],
+            0,
+        )
+        .unwrap();
+
+        m.set_clock(10).unwrap();
+
+        // expired entries are not swept, read must still hide them
+        let pairs = m.read(BYTE_256).unwrap();
+        assert_eq!(pairs[0].get_key(), Vec::<i32>::new());
+        assert_eq!(pairs[0].get_value(), Vec::<i32>::new());
+        assert_eq!(pairs[1].get_key(), test_matrix.keys[1]);
+        assert_eq!(pairs[1].get_value(), test_matrix.values[1]);
+        assert_eq!(pairs[2].get_key(), Vec::<i32>::new());
+        assert_eq!(pairs[3].get_key(), test_matrix.keys[3]);
+
+        let mut expected_sizes = vec![0; MAP_CAPACITY];
+        expected_sizes[1] = BYTE_256 as cl_int;
+        expected_sizes[3] = BYTE_256 as cl_int;
+        assert_eq!(m.read_sizes_for_block(BYTE_256).unwrap(), expected_sizes);
+        assert_eq!(m.read_sizes().unwrap()[0].entries_sizes, expected_sizes);
+
+        let map_entries = m.read_assigned_keys().unwrap();
+        let entry_indices: Vec<_> = map_entries[0]
+            .pairs
+            .iter()
+            .map(|x| x.entry_index.unwrap())
+            .collect();
+        assert_eq!(entry_indices, vec![1, 3]);
+        assert_eq!(map_entries[0].pairs[0].get_key(), test_matrix.keys[1]);
+        assert_eq!(map_entries[0].pairs[1].get_value(), test_matrix.values[3]);
+
+        let expected_expiry: Vec<cl_long> = vec![5, 50, 5, 50]
+            .into_iter()
+            .chain(vec![MAP_NO_EXPIRY; MAP_CAPACITY - 4])
+            .collect();
+        assert_eq!(m.read_expiry(BYTE_256).unwrap(), expected_expiry);
+    }
+
+    #[test]
+    fn put_clears_the_expiry() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
+        map_src.add_with_ttl(BYTE_256, MAP_CAPACITY);
+
+        let system = generate_arc_opencl_block_default(&map_src);
+        let m = MapHandle::new(0, &map_src, system);
+
+        let test_matrix = TestMatrix::new(4, DEFAULT_MAP_KEY_LENGTH, BYTE_256, 1, 10);
+
+        m.put_with_ttl(BYTE_256, &test_matrix.keys, &test_matrix.values, &[5; 4], 0)
+            .unwrap();
+        m.put(BYTE_256, &test_matrix.keys, &test_matrix.values)
+            .unwrap();
+
+        assert_eq!(
+            m.read_expiry(BYTE_256).unwrap(),
+            vec![MAP_NO_EXPIRY; MAP_CAPACITY]
+        );
+
+        let result = m.sweep_expired(10).unwrap();
+        assert!(result[0].indices.is_empty());
+    }
+
+    #[test]
+    fn block_without_ttl() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
+        map_src.add(BYTE_256, MAP_CAPACITY);
+        map_src.add_with_ttl(BYTE_512, MAP_CAPACITY);
+
+        let system = generate_arc_opencl_block_default(&map_src);
+        let m = MapHandle::new(0, &map_src, system);
+
+        let test_matrix = TestMatrix::new(4, DEFAULT_MAP_KEY_LENGTH, BYTE_256, 1, 10);
+
+        let result = m.put_with_ttl(BYTE_256, &test_matrix.keys, &test_matrix.values, &[5; 4], 0);
+        assert_eq!(
+            result,
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_MAP_TTL_NOT_ENABLED
+            ))
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use crate::config::{ClTypeTrait, DEBUG_MODE};
-use crate::error::OpenClResult;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_MAP_INPUT_LEN};
 use crate::map::config::{check_local_work_size, DEFAULT_MAP_KEY_LENGTH};
 use crate::map::handle::read::map_get_empty_key::PipeIndices;
 use crate::map::handle::{EntryIndices, MapBlockSizes, MapHandle, MapKeys, MapValues};
@@ -22,7 +22,9 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         let global_work_size = keys.len();
 
         if global_work_size != values.len() {
-            panic!("TODO handle error input keys & values len")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
         }
 
         let local_work_size = check_local_work_size(global_work_size);
@@ -30,7 +32,7 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         let key_input_capacity = DEFAULT_MAP_KEY_LENGTH * global_work_size;
 
         // ...
-        let max_value_len = map_config.get_max_value_len();
+        let max_value_len = map_config.get_max_value_len()?;
         let value_input_capacity = max_value_len * global_work_size;
 
         let enqueue_kernel_output_capacity = global_work_size;
@@ -138,7 +140,7 @@ mod tests_map_add {
 
     #[test]
     fn all_index_available() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY / 2);
         map_src.add(BYTE_512, MAP_CAPACITY / 2);
 
@@ -201,7 +203,7 @@ mod tests_map_add {
 
     #[test]
     fn no_index_available() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -228,7 +230,7 @@ mod tests_map_add {
 
     #[test]
     fn no_capacity_to_store() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -1,5 +1,5 @@
 use crate::config::{ClTypeTrait, DEBUG_MODE};
-use crate::error::OpenClResult;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_MAP_INPUT_LEN};
 use crate::map::config::{check_local_work_size, DEFAULT_MAP_KEY_LENGTH};
 use crate::map::handle::read::map_get_empty_key::PipeIndices;
 use crate::map::handle::tmp::TmpMultiple;
@@ -27,7 +27,9 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         let _ = &self.map_src.get_config_by_value_len(map_value_len)?;
 
         if values.len() != indices.len() {
-            panic!("TODO error values & indices len");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
         }
 
         let global_work_size = values.len();
@@ -134,11 +136,13 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
 
         let global_work_size = keys.len();
         if global_work_size != values.len() {
-            panic!("TODO handle error input lens")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
         }
         let local_work_size = check_local_work_size(global_work_size);
 
-        let max_value_len = map_config.get_max_value_len();
+        let max_value_len = map_config.get_max_value_len()?;
 
         // CMQ_COMPARE_KEY_IN_BLOCKS
         // CMQ_CONFIRM_MAP_APPEND
@@ -258,7 +262,7 @@ mod tests_map_append_for_block {
 
     #[test]
     fn simple_cases() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, MAP_CAPACITY);
 
         map_src.add_map_append_for_block_program_src();
@@ -404,7 +408,7 @@ mod tests_map_append {
     // FIXME error relocation
     #[test]
     fn with_space_to_append() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 16);
         map_src.add(KB, 16);
@@ -541,7 +545,7 @@ mod tests_map_append {
 
     #[test]
     fn no_space_to_append() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 16);
         map_src.add(KB, 16);
@@ -117,7 +117,7 @@ mod tests_map_copy_value {
 
     #[test]
     fn simple_cases() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
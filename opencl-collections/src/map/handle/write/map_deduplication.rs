@@ -116,7 +116,7 @@ mod tests_get_tmp_for_map_deduplication {
 
     #[test]
     fn is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -146,7 +146,7 @@ mod tests_map_deduplication_for_block {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -166,7 +166,7 @@ mod tests_map_deduplication_for_block {
 
     #[test]
     fn no_duplicates() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
 
@@ -207,7 +207,7 @@ mod tests_map_deduplication_for_block {
     fn all_keys_are_the_same() {
         let input_len = 32;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, input_len);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -263,7 +263,7 @@ mod tests_map_deduplication_for_block {
     fn multiple_duplicate_keys() {
         let input_len = 32;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
 
@@ -326,7 +326,7 @@ mod tests_map_deduplication {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -354,7 +354,7 @@ mod tests_map_deduplication {
 
     #[test]
     fn all_keys_are_the_same() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -461,7 +461,7 @@ mod tests_map_deduplication {
 
     #[test]
     fn multiple_duplicate_keys() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
 
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
@@ -121,7 +121,7 @@ mod tests_get_tmp_for_deep_deduplication {
 
     #[test]
     fn is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -152,7 +152,7 @@ mod tests_map_deep_deduplication_for_block {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -180,7 +180,7 @@ mod tests_map_deep_deduplication_for_block {
 
     #[test]
     fn no_duplicates() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -237,7 +237,7 @@ mod tests_map_deep_deduplication_for_block {
     // FIXME rename test
     #[test]
     fn all_keys_are_the_same() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -307,7 +307,7 @@ mod tests_map_deep_deduplication_for_block {
 
     #[test]
     fn all_keys_are_the_same_2() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -377,7 +377,7 @@ mod tests_map_deep_deduplication_for_block {
 
     #[test]
     fn all_keys_are_the_same_3() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -463,7 +463,7 @@ mod tests_map_deep_deduplication_for_block {
     // FIXME rename tests
     #[test]
     fn multiple_duplicate_keys() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 64);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -589,7 +589,7 @@ mod tests_map_deep_deduplication_for_block {
 
     #[test]
     fn multiple_duplicate_keys_2() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 64);
@@ -665,7 +665,7 @@ mod tests_map_deep_deduplication {
 
     #[test]
     fn is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -695,7 +695,7 @@ mod tests_map_deep_deduplication {
 
     #[test]
     fn all_keys_are_the_same() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -759,7 +759,7 @@ mod tests_map_deep_deduplication {
 
     #[test]
     fn all_keys_are_the_same_3() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -851,7 +851,7 @@ mod tests_map_deep_deduplication {
 
     #[test]
     fn multiple_duplicate_keys() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 64);
         map_src.add(KB, 16);
@@ -954,7 +954,7 @@ mod tests_map_deep_deduplication {
 
     #[test]
     fn multiple_duplicate_keys_2() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 64);
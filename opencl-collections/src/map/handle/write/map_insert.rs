@@ -1,5 +1,5 @@
 use crate::config::{ClTypeTrait, DEBUG_MODE};
-use crate::error::OpenClResult;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_MAP_INPUT_LEN};
 use crate::map::config::{check_local_work_size, check_max_find_work_size, DEFAULT_MAP_KEY_LENGTH};
 use crate::map::handle::read::map_get_empty_key::PipeIndices;
 use crate::map::handle::tmp::TmpMultiple;
@@ -17,17 +17,19 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         values: &MapValues<T>,
         pipes: &[PipeIndices],
     ) -> OpenClResult<(EntryIndices, MapBlockSizes)> {
-        check_max_find_work_size(keys.len());
+        check_max_find_work_size(keys.len())?;
 
         let map_config = &self.map_src;
 
         let global_work_size = keys.len();
         if global_work_size != values.len() {
-            panic!("TODO handle error input lens")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
         }
         let local_work_size = check_local_work_size(global_work_size);
 
-        let max_value_len = map_config.get_max_value_len();
+        let max_value_len = map_config.get_max_value_len()?;
 
         // CMQ_COMPARE_KEY_IN_BLOCKS
         // CMQ_CONFIRM_MAP_INSERT
@@ -149,7 +151,7 @@ mod tests_map_insert {
     fn key_does_not_exist() {
         let input_len = 8;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -213,7 +215,7 @@ mod tests_map_insert {
     fn key_does_not_exist_and_save_in_multiple_blocks() {
         let input_len = 16;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 8);
         map_src.add(BYTE_512, 8);
 
@@ -293,7 +295,7 @@ mod tests_map_insert {
     fn no_capacity_to_store() {
         let input_len = 8;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -335,7 +337,7 @@ mod tests_map_insert {
     fn key_already_exists() {
         let input_len = 16;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 16);
         map_src.add(KB, 8);
@@ -418,7 +420,7 @@ mod tests_map_insert {
     fn relocate_blocks() {
         let input_len = 16;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -492,7 +494,7 @@ mod tests_map_insert {
     fn failed_reassignment() {
         let input_len = 16;
 
-        let mut map_src = MapSrc::new(2);
+        let mut map_src = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 16);
         map_src.add(KB, 16);
@@ -549,7 +551,7 @@ mod tests_map_insert {
     fn failed_reassignment_2() {
         let input_len = 16;
 
-        let mut map_src = MapSrc::new(2);
+        let mut map_src = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 16);
         map_src.add(KB, 16);
@@ -606,7 +608,7 @@ mod tests_map_insert {
     fn no_indices_available() {
         let input_len = 8;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -654,7 +656,7 @@ mod tests_map_insert {
     fn the_same_simultaneous_key() {
         let input_len = 16;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 16);
 
@@ -700,7 +702,7 @@ mod tests_map_insert {
     fn the_same_simultaneous_key_2() {
         let input_len = 16;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 16);
 
@@ -1,5 +1,5 @@
 use crate::config::ClTypeTrait;
-use crate::error::OpenClResult;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_MAP_INPUT_LEN};
 use crate::map::config::{check_local_work_size, DEFAULT_MAP_KEY_LENGTH};
 use crate::map::handle::{EntryIndices, MapHandle, MapKeys, MapValues};
 use crate::map::kernel::name::{
@@ -18,7 +18,7 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         values: &MapKeys<T>,
     ) -> OpenClResult<()> {
         let config = self.map_src.get_config_by_value_len(map_value_len)?;
-        config.can_hold(keys.len());
+        config.can_hold(keys.len())?;
 
         let global_work_size = keys.len();
 
@@ -74,7 +74,7 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
         values: &MapKeys<T>,
     ) -> OpenClResult<()> {
         let config = self.map_src.get_config_by_value_len(map_value_len)?;
-        config.can_hold(keys.len());
+        config.can_hold(keys.len())?;
 
         let global_work_size = keys.len();
 
@@ -152,12 +152,14 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
     ) -> OpenClResult<()> {
         let config = self.map_src.get_config_by_value_len(map_value_len)?;
 
-        config.can_hold(keys.len());
+        config.can_hold(keys.len())?;
 
         let global_work_size = keys.len();
 
         if global_work_size != indices.len() || global_work_size != values.len() {
-            panic!("TODO message invalid input");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
         }
 
         let local_work_size = check_local_work_size(global_work_size);
@@ -221,10 +223,12 @@ impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
 
         let global_work_size = keys.len();
 
-        config.can_hold(global_work_size);
+        config.can_hold(global_work_size)?;
 
         if global_work_size != values.len() {
-            panic!("TODO message invalid input")
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_MAP_INPUT_LEN,
+            ));
         }
 
         let local_work_size = check_local_work_size(global_work_size);
@@ -313,7 +317,7 @@ mod tests_map_put {
 
     #[test]
     fn with_exact_size() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -330,7 +334,7 @@ mod tests_map_put {
 
     #[test]
     fn with_size_less_than_maximum() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -367,7 +371,7 @@ mod tests_map_put_with_cmq {
 
     #[test]
     fn with_exact_size() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -384,7 +388,7 @@ mod tests_map_put_with_cmq {
 
     #[test]
     fn with_size_less_than_maximum() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -422,7 +426,7 @@ mod tests_map_put_with_index {
 
     #[test]
     fn all_indices() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -444,7 +448,7 @@ mod tests_map_put_with_index {
 
     #[test]
     fn some_indices() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(MAP_VALUE_LEN, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -499,7 +503,7 @@ mod tests_map_put_with_pipe_and_cmq {
 
     #[test]
     fn all_index_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -525,7 +529,7 @@ mod tests_map_put_with_pipe_and_cmq {
 
     #[test]
     fn no_index_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -554,7 +558,7 @@ mod tests_map_put_with_pipe_and_cmq {
 
     #[test]
     fn some_indices_available() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i16> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(CONFIG_SIZE, MAP_CAPACITY);
 
         let system = generate_arc_opencl_block_default(&map_src);
@@ -12,7 +12,7 @@ use opencl::wrapper::system::OpenclCommonOperation;
 impl<T: ClTypeTrait, D: OpenclCommonOperation> MapHandle<T, D> {
     // For now, only searches for a few simultaneous elements
     pub fn map_remove(&self, keys: &MapKeys<T>) -> OpenClResult<(EntryIndices, MapBlockSizes)> {
-        check_max_find_work_size(keys.len());
+        check_max_find_work_size(keys.len())?;
 
         let global_work_size = keys.len();
         let local_work_size = check_local_work_size(global_work_size);
@@ -125,7 +125,7 @@ mod tests_map_remove {
 
     #[test]
     fn key_not_exist() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY);
@@ -166,7 +166,7 @@ mod tests_map_remove {
 
     #[test]
     fn key_exist() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
 
@@ -219,7 +219,7 @@ mod tests_map_remove {
 
     #[test]
     fn multiple_matches() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
         map_src.add(BYTE_256, MAP_CAPACITY);
         map_src.add(BYTE_512, MAP_CAPACITY);
         map_src.add(KB, MAP_CAPACITY);
@@ -278,7 +278,7 @@ mod tests_map_remove {
         let input_len = 2;
         let total_blocks = 16;
 
-        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+        let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
 
         for i in 0..total_blocks {
             map_src.add(BYTE_256 * (i + 1), MAP_CAPACITY);
@@ -337,7 +337,7 @@ mod tests_map_remove {
 //     const MAP_CAPACITY: usize = 1024;
 //
 //     fn map_remove(input_len: usize, total_blocks: usize) {
-//         let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS);
+//         let mut map_src: MapSrc<i32> = MapSrc::new(TOTAL_MAPS).unwrap();
 //
 //         for i in 0..total_blocks {
 //             map_src.add(BYTE_256 * (i + 1), MAP_CAPACITY);
@@ -137,7 +137,7 @@ mod tests_reorder_for_block {
 
     #[test]
     fn map_is_empty() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 16);
@@ -160,7 +160,7 @@ mod tests_reorder_for_block {
     fn map_is_full() {
         let map_capacity = 32;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -188,7 +188,7 @@ mod tests_reorder_for_block {
     fn case_1() {
         let map_capacity = 32;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -216,7 +216,7 @@ mod tests_reorder_for_block {
     fn case_1_large() {
         let map_capacity = 256;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -244,7 +244,7 @@ mod tests_reorder_for_block {
     fn case_2() {
         let map_capacity = 64;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -279,7 +279,7 @@ mod tests_reorder_for_block {
     fn case_2_large() {
         let map_capacity = 256;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -314,7 +314,7 @@ mod tests_reorder_for_block {
     fn case_3() {
         let map_capacity = 32;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -341,7 +341,7 @@ mod tests_reorder_for_block {
     fn case_3_large() {
         let map_capacity = 256;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -368,7 +368,7 @@ mod tests_reorder_for_block {
     fn case_4() {
         let map_capacity = 32;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -395,7 +395,7 @@ mod tests_reorder_for_block {
     fn case_4_large() {
         let map_capacity = 256;
 
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, map_capacity);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, 32);
@@ -422,7 +422,7 @@ mod tests_reorder_for_block {
     fn case_5() {
         let map_capacity = 32;
 
-        let mut map_src = MapSrc::new(2);
+        let mut map_src = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, map_capacity);
@@ -455,7 +455,7 @@ mod tests_reorder_for_block {
     fn case_5_large() {
         let map_capacity = 256;
 
-        let mut map_src = MapSrc::new(2);
+        let mut map_src = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(BYTE_512, 32);
         map_src.add(KB, map_capacity);
@@ -172,7 +172,7 @@ impl<T: ClTypeTrait> MapSrc<T> {
         let map_blocks = self.get_configs();
         let total_blocks = map_blocks.len();
 
-        let max_value_len = self.get_max_value_len();
+        let max_value_len = self.get_max_value_len().unwrap_or_default();
 
         // FIXME improve code iteration and simplicity
 
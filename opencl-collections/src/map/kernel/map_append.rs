@@ -526,6 +526,9 @@ const MAP_PUT_KERNELS: &str = r#"
         const int entry_index
         ) {
         int i = get_global_id(0);
+        if (i == 0) {
+            map_entry_expiry_reset__BLOCK_NAME(map_id, entry_index);
+        }
         if (i < DEFAULT_MAP_KEY_LENGTH) {
             map_keys__BLOCK_NAME[map_id][entry_index][i] = CL_DEFAULT_VALUE;
         }
@@ -599,7 +602,7 @@ impl<T: ClTypeTrait> MapSrc<T> {
         let map_blocks = self.get_configs();
         let total_blocks = map_blocks.len();
 
-        let max_value_len = self.get_max_value_len();
+        let max_value_len = self.get_max_value_len().unwrap_or_default();
 
         let cmq_compare_key_in_blocks = total_blocks;
         let cmq_confirm_map_append = total_blocks + 1;
@@ -766,7 +769,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_append_program_src(8);
@@ -776,7 +779,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -787,7 +790,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 32);
         map_src.add(1024, 16);
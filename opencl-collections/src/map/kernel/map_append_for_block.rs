@@ -116,7 +116,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_append_for_block_program_src();
@@ -126,7 +126,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(2);
+        let mut map_src: MapSrc<i16> = MapSrc::new(2).unwrap();
         map_src.add(BYTE_256, 32);
         map_src.add(KB, 16);
 
@@ -76,6 +76,8 @@ const MAP_DEDUPLICATION_FOR_BLOCK_KERNEL: &str = r#"
                 map_values__BLOCK_NAME[map_id][i][key_index] = CL_DEFAULT_VALUE;
             }
 
+            map_entry_expiry_reset__BLOCK_NAME(map_id, i);
+
         }
 
     }
@@ -215,7 +217,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_deduplication_program_src();
@@ -225,7 +227,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -236,7 +238,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 32);
         map_src.add(1024, 16);
@@ -121,6 +121,8 @@ const CONFIRM_REMOVE_BODY: &str = r#"
                     map_values__BLOCK_NAME[map_id][i][key_index] = CL_DEFAULT_VALUE;
                 }
 
+                map_entry_expiry_reset__BLOCK_NAME(map_id, i);
+
             }
         }
 
@@ -302,7 +304,7 @@ impl<T: ClTypeTrait> MapSrc<T> {
 
         let map_blocks = self.get_configs();
 
-        let confirm_global_work_size = self.get_max_capacity();
+        let confirm_global_work_size = self.get_max_capacity().unwrap_or_default();
         let confirm_local_work_size = check_local_work_size(confirm_global_work_size);
 
         for config in map_blocks.iter() {
@@ -445,7 +447,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_deep_deduplication_program_src();
@@ -455,7 +457,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -466,7 +468,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 32);
         map_src.add(1024, 16);
@@ -0,0 +1,94 @@
+// device-callable functions, the kernels of the user can operate on the maps
+// (CollectionsSrc::enable_device_api or MapSrc::build_device_api)
+//
+//   int map_get__BLOCK_NAME(uint map_id, CL_TYPE* key, CL_TYPE* value_output)
+//       index of the entry, -1 if the key does not exist (value_output is CL_DEFAULT_VALUE)
+//   bool map_contains__BLOCK_NAME(uint map_id, CL_TYPE* key)
+//
+// key is MAP_KEY_LEN items and value MAP_VALUE_LEN items, the search is linear
+// (a single work item), the expired entries are treated as missing
+
+use crate::config::ClTypeTrait;
+use crate::map::config::MapSrc;
+use crate::map::kernel::common_replace;
+
+const MAP_API: &str = r#"
+    int map_get__BLOCK_NAME(uint map_id, CL_TYPE* key, CL_TYPE* value_output) {
+
+        for (int entry_index = 0; entry_index < MAP_CAPACITY; entry_index++) {
+
+            if (is_map_key_empty__BLOCK_NAME(map_id, entry_index)) {
+                continue;
+            }
+
+            if (is_map_key_is_equal_to_input__BLOCK_NAME(map_id, entry_index, 0, key)) {
+
+                for (int value_index = 0; value_index < MAP_VALUE_LEN; value_index++) {
+                    value_output[value_index] = map_values__BLOCK_NAME[map_id][entry_index][value_index];
+                }
+
+                return entry_index;
+            }
+        }
+
+        for (int value_index = 0; value_index < MAP_VALUE_LEN; value_index++) {
+            value_output[value_index] = CL_DEFAULT_VALUE;
+        }
+
+        return -1;
+    }
+
+    bool map_contains__BLOCK_NAME(uint map_id, CL_TYPE* key) {
+
+        for (int entry_index = 0; entry_index < MAP_CAPACITY; entry_index++) {
+
+            if (is_map_key_empty__BLOCK_NAME(map_id, entry_index)) {
+                continue;
+            }
+
+            if (is_map_key_is_equal_to_input__BLOCK_NAME(map_id, entry_index, 0, key)) {
+                return true;
+            }
+        }
+
+        return false;
+    }
+    "#;
+
+impl<T: ClTypeTrait> MapSrc<T> {
+    /// the source must be added after the source of the maps (build)
+    pub fn build_device_api(&self) -> String {
+        let mut device_api = String::new();
+
+        for config in self.get_configs() {
+            device_api.push_str(&common_replace(MAP_API, config));
+        }
+
+        device_api
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{BYTE_256, KB};
+
+    #[test]
+    fn test_a() {
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
+        map_src.add(BYTE_256, 16);
+        map_src.add(KB, 8);
+
+        let device_api = map_src.build_device_api();
+        println!("{device_api}");
+        assert!(device_api
+            .contains("int map_get__256_byte(uint map_id, int* key, int* value_output) {"));
+        assert!(device_api.contains("bool map_contains__1024_byte(uint map_id, int* key) {"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let map_src: MapSrc<i16> = MapSrc::new(8).unwrap();
+        assert!(map_src.build_device_api().is_empty());
+    }
+}
@@ -342,7 +342,7 @@ impl<T: ClTypeTrait> MapSrc<T> {
         let total_queues = 4;
         let total_indices = self.get_maximum_assignable_keys();
 
-        let max_value_len = self.get_max_value_len();
+        let max_value_len = self.get_max_value_len().unwrap_or_default();
 
         // const definitions
         let mut const_def = String::new();
@@ -464,7 +464,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_get_program_src(16);
@@ -474,7 +474,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -485,7 +485,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 8);
         map_src.add(1024, 16);
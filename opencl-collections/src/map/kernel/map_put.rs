@@ -12,6 +12,9 @@ const MAP_PUT_KERNELS: &str = r#"
         global CL_TYPE* value_input
         ) {
         int i = get_global_id(0);
+        if (i == 0) {
+            map_entry_expiry_reset__BLOCK_NAME(map_id, entry_index);
+        }
         if (i < MAP_KEY_LEN) {
             map_keys__BLOCK_NAME[map_id][entry_index][i] = key_input[i + key_input_index];
         }
@@ -25,6 +28,9 @@ const MAP_PUT_KERNELS: &str = r#"
         global CL_TYPE* key_input
         ) {
         int i = get_global_id(0);
+        if (i == 0) {
+            map_entry_expiry_reset__BLOCK_NAME(map_id, entry_index);
+        }
         map_keys__BLOCK_NAME[map_id][entry_index][i] = key_input[i + key_input_index];
     }
 
@@ -54,6 +60,8 @@ const MAP_PUT_KERNELS: &str = r#"
         for (int index = 0; index < MAP_VALUE_LEN; index++) {
             map_values__BLOCK_NAME[map_id][i][index] = values_input[index + value_input_index];
         }
+
+        map_entry_expiry_reset__BLOCK_NAME(map_id, i);
     }
 
     kernel void map_put_with_cmq__BLOCK_NAME(
@@ -116,6 +124,8 @@ const MAP_PUT_KERNELS: &str = r#"
         for (int index = 0; index < MAP_VALUE_LEN; index++) {
             map_values__BLOCK_NAME[map_id][entry_index][index] = values_input[index + value_input_index];
         }
+
+        map_entry_expiry_reset__BLOCK_NAME(map_id, entry_index);
     }
 
     kernel void map_put_with_pipe_and_cmq__BLOCK_NAME(
@@ -13,11 +13,14 @@ const MAP_READ_KERNELS: &str = r#"
         ) {
         int i = get_global_id(0);
 
+        // expired entries are read as empty
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, entry_index);
+
         if (i < MAP_KEY_LEN) {
-            key_output[i + key_output_index] = map_keys__BLOCK_NAME[map_id][entry_index][i];
+            key_output[i + key_output_index] = expired ? CL_DEFAULT_VALUE : map_keys__BLOCK_NAME[map_id][entry_index][i];
         }
 
-        value_output[i + value_output_index] = map_values__BLOCK_NAME[map_id][entry_index][i];
+        value_output[i + value_output_index] = expired ? CL_DEFAULT_VALUE : map_values__BLOCK_NAME[map_id][entry_index][i];
     }
 
     kernel void map_read_one_key__BLOCK_NAME(
@@ -27,7 +30,8 @@ const MAP_READ_KERNELS: &str = r#"
         global CL_TYPE* key_output
         ) {
         int i = get_global_id(0);
-        key_output[i + key_output_index] = map_keys__BLOCK_NAME[map_id][entry_index][i];
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, entry_index);
+        key_output[i + key_output_index] = expired ? CL_DEFAULT_VALUE : map_keys__BLOCK_NAME[map_id][entry_index][i];
     }
 
     kernel void map_read_one_value__BLOCK_NAME(
@@ -37,7 +41,8 @@ const MAP_READ_KERNELS: &str = r#"
         global CL_TYPE* value_output
         ) {
         int i = get_global_id(0);
-        value_output[i + value_output_index] = map_values__BLOCK_NAME[map_id][entry_index][i];
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, entry_index);
+        value_output[i + value_output_index] = expired ? CL_DEFAULT_VALUE : map_values__BLOCK_NAME[map_id][entry_index][i];
     }
 
     kernel void map_read_keys__BLOCK_NAME(
@@ -47,9 +52,10 @@ const MAP_READ_KERNELS: &str = r#"
 
         int i = get_global_id(0);
         int key_output_index = i * MAP_KEY_LEN;
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, i);
 
         for (int index = 0; index < MAP_KEY_LEN; index++) {
-            keys_output[index + key_output_index] = map_keys__BLOCK_NAME[map_id][i][index];
+            keys_output[index + key_output_index] = expired ? CL_DEFAULT_VALUE : map_keys__BLOCK_NAME[map_id][i][index];
         }
     }
 
@@ -62,13 +68,14 @@ const MAP_READ_KERNELS: &str = r#"
         int i = get_global_id(0);
         int key_output_index = i * MAP_KEY_LEN;
         int value_output_index = i * MAP_VALUE_LEN;
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, i);
 
         for (int index = 0; index < MAP_KEY_LEN; index++) {
-            keys_output[index + key_output_index] = map_keys__BLOCK_NAME[map_id][i][index];
+            keys_output[index + key_output_index] = expired ? CL_DEFAULT_VALUE : map_keys__BLOCK_NAME[map_id][i][index];
         }
 
         for (int index = 0; index < MAP_VALUE_LEN; index++) {
-            values_output[index + value_output_index] = map_values__BLOCK_NAME[map_id][i][index];
+            values_output[index + value_output_index] = expired ? CL_DEFAULT_VALUE : map_values__BLOCK_NAME[map_id][i][index];
         }
     }
 
@@ -126,13 +133,14 @@ const MAP_READ_KERNELS: &str = r#"
         int value_output_index = i * MAP_VALUE_LEN;
 
         int entry_index = indices_input[i];
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, entry_index);
 
         for (int index = 0; index < MAP_KEY_LEN; index++) {
-            keys_output[index + key_output_index] = map_keys__BLOCK_NAME[map_id][entry_index][index];
+            keys_output[index + key_output_index] = expired ? CL_DEFAULT_VALUE : map_keys__BLOCK_NAME[map_id][entry_index][index];
         }
 
         for (int index = 0; index < MAP_VALUE_LEN; index++) {
-            values_output[index + value_output_index] = map_values__BLOCK_NAME[map_id][entry_index][index];
+            values_output[index + value_output_index] = expired ? CL_DEFAULT_VALUE : map_values__BLOCK_NAME[map_id][entry_index][index];
         }
     }
 
@@ -222,8 +230,10 @@ const KERNEL_GET_MATRIX_FOR_BLOCK: &str = r#"
 
         // BLOCK_NAME (block output index) = BLOCK_OUTPUT_INDEX
 
+        bool expired = is_map_entry_expired__BLOCK_NAME(map_id, i);
+
         for (int index = 0; index < MAP_KEY_LEN; index++) {
-            keys_output[index + key_output_index + BLOCK_OUTPUT_INDEX] = map_keys__BLOCK_NAME[map_id][i][index];
+            keys_output[index + key_output_index + BLOCK_OUTPUT_INDEX] = expired ? CL_DEFAULT_VALUE : map_keys__BLOCK_NAME[map_id][i][index];
         }
     }
     "#;
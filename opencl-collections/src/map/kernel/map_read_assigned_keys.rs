@@ -47,7 +47,8 @@ const MAP_READ_ENTRIES: &str = r#"
         int key_output_index = i * DEFAULT_MAP_KEY_LENGTH;
         int value_output_index = i * MAP_VALUE_LEN;
 
-        int key_size = get_map_key_size__BLOCK_NAME(
+        // expired entries are read as empty
+        int key_size = is_map_entry_expired__BLOCK_NAME(map_id, i) ? 0 : get_map_key_size__BLOCK_NAME(
             map_id,
             i // entry_index
         );
@@ -153,7 +154,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_read_assigned_keys_program_src();
@@ -163,7 +164,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -174,7 +175,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 32);
         map_src.add(1024, 16);
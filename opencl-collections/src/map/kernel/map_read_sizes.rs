@@ -38,7 +38,8 @@ const MAP_READ_SIZES_FOR_BLOCK_KERNEL: &str = r#"
 
         int i = get_global_id(0);
 
-        int last_index = get_map_value_size__BLOCK_NAME(
+        // expired entries are read as empty
+        int last_index = is_map_entry_expired__BLOCK_NAME(map_id, i) ? 0 : get_map_value_size__BLOCK_NAME(
             map_id,
             i // entry_index
         );
@@ -52,7 +53,7 @@ const MAP_READ_SIZES_FOR_BLOCK_KERNEL: &str = r#"
 
         int i = get_global_id(0);
 
-        int last_index = get_map_value_size__BLOCK_NAME(
+        int last_index = is_map_entry_expired__BLOCK_NAME(map_id, i) ? 0 : get_map_value_size__BLOCK_NAME(
             map_id,
             i // entry_index
         );
@@ -116,7 +117,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_read_sizes_program_src();
@@ -126,7 +127,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -137,7 +138,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 32);
         map_src.add(1024, 16);
@@ -107,6 +107,9 @@ const CONFIRM_SEARCH_KERNEL: &str = r#"
         ) {
         int i = get_global_id(0);
 
+        if (i == 0) {
+            map_entry_expiry_reset__BLOCK_NAME(map_id, entry_index);
+        }
         if (i < DEFAULT_MAP_KEY_LENGTH) {
             map_keys__BLOCK_NAME[map_id][entry_index][i] = CL_DEFAULT_VALUE;
         }
@@ -393,7 +396,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_remove_program_src(16);
@@ -403,7 +406,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -414,7 +417,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 8);
         map_src.add(1024, 16);
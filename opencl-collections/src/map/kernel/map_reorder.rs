@@ -127,6 +127,8 @@ const EXECUTE_MAP_REORDER_FOR_BLOCK_KERNEL: &str = r#"
                 map_values__BLOCK_NAME[map_id][from_entry_index][index] = CL_DEFAULT_VALUE;
             }
 
+            map_entry_expiry_move__BLOCK_NAME(map_id, from_entry_index, to_entry_index);
+
         }
     }
 
@@ -229,7 +231,7 @@ mod tests {
 
     #[test]
     fn test_a() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(KB, 16);
 
         let program_source = map_src.generate_map_reorder_program_src();
@@ -239,7 +241,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 16);
 
@@ -250,7 +252,7 @@ mod tests {
 
     #[test]
     fn test_c() {
-        let mut map_src: MapSrc<i32> = MapSrc::new(2);
+        let mut map_src: MapSrc<i32> = MapSrc::new(2).unwrap();
         map_src.add(256, 8);
         map_src.add(512, 32);
         map_src.add(1024, 16);
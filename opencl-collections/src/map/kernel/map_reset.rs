@@ -53,11 +53,7 @@ const MAP_BLOCK_RESET_ENQUEUE_KERNEL: &str = r#"
      "#;
 
 const RESET_MAP_BLOCK_KERNEL: &str = r#"
-    kernel void map_block_reset__BLOCK_NAME(
-        const uint map_id
-        ) {
-        int entry_index = get_global_id(0);
-
+    void map_entry_reset__BLOCK_NAME(uint map_id, int entry_index) {
         for (int index = 0; index < MAP_KEY_LEN; index++) {
             map_keys__BLOCK_NAME[map_id][entry_index][index] = CL_DEFAULT_VALUE;
         }
@@ -65,6 +61,16 @@ const RESET_MAP_BLOCK_KERNEL: &str = r#"
         for (int index = 0; index < MAP_VALUE_LEN; index++) {
             map_values__BLOCK_NAME[map_id][entry_index][index] = CL_DEFAULT_VALUE;
         }
+
+        map_entry_expiry_reset__BLOCK_NAME(map_id, entry_index);
+    }
+
+    kernel void map_block_reset__BLOCK_NAME(
+        const uint map_id
+        ) {
+        int entry_index = get_global_id(0);
+
+        map_entry_reset__BLOCK_NAME(map_id, entry_index);
     }
     "#;
 
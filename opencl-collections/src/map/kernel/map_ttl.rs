@@ -0,0 +1,139 @@
+use crate::config::ClTypeTrait;
+use crate::map::config::MapSrc;
+use crate::map::kernel::common_replace;
+
+const MAP_CLOCK_KERNEL: &str = r#"
+    kernel void map_set_clock(
+        const uint map_id,
+        const long now
+        ) {
+        map_clock[map_id] = now;
+    }
+    "#;
+
+const MAP_TTL_KERNELS: &str = r#"
+    kernel void map_put_with_ttl__BLOCK_NAME(
+        const uint map_id,
+        global CL_TYPE* keys_input,
+        global CL_TYPE* values_input,
+        global long* expiry_input
+        ) {
+        int i = get_global_id(0);
+        int key_input_index = i * MAP_KEY_LEN;
+        int value_input_index = i * MAP_VALUE_LEN;
+
+        for (int index = 0; index < MAP_KEY_LEN; index++) {
+            map_keys__BLOCK_NAME[map_id][i][index] = keys_input[index + key_input_index];
+        }
+
+        for (int index = 0; index < MAP_VALUE_LEN; index++) {
+            map_values__BLOCK_NAME[map_id][i][index] = values_input[index + value_input_index];
+        }
+
+        map_expiry__BLOCK_NAME[map_id][i] = expiry_input[i];
+    }
+
+    kernel void map_set_expiry_with_index__BLOCK_NAME(
+        const uint map_id,
+        global int* indices_input,
+        global long* expiry_input
+        ) {
+        int i = get_global_id(0);
+        int entry_index = indices_input[i];
+
+        if (entry_index >= 0 && entry_index < MAP_CAPACITY) {
+            map_expiry__BLOCK_NAME[map_id][entry_index] = expiry_input[i];
+        }
+    }
+
+    kernel void map_sweep_expired__BLOCK_NAME(
+        const uint map_id,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        if (is_map_entry_expired__BLOCK_NAME(map_id, i)) {
+            map_entry_reset__BLOCK_NAME(map_id, i);
+            indices_output[i] = i;
+        } else {
+            indices_output[i] = -1;
+        }
+    }
+
+    kernel void map_read_expiry__BLOCK_NAME(
+        const uint map_id,
+        global long* expiry_output
+        ) {
+        int i = get_global_id(0);
+        expiry_output[i] = map_expiry__BLOCK_NAME[map_id][i];
+    }
+    "#;
+
+impl<T: ClTypeTrait> MapSrc<T> {
+    pub fn generate_map_ttl_program_src(&self) -> String {
+        if !self.has_ttl() {
+            return String::new();
+        }
+
+        let mut map_ttl_kernels = String::new();
+
+        for config in self.get_configs().iter().filter(|x| x.ttl) {
+            let template = common_replace(MAP_TTL_KERNELS, config);
+            map_ttl_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// - MAP_TTL START ///
+
+    /// constants
+    // ...
+
+    /// globals
+    // ...
+
+    /// kernels
+    {MAP_CLOCK_KERNEL}
+    {map_ttl_kernels}
+
+    /// - MAP_TTL END ///
+        "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut map_src: MapSrc<i32> = MapSrc::default();
+        map_src.add_with_ttl(256, 16);
+
+        let program_source = map_src.generate_map_ttl_program_src();
+        println!("{program_source}");
+        assert!(program_source.contains("map_sweep_expired__256_byte"));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut map_src: MapSrc<i32> = MapSrc::default();
+        map_src.add(256, 8);
+        map_src.add_with_ttl(512, 16);
+
+        let program_source = map_src.generate_map_ttl_program_src();
+        println!("{program_source}");
+        assert!(!program_source.contains("map_put_with_ttl__256_byte"));
+        assert!(program_source.contains("map_put_with_ttl__512_byte"));
+    }
+
+    #[test]
+    fn test_without_ttl() {
+        let mut map_src: MapSrc<i32> = MapSrc::default();
+        map_src.add(256, 8);
+
+        let program_source = map_src.generate_map_ttl_program_src();
+        assert!(program_source.is_empty());
+    }
+}
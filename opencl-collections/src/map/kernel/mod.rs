@@ -12,6 +12,7 @@ pub mod map_append_for_block;
 pub mod map_copy;
 pub mod map_deduplication;
 pub mod map_deep_deduplication;
+pub mod map_device_api;
 pub mod map_get;
 pub mod map_get_empty_key;
 pub mod map_get_index;
@@ -25,6 +26,7 @@ pub mod map_remove;
 pub mod map_remove_v2;
 pub mod map_reorder;
 pub mod map_reset;
+pub mod map_ttl;
 
 #[allow(dead_code)]
 pub enum RemoveVersion {
@@ -52,6 +54,42 @@ const GLOBAL_ARRAY: &str = r#"
     __global CL_TYPE map_values__BLOCK_NAME[TOTAL_MAPS][MAP_CAPACITY][MAP_VALUE_LEN];
     "#;
 
+const GLOBAL_TTL_ARRAY: &str = r#"
+    __global long map_expiry__BLOCK_NAME[TOTAL_MAPS][MAP_CAPACITY];
+    "#;
+
+const GLOBAL_CLOCK: &str = r#"
+    __global long map_clock[TOTAL_MAPS];
+    "#;
+
+// The expiry timestamp is 0 when the entry does not expire,
+// the time is supplied by the host (map_clock).
+const FN_TTL_UTILS: &str = r#"
+    bool is_map_entry_expired__BLOCK_NAME(uint map_id, int entry_index) {
+        long expiry = map_expiry__BLOCK_NAME[map_id][entry_index];
+        return expiry > 0 && expiry <= map_clock[map_id];
+    }
+
+    void map_entry_expiry_reset__BLOCK_NAME(uint map_id, int entry_index) {
+        map_expiry__BLOCK_NAME[map_id][entry_index] = 0;
+    }
+
+    void map_entry_expiry_move__BLOCK_NAME(uint map_id, int from_entry_index, int to_entry_index) {
+        map_expiry__BLOCK_NAME[map_id][to_entry_index] = map_expiry__BLOCK_NAME[map_id][from_entry_index];
+        map_expiry__BLOCK_NAME[map_id][from_entry_index] = 0;
+    }
+    "#;
+
+const FN_WITHOUT_TTL_UTILS: &str = r#"
+    bool is_map_entry_expired__BLOCK_NAME(uint map_id, int entry_index) {
+        return false;
+    }
+
+    void map_entry_expiry_reset__BLOCK_NAME(uint map_id, int entry_index) {}
+
+    void map_entry_expiry_move__BLOCK_NAME(uint map_id, int from_entry_index, int to_entry_index) {}
+    "#;
+
 const FN_UTILS: &str = r#"
     bool is_map_key_is_equal_to_input__BLOCK_NAME(
         uint map_id,
@@ -59,6 +97,11 @@ const FN_UTILS: &str = r#"
         int key_input_index,
         CL_TYPE* key_input
         ) {
+        // expired entries are treated as missing
+        if (is_map_entry_expired__BLOCK_NAME(map_id, entry_index)) {
+            return false;
+        }
+
         for (int key_index = 0; key_index < MAP_KEY_LEN; key_index++) {
             if (map_keys__BLOCK_NAME[map_id][entry_index][key_index] != key_input[key_index + key_input_index]) {
                 return false;
@@ -200,10 +243,27 @@ impl<T: ClTypeTrait> MapSrc<T> {
 
         let total_maps = self.get_total_maps().to_string();
 
+        if self.has_ttl() {
+            global_arrays.push_str(&GLOBAL_CLOCK.replace("TOTAL_MAPS", &total_maps));
+        }
+
         for config in blocks {
             let template = common_replace(GLOBAL_ARRAY, config).replace("TOTAL_MAPS", &total_maps);
             global_arrays.push_str(&template);
 
+            let fn_ttl_utils = if config.ttl {
+                let template =
+                    common_replace(GLOBAL_TTL_ARRAY, config).replace("TOTAL_MAPS", &total_maps);
+                global_arrays.push_str(&template);
+
+                FN_TTL_UTILS
+            } else {
+                FN_WITHOUT_TTL_UTILS
+            };
+
+            let template = common_replace(fn_ttl_utils, config);
+            fn_utils.push_str(&template);
+
             let template = common_replace(FN_UTILS, config);
             fn_utils.push_str(&template);
         }
@@ -220,6 +280,8 @@ impl<T: ClTypeTrait> MapSrc<T> {
 
         let map_reset_program_src = self.generate_map_reset_program_src();
 
+        let map_ttl_program_src = self.generate_map_ttl_program_src();
+
         // The code generation using strings got a little out of hand,
         // compilation becomes very slow if all kernels written in strings are used.
         let mut optional_src = String::new();
@@ -266,6 +328,7 @@ impl<T: ClTypeTrait> MapSrc<T> {
     {map_read_assigned_keys_program_src}
 
     {map_reset_program_src}
+    {map_ttl_program_src}
     {map_get_summary_program_src}
 
     /// optional src
@@ -294,7 +357,7 @@ mod tests {
 
     #[test]
     fn test_b() {
-        let mut map_src: MapSrc<i16> = MapSrc::new(8);
+        let mut map_src: MapSrc<i16> = MapSrc::new(8).unwrap();
         map_src.add(BYTE_256, 16);
         map_src.add(BYTE_512, 8);
         map_src.add(KB, 32);
@@ -317,9 +380,23 @@ mod tests {
         assert!(!program_source.is_empty());
     }
 
+    #[test]
+    fn test_with_ttl() {
+        let mut map_src: MapSrc<i32> = MapSrc::default();
+        map_src.add(BYTE_256, 8);
+        map_src.add_with_ttl(BYTE_512, 8);
+
+        let program_source = map_src.build();
+        println!("{program_source}");
+        assert!(program_source.contains("__global long map_clock[1];"));
+        assert!(program_source.contains("__global long map_expiry__512_byte[1][8];"));
+        assert!(!program_source.contains("map_expiry__256_byte"));
+        assert!(program_source.contains("map_sweep_expired__512_byte"));
+    }
+
     #[test]
     fn test_config_is_empty() {
-        let map_src: MapSrc<i16> = MapSrc::new(8);
+        let map_src: MapSrc<i16> = MapSrc::new(8).unwrap();
 
         let program_source = map_src.build();
         println!("{program_source}");
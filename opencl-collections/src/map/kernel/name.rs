@@ -92,6 +92,13 @@ pub const GET_TMP_FOR_MAP_SUMMARY: &str = "get_tmp_for_map_get_summary";
 pub const MAP_RESET: &str = "map_reset";
 pub const RESET_ALL_MAPS: &str = "reset_all_maps";
 
+// src/kernel/map_ttl.rs
+pub const MAP_SET_CLOCK: &str = "map_set_clock";
+pub const MAP_PUT_WITH_TTL: &str = "map_put_with_ttl__BLOCK_NAME";
+pub const MAP_SET_EXPIRY_WITH_INDEX: &str = "map_set_expiry_with_index__BLOCK_NAME";
+pub const MAP_SWEEP_EXPIRED: &str = "map_sweep_expired__BLOCK_NAME";
+pub const MAP_READ_EXPIRY: &str = "map_read_expiry__BLOCK_NAME";
+
 // util kernels
 
 // src/kernel/mod.rs - KERNEL_UTILS
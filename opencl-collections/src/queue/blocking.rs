@@ -0,0 +1,397 @@
+//! # Blocking queue
+//!
+//! Host side wrapper over a device queue, the push and pop operations are
+//! retried (polling the device queue) until they complete or the timeout expires.
+//!
+//! The wait between two attempts starts at `initial_backoff` and is doubled
+//! up to `max_backoff`.
+
+use crate::error::OpenClResult;
+use crate::queue::handle::circular::CircularQueueHandle;
+use crate::queue::handle::linear::LinearQueueHandle;
+use crate::queue::handle::mpmc::MpmcQueueHandle;
+use crate::queue::handle::priority::PriorityQueueHandle;
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::OpenclCommonOperation;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(1);
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_millis(64);
+
+// value returned by the dequeue kernels when the queue is empty
+const QUEUE_EMPTY_VALUE: cl_int = -1;
+
+/// single attempt of the queue operations
+pub trait DeviceQueue {
+    type Item: Clone;
+
+    /// the items that could not be enqueued are returned
+    fn try_push(&self, items: &[Self::Item]) -> OpenClResult<Vec<Self::Item>>;
+
+    /// up to take values, the queue could return less
+    fn try_pop(&self, take: usize) -> OpenClResult<Vec<cl_int>>;
+}
+
+fn get_rejected<I: Clone>(items: &[I], indices: &[cl_int]) -> Vec<I> {
+    items
+        .iter()
+        .zip(indices)
+        .filter(|(_, &index)| index < 0)
+        .map(|(x, _)| x.clone())
+        .collect()
+}
+
+fn get_values(output: Vec<cl_int>) -> Vec<cl_int> {
+    output
+        .into_iter()
+        .filter(|&x| x != QUEUE_EMPTY_VALUE)
+        .collect()
+}
+
+impl<T: OpenclCommonOperation> DeviceQueue for LinearQueueHandle<T> {
+    type Item = cl_int;
+
+    fn try_push(&self, items: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        let indices = self.enqueue(items)?;
+        Ok(get_rejected(items, &indices))
+    }
+
+    fn try_pop(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        Ok(get_values(self.dequeue(take)?))
+    }
+}
+
+impl<T: OpenclCommonOperation> DeviceQueue for CircularQueueHandle<T> {
+    type Item = cl_int;
+
+    fn try_push(&self, items: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        let indices = self.enqueue(items)?;
+        Ok(get_rejected(items, &indices))
+    }
+
+    fn try_pop(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        Ok(get_values(self.dequeue(take)?))
+    }
+}
+
+impl<T: OpenclCommonOperation> DeviceQueue for MpmcQueueHandle<T> {
+    type Item = cl_int;
+
+    fn try_push(&self, items: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        let indices = self.enqueue(items)?;
+        Ok(get_rejected(items, &indices))
+    }
+
+    fn try_pop(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        Ok(get_values(self.dequeue(take)?))
+    }
+}
+
+/// items (value, priority)
+impl<T: OpenclCommonOperation> DeviceQueue for PriorityQueueHandle<T> {
+    type Item = (cl_int, cl_int);
+
+    fn try_push(&self, items: &[(cl_int, cl_int)]) -> OpenClResult<Vec<(cl_int, cl_int)>> {
+        let (values, priorities): (Vec<cl_int>, Vec<cl_int>) = items.iter().copied().unzip();
+
+        let indices = self.enqueue(&values, &priorities)?;
+        Ok(get_rejected(items, &indices))
+    }
+
+    fn try_pop(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        Ok(get_values(self.dequeue(take)?))
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockingQueue<Q: DeviceQueue> {
+    queue: Q,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl<Q: DeviceQueue> BlockingQueue<Q> {
+    pub fn new(queue: Q) -> Self {
+        Self::with_backoff(queue, DEFAULT_INITIAL_BACKOFF, DEFAULT_MAX_BACKOFF)
+    }
+
+    pub fn with_backoff(queue: Q, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            queue,
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+        }
+    }
+
+    pub fn get_queue(&self) -> &Q {
+        &self.queue
+    }
+
+    pub fn into_queue(self) -> Q {
+        self.queue
+    }
+
+    /// wait before the next attempt, false if the timeout has expired
+    fn wait(&self, deadline: Instant, backoff: &mut Duration) -> bool {
+        let now = Instant::now();
+
+        if now >= deadline {
+            return false;
+        }
+
+        thread::sleep((*backoff).min(deadline - now));
+        *backoff = (*backoff * 2).min(self.max_backoff);
+
+        true
+    }
+
+    /// the items that could not be enqueued before the timeout are returned
+    pub fn push_timeout(&self, items: &[Q::Item], timeout: Duration) -> OpenClResult<Vec<Q::Item>> {
+        let deadline = Instant::now() + timeout;
+        let mut backoff = self.initial_backoff;
+
+        let mut pending = self.queue.try_push(items)?;
+
+        while !pending.is_empty() && self.wait(deadline, &mut backoff) {
+            pending = self.queue.try_push(&pending)?;
+        }
+
+        Ok(pending)
+    }
+
+    /// up to take values, less if the timeout expires before
+    pub fn pop_timeout(&self, take: usize, timeout: Duration) -> OpenClResult<Vec<cl_int>> {
+        let deadline = Instant::now() + timeout;
+        let mut backoff = self.initial_backoff;
+
+        let mut values = self.queue.try_pop(take)?;
+
+        while values.len() < take && self.wait(deadline, &mut backoff) {
+            let mut next = self.queue.try_pop(take - values.len())?;
+            values.append(&mut next);
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests_blocking_queue_model {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// bounded host queue, the consumer pops `release` items on every push attempt
+    struct ModelQueue {
+        items: RefCell<VecDeque<cl_int>>,
+        capacity: usize,
+        push_attempts: RefCell<usize>,
+        release: usize,
+    }
+
+    impl ModelQueue {
+        fn new(capacity: usize, release: usize) -> Self {
+            Self {
+                items: RefCell::new(VecDeque::new()),
+                capacity,
+                push_attempts: RefCell::new(0),
+                release,
+            }
+        }
+    }
+
+    impl DeviceQueue for ModelQueue {
+        type Item = cl_int;
+
+        fn try_push(&self, items: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+            *self.push_attempts.borrow_mut() += 1;
+
+            let mut queue = self.items.borrow_mut();
+
+            if *self.push_attempts.borrow() > 1 {
+                for _ in 0..self.release {
+                    queue.pop_front();
+                }
+            }
+
+            let mut rejected = Vec::new();
+
+            for &x in items {
+                if queue.len() < self.capacity {
+                    queue.push_back(x);
+                } else {
+                    rejected.push(x);
+                }
+            }
+
+            Ok(rejected)
+        }
+
+        fn try_pop(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+            let mut queue = self.items.borrow_mut();
+            let len = take.min(queue.len());
+            Ok(queue.drain(0..len).collect())
+        }
+    }
+
+    #[test]
+    fn push_without_waiting() {
+        let queue = BlockingQueue::new(ModelQueue::new(8, 0));
+
+        let pending = queue
+            .push_timeout(&[1, 2, 3], Duration::from_millis(10))
+            .unwrap();
+
+        assert!(pending.is_empty());
+        assert_eq!(*queue.get_queue().push_attempts.borrow(), 1);
+    }
+
+    #[test]
+    fn push_waits_for_space() {
+        let queue = BlockingQueue::new(ModelQueue::new(4, 2));
+
+        let items: Vec<cl_int> = (0..8).collect();
+
+        let pending = queue.push_timeout(&items, Duration::from_secs(5)).unwrap();
+
+        assert!(pending.is_empty());
+        assert_eq!(*queue.get_queue().push_attempts.borrow(), 3);
+    }
+
+    #[test]
+    fn push_timeout_expires() {
+        let queue = BlockingQueue::with_backoff(
+            ModelQueue::new(4, 0),
+            Duration::from_millis(1),
+            Duration::from_millis(4),
+        );
+
+        let items: Vec<cl_int> = (0..6).collect();
+
+        let start = Instant::now();
+        let pending = queue
+            .push_timeout(&items, Duration::from_millis(30))
+            .unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(pending, vec![4, 5]);
+        assert!(*queue.get_queue().push_attempts.borrow() > 2);
+    }
+
+    #[test]
+    fn pop_timeout() {
+        let queue = BlockingQueue::new(ModelQueue::new(8, 0));
+
+        queue
+            .push_timeout(&[1, 2, 3], Duration::from_millis(10))
+            .unwrap();
+
+        let values = queue.pop_timeout(2, Duration::from_millis(10)).unwrap();
+        assert_eq!(values, vec![1, 2]);
+
+        let values = queue.pop_timeout(4, Duration::from_millis(20)).unwrap();
+        assert_eq!(values, vec![3]);
+
+        let values = queue.pop_timeout(1, Duration::ZERO).unwrap();
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn rejected_items() {
+        assert_eq!(get_rejected(&[1, 2, 3, 4], &[0, -1, 2, -1]), vec![2, 4]);
+        assert_eq!(get_values(vec![5, -1, 6, -1]), vec![5, 6]);
+    }
+}
+
+#[cfg(test)]
+mod tests_blocking_queue {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn circular_queue() {
+        let queue_capacity = 8;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_cq(queue_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_cq_config_by_id(0).unwrap();
+        let cq = CircularQueueHandle::new(config, system);
+        cq.reset().unwrap();
+
+        let queue = BlockingQueue::new(cq);
+
+        let items: Vec<cl_int> = (1..=12).collect();
+
+        let pending = queue
+            .push_timeout(&items, Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(pending.len(), 4);
+
+        let mut values = queue.pop_timeout(8, Duration::from_millis(10)).unwrap();
+        values.sort();
+        assert_eq!(values.len(), 8);
+
+        let pending = queue
+            .push_timeout(&pending, Duration::from_millis(10))
+            .unwrap();
+        assert!(pending.is_empty());
+
+        let values = queue.pop_timeout(8, Duration::from_millis(10)).unwrap();
+        assert_eq!(values.len(), 4);
+    }
+
+    #[test]
+    fn priority_queue() {
+        let queue_capacity = 8;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(queue_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+        pq.reset().unwrap();
+
+        let queue = BlockingQueue::new(pq);
+
+        let items: Vec<(cl_int, cl_int)> = (1..=10).map(|x| (x, x)).collect();
+
+        let pending = queue
+            .push_timeout(&items, Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(pending.len(), 2);
+
+        let values = queue.pop_timeout(2, Duration::from_millis(10)).unwrap();
+        assert_eq!(values.len(), 2);
+
+        // the released indices are reused
+        let pending = queue
+            .push_timeout(&pending, Duration::from_millis(10))
+            .unwrap();
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn linear_queue_is_empty() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_lq_config_by_id(0).unwrap();
+        let lq = LinearQueueHandle::new(config, system);
+        lq.reset().unwrap();
+
+        let queue = BlockingQueue::new(lq);
+
+        let values = queue.pop_timeout(4, Duration::from_millis(10)).unwrap();
+        assert!(values.is_empty());
+    }
+}
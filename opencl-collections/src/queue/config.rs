@@ -1,11 +1,18 @@
 // ...
 
-use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_QUEUE_ID};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_QUEUE_CAPACITY,
+    CL_COLLECTION_INVALID_QUEUE_ID, CL_COLLECTION_INVALID_QUEUE_OVERFLOW_POLICY,
+};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
 pub enum PriorityQueueType {
+    /// array sorted by priority after every enqueue and dequeue
+    #[default]
     Ordered,
     // UnOrdered,
+    /// binary max heap, O(log n) insert and pop
+    Heap,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -13,6 +20,42 @@ pub enum QueueType {
     Lineal,
     Priority,
     Circular,
+    /// multi-producer multi-consumer, lock-free
+    Mpmc,
+}
+
+/// what happens to the items enqueued in a full queue
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum QueueOverflowPolicy {
+    /// the item is not enqueued (index -1), all queue types
+    #[default]
+    Reject,
+    /// the oldest item is dropped, circular queue only
+    OverwriteOldest,
+    /// the item with the lowest priority is dropped if the new item has a
+    /// greater priority, priority queue only
+    DropLowestPriority,
+}
+
+/// the mpmc queue needs at least 2 slots, with 1 slot the sequence number of a
+/// full slot is the same as the sequence number of the next free slot
+fn check_capacity(capacity: usize, queue_type: QueueType) -> OpenClResult<()> {
+    if queue_type == QueueType::Mpmc && capacity < 2 {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_QUEUE_CAPACITY,
+        ));
+    }
+    Ok(())
+}
+
+impl QueueOverflowPolicy {
+    pub fn is_supported(&self, queue_type: QueueType) -> bool {
+        match self {
+            QueueOverflowPolicy::Reject => true,
+            QueueOverflowPolicy::OverwriteOldest => queue_type == QueueType::Circular,
+            QueueOverflowPolicy::DropLowestPriority => queue_type == QueueType::Priority,
+        }
+    }
 }
 
 /// ...
@@ -45,11 +88,31 @@ pub enum QueueType {
 /// __global int cq_operations__QUEUE_ID[QUEUE_CAPACITY];
 /// ```
 ///
+/// mpmc queue
+/// ```c
+/// __global int mq__QUEUE_ID[QUEUE_CAPACITY];
+/// __global uint mq_seq__QUEUE_ID[QUEUE_CAPACITY];
+/// __global uint mq_head__QUEUE_ID = 0;
+/// __global uint mq_tail__QUEUE_ID = 0;
+/// ```
+///
+/// the overflow policy is a constant of the queue
+/// ```c
+/// const int cq_overflow_overwrite_oldest__QUEUE_ID = 0;
+/// const int pq_overflow_drop_lowest__QUEUE_ID = 0;
+/// ```
+///
+/// the heap priority queue uses the same globals, pq_value and pq_priority
+/// store a binary max heap (the root at index 0)
+///
 #[derive(Debug, Clone, PartialEq)]
 pub struct QueueConfig {
     pub id: usize,
     pub capacity: usize,
     pub queue_type: QueueType,
+    pub overflow_policy: QueueOverflowPolicy,
+    /// only used by the priority queue
+    pub priority_queue_type: PriorityQueueType,
 }
 
 impl QueueConfig {
@@ -58,6 +121,8 @@ impl QueueConfig {
             id,
             capacity,
             queue_type,
+            overflow_policy: QueueOverflowPolicy::Reject,
+            priority_queue_type: PriorityQueueType::Ordered,
         }
     }
 }
@@ -76,6 +141,10 @@ impl QueueSrc {
         &self.blocks
     }
 
+    pub(crate) fn get_configs_mut(&mut self) -> &mut Vec<QueueConfig> {
+        &mut self.blocks
+    }
+
     pub fn get_configs_by_type(&self, queue_type: QueueType) -> Vec<&QueueConfig> {
         self.blocks
             .iter()
@@ -101,6 +170,42 @@ impl QueueSrc {
         self.blocks.push(QueueConfig::new(id, capacity, queue_type));
     }
 
+    pub fn add_with_overflow_policy(
+        &mut self,
+        capacity: usize,
+        queue_type: QueueType,
+        overflow_policy: QueueOverflowPolicy,
+    ) -> OpenClResult<()> {
+        if !overflow_policy.is_supported(queue_type) {
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_QUEUE_OVERFLOW_POLICY,
+            ));
+        }
+
+        check_capacity(capacity, queue_type)?;
+
+        let id = self.get_queue_id(queue_type);
+
+        self.blocks.push(QueueConfig {
+            overflow_policy,
+            ..QueueConfig::new(id, capacity, queue_type)
+        });
+
+        Ok(())
+    }
+
+    /// Add a copy of the config (of another src) with a new id, the new id is returned
+    pub(crate) fn add_config(&mut self, config: &QueueConfig) -> usize {
+        let id = self.get_queue_id(config.queue_type);
+
+        self.blocks.push(QueueConfig {
+            id,
+            ..config.clone()
+        });
+
+        id
+    }
+
     // lq = linear queue
     pub fn add_lq(&mut self, capacity: usize) {
         self.add(capacity, QueueType::Lineal)
@@ -111,11 +216,27 @@ impl QueueSrc {
         self.add(capacity, QueueType::Priority)
     }
 
+    pub fn add_pq_with_type(&mut self, capacity: usize, priority_queue_type: PriorityQueueType) {
+        let id = self.get_queue_id(QueueType::Priority);
+
+        self.blocks.push(QueueConfig {
+            priority_queue_type,
+            ..QueueConfig::new(id, capacity, QueueType::Priority)
+        });
+    }
+
     // cq = circular queue
     pub fn add_cq(&mut self, capacity: usize) {
         self.add(capacity, QueueType::Circular)
     }
 
+    // mq = mpmc queue
+    pub fn add_mq(&mut self, capacity: usize) -> OpenClResult<()> {
+        check_capacity(capacity, QueueType::Mpmc)?;
+        self.add(capacity, QueueType::Mpmc);
+        Ok(())
+    }
+
     pub fn add_many(&mut self, capacity: usize, quantity: usize, queue_type: QueueType) {
         for _ in 0..quantity {
             self.add(capacity, queue_type);
@@ -137,6 +258,13 @@ impl QueueSrc {
         self.add_many(capacity, quantity, QueueType::Circular)
     }
 
+    // mq = mpmc queue
+    pub fn add_many_mq(&mut self, capacity: usize, quantity: usize) -> OpenClResult<()> {
+        check_capacity(capacity, QueueType::Mpmc)?;
+        self.add_many(capacity, quantity, QueueType::Mpmc);
+        Ok(())
+    }
+
     pub fn get_config_by_id(&self, id: usize, queue_type: QueueType) -> OpenClResult<&QueueConfig> {
         match self
             .blocks
@@ -163,6 +291,10 @@ impl QueueSrc {
         self.get_config_by_id(id, QueueType::Circular)
     }
 
+    pub fn get_mq_config_by_id(&self, id: usize) -> OpenClResult<&QueueConfig> {
+        self.get_config_by_id(id, QueueType::Mpmc)
+    }
+
     // TODO get_config_by_capacity
 }
 
@@ -230,4 +362,130 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_queue_src_add_with_overflow_policy() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_cq(8);
+
+        queue_src
+            .add_with_overflow_policy(
+                16,
+                QueueType::Circular,
+                QueueOverflowPolicy::OverwriteOldest,
+            )
+            .unwrap();
+        queue_src
+            .add_with_overflow_policy(
+                16,
+                QueueType::Priority,
+                QueueOverflowPolicy::DropLowestPriority,
+            )
+            .unwrap();
+        queue_src
+            .add_with_overflow_policy(16, QueueType::Lineal, QueueOverflowPolicy::Reject)
+            .unwrap();
+
+        let config = queue_src.get_cq_config_by_id(1).unwrap();
+        assert_eq!(config.overflow_policy, QueueOverflowPolicy::OverwriteOldest);
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        assert_eq!(
+            config.overflow_policy,
+            QueueOverflowPolicy::DropLowestPriority
+        );
+
+        let config = queue_src.get_cq_config_by_id(0).unwrap();
+        assert_eq!(config.overflow_policy, QueueOverflowPolicy::Reject);
+    }
+
+    #[test]
+    fn test_queue_src_unsupported_overflow_policy() {
+        let mut queue_src = QueueSrc::new();
+
+        let error = Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_QUEUE_OVERFLOW_POLICY,
+        ));
+
+        assert_eq!(
+            queue_src.add_with_overflow_policy(
+                8,
+                QueueType::Lineal,
+                QueueOverflowPolicy::OverwriteOldest
+            ),
+            error
+        );
+        assert_eq!(
+            queue_src.add_with_overflow_policy(
+                8,
+                QueueType::Priority,
+                QueueOverflowPolicy::OverwriteOldest
+            ),
+            error
+        );
+        assert_eq!(
+            queue_src.add_with_overflow_policy(
+                8,
+                QueueType::Circular,
+                QueueOverflowPolicy::DropLowestPriority
+            ),
+            error
+        );
+        assert_eq!(
+            queue_src.add_with_overflow_policy(
+                8,
+                QueueType::Mpmc,
+                QueueOverflowPolicy::OverwriteOldest
+            ),
+            error
+        );
+
+        assert!(queue_src.is_empty());
+    }
+
+    #[test]
+    fn test_queue_src_add_pq_with_type() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(8);
+        queue_src.add_pq_with_type(16, PriorityQueueType::Heap);
+        queue_src.add_pq_with_type(32, PriorityQueueType::Ordered);
+
+        let types: Vec<PriorityQueueType> = queue_src
+            .get_configs()
+            .iter()
+            .map(|x| x.priority_queue_type)
+            .collect();
+
+        assert_eq!(
+            types,
+            vec![
+                PriorityQueueType::Ordered,
+                PriorityQueueType::Heap,
+                PriorityQueueType::Ordered
+            ]
+        );
+
+        let config = queue_src.get_pq_config_by_id(1).unwrap();
+        assert_eq!(config.capacity, 16);
+        assert_eq!(config.priority_queue_type, PriorityQueueType::Heap);
+    }
+
+    #[test]
+    fn test_queue_src_mq_invalid_capacity() {
+        let mut queue_src = QueueSrc::new();
+
+        let error = || OpenclError::OpenclCollection(CL_COLLECTION_INVALID_QUEUE_CAPACITY);
+
+        assert_eq!(queue_src.add_mq(0), Err(error()));
+        assert_eq!(queue_src.add_mq(1), Err(error()));
+        assert_eq!(queue_src.add_many_mq(1, 2), Err(error()));
+        assert_eq!(
+            queue_src.add_with_overflow_policy(1, QueueType::Mpmc, QueueOverflowPolicy::Reject),
+            Err(error())
+        );
+        assert!(queue_src.is_empty());
+
+        queue_src.add_mq(2).unwrap();
+        assert_eq!(queue_src.len(), 1);
+    }
 }
@@ -4,7 +4,8 @@ use crate::queue::config::QueueConfig;
 use crate::queue::kernel::name::{
     get_queue_kernel_name, CIRCULAR_QUEUE_CONFIRM_READ, CIRCULAR_QUEUE_CONFIRM_WRITE,
     CIRCULAR_QUEUE_DEBUG, CIRCULAR_QUEUE_PREPARE_READ, CIRCULAR_QUEUE_PREPARE_WRITE,
-    CIRCULAR_QUEUE_RESET, PREPARE_AND_READ_ON_CIRCULAR_QUEUE, PREPARE_AND_WRITE_TO_CIRCULAR_QUEUE,
+    CIRCULAR_QUEUE_RESET, GET_CIRCULAR_QUEUE_LEN, PEEK_ON_CIRCULAR_QUEUE,
+    PREPARE_AND_READ_ON_CIRCULAR_QUEUE, PREPARE_AND_WRITE_TO_CIRCULAR_QUEUE,
 };
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
 use opencl::wrapper::system::OpenclCommonOperation;
@@ -323,6 +324,82 @@ CircularQueueSnapshot (
 
         Ok(values)
     }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_queue_kernel_name(GET_CIRCULAR_QUEUE_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// up to take values, the values are not removed from the queue
+    pub fn peek(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_queue_kernel_name(PEEK_ON_CIRCULAR_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let mut output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("peek output {output:?}");
+        }
+
+        output.truncate(meta_output[0] as usize);
+
+        Ok(output)
+    }
 }
 
 #[cfg(test)]
@@ -933,3 +1010,141 @@ mod tests_cq_examples {
         cq.print().unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests_cq_overflow {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::{QueueOverflowPolicy, QueueSrc, QueueType};
+    use opencl::wrapper::system::System;
+
+    fn create_cq(
+        queue_capacity: usize,
+        overflow_policy: QueueOverflowPolicy,
+    ) -> CircularQueueHandle<System> {
+        let mut queue_src = QueueSrc::new();
+        queue_src
+            .add_with_overflow_policy(queue_capacity, QueueType::Circular, overflow_policy)
+            .unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_cq_config_by_id(0).unwrap();
+        let cq = CircularQueueHandle::new(config, system);
+        cq.reset().unwrap();
+        cq
+    }
+
+    #[test]
+    fn reject() {
+        let queue_capacity = 8;
+        let cq = create_cq(queue_capacity, QueueOverflowPolicy::Reject);
+
+        let input: Vec<cl_int> = (1..=8).collect();
+        let result = cq.enqueue(&input).unwrap();
+        assert!(result.iter().all(|&x| x >= 0));
+        assert_eq!(cq.len(), Ok(queue_capacity));
+
+        let result = cq.enqueue(&[9, 10, 11]).unwrap();
+        assert_eq!(result, vec![-1; 3]);
+        assert_eq!(cq.len(), Ok(queue_capacity));
+
+        let result = cq.dequeue(queue_capacity).unwrap();
+        assert_eq!(result, input);
+        assert_eq!(cq.len(), Ok(0));
+    }
+
+    #[test]
+    fn overwrite_oldest() {
+        let queue_capacity = 8;
+        let cq = create_cq(queue_capacity, QueueOverflowPolicy::OverwriteOldest);
+
+        let input: Vec<cl_int> = (1..=8).collect();
+        let result = cq.enqueue(&input).unwrap();
+        assert!(result.iter().all(|&x| x >= 0));
+        assert_eq!(cq.len(), Ok(queue_capacity));
+
+        // 1, 2 and 3 are dropped, the new items use their slots
+        let result = cq.enqueue(&[9, 10, 11]).unwrap();
+        let mut result_sorted = result.clone();
+        result_sorted.sort();
+        assert_eq!(result_sorted, vec![0, 1, 2]);
+        assert_eq!(cq.len(), Ok(queue_capacity));
+
+        let result = cq.dequeue(2).unwrap();
+        assert_eq!(result, vec![4, 5]);
+        assert_eq!(cq.len(), Ok(queue_capacity - 2));
+
+        // the queue is not full, nothing is dropped
+        let result = cq.enqueue(&[12, 13]).unwrap();
+        assert!(result.iter().all(|&x| x >= 0));
+
+        let result = cq.dequeue(queue_capacity).unwrap();
+        assert_eq!(result, vec![6, 7, 8, 9, 10, 11, 12, 13]);
+        assert_eq!(cq.len(), Ok(0));
+    }
+}
+
+#[cfg(test)]
+mod tests_cq_peek {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn queue_is_empty() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_cq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_cq_config_by_id(0).unwrap();
+        let cq = CircularQueueHandle::new(config, system);
+        cq.reset().unwrap();
+
+        assert_eq!(cq.len(), Ok(0));
+        assert_eq!(cq.is_empty(), Ok(true));
+        assert_eq!(cq.peek(4), Ok(vec![]));
+    }
+
+    #[test]
+    fn partially_full_queue() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_cq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_cq_config_by_id(0).unwrap();
+        let cq = CircularQueueHandle::new(config, system);
+        cq.reset().unwrap();
+
+        cq.enqueue(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(cq.len(), Ok(6));
+
+        let mut values = cq.dequeue(4).unwrap();
+        values.sort();
+        assert_eq!(values, vec![1, 2, 3, 4]);
+        assert_eq!(cq.len(), Ok(2));
+
+        // the rear wraps around
+        cq.enqueue(&[7, 8, 9, 10, 11]).unwrap();
+        assert_eq!(cq.len(), Ok(7));
+        assert_eq!(cq.is_empty(), Ok(false));
+
+        let items = cq.peek(8).unwrap();
+        assert_eq!(items.len(), 7);
+        assert_eq!(cq.peek(7), Ok(items.clone()));
+
+        // the items are not removed
+        let mut values = cq.dequeue(8).unwrap();
+        values.retain(|&x| x != -1);
+
+        let mut items = items;
+        items.sort();
+        values.sort();
+        assert_eq!(values, items);
+
+        assert_eq!(cq.len(), Ok(0));
+    }
+}
@@ -2,8 +2,8 @@ use crate::config::DEBUG_MODE;
 use crate::error::OpenClResult;
 use crate::queue::config::QueueConfig;
 use crate::queue::kernel::name::{
-    get_queue_kernel_name, LINEAR_QUEUE_DEBUG, LINEAR_QUEUE_RESET, READ_ON_LINEAR_QUEUE,
-    WRITE_TO_LINEAR_QUEUE,
+    get_queue_kernel_name, GET_LINEAR_QUEUE_LEN, LINEAR_QUEUE_DEBUG, LINEAR_QUEUE_RESET,
+    PEEK_ON_LINEAR_QUEUE, READ_ON_LINEAR_QUEUE, WRITE_TO_LINEAR_QUEUE,
 };
 use opencl::opencl_sys::bindings::cl_int;
 use opencl::wrapper::system::OpenclCommonOperation;
@@ -179,6 +179,82 @@ impl<T: OpenclCommonOperation> LinearQueueHandle<T> {
 
         Ok(output)
     }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_queue_kernel_name(GET_LINEAR_QUEUE_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// up to take values, the values are not removed from the queue
+    pub fn peek(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_queue_kernel_name(PEEK_ON_LINEAR_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let mut output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("peek output {output:?}");
+        }
+
+        output.truncate(meta_output[0] as usize);
+
+        Ok(output)
+    }
 }
 
 #[cfg(test)]
@@ -718,3 +794,75 @@ mod tests_lq_examples {
         lq.print().unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests_lq_peek {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn queue_is_empty() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_lq_config_by_id(0).unwrap();
+        let lq = LinearQueueHandle::new(config, system);
+
+        assert_eq!(lq.len(), Ok(0));
+        assert_eq!(lq.is_empty(), Ok(true));
+        assert_eq!(lq.peek(4), Ok(vec![]));
+        assert_eq!(lq.peek(0), Ok(vec![]));
+    }
+
+    #[test]
+    fn partially_full_queue() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_lq_config_by_id(0).unwrap();
+        let lq = LinearQueueHandle::new(config, system);
+
+        lq.enqueue(&[10, 20, 30, 40, 50]).unwrap();
+        assert_eq!(lq.len(), Ok(5));
+
+        assert_eq!(lq.peek(2), Ok(vec![10, 20]));
+        assert_eq!(lq.peek(8), Ok(vec![10, 20, 30, 40, 50]));
+
+        // the items are not removed
+        assert_eq!(lq.len(), Ok(5));
+
+        assert_eq!(lq.dequeue(2).unwrap(), vec![10, 20]);
+        assert_eq!(lq.len(), Ok(3));
+        assert_eq!(lq.is_empty(), Ok(false));
+        assert_eq!(lq.peek(2), Ok(vec![30, 40]));
+
+        lq.dequeue(4).unwrap();
+        assert_eq!(lq.len(), Ok(0));
+        assert_eq!(lq.peek(2), Ok(vec![]));
+    }
+
+    #[test]
+    fn queue_is_full() {
+        let queue_capacity = 8;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(queue_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_lq_config_by_id(0).unwrap();
+        let lq = LinearQueueHandle::new(config, system);
+
+        let input: Vec<cl_int> = (0..(queue_capacity * 2) as cl_int).collect();
+        lq.enqueue(&input).unwrap();
+
+        assert_eq!(lq.len(), Ok(queue_capacity));
+        assert_eq!(lq.peek(queue_capacity * 2).unwrap().len(), queue_capacity);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod circular;
 pub mod linear;
+pub mod mpmc;
 pub mod priority;
@@ -0,0 +1,603 @@
+use crate::config::DEBUG_MODE;
+use crate::error::OpenClResult;
+use crate::queue::config::QueueConfig;
+use crate::queue::kernel::name::{
+    get_queue_kernel_name, GET_MPMC_QUEUE_LEN, MPMC_QUEUE_DEBUG, MPMC_QUEUE_RESET,
+    PEEK_ON_MPMC_QUEUE, READ_ON_MPMC_QUEUE, WRITE_AND_READ_ON_MPMC_QUEUE, WRITE_TO_MPMC_QUEUE,
+};
+use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+use opencl::wrapper::system::OpenclCommonOperation;
+
+#[derive(Debug, PartialEq)]
+pub struct MpmcQueueSnapshot {
+    pub head: cl_int,
+    pub tail: cl_int,
+    pub items: Vec<cl_int>,
+    /// sequence number - slot index
+    pub sequences: Vec<cl_int>,
+}
+
+impl MpmcQueueSnapshot {
+    pub fn new(head: cl_int, tail: cl_int, items: Vec<cl_int>, sequences: Vec<cl_int>) -> Self {
+        Self {
+            head,
+            tail,
+            items,
+            sequences,
+        }
+    }
+
+    pub fn create_empty(capacity: usize) -> Self {
+        Self::new(0, 0, vec![0; capacity], vec![0; capacity])
+    }
+}
+
+#[derive(Debug)]
+pub struct MpmcQueueHandle<T: OpenclCommonOperation> {
+    config: QueueConfig,
+    system: T,
+}
+
+impl<T: OpenclCommonOperation> MpmcQueueHandle<T> {
+    pub fn new(config: &QueueConfig, system: T) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn debug(&self) -> OpenClResult<MpmcQueueSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_capacity = global_work_size * 2;
+        let meta_output_capacity = 2;
+
+        let output_buf = self.system.create_output_buffer(output_capacity)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_queue_kernel_name(MPMC_QUEUE_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(output_capacity, &output_buf, &[])?;
+
+        let items = output[0..self.config.capacity].to_vec();
+        let sequences = output[self.config.capacity..].to_vec();
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        Ok(MpmcQueueSnapshot {
+            head: meta_output[0],
+            tail: meta_output[1],
+            items,
+            sequences,
+        })
+    }
+
+    pub fn print(&self) -> OpenClResult<MpmcQueueSnapshot> {
+        let qs = self.debug()?;
+        println!("{qs:?}");
+        Ok(qs)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_queue_kernel_name(MPMC_QUEUE_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_queue_kernel_name(GET_MPMC_QUEUE_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// up to take values, the values are not removed from the queue
+    pub fn peek(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_queue_kernel_name(PEEK_ON_MPMC_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let mut output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("peek output {output:?}");
+        }
+
+        output.truncate(meta_output[0] as usize);
+
+        Ok(output)
+    }
+
+    /// one work item per value, the slot index (or -1 if the queue is full) is returned
+    pub fn enqueue(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        let global_work_size = input.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_queue_kernel_name(WRITE_TO_MPMC_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("enqueue output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// one work item per value, -1 if the queue is empty
+    pub fn dequeue(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_queue_kernel_name(READ_ON_MPMC_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("dequeue output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// the producers and the consumers run concurrently (device enqueued kernels),
+    /// (indices, values) are returned
+    pub fn enqueue_and_dequeue(
+        &self,
+        input: &[cl_int],
+        take: usize,
+    ) -> OpenClResult<(Vec<cl_int>, Vec<cl_int>)> {
+        let global_work_size = 1;
+        let local_work_size = 1;
+
+        // CMQ_WRITE = 0
+        // CMQ_READ = 1
+        let enqueue_kernel_output_capacity = 2;
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+
+        let indices_buf = self.system.create_output_buffer(input.len())?;
+        let values_buf = self.system.create_output_buffer(take)?;
+
+        let enqueue_kernel_output_buf = self
+            .system
+            .create_output_buffer(enqueue_kernel_output_capacity)?;
+
+        let input_global_work_size = input.len() as cl_uint;
+        let input_local_work_size =
+            self.system.first_device_check_local_work_size(input.len()) as cl_uint;
+
+        let output_global_work_size = take as cl_uint;
+        let output_local_work_size =
+            self.system.first_device_check_local_work_size(take) as cl_uint;
+
+        let q0 = self
+            .system
+            .get_device_command_queue_0()
+            .get_cl_command_queue();
+
+        let kernel_name = get_queue_kernel_name(WRITE_AND_READ_ON_MPMC_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&q0)?;
+            kernel.set_arg(&input_global_work_size)?;
+            kernel.set_arg(&input_local_work_size)?;
+            kernel.set_arg(&output_global_work_size)?;
+            kernel.set_arg(&output_local_work_size)?;
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_buf.get_cl_mem())?;
+            kernel.set_arg(&values_buf.get_cl_mem())?;
+            kernel.set_arg(&enqueue_kernel_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let indices = self
+            .system
+            .blocking_enqueue_read_buffer(input.len(), &indices_buf, &[])?;
+
+        let values = self
+            .system
+            .blocking_enqueue_read_buffer(take, &values_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("enqueue output {indices:?}");
+            println!("dequeue output {values:?}");
+        }
+
+        self.system.assert_device_enqueue_kernel(
+            enqueue_kernel_output_capacity,
+            enqueue_kernel_output_buf,
+            &[],
+        )?;
+
+        Ok((indices, values))
+    }
+}
+
+#[cfg(test)]
+mod tests_mq_debug {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn queue_is_empty() {
+        let queue_capacity = 32;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(queue_capacity).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        let qs = mq.print().unwrap();
+        assert_eq!(qs, MpmcQueueSnapshot::create_empty(queue_capacity));
+
+        // no reset required before the first use
+        let indices = mq.enqueue(&[1, 2, 3]).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(mq.len(), Ok(3));
+    }
+
+    #[test]
+    fn queue_is_full() {
+        let queue_capacity = 32;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(queue_capacity).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        let input: Vec<cl_int> = (0..queue_capacity as cl_int).collect();
+        mq.enqueue(&input).unwrap();
+
+        let qs = mq.print().unwrap();
+        assert_eq!(qs.head, queue_capacity as cl_int);
+        assert_eq!(qs.tail, 0);
+
+        let mut items = qs.items.clone();
+        items.sort();
+        assert_eq!(items, input);
+
+        // seq - i == 1 for every published slot of the first round
+        assert_eq!(qs.sequences, vec![1; queue_capacity]);
+
+        mq.reset().unwrap();
+
+        let qs = mq.debug().unwrap();
+        assert_eq!(qs, MpmcQueueSnapshot::create_empty(queue_capacity));
+    }
+}
+
+#[cfg(test)]
+mod tests_mq_enqueue_dequeue {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn fifo_order() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(8).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        // single work item per call, the order is deterministic
+        for x in [10, 20, 30] {
+            mq.enqueue(&[x]).unwrap();
+        }
+
+        assert_eq!(mq.peek(2), Ok(vec![10, 20]));
+        assert_eq!(mq.dequeue(1).unwrap(), vec![10]);
+        assert_eq!(mq.dequeue(1).unwrap(), vec![20]);
+
+        assert_eq!(mq.len(), Ok(1));
+        assert_eq!(mq.is_empty(), Ok(false));
+    }
+
+    #[test]
+    fn queue_without_enough_space() {
+        let queue_capacity = 16;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(queue_capacity).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        let input: Vec<cl_int> = (0..(queue_capacity * 2) as cl_int).collect();
+
+        let indices = mq.enqueue(&input).unwrap();
+        assert_eq!(indices.iter().filter(|&&x| x >= 0).count(), queue_capacity);
+        assert_eq!(mq.len(), Ok(queue_capacity));
+
+        let values = mq.dequeue(queue_capacity * 2).unwrap();
+        assert_eq!(values.iter().filter(|&&x| x >= 0).count(), queue_capacity);
+        assert_eq!(mq.is_empty(), Ok(true));
+    }
+
+    #[test]
+    fn wrap_around() {
+        let queue_capacity = 8;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(queue_capacity).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        for round in 0..5 {
+            let input: Vec<cl_int> = (0..6).map(|x| round * 100 + x).collect();
+
+            let indices = mq.enqueue(&input).unwrap();
+            assert!(indices.iter().all(|&x| x >= 0));
+
+            let mut values = mq.dequeue(6).unwrap();
+            values.sort();
+            assert_eq!(values, input);
+        }
+
+        let qs = mq.debug().unwrap();
+        assert_eq!(qs.head, 30);
+        assert_eq!(qs.tail, 30);
+    }
+}
+
+#[cfg(test)]
+mod tests_mq_stress {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+    use std::collections::{HashMap, VecDeque};
+
+    /// host reference: every value is dequeued at most once and only if it
+    /// was enqueued, the final len matches the enqueued - dequeued values
+    fn check_against_host_reference(
+        produced: &[cl_int],
+        indices: &[cl_int],
+        consumed: &[cl_int],
+        remaining: &[cl_int],
+    ) {
+        let mut reference: HashMap<cl_int, usize> = HashMap::new();
+
+        for (value, index) in produced.iter().zip(indices) {
+            if *index >= 0 {
+                *reference.entry(*value).or_default() += 1;
+            }
+        }
+
+        for value in consumed.iter().chain(remaining).filter(|&&x| x != -1) {
+            let count = reference.get_mut(value).expect("value not enqueued");
+            assert!(*count > 0, "value {value} dequeued twice");
+            *count -= 1;
+        }
+
+        assert!(reference.values().all(|&x| x == 0), "values lost");
+    }
+
+    #[test]
+    fn concurrent_producers_and_consumers() {
+        let queue_capacity = 256;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(queue_capacity).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        for round in 0..16 {
+            let input: Vec<cl_int> = (0..512).map(|x| round * 1000 + x).collect();
+
+            let (indices, values) = mq.enqueue_and_dequeue(&input, 384).unwrap();
+
+            let remaining_len = mq.len().unwrap();
+            let remaining = mq.dequeue(queue_capacity).unwrap();
+
+            assert_eq!(
+                remaining.iter().filter(|&&x| x != -1).count(),
+                remaining_len
+            );
+
+            check_against_host_reference(&input, &indices, &values, &remaining);
+            assert_eq!(mq.is_empty(), Ok(true));
+        }
+    }
+
+    #[test]
+    fn sequential_batches_against_vec_deque() {
+        let queue_capacity = 64;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(queue_capacity).unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_mq_config_by_id(0).unwrap();
+        let mq = MpmcQueueHandle::new(config, system);
+
+        let mut reference: VecDeque<Vec<cl_int>> = VecDeque::new();
+        let mut next = 0;
+
+        for step in 0..40 {
+            let len = (step * 7) % 24 + 1;
+            let input: Vec<cl_int> = (next..next + len).collect();
+            next += len;
+
+            let indices = mq.enqueue(&input).unwrap();
+
+            // the order inside a batch depends on the work items
+            let mut batch: Vec<cl_int> = input
+                .iter()
+                .zip(&indices)
+                .filter(|(_, &i)| i >= 0)
+                .map(|(x, _)| *x)
+                .collect();
+            batch.sort();
+            reference.push_back(batch);
+
+            if step % 3 == 2 {
+                let batch = reference.pop_front().unwrap();
+
+                let mut values = mq.dequeue(batch.len()).unwrap();
+                values.sort();
+                assert_eq!(values, batch);
+            }
+        }
+
+        let expected_len: usize = reference.iter().map(|x| x.len()).sum();
+        assert_eq!(mq.len(), Ok(expected_len));
+    }
+}
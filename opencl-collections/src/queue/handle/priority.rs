@@ -1,9 +1,10 @@
 use crate::config::DEBUG_MODE;
-use crate::error::OpenClResult;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_QUEUE_INPUT_LEN};
 use crate::queue::config::QueueConfig;
 use crate::queue::kernel::name::{
-    get_queue_kernel_name, PRIORITY_QUEUE_DEBUG, PRIORITY_QUEUE_RESET, PRIORITY_QUEUE_SORT,
-    READ_ON_PRIORITY_QUEUE_AND_SORT, WRITE_TO_PRIORITY_QUEUE_AND_SORT,
+    get_queue_kernel_name, GET_PRIORITY_QUEUE_LEN, PEEK_ON_PRIORITY_QUEUE,
+    PRIORITY_QUEUE_CHANGE_PRIORITY, PRIORITY_QUEUE_DEBUG, PRIORITY_QUEUE_RESET,
+    PRIORITY_QUEUE_SORT, READ_ON_PRIORITY_QUEUE_AND_SORT, WRITE_TO_PRIORITY_QUEUE_AND_SORT,
 };
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
 use opencl::wrapper::system::OpenclCommonOperation;
@@ -159,7 +160,9 @@ PriorityQueueSnapshot (
 
     pub fn enqueue(&self, values: &[cl_int], priorities: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
         if values.len() != priorities.len() {
-            panic!("error handle values & priorities len");
+            return Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_QUEUE_INPUT_LEN,
+            ));
         }
 
         let global_work_size = 1;
@@ -286,6 +289,132 @@ PriorityQueueSnapshot (
 
         Ok(output)
     }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_queue_kernel_name(GET_PRIORITY_QUEUE_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// up to take (value, priority) items, the highest priority first
+    pub fn peek_with_priority(&self, take: usize) -> OpenClResult<Vec<(cl_int, cl_int)>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_capacity = global_work_size * 2;
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(output_capacity)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_queue_kernel_name(PEEK_ON_PRIORITY_QUEUE, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output = self
+            .system
+            .blocking_enqueue_read_buffer(output_capacity, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("peek output {output:?}");
+        }
+
+        let len = (meta_output[0] as usize).min(take);
+
+        let (values, priorities) = output.split_at(take);
+
+        Ok(values[0..len]
+            .iter()
+            .copied()
+            .zip(priorities[0..len].iter().copied())
+            .collect())
+    }
+
+    /// up to take values, the values are not removed from the queue
+    pub fn peek(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        let items = self.peek_with_priority(take)?;
+        Ok(items.into_iter().map(|(value, _)| value).collect())
+    }
+
+    /// (value, priority) of the item with the highest priority
+    pub fn peek_max(&self) -> OpenClResult<Option<(cl_int, cl_int)>> {
+        let items = self.peek_with_priority(1)?;
+        Ok(items.first().copied())
+    }
+
+    /// the number of items updated is returned, the queue is sorted again
+    pub fn change_priority(&self, value: cl_int, new_priority: cl_int) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_queue_kernel_name(PRIORITY_QUEUE_CHANGE_PRIORITY, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&value)?;
+            kernel.set_arg(&new_priority)?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("change priority output {output:?}");
+        }
+
+        Ok(output[0] as usize)
+    }
 }
 
 #[cfg(test)]
@@ -993,3 +1122,345 @@ mod tests_pq_examples {
         assert_eq!(qs.priorities, vec![1; 8]);
     }
 }
+
+#[cfg(test)]
+mod tests_pq_overflow {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::{QueueOverflowPolicy, QueueSrc, QueueType};
+    use opencl::wrapper::system::System;
+
+    fn create_pq(
+        queue_capacity: usize,
+        overflow_policy: QueueOverflowPolicy,
+    ) -> PriorityQueueHandle<System> {
+        let mut queue_src = QueueSrc::new();
+        queue_src
+            .add_with_overflow_policy(queue_capacity, QueueType::Priority, overflow_policy)
+            .unwrap();
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+        pq.reset().unwrap();
+        pq
+    }
+
+    #[test]
+    fn reject() {
+        let queue_capacity = 8;
+        let pq = create_pq(queue_capacity, QueueOverflowPolicy::Reject);
+
+        let values: Vec<cl_int> = (1..=8).map(|x| x * 10).collect();
+        let priorities: Vec<cl_int> = (1..=8).collect();
+        let result = pq.enqueue(&values, &priorities).unwrap();
+        assert!(result.iter().all(|&x| x >= 0));
+        assert_eq!(pq.len(), Ok(queue_capacity));
+
+        let result = pq.enqueue(&[90, 100], &[9, 10]).unwrap();
+        assert_eq!(result, vec![-1, -1]);
+        assert_eq!(pq.len(), Ok(queue_capacity));
+
+        let result = pq.dequeue(queue_capacity).unwrap();
+        assert_eq!(result, vec![80, 70, 60, 50, 40, 30, 20, 10]);
+        assert_eq!(pq.len(), Ok(0));
+    }
+
+    #[test]
+    fn rejected_items_do_not_leak_indices() {
+        let queue_capacity = 8;
+        let pq = create_pq(queue_capacity, QueueOverflowPolicy::Reject);
+
+        let values: Vec<cl_int> = (1..=8).map(|x| x * 10).collect();
+        let priorities: Vec<cl_int> = (1..=8).collect();
+        pq.enqueue(&values, &priorities).unwrap();
+
+        let result = pq.enqueue(&[90, 100], &[9, 10]).unwrap();
+        assert_eq!(result, vec![-1, -1]);
+
+        // the sort moves the tmp rear back to the rear
+        let snapshot = pq.debug().unwrap();
+        assert_eq!(snapshot.tmp_rear, 7);
+        assert_eq!(snapshot.rear, 7);
+
+        let result = pq.dequeue(2).unwrap();
+        assert_eq!(result, vec![80, 70]);
+
+        let snapshot = pq.debug().unwrap();
+        assert_eq!(snapshot.tmp_rear, 5);
+        assert_eq!(snapshot.rear, 5);
+
+        // the released indices are reused
+        let result = pq.enqueue(&[90, 100], &[9, 10]).unwrap();
+        assert!(result.iter().all(|&x| x >= 0));
+        assert_eq!(pq.len(), Ok(queue_capacity));
+
+        let result = pq.dequeue(queue_capacity).unwrap();
+        assert_eq!(result, vec![100, 90, 60, 50, 40, 30, 20, 10]);
+    }
+
+    #[test]
+    fn drop_lowest_priority() {
+        let queue_capacity = 8;
+        let pq = create_pq(queue_capacity, QueueOverflowPolicy::DropLowestPriority);
+
+        let values: Vec<cl_int> = (1..=8).map(|x| x * 10).collect();
+        let priorities: Vec<cl_int> = (1..=8).collect();
+        pq.enqueue(&values, &priorities).unwrap();
+
+        // 90 replaces the priority 1, the priority 0 is lower than all the items
+        let result = pq.enqueue(&[90, 5], &[9, 0]).unwrap();
+        assert!(result[0] >= 0);
+        assert_eq!(result[1], -1);
+        assert_eq!(pq.len(), Ok(queue_capacity));
+
+        let snapshot = pq.debug().unwrap();
+        assert_eq!(snapshot.tmp_rear, 7);
+        assert_eq!(snapshot.rear, 7);
+
+        let result = pq.dequeue(queue_capacity).unwrap();
+        assert_eq!(result, vec![90, 80, 70, 60, 50, 40, 30, 20]);
+        assert_eq!(pq.len(), Ok(0));
+    }
+}
+
+#[cfg(test)]
+mod tests_pq_peek {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::QueueSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn queue_is_empty() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+
+        assert_eq!(pq.len(), Ok(0));
+        assert_eq!(pq.is_empty(), Ok(true));
+        assert_eq!(pq.peek(4), Ok(vec![]));
+        assert_eq!(pq.peek_max(), Ok(None));
+        assert_eq!(pq.change_priority(1, 10), Ok(0));
+    }
+
+    #[test]
+    fn partially_full_queue() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+
+        pq.enqueue(&[10, 20, 30, 40], &[2, 4, 1, 3]).unwrap();
+        assert_eq!(pq.len(), Ok(4));
+
+        assert_eq!(pq.peek_max(), Ok(Some((20, 4))));
+        assert_eq!(pq.peek(3), Ok(vec![20, 40, 10]));
+        assert_eq!(
+            pq.peek_with_priority(8),
+            Ok(vec![(20, 4), (40, 3), (10, 2), (30, 1)])
+        );
+
+        // the items are not removed
+        assert_eq!(pq.len(), Ok(4));
+        assert_eq!(pq.dequeue(1).unwrap(), vec![20]);
+
+        assert_eq!(pq.len(), Ok(3));
+        assert_eq!(pq.peek_max(), Ok(Some((40, 3))));
+    }
+
+    #[test]
+    fn change_priority() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(8);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+
+        pq.enqueue(&[10, 20, 30, 40], &[2, 4, 1, 3]).unwrap();
+
+        assert_eq!(pq.change_priority(30, 9), Ok(1));
+        assert_eq!(pq.peek_max(), Ok(Some((30, 9))));
+
+        assert_eq!(pq.change_priority(20, 0), Ok(1));
+        assert_eq!(pq.peek(4), Ok(vec![30, 40, 10, 20]));
+
+        // value not found
+        assert_eq!(pq.change_priority(50, 1), Ok(0));
+
+        assert_eq!(pq.dequeue(4).unwrap(), vec![30, 40, 10, 20]);
+    }
+}
+
+#[cfg(test)]
+mod tests_pq_heap {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::queue::config::{PriorityQueueType, QueueOverflowPolicy, QueueSrc, QueueType};
+    use opencl::wrapper::system::System;
+    use std::collections::BinaryHeap;
+
+    // value = priority * 1000 + i, the priorities could be repeated
+    fn get_items(len: usize) -> (Vec<cl_int>, Vec<cl_int>) {
+        let priorities: Vec<cl_int> = (0..len as cl_int).map(|i| (i * 37) % 11).collect();
+
+        let values = priorities
+            .iter()
+            .enumerate()
+            .map(|(i, p)| p * 1000 + i as cl_int)
+            .collect();
+
+        (values, priorities)
+    }
+
+    fn get_priorities(values: &[cl_int]) -> Vec<cl_int> {
+        values
+            .iter()
+            .filter(|&&x| x != -1)
+            .map(|x| x / 1000)
+            .collect()
+    }
+
+    #[test]
+    fn same_order_as_binary_heap() {
+        let queue_capacity = 64;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq_with_type(queue_capacity, PriorityQueueType::Heap);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+        pq.reset().unwrap();
+
+        let mut binary_heap = BinaryHeap::new();
+
+        let (values, priorities) = get_items(48);
+
+        let indices = pq.enqueue(&values[0..32], &priorities[0..32]).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        binary_heap.extend(priorities[0..32].iter().copied());
+
+        assert_eq!(pq.len(), Ok(32));
+        assert_eq!(
+            pq.peek_max().unwrap().map(|x| x.1),
+            binary_heap.peek().copied()
+        );
+
+        let output = pq.dequeue(10).unwrap();
+        let expected: Vec<cl_int> = (0..10).filter_map(|_| binary_heap.pop()).collect();
+        assert_eq!(get_priorities(&output), expected);
+
+        pq.enqueue(&values[32..], &priorities[32..]).unwrap();
+        binary_heap.extend(priorities[32..].iter().copied());
+
+        assert_eq!(pq.len(), Ok(binary_heap.len()));
+
+        let peek: Vec<cl_int> = pq
+            .peek_with_priority(5)
+            .unwrap()
+            .iter()
+            .map(|x| x.1)
+            .collect();
+        let mut expected = binary_heap.clone().into_sorted_vec();
+        expected.reverse();
+        assert_eq!(peek, expected[0..5]);
+
+        let output = pq.dequeue(queue_capacity).unwrap();
+        assert_eq!(get_priorities(&output), expected);
+
+        assert_eq!(pq.len(), Ok(0));
+        assert_eq!(pq.dequeue(2).unwrap(), vec![-1, -1]);
+    }
+
+    #[test]
+    fn queue_is_full() {
+        let queue_capacity = 16;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq_with_type(queue_capacity, PriorityQueueType::Heap);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+        pq.reset().unwrap();
+
+        let (values, priorities) = get_items(20);
+
+        let indices = pq.enqueue(&values, &priorities).unwrap();
+        assert_eq!(indices.iter().filter(|&&x| x == -1).count(), 4);
+        assert_eq!(pq.len(), Ok(queue_capacity));
+
+        let snapshot = pq.debug().unwrap();
+        assert_eq!(snapshot.rear, queue_capacity as cl_int - 1);
+
+        // heap property
+        for i in 1..queue_capacity {
+            assert!(snapshot.priorities[(i - 1) / 2] >= snapshot.priorities[i]);
+        }
+    }
+
+    #[test]
+    fn drop_lowest_priority() {
+        let queue_capacity = 8;
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_config(&QueueConfig {
+            overflow_policy: QueueOverflowPolicy::DropLowestPriority,
+            priority_queue_type: PriorityQueueType::Heap,
+            ..QueueConfig::new(0, queue_capacity, QueueType::Priority)
+        });
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+        pq.reset().unwrap();
+
+        let values: Vec<cl_int> = (1..=8).collect();
+        pq.enqueue(&values, &values).unwrap();
+
+        // 20 and 30 replace the priorities 1 and 2, 0 is rejected
+        let indices = pq.enqueue(&[20, 30, 0], &[20, 30, 0]).unwrap();
+        assert!(indices[0] >= 0);
+        assert!(indices[1] >= 0);
+        assert_eq!(indices[2], -1);
+
+        let output = pq.dequeue(queue_capacity).unwrap();
+        assert_eq!(output, vec![30, 20, 8, 7, 6, 5, 4, 3]);
+    }
+
+    #[test]
+    fn change_priority() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq_with_type(8, PriorityQueueType::Heap);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &queue_src.build()).unwrap();
+
+        let config = queue_src.get_pq_config_by_id(0).unwrap();
+        let pq = PriorityQueueHandle::new(config, system);
+        pq.reset().unwrap();
+
+        pq.enqueue(&[10, 20, 30, 40], &[2, 4, 1, 3]).unwrap();
+
+        assert_eq!(pq.change_priority(30, 9), Ok(1));
+        assert_eq!(pq.peek_max(), Ok(Some((30, 9))));
+
+        assert_eq!(pq.change_priority(20, 0), Ok(1));
+        assert_eq!(pq.peek(4), Ok(vec![30, 40, 10, 20]));
+
+        assert_eq!(pq.dequeue(4).unwrap(), vec![30, 40, 10, 20]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::queue::config::{QueueSrc, QueueType};
+use crate::queue::config::{QueueConfig, QueueOverflowPolicy, QueueSrc, QueueType};
 use crate::queue::kernel::common_replace;
 
 const GLOBALS: &str = r#"
@@ -9,6 +9,8 @@ const GLOBALS: &str = r#"
     __global int cq_entry_index__QUEUE_ID = -1;
     __global int cq_max_entries__QUEUE_ID = -1;
     __global int cq_entries__QUEUE_ID[QUEUE_CAPACITY];
+
+    const int cq_overflow_overwrite_oldest__QUEUE_ID = CQ_OVERWRITE_OLDEST;
     "#;
 
 const BASE_FUNCTIONS: &str = r#"
@@ -30,7 +32,13 @@ const BASE_FUNCTIONS: &str = r#"
 
             // isFull
             if ((rear + 1) % QUEUE_CAPACITY == front) {
-                break;
+
+                if (!cq_overflow_overwrite_oldest__QUEUE_ID) {
+                    break;
+                }
+
+                // the oldest item is dropped
+                front = (front + 1) % QUEUE_CAPACITY;
             }
 
             if (front == -1) {
@@ -64,7 +72,12 @@ const BASE_FUNCTIONS: &str = r#"
 
             // isFull
             if ((cq_rear__QUEUE_ID + 1) % QUEUE_CAPACITY == cq_front__QUEUE_ID) {
-                break;
+
+                if (!cq_overflow_overwrite_oldest__QUEUE_ID) {
+                    break;
+                }
+
+                cq_front__QUEUE_ID = (cq_front__QUEUE_ID + 1) % QUEUE_CAPACITY;
             }
 
             if (cq_front__QUEUE_ID == -1) {
@@ -140,6 +153,14 @@ const BASE_FUNCTIONS: &str = r#"
         cq_max_entries__QUEUE_ID = -1;
     }
     
+    int cq_get_len__QUEUE_ID() {
+        if (cq_front__QUEUE_ID == -1) {
+            return 0;
+        }
+
+        return ((cq_rear__QUEUE_ID - cq_front__QUEUE_ID + QUEUE_CAPACITY) % QUEUE_CAPACITY) + 1;
+    }
+
     int cq_push__QUEUE_ID(int* value) {
         int rear_i = -1;
 
@@ -258,6 +279,28 @@ const BASE_KERNELS: &str = r#"
         output[i] = pi;
     }
 
+    kernel void get_cq_len__QUEUE_ID(
+        global int* len_output
+        ) {
+        len_output[0] = cq_get_len__QUEUE_ID();
+    }
+
+    // the items are not removed
+    kernel void peek_on_cq__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        int len = cq_get_len__QUEUE_ID();
+
+        items_output[i] = (i < len) ? cq__QUEUE_ID[(cq_front__QUEUE_ID + i) % QUEUE_CAPACITY] : -1;
+
+        if (i == 0) {
+            meta_output[0] = len;
+        }
+    }
+
     kernel void prepare_and_write_to_cq__QUEUE_ID(
         queue_t q0,
         const uint input_global_work_size,
@@ -346,6 +389,15 @@ const BASE_KERNELS: &str = r#"
     }
     "#;
 
+fn overflow_replace(src: &str, config: &QueueConfig) -> String {
+    let overwrite_oldest = config.overflow_policy == QueueOverflowPolicy::OverwriteOldest;
+
+    src.replace(
+        "CQ_OVERWRITE_OLDEST",
+        if overwrite_oldest { "1" } else { "0" },
+    )
+}
+
 impl QueueSrc {
     pub fn generate_circular_queue_program_source(&self) -> String {
         let blocks = self.get_configs_by_type(QueueType::Circular);
@@ -362,7 +414,7 @@ impl QueueSrc {
         let mut base_kernels = String::new();
 
         for config in blocks {
-            let template = common_replace(GLOBALS, config);
+            let template = overflow_replace(&common_replace(GLOBALS, config), config);
             globals.push_str(&template);
 
             let template = common_replace(BASE_FUNCTIONS, config);
@@ -427,4 +479,17 @@ mod tests {
         println!("{program_source}");
         assert_eq!(program_source, String::new());
     }
+
+    #[test]
+    fn test_overflow_policy() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_cq(8);
+        queue_src
+            .add_with_overflow_policy(8, QueueType::Circular, QueueOverflowPolicy::OverwriteOldest)
+            .unwrap();
+
+        let program_source = queue_src.generate_circular_queue_program_source();
+        assert!(program_source.contains("const int cq_overflow_overwrite_oldest__0 = 0;"));
+        assert!(program_source.contains("const int cq_overflow_overwrite_oldest__1 = 1;"));
+    }
 }
@@ -0,0 +1,92 @@
+// device-callable functions, the kernels of the user can operate on the queues
+// (CollectionsSrc::enable_device_api or QueueSrc::build_device_api)
+//
+// linear queue
+//   int lq_enqueue__QUEUE_ID(int v)        index of the slot, -1 if the queue is full
+//   int lq_dequeue__QUEUE_ID(int* v)       index of the slot, -1 if the queue is empty
+//   int lq_len__QUEUE_ID()
+//
+// mpmc queue
+//   int mq_enqueue__QUEUE_ID(int v)
+//   int mq_dequeue__QUEUE_ID(int* v)
+//   int mq_len__QUEUE_ID()
+//
+// the circular queue and the priority queue are not included, the host prepares
+// their operations (prepare kernels and sort)
+
+use crate::queue::config::{QueueSrc, QueueType};
+use crate::queue::kernel::common_replace;
+
+const LINEAR_QUEUE_API: &str = r#"
+    int lq_enqueue__QUEUE_ID(int v) {
+        return lq_push__QUEUE_ID(&v);
+    }
+
+    int lq_dequeue__QUEUE_ID(int* v) {
+        return lq_pop__QUEUE_ID(v);
+    }
+
+    int lq_len__QUEUE_ID() {
+        return lq_get_len__QUEUE_ID();
+    }
+    "#;
+
+const MPMC_QUEUE_API: &str = r#"
+    int mq_enqueue__QUEUE_ID(int v) {
+        return mq_push__QUEUE_ID(v);
+    }
+
+    int mq_dequeue__QUEUE_ID(int* v) {
+        return mq_pop__QUEUE_ID(v);
+    }
+
+    int mq_len__QUEUE_ID() {
+        return mq_get_len__QUEUE_ID();
+    }
+    "#;
+
+impl QueueSrc {
+    /// the source must be added after the source of the queues (build)
+    pub fn build_device_api(&self) -> String {
+        let mut device_api = String::new();
+
+        for config in self.get_configs() {
+            let template = match config.queue_type {
+                QueueType::Lineal => LINEAR_QUEUE_API,
+                QueueType::Mpmc => MPMC_QUEUE_API,
+                QueueType::Priority | QueueType::Circular => continue,
+            };
+            device_api.push_str(&common_replace(template, config));
+        }
+
+        device_api
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+        queue_src.add_pq(8);
+        queue_src.add_cq(8);
+        queue_src.add_mq(8).unwrap();
+
+        let device_api = queue_src.build_device_api();
+        println!("{device_api}");
+        assert!(device_api.contains("int lq_enqueue__0(int v) {"));
+        assert!(device_api.contains("int mq_dequeue__0(int* v) {"));
+        assert!(!device_api.contains("cq_"));
+        assert!(!device_api.contains("pq_"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(8);
+        assert!(queue_src.build_device_api().is_empty());
+    }
+}
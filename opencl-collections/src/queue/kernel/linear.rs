@@ -54,6 +54,13 @@ const BASE_KERNELS: &str = r#"
         return front_i;
     }
 
+    int lq_get_len__QUEUE_ID() {
+        int rear = min(lq_rear__QUEUE_ID, QUEUE_MAX_CAPACITY);
+        int front = min(lq_front__QUEUE_ID, rear);
+
+        return rear - front;
+    }
+
     kernel void lq_reset__QUEUE_ID() {
         int i = get_global_id(0);
 
@@ -96,6 +103,29 @@ const BASE_KERNELS: &str = r#"
 
         output[i] = pi;
     }
+
+    kernel void get_lq_len__QUEUE_ID(
+        global int* len_output
+        ) {
+        len_output[0] = lq_get_len__QUEUE_ID();
+    }
+
+    // the items are not removed
+    kernel void peek_on_lq__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        int len = lq_get_len__QUEUE_ID();
+        int front = min(lq_front__QUEUE_ID, min(lq_rear__QUEUE_ID, QUEUE_MAX_CAPACITY));
+
+        items_output[i] = (i < len) ? lq__QUEUE_ID[front + 1 + i] : -1;
+
+        if (i == 0) {
+            meta_output[0] = len;
+        }
+    }
     "#;
 
 impl QueueSrc {
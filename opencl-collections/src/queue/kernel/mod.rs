@@ -1,8 +1,11 @@
 pub mod name;
 
 mod circular;
+mod device_api;
 mod linear;
+mod mpmc;
 mod priority;
+mod priority_heap;
 
 use crate::queue::config::{QueueConfig, QueueSrc};
 
@@ -23,6 +26,8 @@ impl QueueSrc {
 
         let circular_queue_src = self.generate_circular_queue_program_source();
 
+        let mpmc_queue_src = self.generate_mpmc_queue_program_source();
+
         format!(
             "
     /// *** QUEUE SRC START *** ///
@@ -33,6 +38,8 @@ impl QueueSrc {
 
     {circular_queue_src}
 
+    {mpmc_queue_src}
+
     /// *** QUEUE SRC END *** ///
     "
         )
@@ -49,6 +56,7 @@ mod tests {
         queue_src.add_lq(8);
         queue_src.add_pq(8);
         queue_src.add_cq(8);
+        queue_src.add_mq(8).unwrap();
 
         let program_source = queue_src.build();
         println!("{program_source}");
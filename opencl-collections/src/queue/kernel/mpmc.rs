@@ -0,0 +1,337 @@
+// bounded multi-producer multi-consumer queue (FIFO), lock-free
+//
+// SOURCE: Dmitry Vyukov, bounded MPMC queue
+//
+// every slot has a sequence number:
+//   seq == pos          the slot is free for the producer of pos
+//   seq == pos + 1      the slot holds the item of pos
+//   seq == pos + CAP    the slot was released for the producer of pos + CAP
+//
+// mq_seq stores seq - i (i = slot index), the zero initialized queue is empty
+//
+// the positions and the sequence numbers wrap at MQ_POSITION_WRAP (the greatest multiple
+// of the capacity <= 2^31), so the slot of a position is the same before and after the wrap
+//
+// the sequence number is published with release and read with acquire before the
+// slot is accessed (the item is visible for the consumer that sees the sequence number)
+
+use crate::queue::config::{QueueSrc, QueueType};
+use crate::queue::kernel::common_replace;
+
+const GLOBALS: &str = r#"
+    __global int mq__QUEUE_ID[QUEUE_CAPACITY];
+    __global uint mq_seq__QUEUE_ID[QUEUE_CAPACITY];
+    __global uint mq_head__QUEUE_ID = 0;
+    __global uint mq_tail__QUEUE_ID = 0;
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    uint mq_next_position__QUEUE_ID(uint pos, uint n) {
+        return (pos + n) % MQ_POSITION_WRAP;
+    }
+
+    // a - b in (-MQ_POSITION_WRAP / 2, MQ_POSITION_WRAP / 2]
+    int mq_position_diff__QUEUE_ID(uint a, uint b) {
+        uint d = (a + MQ_POSITION_WRAP - b) % MQ_POSITION_WRAP;
+        return d > MQ_POSITION_WRAP / 2 ? (int) (d - MQ_POSITION_WRAP) : (int) d;
+    }
+
+    uint mq_load_seq__QUEUE_ID(int i) {
+        uint seq = atomic_load_explicit(
+            (volatile __global atomic_uint*) &mq_seq__QUEUE_ID[i],
+            memory_order_acquire,
+            memory_scope_device
+        );
+        return mq_next_position__QUEUE_ID(seq, i);
+    }
+
+    void mq_store_seq__QUEUE_ID(int i, uint seq) {
+        atomic_store_explicit(
+            (volatile __global atomic_uint*) &mq_seq__QUEUE_ID[i],
+            mq_next_position__QUEUE_ID(seq, MQ_POSITION_WRAP - i),
+            memory_order_release,
+            memory_scope_device
+        );
+    }
+
+    int mq_push__QUEUE_ID(int value) {
+        uint pos = mq_head__QUEUE_ID;
+
+        for (;;) {
+            int i = pos % QUEUE_CAPACITY;
+            int diff = mq_position_diff__QUEUE_ID(mq_load_seq__QUEUE_ID(i), pos);
+
+            if (diff == 0) {
+                uint r = atomic_cmpxchg(&mq_head__QUEUE_ID, pos, mq_next_position__QUEUE_ID(pos, 1));
+
+                if (r == pos) {
+                    mq__QUEUE_ID[i] = value;
+
+                    // the item is visible for the consumers
+                    mq_store_seq__QUEUE_ID(i, mq_next_position__QUEUE_ID(pos, 1));
+                    return i;
+                }
+
+                pos = r;
+            } else if (diff < 0) {
+                // full
+                return -1;
+            } else {
+                pos = atomic_add(&mq_head__QUEUE_ID, 0);
+            }
+        }
+    }
+
+    int mq_pop__QUEUE_ID(int* value) {
+        uint pos = mq_tail__QUEUE_ID;
+
+        for (;;) {
+            int i = pos % QUEUE_CAPACITY;
+            int diff = mq_position_diff__QUEUE_ID(
+                mq_load_seq__QUEUE_ID(i),
+                mq_next_position__QUEUE_ID(pos, 1)
+            );
+
+            if (diff == 0) {
+                uint r = atomic_cmpxchg(&mq_tail__QUEUE_ID, pos, mq_next_position__QUEUE_ID(pos, 1));
+
+                if (r == pos) {
+                    *value = mq__QUEUE_ID[i];
+
+                    // the slot is free for the next round of the producers
+                    mq_store_seq__QUEUE_ID(i, mq_next_position__QUEUE_ID(pos, QUEUE_CAPACITY));
+                    return i;
+                }
+
+                pos = r;
+            } else if (diff < 0) {
+                // empty (or the item is not published yet)
+                return -1;
+            } else {
+                pos = atomic_add(&mq_tail__QUEUE_ID, 0);
+            }
+        }
+    }
+
+    int mq_get_len__QUEUE_ID() {
+        int len = mq_position_diff__QUEUE_ID(mq_head__QUEUE_ID, mq_tail__QUEUE_ID);
+        return clamp(len, 0, QUEUE_CAPACITY);
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void mq_reset__QUEUE_ID() {
+        int i = get_global_id(0);
+
+        mq__QUEUE_ID[i] = 0;
+        mq_seq__QUEUE_ID[i] = 0;
+
+        if (i == 0) {
+            mq_head__QUEUE_ID = 0, mq_tail__QUEUE_ID = 0;
+        }
+    }
+
+    kernel void mq_debug__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        items_output[i] = mq__QUEUE_ID[i];
+        items_output[i + QUEUE_CAPACITY] = mq_seq__QUEUE_ID[i];
+
+        if (i == 0) {
+            meta_output[0] = mq_head__QUEUE_ID;
+            meta_output[1] = mq_tail__QUEUE_ID;
+        }
+    }
+
+    kernel void get_mq_len__QUEUE_ID(
+        global int* len_output
+        ) {
+        len_output[0] = mq_get_len__QUEUE_ID();
+    }
+
+    // the items are not removed
+    kernel void peek_on_mq__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        uint tail = mq_tail__QUEUE_ID;
+        int len = mq_get_len__QUEUE_ID();
+
+        items_output[i] = (i < len) ? mq__QUEUE_ID[(tail + i) % QUEUE_CAPACITY] : -1;
+
+        if (i == 0) {
+            meta_output[0] = len;
+        }
+    }
+
+    kernel void write_to_mq__QUEUE_ID(
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = mq_push__QUEUE_ID(input[i]);
+    }
+
+    kernel void read_on_mq__QUEUE_ID(
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        int pi = -1;
+        mq_pop__QUEUE_ID(&pi);
+
+        output[i] = pi;
+    }
+
+    // the producers and the consumers are enqueued without waiting each other
+    kernel void write_and_read_on_mq__QUEUE_ID(
+        queue_t q0,
+        const uint input_global_work_size,
+        const uint input_local_work_size,
+        const uint output_global_work_size,
+        const uint output_local_work_size,
+        global int* input,
+        global int* indices_output,
+        global int* values_output,
+        global int* enqueue_kernel_output
+        ) {
+
+        enqueue_kernel_output[0] = enqueue_kernel(
+            q0,
+            CLK_ENQUEUE_FLAGS_NO_WAIT,
+            ndrange_1D(input_global_work_size, input_local_work_size),
+            ^{
+                int i = get_global_id(0);
+                indices_output[i] = mq_push__QUEUE_ID(input[i]);
+            }
+        );
+
+        enqueue_kernel_output[1] = enqueue_kernel(
+            q0,
+            CLK_ENQUEUE_FLAGS_NO_WAIT,
+            ndrange_1D(output_global_work_size, output_local_work_size),
+            ^{
+                int i = get_global_id(0);
+
+                int pi = -1;
+                mq_pop__QUEUE_ID(&pi);
+
+                values_output[i] = pi;
+            }
+        );
+    }
+    "#;
+
+/// greatest multiple of the capacity <= 2^31
+pub fn get_mq_position_wrap(capacity: usize) -> u64 {
+    let wrap: u64 = 1 << 31;
+    wrap / capacity as u64 * capacity as u64
+}
+
+impl QueueSrc {
+    pub fn generate_mpmc_queue_program_source(&self) -> String {
+        let blocks = self.get_configs_by_type(QueueType::Mpmc);
+
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+
+        let mut base_functions = String::new();
+        let mut base_kernels = String::new();
+
+        for config in blocks {
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config).replace(
+                "MQ_POSITION_WRAP",
+                &format!("{}u", get_mq_position_wrap(config.capacity)),
+            );
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config);
+            base_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// *** MPMC QUEUE SRC *** ///
+
+    // mq = mpmc_queue
+
+    /// constants
+    // ...
+
+    /// globals
+    {globals}
+
+    /// kernels
+    {base_functions}
+
+    {base_kernels}
+
+    /// *** MPMC QUEUE SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(8).unwrap();
+
+        let program_source = queue_src.generate_mpmc_queue_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global uint mq_seq__0[8];"));
+        assert!(program_source.contains("kernel void write_and_read_on_mq__0("));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(8).unwrap();
+        queue_src.add_lq(32);
+        queue_src.add_mq(16).unwrap();
+
+        let program_source = queue_src.generate_mpmc_queue_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int mq__0[8];"));
+        assert!(program_source.contains("__global int mq__1[16];"));
+        assert!(!program_source.contains("lq__0"));
+    }
+
+    #[test]
+    fn test_position_wrap() {
+        assert_eq!(get_mq_position_wrap(8), 1 << 31);
+        assert_eq!(get_mq_position_wrap(12), 2147483640);
+
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_mq(12).unwrap();
+
+        let program_source = queue_src.generate_mpmc_queue_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("return (pos + n) % 2147483640u;"));
+        assert!(!program_source.contains("MQ_POSITION_WRAP"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_lq(8);
+
+        let program_source = queue_src.generate_mpmc_queue_program_source();
+        assert!(program_source.is_empty());
+    }
+}
@@ -10,6 +10,9 @@ pub const LINEAR_QUEUE_RESET: &str = "lq_reset__QUEUE_ID";
 pub const WRITE_TO_LINEAR_QUEUE: &str = "write_to_lq__QUEUE_ID";
 pub const READ_ON_LINEAR_QUEUE: &str = "read_on_lq__QUEUE_ID";
 
+pub const GET_LINEAR_QUEUE_LEN: &str = "get_lq_len__QUEUE_ID";
+pub const PEEK_ON_LINEAR_QUEUE: &str = "peek_on_lq__QUEUE_ID";
+
 // priority queue (pq)
 
 pub const PRIORITY_QUEUE_DEBUG: &str = "pq_debug__QUEUE_ID";
@@ -23,6 +26,10 @@ pub const READ_ON_PRIORITY_QUEUE: &str = "read_on_pq__QUEUE_ID";
 pub const WRITE_TO_PRIORITY_QUEUE_AND_SORT: &str = "write_to_pq_and_sort__QUEUE_ID";
 pub const READ_ON_PRIORITY_QUEUE_AND_SORT: &str = "read_on_pq_and_sort__QUEUE_ID";
 
+pub const GET_PRIORITY_QUEUE_LEN: &str = "get_pq_len__QUEUE_ID";
+pub const PEEK_ON_PRIORITY_QUEUE: &str = "peek_on_pq__QUEUE_ID";
+pub const PRIORITY_QUEUE_CHANGE_PRIORITY: &str = "pq_change_priority__QUEUE_ID";
+
 // circular queue (cq)
 
 pub const CIRCULAR_QUEUE_DEBUG: &str = "cq_debug__QUEUE_ID";
@@ -31,6 +38,9 @@ pub const CIRCULAR_QUEUE_RESET: &str = "cq_reset__QUEUE_ID";
 pub const WRITE_TO_CIRCULAR_QUEUE: &str = "write_to_cq__QUEUE_ID";
 pub const READ_ON_CIRCULAR_QUEUE: &str = "read_on_cq__QUEUE_ID";
 
+pub const GET_CIRCULAR_QUEUE_LEN: &str = "get_cq_len__QUEUE_ID";
+pub const PEEK_ON_CIRCULAR_QUEUE: &str = "peek_on_cq__QUEUE_ID";
+
 pub const CIRCULAR_QUEUE_PREPARE_WRITE: &str = "circular_queue_prepare_write__QUEUE_ID";
 pub const CIRCULAR_QUEUE_CONFIRM_WRITE: &str = "circular_queue_confirm_write__QUEUE_ID";
 
@@ -39,3 +49,16 @@ pub const CIRCULAR_QUEUE_CONFIRM_READ: &str = "circular_queue_confirm_read__QUEU
 
 pub const PREPARE_AND_WRITE_TO_CIRCULAR_QUEUE: &str = "prepare_and_write_to_cq__QUEUE_ID";
 pub const PREPARE_AND_READ_ON_CIRCULAR_QUEUE: &str = "prepare_and_read_on_cq__QUEUE_ID";
+
+// mpmc queue (mq)
+
+pub const MPMC_QUEUE_DEBUG: &str = "mq_debug__QUEUE_ID";
+pub const MPMC_QUEUE_RESET: &str = "mq_reset__QUEUE_ID";
+
+pub const WRITE_TO_MPMC_QUEUE: &str = "write_to_mq__QUEUE_ID";
+pub const READ_ON_MPMC_QUEUE: &str = "read_on_mq__QUEUE_ID";
+
+pub const WRITE_AND_READ_ON_MPMC_QUEUE: &str = "write_and_read_on_mq__QUEUE_ID";
+
+pub const GET_MPMC_QUEUE_LEN: &str = "get_mq_len__QUEUE_ID";
+pub const PEEK_ON_MPMC_QUEUE: &str = "peek_on_mq__QUEUE_ID";
@@ -2,8 +2,11 @@
 // resources/priority_queue_ordered_array.c
 // resources/selectionSort.c
 
-use crate::queue::config::{QueueSrc, QueueType};
+use crate::queue::config::{
+    PriorityQueueType, QueueConfig, QueueOverflowPolicy, QueueSrc, QueueType,
+};
 use crate::queue::kernel::common_replace;
+use crate::queue::kernel::priority_heap::{HEAP_BASE_FUNCTIONS, HEAP_BASE_KERNELS};
 
 // FIXME pq_tmp_rear__QUEUE_ID
 
@@ -15,6 +18,48 @@ const GLOBALS: &str = r#"
     __global int pq_value__QUEUE_ID[QUEUE_CAPACITY];
     __global int pq_priority__QUEUE_ID[QUEUE_CAPACITY];
 
+    const int pq_overflow_drop_lowest__QUEUE_ID = PQ_DROP_LOWEST_PRIORITY;
+    "#;
+
+// shared by the ordered and the heap priority queue
+const COMMON_FUNCTIONS: &str = r#"
+    int pq_get_len__QUEUE_ID() {
+        return clamp(pq_rear__QUEUE_ID + 1, 0, QUEUE_CAPACITY);
+    }
+    "#;
+
+const COMMON_KERNELS: &str = r#"
+    kernel void pq_reset__QUEUE_ID() {
+        int i = get_global_id(0);
+
+        pq_value__QUEUE_ID[i] = 0;
+        pq_priority__QUEUE_ID[i] = 0;
+
+        if (i == 0) {
+            pq_tmp_rear__QUEUE_ID = -1, pq_rear__QUEUE_ID = -1;
+        }
+    }
+
+    kernel void pq_debug__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        items_output[i] = pq_value__QUEUE_ID[i];
+        items_output[i + QUEUE_CAPACITY] = pq_priority__QUEUE_ID[i];
+
+        if (i == 0) {
+            meta_output[0] = pq_tmp_rear__QUEUE_ID;
+            meta_output[1] = pq_rear__QUEUE_ID;
+        }
+    }
+
+    kernel void get_pq_len__QUEUE_ID(
+        global int* len_output
+        ) {
+        len_output[0] = pq_get_len__QUEUE_ID();
+    }
     "#;
 
 const BASE_FUNCTIONS: &str = r#"
@@ -63,8 +108,49 @@ const BASE_FUNCTIONS: &str = r#"
             }
 
         }
+
+        // the indices released by read_on_pq (or rejected by write_to_pq) are reused
+        if (pq_rear__QUEUE_ID >= 0) {
+            pq_tmp_rear__QUEUE_ID = pq_rear__QUEUE_ID;
+        }
     
     }
+
+    void pq_drop_lowest_priority__QUEUE_ID(
+        const uint input_len,
+        global int* input,
+        global int* output
+        ) {
+
+        if (!pq_overflow_drop_lowest__QUEUE_ID) {
+            return;
+        }
+
+        for (int i = 0; i < input_len; i++) {
+
+            // enqueued
+            if (output[i] != -1) {
+                continue;
+            }
+
+            int lowest_i = -1;
+
+            for (int j = 0; j <= pq_rear__QUEUE_ID && j < QUEUE_CAPACITY; j++) {
+                if (lowest_i == -1 || pq_priority__QUEUE_ID[j] < pq_priority__QUEUE_ID[lowest_i]) {
+                    lowest_i = j;
+                }
+            }
+
+            int priority = input[i + input_len];
+
+            if (lowest_i != -1 && priority > pq_priority__QUEUE_ID[lowest_i]) {
+                pq_value__QUEUE_ID[lowest_i] = input[i];
+                pq_priority__QUEUE_ID[lowest_i] = priority;
+
+                output[i] = lowest_i;
+            }
+        }
+    }
     
     int pq_push__QUEUE_ID(int* value, int* priority) {
         int rear_i = -1;
@@ -110,32 +196,6 @@ const BASE_FUNCTIONS: &str = r#"
     "#;
 
 const BASE_KERNELS: &str = r#"
-    kernel void pq_reset__QUEUE_ID() {
-        int i = get_global_id(0);
-
-        pq_value__QUEUE_ID[i] = 0;
-        pq_priority__QUEUE_ID[i] = 0;
-
-        if (i == 0) {
-            pq_tmp_rear__QUEUE_ID = -1, pq_rear__QUEUE_ID = -1;
-        }
-    }
-
-    kernel void pq_debug__QUEUE_ID(
-        global int* items_output,
-        global int* meta_output
-        ) {
-        int i = get_global_id(0);
-
-        items_output[i] = pq_value__QUEUE_ID[i];
-        items_output[i + QUEUE_CAPACITY] = pq_priority__QUEUE_ID[i];
-
-        if (i == 0) {
-            meta_output[0] = pq_tmp_rear__QUEUE_ID;
-            meta_output[1] = pq_rear__QUEUE_ID;
-        }
-    }
-
     kernel void priority_queue_sort__QUEUE_ID() {
         pq_selection_sort__QUEUE_ID();
     }
@@ -164,6 +224,47 @@ const BASE_KERNELS: &str = r#"
         output[i] = pi;
     }
 
+    // the items are not removed, the highest priority first
+    kernel void peek_on_pq__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+        int n = get_global_size(0);
+
+        int len = pq_get_len__QUEUE_ID();
+        int index = len - 1 - i;
+
+        items_output[i] = (i < len) ? pq_value__QUEUE_ID[index] : -1;
+        items_output[i + n] = (i < len) ? pq_priority__QUEUE_ID[index] : 0;
+
+        if (i == 0) {
+            meta_output[0] = len;
+        }
+    }
+
+    kernel void pq_change_priority__QUEUE_ID(
+        const int value,
+        const int new_priority,
+        global int* output
+        ) {
+        int len = pq_get_len__QUEUE_ID();
+        int changed = 0;
+
+        for (int i = 0; i < len; i++) {
+            if (pq_value__QUEUE_ID[i] == value) {
+                pq_priority__QUEUE_ID[i] = new_priority;
+                changed++;
+            }
+        }
+
+        if (changed > 0) {
+            pq_selection_sort__QUEUE_ID();
+        }
+
+        output[0] = changed;
+    }
+
     kernel void write_to_pq_and_sort__QUEUE_ID(
         queue_t q0,
         const uint input_global_work_size,
@@ -200,6 +301,7 @@ const BASE_KERNELS: &str = r#"
             &evt0,
             NULL,
             ^{
+               pq_drop_lowest_priority__QUEUE_ID(input_global_work_size, input, output);
                pq_selection_sort__QUEUE_ID();
             }
         );
@@ -249,6 +351,15 @@ const BASE_KERNELS: &str = r#"
     }
     "#;
 
+fn overflow_replace(src: &str, config: &QueueConfig) -> String {
+    let drop_lowest = config.overflow_policy == QueueOverflowPolicy::DropLowestPriority;
+
+    src.replace(
+        "PQ_DROP_LOWEST_PRIORITY",
+        if drop_lowest { "1" } else { "0" },
+    )
+}
+
 impl QueueSrc {
     pub fn generate_priority_queue_program_source(&self) -> String {
         let blocks = self.get_configs_by_type(QueueType::Priority);
@@ -263,13 +374,24 @@ impl QueueSrc {
         let mut base_functions = String::new();
 
         for config in blocks {
-            let template = common_replace(GLOBALS, config);
+            let template = overflow_replace(&common_replace(GLOBALS, config), config);
             globals.push_str(&template);
 
-            let template = common_replace(BASE_FUNCTIONS, config);
+            let (functions, kernels) = match config.priority_queue_type {
+                PriorityQueueType::Ordered => (BASE_FUNCTIONS, BASE_KERNELS),
+                PriorityQueueType::Heap => (HEAP_BASE_FUNCTIONS, HEAP_BASE_KERNELS),
+            };
+
+            let template = common_replace(COMMON_FUNCTIONS, config);
+            base_functions.push_str(&template);
+
+            let template = common_replace(functions, config);
             base_functions.push_str(&template);
 
-            let template = common_replace(BASE_KERNELS, config);
+            let template = common_replace(COMMON_KERNELS, config);
+            base_kernels.push_str(&template);
+
+            let template = common_replace(kernels, config);
             base_kernels.push_str(&template);
         }
 
@@ -322,6 +444,27 @@ mod tests {
         assert!(!program_source.is_empty());
     }
 
+    #[test]
+    fn test_heap() {
+        let mut queue_src = QueueSrc::new();
+        queue_src.add_pq(8);
+        queue_src.add_pq_with_type(16, PriorityQueueType::Heap);
+
+        let program_source = queue_src.generate_priority_queue_program_source();
+        println!("{program_source}");
+
+        assert!(program_source.contains("void pq_selection_sort__0()"));
+        assert!(!program_source.contains("void pq_selection_sort__1()"));
+
+        assert!(program_source.contains("int pq_heap_push__1(int value, int priority)"));
+        assert!(!program_source.contains("pq_heap_push__0"));
+
+        // the kernels of both types have the same names
+        assert!(program_source.contains("kernel void write_to_pq_and_sort__0("));
+        assert!(program_source.contains("kernel void write_to_pq_and_sort__1("));
+        assert!(program_source.contains("kernel void pq_debug__1("));
+    }
+
     #[test]
     fn test_is_empty() {
         let queue_src = QueueSrc::new();
@@ -330,4 +473,21 @@ mod tests {
         println!("{program_source}");
         assert!(program_source.is_empty());
     }
+
+    #[test]
+    fn test_overflow_policy() {
+        let mut queue_src = QueueSrc::new();
+        queue_src
+            .add_with_overflow_policy(
+                8,
+                QueueType::Priority,
+                QueueOverflowPolicy::DropLowestPriority,
+            )
+            .unwrap();
+        queue_src.add_pq(8);
+
+        let program_source = queue_src.generate_priority_queue_program_source();
+        assert!(program_source.contains("const int pq_overflow_drop_lowest__0 = 1;"));
+        assert!(program_source.contains("const int pq_overflow_drop_lowest__1 = 0;"));
+    }
 }
@@ -0,0 +1,263 @@
+// binary max heap, the root (highest priority) at index 0
+//
+// the heap is not safe for concurrent writes, the write and read kernels
+// insert or pop all the items from a single work item (O(log n) per item)
+
+pub const HEAP_BASE_FUNCTIONS: &str = r#"
+    void pq_heap_swap__QUEUE_ID(int a, int b) {
+        int temp_value = pq_value__QUEUE_ID[a];
+
+        pq_value__QUEUE_ID[a] = pq_value__QUEUE_ID[b];
+        pq_value__QUEUE_ID[b] = temp_value;
+
+        int temp_priority = pq_priority__QUEUE_ID[a];
+
+        pq_priority__QUEUE_ID[a] = pq_priority__QUEUE_ID[b];
+        pq_priority__QUEUE_ID[b] = temp_priority;
+    }
+
+    int pq_heap_sift_up__QUEUE_ID(int i) {
+        while (i > 0) {
+            int parent = (i - 1) / 2;
+
+            if (pq_priority__QUEUE_ID[parent] >= pq_priority__QUEUE_ID[i]) {
+                break;
+            }
+
+            pq_heap_swap__QUEUE_ID(parent, i);
+            i = parent;
+        }
+
+        return i;
+    }
+
+    void pq_heap_sift_down__QUEUE_ID(int i) {
+        int len = pq_get_len__QUEUE_ID();
+
+        for (;;) {
+            int left = (2 * i) + 1;
+            int right = left + 1;
+            int largest = i;
+
+            if (left < len && pq_priority__QUEUE_ID[left] > pq_priority__QUEUE_ID[largest]) {
+                largest = left;
+            }
+
+            if (right < len && pq_priority__QUEUE_ID[right] > pq_priority__QUEUE_ID[largest]) {
+                largest = right;
+            }
+
+            if (largest == i) {
+                break;
+            }
+
+            pq_heap_swap__QUEUE_ID(largest, i);
+            i = largest;
+        }
+    }
+
+    void pq_heapify__QUEUE_ID() {
+        for (int i = (pq_get_len__QUEUE_ID() / 2) - 1; i >= 0; i--) {
+            pq_heap_sift_down__QUEUE_ID(i);
+        }
+    }
+
+    // the lowest priority is one of the leaves
+    int pq_heap_drop_lowest_priority__QUEUE_ID(int value, int priority) {
+        if (!pq_overflow_drop_lowest__QUEUE_ID) {
+            return -1;
+        }
+
+        int len = pq_get_len__QUEUE_ID();
+        int lowest_i = -1;
+
+        for (int j = len / 2; j < len; j++) {
+            if (lowest_i == -1 || pq_priority__QUEUE_ID[j] < pq_priority__QUEUE_ID[lowest_i]) {
+                lowest_i = j;
+            }
+        }
+
+        if (lowest_i == -1 || priority <= pq_priority__QUEUE_ID[lowest_i]) {
+            return -1;
+        }
+
+        pq_value__QUEUE_ID[lowest_i] = value;
+        pq_priority__QUEUE_ID[lowest_i] = priority;
+
+        return pq_heap_sift_up__QUEUE_ID(lowest_i);
+    }
+
+    int pq_heap_push__QUEUE_ID(int value, int priority) {
+        if (pq_rear__QUEUE_ID >= QUEUE_MAX_CAPACITY) {
+            return pq_heap_drop_lowest_priority__QUEUE_ID(value, priority);
+        }
+
+        pq_rear__QUEUE_ID++;
+        pq_tmp_rear__QUEUE_ID = pq_rear__QUEUE_ID;
+
+        pq_value__QUEUE_ID[pq_rear__QUEUE_ID] = value;
+        pq_priority__QUEUE_ID[pq_rear__QUEUE_ID] = priority;
+
+        return pq_heap_sift_up__QUEUE_ID(pq_rear__QUEUE_ID);
+    }
+
+    int pq_heap_pop__QUEUE_ID(int* value) {
+        if (pq_rear__QUEUE_ID < 0) {
+            return -1;
+        }
+
+        *value = pq_value__QUEUE_ID[0];
+
+        int last = pq_rear__QUEUE_ID;
+
+        pq_value__QUEUE_ID[0] = pq_value__QUEUE_ID[last];
+        pq_priority__QUEUE_ID[0] = pq_priority__QUEUE_ID[last];
+
+        pq_value__QUEUE_ID[last] = 0;
+        pq_priority__QUEUE_ID[last] = 0;
+
+        pq_rear__QUEUE_ID--;
+        pq_tmp_rear__QUEUE_ID = pq_rear__QUEUE_ID;
+
+        pq_heap_sift_down__QUEUE_ID(0);
+
+        return 0;
+    }
+    "#;
+
+pub const HEAP_BASE_KERNELS: &str = r#"
+    kernel void priority_queue_sort__QUEUE_ID() {
+        pq_heapify__QUEUE_ID();
+    }
+
+    kernel void write_to_pq__QUEUE_ID(
+        const uint input_global_work_size,
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        if (i != 0) {
+            return;
+        }
+
+        for (int j = 0; j < input_global_work_size; j++) {
+            output[j] = pq_heap_push__QUEUE_ID(
+                input[j], // value
+                input[j + input_global_work_size] // priority
+            );
+        }
+    }
+
+    kernel void read_on_pq__QUEUE_ID(
+        global int* output
+        ) {
+        int i = get_global_id(0);
+
+        if (i != 0) {
+            return;
+        }
+
+        for (int j = 0; j < get_global_size(0); j++) {
+            int pi = -1;
+            pq_heap_pop__QUEUE_ID(&pi);
+
+            output[j] = pi;
+        }
+    }
+
+    // same arguments as the ordered priority queue, no kernel is enqueued
+    kernel void write_to_pq_and_sort__QUEUE_ID(
+        queue_t q0,
+        const uint input_global_work_size,
+        const uint input_local_work_size,
+        global int* input,
+        global int* output,
+        global int* enqueue_kernel_output
+        ) {
+
+        for (int j = 0; j < input_global_work_size; j++) {
+            output[j] = pq_heap_push__QUEUE_ID(
+                input[j], // value
+                input[j + input_global_work_size] // priority
+            );
+        }
+
+        enqueue_kernel_output[0] = 0;
+        enqueue_kernel_output[1] = 0;
+    }
+
+    kernel void read_on_pq_and_sort__QUEUE_ID(
+        queue_t q0,
+        const uint output_global_work_size,
+        const uint output_local_work_size,
+        global int* output,
+        global int* enqueue_kernel_output
+        ) {
+
+        for (int j = 0; j < output_global_work_size; j++) {
+            int pi = -1;
+            pq_heap_pop__QUEUE_ID(&pi);
+
+            output[j] = pi;
+        }
+
+        enqueue_kernel_output[0] = 0;
+        enqueue_kernel_output[1] = 0;
+    }
+
+    // the items are not removed, the highest priority first
+    kernel void peek_on_pq__QUEUE_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+        int n = get_global_size(0);
+
+        int len = pq_get_len__QUEUE_ID();
+
+        // the position of the item j is the number of items before it
+        for (int j = i; j < len; j += n) {
+            int rank = 0;
+
+            for (int k = 0; k < len; k++) {
+                int priority = pq_priority__QUEUE_ID[k];
+
+                if (priority > pq_priority__QUEUE_ID[j] || (priority == pq_priority__QUEUE_ID[j] && k < j)) {
+                    rank++;
+                }
+            }
+
+            if (rank < n) {
+                items_output[rank] = pq_value__QUEUE_ID[j];
+                items_output[rank + n] = pq_priority__QUEUE_ID[j];
+            }
+        }
+
+        if (i == 0) {
+            meta_output[0] = len;
+        }
+    }
+
+    kernel void pq_change_priority__QUEUE_ID(
+        const int value,
+        const int new_priority,
+        global int* output
+        ) {
+        int len = pq_get_len__QUEUE_ID();
+        int changed = 0;
+
+        for (int i = 0; i < len; i++) {
+            if (pq_value__QUEUE_ID[i] == value) {
+                pq_priority__QUEUE_ID[i] = new_priority;
+                changed++;
+            }
+        }
+
+        if (changed > 0) {
+            pq_heapify__QUEUE_ID();
+        }
+
+        output[0] = changed;
+    }
+    "#;
@@ -1,4 +1,7 @@
-use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_ARRAY_SET_ID};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_ARRAY_SET_ID,
+    CL_COLLECTION_INVALID_HASH_SET_ID,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ArraySetVersion {
@@ -10,7 +13,7 @@ pub enum ArraySetVersion {
 pub enum SetType {
     ArraySet,
     // Set,
-    // HashSet,
+    HashSet,
 }
 
 /// ...
@@ -24,6 +27,10 @@ pub enum SetType {
 /// __global int array_set__SET_ID[SET_CAPACITY];
 /// __global int array_set_entries__SET_ID[SET_CAPACITY];
 ///
+/// // hash set (open addressing, -1 empty, -2 tombstone)
+/// __global int hash_set__SET_ID[SET_CAPACITY];
+/// __global int hash_set_len__SET_ID;
+///
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct SetConfig {
@@ -56,6 +63,10 @@ impl SetSrc {
         &self.blocks
     }
 
+    pub(crate) fn get_configs_mut(&mut self) -> &mut Vec<SetConfig> {
+        &mut self.blocks
+    }
+
     pub fn get_configs_by_type(&self, set_type: SetType) -> Vec<&SetConfig> {
         self.blocks
             .iter()
@@ -84,6 +95,25 @@ impl SetSrc {
         }
     }
 
+    pub fn add_hash_set(&mut self, capacity: usize) {
+        let id = self.blocks.len();
+
+        self.blocks
+            .push(SetConfig::new(id, capacity, SetType::HashSet));
+    }
+
+    /// Add a copy of the config (of another src) with a new id, the new id is returned
+    pub(crate) fn add_config(&mut self, config: &SetConfig) -> usize {
+        let id = self.blocks.len();
+
+        self.blocks.push(SetConfig {
+            id,
+            ..config.clone()
+        });
+
+        id
+    }
+
     pub fn get_config_by_id(&self, id: usize) -> OpenClResult<&SetConfig> {
         match self.blocks.iter().find(|x| x.id == id) {
             None => Err(OpenclError::OpenclCollection(
@@ -92,6 +122,19 @@ impl SetSrc {
             Some(c) => Ok(c),
         }
     }
+
+    pub fn get_hash_set_by_id(&self, id: usize) -> OpenClResult<&SetConfig> {
+        match self
+            .blocks
+            .iter()
+            .find(|x| x.id == id && x.set_type == SetType::HashSet)
+        {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_HASH_SET_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
 }
 
 impl Default for SetSrc {
@@ -142,4 +185,30 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_set_src_add_hash_set() {
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+        set_src.add_hash_set(16);
+        set_src.add_hash_set(32);
+
+        let ids: Vec<usize> = set_src
+            .get_configs_by_type(SetType::HashSet)
+            .iter()
+            .map(|x| x.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        assert_eq!(
+            set_src.get_hash_set_by_id(1),
+            Ok(&SetConfig::new(1, 16, SetType::HashSet))
+        );
+        assert_eq!(
+            set_src.get_hash_set_by_id(0),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_HASH_SET_ID
+            ))
+        );
+    }
 }
@@ -2,7 +2,8 @@ use crate::config::DEBUG_MODE;
 use crate::error::OpenClResult;
 use crate::set::config::SetConfig;
 use crate::set::kernel::name::{
-    get_set_kernel_name, ARRAY_SET_DEBUG, ARRAY_SET_RESET, REMOVE_IN_ARRAY_SET, WRITE_IN_ARRAY_SET,
+    get_set_kernel_name, ARRAY_SET_DEBUG, ARRAY_SET_RESET, CONTAINS_IN_ARRAY_SET,
+    GET_ARRAY_SET_LEN, REMOVE_IN_ARRAY_SET, WRITE_IN_ARRAY_SET,
 };
 use opencl::opencl_sys::bindings::cl_int;
 use opencl::wrapper::system::OpenclCommonOperation;
@@ -120,6 +121,69 @@ impl<T: OpenclCommonOperation> ArraySetHandle<T> {
         self.reset()
     }
 
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_set_kernel_name(GET_ARRAY_SET_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn contains(&self, input: &[cl_int]) -> OpenClResult<Vec<bool>> {
+        let global_work_size = input.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_set_kernel_name(CONTAINS_IN_ARRAY_SET, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("contains output {output:?}");
+        }
+
+        Ok(output.into_iter().map(|x| x >= 0).collect())
+    }
+
     pub fn insert(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
         let global_work_size = input.len();
         let local_work_size = self
@@ -769,3 +833,125 @@ mod tests_array_set_remove {
         assert_eq!(ns, ArraySetSnapshot::create_empty(set_capacity));
     }
 }
+
+#[cfg(test)]
+mod tests_array_set_contains {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::set::config::{ArraySetVersion, SetSrc};
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn array_set_is_empty() {
+        let set_capacity = 32;
+
+        let mut set_src = SetSrc::new();
+        set_src.add(set_capacity);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config = set_src.get_config_by_id(0).unwrap();
+        let set = ArraySetHandle::new(config, system);
+
+        set.initialize().unwrap();
+
+        let input: Vec<cl_int> = (-1..set_capacity as cl_int).collect();
+
+        let contains = set.contains(&input).unwrap();
+        assert_eq!(contains, vec![false; set_capacity + 1]);
+    }
+
+    #[test]
+    fn some_values_exist() {
+        let set_capacity = 32;
+
+        let mut set_src = SetSrc::new();
+        set_src.add(set_capacity);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config = set_src.get_config_by_id(0).unwrap();
+        let set = ArraySetHandle::new(config, system);
+
+        set.initialize().unwrap();
+
+        let input: Vec<cl_int> = (0..set_capacity as cl_int).collect();
+        let even: Vec<cl_int> = input.iter().copied().filter(|x| x % 2 == 0).collect();
+
+        set.insert(&even).unwrap();
+
+        let contains = set.contains(&input).unwrap();
+        let expected: Vec<bool> = input.iter().map(|x| x % 2 == 0).collect();
+
+        assert_eq!(contains, expected);
+    }
+}
+
+#[cfg(test)]
+mod tests_array_set_len {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::set::config::{ArraySetVersion, SetSrc};
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn insert_and_remove() {
+        let set_capacity = 32;
+
+        let mut set_src = SetSrc::new();
+        set_src.add(set_capacity);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config = set_src.get_config_by_id(0).unwrap();
+        let set = ArraySetHandle::new(config, system);
+
+        set.initialize().unwrap();
+        assert_eq!(set.len(), Ok(0));
+        assert_eq!(set.is_empty(), Ok(true));
+
+        let input: Vec<cl_int> = (0..set_capacity as cl_int).collect();
+
+        set.insert(&input[0..10]).unwrap();
+        assert_eq!(set.len(), Ok(10));
+
+        // the existing values are not counted again
+        set.insert(&input[5..20]).unwrap();
+        assert_eq!(set.len(), Ok(20));
+
+        set.remove(&input[0..5]).unwrap();
+        assert_eq!(set.len(), Ok(15));
+
+        // full
+        set.insert(&input).unwrap();
+        assert_eq!(set.len(), Ok(set_capacity));
+
+        set.reset().unwrap();
+        assert_eq!(set.len(), Ok(0));
+    }
+
+    #[test]
+    fn repeated_values() {
+        let set_capacity = 32;
+
+        let mut set_src = SetSrc::new();
+        set_src.add(set_capacity);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config = set_src.get_config_by_id(0).unwrap();
+        let set = ArraySetHandle::new(config, system);
+
+        set.initialize().unwrap();
+
+        set.insert(&vec![1; set_capacity]).unwrap();
+        assert_eq!(set.len(), Ok(1));
+
+        set.remove(&vec![1; set_capacity]).unwrap();
+        assert_eq!(set.len(), Ok(0));
+    }
+}
@@ -0,0 +1,661 @@
+use crate::config::DEBUG_MODE;
+use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_HASH_SET_ID};
+use crate::set::config::{SetConfig, SetType};
+use crate::set::kernel::name::{
+    get_set_kernel_name, CONTAINS_IN_HASH_SET, GET_HASH_SET_LEN, HASH_SET_DEBUG,
+    HASH_SET_DIFFERENCE, HASH_SET_INTERSECTION, HASH_SET_RESET, HASH_SET_UNION, REMOVE_IN_HASH_SET,
+    WRITE_IN_HASH_SET,
+};
+use opencl::opencl_sys::bindings::cl_int;
+use opencl::wrapper::system::OpenclCommonOperation;
+
+pub const HASH_SET_EMPTY: cl_int = -1;
+pub const HASH_SET_TOMBSTONE: cl_int = -2;
+
+fn is_hash_set_item(k: cl_int) -> bool {
+    k != HASH_SET_EMPTY && k != HASH_SET_TOMBSTONE
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HashSetSnapshot {
+    pub items: Vec<cl_int>,
+    pub len: usize,
+}
+
+impl HashSetSnapshot {
+    pub fn new(items: Vec<cl_int>, len: usize) -> Self {
+        Self { items, len }
+    }
+
+    pub fn create_empty(capacity: usize) -> Self {
+        Self::new(vec![HASH_SET_EMPTY; capacity], 0)
+    }
+
+    /// items stored in the set (without empty slots and tombstones), sorted
+    pub fn get_items(&self) -> Vec<cl_int> {
+        let mut items: Vec<cl_int> = self
+            .items
+            .iter()
+            .copied()
+            .filter(|&x| is_hash_set_item(x))
+            .collect();
+        items.sort();
+        items
+    }
+
+    pub fn get_tombstones(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|&&x| x == HASH_SET_TOMBSTONE)
+            .count()
+    }
+}
+
+/// ...
+///
+/// the items -1 (empty) and -2 (tombstone) can not be stored in the set,
+/// insert returns -1 for them.
+#[derive(Debug)]
+pub struct HashSetHandle<T: OpenclCommonOperation> {
+    pub config: SetConfig,
+    pub(crate) system: T,
+}
+
+impl<T: OpenclCommonOperation> HashSetHandle<T> {
+    pub fn new(config: &SetConfig, system: T) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn debug(&self) -> OpenClResult<HashSetSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_capacity = self.config.capacity + 1;
+
+        let output_buf = self.system.create_output_buffer(output_capacity)?;
+
+        let kernel_name = get_set_kernel_name(HASH_SET_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(output_capacity, &output_buf, &[])?;
+
+        let capacity = self.config.capacity;
+
+        Ok(HashSetSnapshot::new(
+            output[0..capacity].to_vec(),
+            output[capacity] as usize,
+        ))
+    }
+
+    pub fn print(&self) -> OpenClResult<HashSetSnapshot> {
+        let sn = self.debug()?;
+        println!(
+            "HashSetSnapshot (
+            items: {:?},
+            len:   {}
+        )",
+            sn.items, sn.len
+        );
+        Ok(sn)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_set_kernel_name(HASH_SET_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn initialize(&self) -> OpenClResult<()> {
+        self.reset()
+    }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_set_kernel_name(GET_HASH_SET_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// index of the slot of each item, -1 if the set is full
+    pub fn insert(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        self.enqueue_items_kernel(WRITE_IN_HASH_SET, input)
+    }
+
+    /// index of the slot of each item, -1 if the item does not exist
+    pub fn contains_indices(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        self.enqueue_items_kernel(CONTAINS_IN_HASH_SET, input)
+    }
+
+    pub fn contains(&self, input: &[cl_int]) -> OpenClResult<Vec<bool>> {
+        let indices = self.contains_indices(input)?;
+        Ok(indices.into_iter().map(|x| x >= 0).collect())
+    }
+
+    /// index of the slot of each removed item, -1 if the item does not exist
+    pub fn remove(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
+        let global_work_size = input.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let indices_buf = self.system.create_output_buffer(global_work_size)?;
+
+        // the slots are found before any item is removed, so repeated items
+        // get the same index
+        let kernel_name = get_set_kernel_name(CONTAINS_IN_HASH_SET, self.get_id());
+        let mut contains_kernel = self.system.create_kernel(&kernel_name)?;
+
+        let kernel_name = get_set_kernel_name(REMOVE_IN_HASH_SET, self.get_id());
+        let mut remove_kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            contains_kernel.set_arg(&input_buf.get_cl_mem())?;
+            contains_kernel.set_arg(&indices_buf.get_cl_mem())?;
+
+            contains_kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+
+            remove_kernel.set_arg(&input_buf.get_cl_mem())?;
+            remove_kernel.set_arg(&indices_buf.get_cl_mem())?;
+
+            remove_kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &indices_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("remove output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// items of both sets, the other set must be a hash set of the same program
+    pub fn union(&self, other: &SetConfig) -> OpenClResult<Vec<cl_int>> {
+        check_hash_set(other)?;
+
+        let global_work_size = self.config.capacity + other.capacity;
+
+        let a_id = self.get_id() as cl_int;
+        let a_capacity = self.config.capacity as cl_int;
+        let b_id = other.id as cl_int;
+
+        self.enqueue_set_algebra_kernel(HASH_SET_UNION, global_work_size, &[a_id, a_capacity, b_id])
+    }
+
+    /// items of this set that exist in the other set
+    pub fn intersection(&self, other: &SetConfig) -> OpenClResult<Vec<cl_int>> {
+        check_hash_set(other)?;
+
+        let a_id = self.get_id() as cl_int;
+        let b_id = other.id as cl_int;
+
+        self.enqueue_set_algebra_kernel(HASH_SET_INTERSECTION, self.config.capacity, &[a_id, b_id])
+    }
+
+    /// items of this set that do not exist in the other set
+    pub fn difference(&self, other: &SetConfig) -> OpenClResult<Vec<cl_int>> {
+        check_hash_set(other)?;
+
+        let a_id = self.get_id() as cl_int;
+        let b_id = other.id as cl_int;
+
+        self.enqueue_set_algebra_kernel(HASH_SET_DIFFERENCE, self.config.capacity, &[a_id, b_id])
+    }
+
+    fn enqueue_items_kernel(
+        &self,
+        kernel_name: &str,
+        input: &[cl_int],
+    ) -> OpenClResult<Vec<cl_int>> {
+        let global_work_size = input.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_set_kernel_name(kernel_name, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("{kernel_name} output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    fn enqueue_set_algebra_kernel(
+        &self,
+        kernel_name: &str,
+        global_work_size: usize,
+        args: &[cl_int],
+    ) -> OpenClResult<Vec<cl_int>> {
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let mut kernel = self.system.create_kernel(kernel_name)?;
+
+        unsafe {
+            for arg in args {
+                kernel.set_arg(arg)?;
+            }
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        Ok(output
+            .into_iter()
+            .filter(|&x| is_hash_set_item(x))
+            .collect())
+    }
+}
+
+fn check_hash_set(config: &SetConfig) -> OpenClResult<()> {
+    if config.set_type != SetType::HashSet {
+        return Err(OpenclError::OpenclCollection(
+            CL_COLLECTION_INVALID_HASH_SET_ID,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_hash_set_snapshot {
+    use super::*;
+
+    #[test]
+    fn get_items() {
+        let sn = HashSetSnapshot::new(vec![5, -1, 2, -2, -2, 9, -1, 0], 4);
+
+        assert_eq!(sn.get_items(), vec![0, 2, 5, 9]);
+        assert_eq!(sn.get_tombstones(), 2);
+
+        let sn = HashSetSnapshot::create_empty(8);
+        assert!(sn.get_items().is_empty());
+        assert_eq!(sn.get_tombstones(), 0);
+    }
+
+    #[test]
+    fn other_set_is_not_a_hash_set() {
+        let config = SetConfig::new(0, 8, SetType::ArraySet);
+
+        assert_eq!(
+            check_hash_set(&config),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_HASH_SET_ID
+            ))
+        );
+        assert_eq!(
+            check_hash_set(&SetConfig::new(1, 8, SetType::HashSet)),
+            Ok(())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_hash_set_insert {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::set::config::{ArraySetVersion, SetSrc};
+    use crate::utils::has_unique_elements;
+    use opencl::wrapper::system::System;
+
+    fn create_hash_set(capacity: usize) -> HashSetHandle<System> {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(capacity);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config = set_src.get_hash_set_by_id(0).unwrap();
+        let set = HashSetHandle::new(config, system);
+        set.initialize().unwrap();
+        set
+    }
+
+    #[test]
+    fn hash_set_is_empty() {
+        let set_capacity = 32;
+        let set = create_hash_set(set_capacity);
+
+        let sn = set.print().unwrap();
+        assert_eq!(sn, HashSetSnapshot::create_empty(set_capacity));
+
+        assert_eq!(set.len(), Ok(0));
+        assert_eq!(set.is_empty(), Ok(true));
+    }
+
+    #[test]
+    fn hash_set_is_full() {
+        let set_capacity = 1024;
+        let set = create_hash_set(set_capacity);
+
+        let input: Vec<cl_int> = (0..set_capacity as cl_int).collect();
+
+        let indices = set.insert(&input).unwrap();
+        assert!(has_unique_elements(&indices));
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        let sn = set.print().unwrap();
+        assert_eq!(sn.get_items(), input);
+        assert_eq!(set.len(), Ok(set_capacity));
+
+        let input_2: Vec<cl_int> = input.iter().map(|x| x + set_capacity as cl_int).collect();
+
+        let indices = set.insert(&input_2).unwrap();
+        assert_eq!(indices, vec![-1; set_capacity]);
+        assert_eq!(set.len(), Ok(set_capacity));
+    }
+
+    #[test]
+    fn all_values_exist() {
+        let set_capacity = 64;
+        let set = create_hash_set(set_capacity);
+
+        let input: Vec<cl_int> = (0..32).collect();
+
+        let indices = set.insert(&input).unwrap();
+        let indices_2 = set.insert(&input).unwrap();
+
+        assert_eq!(indices, indices_2);
+        assert_eq!(set.len(), Ok(32));
+    }
+
+    #[test]
+    fn repeated_values() {
+        let set_capacity = 1024;
+        let set = create_hash_set(set_capacity);
+
+        let input: Vec<cl_int> = (0..set_capacity).map(|x| (x % 3) as cl_int).collect();
+
+        let indices = set.insert(&input).unwrap();
+
+        assert_eq!(indices[0], indices[3]);
+        assert_eq!(indices[1], indices[4]);
+        assert_eq!(indices[2], indices[5]);
+
+        let sn = set.print().unwrap();
+        assert_eq!(sn.get_items(), vec![0, 1, 2]);
+        assert_eq!(sn.len, 3);
+    }
+
+    #[test]
+    fn reserved_values() {
+        let set = create_hash_set(16);
+
+        let indices = set
+            .insert(&[HASH_SET_EMPTY, HASH_SET_TOMBSTONE, -3])
+            .unwrap();
+
+        assert_eq!(indices[0], -1);
+        assert_eq!(indices[1], -1);
+        assert!(indices[2] >= 0);
+
+        assert_eq!(set.len(), Ok(1));
+    }
+}
+
+#[cfg(test)]
+mod tests_hash_set_remove {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::set::config::{ArraySetVersion, SetSrc};
+    use opencl::wrapper::system::System;
+
+    fn create_hash_set(capacity: usize) -> HashSetHandle<System> {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(capacity);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config = set_src.get_hash_set_by_id(0).unwrap();
+        let set = HashSetHandle::new(config, system);
+        set.initialize().unwrap();
+        set
+    }
+
+    #[test]
+    fn none_of_the_values_exist() {
+        let set_capacity = 32;
+        let set = create_hash_set(set_capacity);
+
+        let input: Vec<cl_int> = (0..set_capacity as cl_int).collect();
+
+        let indices = set.remove(&input).unwrap();
+        assert_eq!(indices, vec![-1; set_capacity]);
+
+        let sn = set.print().unwrap();
+        assert_eq!(sn, HashSetSnapshot::create_empty(set_capacity));
+    }
+
+    #[test]
+    fn all_values_exist() {
+        let set_capacity = 256;
+        let set = create_hash_set(set_capacity);
+
+        let input: Vec<cl_int> = (0..set_capacity as cl_int).collect();
+
+        let inserted = set.insert(&input).unwrap();
+        let removed = set.remove(&input).unwrap();
+
+        assert_eq!(inserted, removed);
+
+        let sn = set.print().unwrap();
+        assert!(sn.get_items().is_empty());
+        assert_eq!(sn.get_tombstones(), set_capacity);
+        assert_eq!(set.len(), Ok(0));
+
+        // the tombstones are reused
+        let indices = set.insert(&input).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+        assert_eq!(set.len(), Ok(set_capacity));
+    }
+
+    #[test]
+    fn repeated_values() {
+        let set_capacity = 32;
+        let set = create_hash_set(set_capacity);
+
+        let indices = set.insert(&[7]).unwrap();
+
+        let removed = set.remove(&[7; 16]).unwrap();
+        assert_eq!(removed, vec![indices[0]; 16]);
+
+        assert_eq!(set.len(), Ok(0));
+        assert_eq!(set.contains(&[7]), Ok(vec![false]));
+    }
+
+    #[test]
+    fn contains_after_remove() {
+        let set_capacity = 64;
+        let set = create_hash_set(set_capacity);
+
+        let input: Vec<cl_int> = (0..set_capacity as cl_int).collect();
+        set.insert(&input).unwrap();
+
+        let even: Vec<cl_int> = input.iter().copied().filter(|x| x % 2 == 0).collect();
+        set.remove(&even).unwrap();
+
+        let contains = set.contains(&input).unwrap();
+        let expected: Vec<bool> = input.iter().map(|x| x % 2 != 0).collect();
+
+        assert_eq!(contains, expected);
+        assert_eq!(set.len(), Ok(set_capacity / 2));
+    }
+}
+
+#[cfg(test)]
+mod tests_hash_set_algebra {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::set::config::{ArraySetVersion, SetSrc};
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn union_intersection_and_difference() {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(32);
+        set_src.add_hash_set(64);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config_a = set_src.get_hash_set_by_id(0).unwrap();
+        let config_b = set_src.get_hash_set_by_id(1).unwrap();
+
+        let set_a = HashSetHandle::new(config_a, &system);
+        let set_b = HashSetHandle::new(config_b, &system);
+
+        set_a.initialize().unwrap();
+        set_b.initialize().unwrap();
+
+        set_a.insert(&(0..20).collect::<Vec<cl_int>>()).unwrap();
+        set_b.insert(&(10..40).collect::<Vec<cl_int>>()).unwrap();
+
+        let mut union = set_a.union(config_b).unwrap();
+        union.sort();
+        assert_eq!(union, (0..40).collect::<Vec<cl_int>>());
+
+        let mut intersection = set_a.intersection(config_b).unwrap();
+        intersection.sort();
+        assert_eq!(intersection, (10..20).collect::<Vec<cl_int>>());
+
+        let mut difference = set_a.difference(config_b).unwrap();
+        difference.sort();
+        assert_eq!(difference, (0..10).collect::<Vec<cl_int>>());
+
+        let mut difference = set_b.difference(config_a).unwrap();
+        difference.sort();
+        assert_eq!(difference, (20..40).collect::<Vec<cl_int>>());
+    }
+
+    #[test]
+    fn other_set_is_empty() {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(32);
+        set_src.add_hash_set(32);
+
+        let system =
+            System::new(DEFAULT_DEVICE_INDEX, &set_src.build(ArraySetVersion::V2)).unwrap();
+
+        let config_a = set_src.get_hash_set_by_id(0).unwrap();
+        let config_b = set_src.get_hash_set_by_id(1).unwrap();
+
+        let set_a = HashSetHandle::new(config_a, &system);
+        let set_b = HashSetHandle::new(config_b, &system);
+
+        set_a.initialize().unwrap();
+        set_b.initialize().unwrap();
+
+        set_a.insert(&[1, 2, 3]).unwrap();
+
+        let mut union = set_a.union(config_b).unwrap();
+        union.sort();
+        assert_eq!(union, vec![1, 2, 3]);
+
+        assert!(set_a.intersection(config_b).unwrap().is_empty());
+        assert!(set_b.difference(config_a).unwrap().is_empty());
+    }
+}
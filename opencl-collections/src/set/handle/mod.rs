@@ -1,2 +1,3 @@
 pub mod array_set_v1;
 pub mod array_set_v2;
+pub mod hash_set;
@@ -3,6 +3,7 @@ use crate::set::kernel::common_replace;
 
 const GLOBALS: &str = r#"
     __global int array_set__SET_ID[SET_CAPACITY];
+    __global int array_set_len__SET_ID = 0;
     
     __global int tmp_set__SET_ID[SET_CAPACITY];
     __global int tmp_set_top__SET_ID = -1;
@@ -41,9 +42,35 @@ const BASE_FUNCTIONS: &str = r#"
         }
                 
         for (int i = 0; i < SET_CAPACITY; i++) {
-        
-            if ( array_set__SET_ID[i] == -1 ) {
-                array_set__SET_ID[i] = *k;
+
+            int current = array_set__SET_ID[i];
+
+            // inserted by another work item after the first loop
+            if ( current == *k ) {
+                return i;
+            }
+
+            if ( current != -1 ) {
+                continue;
+            }
+
+            int r = atomic_cmpxchg(&array_set__SET_ID[i], -1, *k);
+
+            if ( r == -1 ) {
+                // the same item can be claimed in a previous slot (released by remove),
+                // the lower slot is kept
+                for (int j = 0; j < i; j++) {
+                    if ( array_set__SET_ID[j] == *k ) {
+                        atomic_xchg(&array_set__SET_ID[i], -1);
+                        return j;
+                    }
+                }
+
+                atomic_inc(&array_set_len__SET_ID);
+                return i;
+            }
+
+            if ( r == *k ) {
                 return i;
             }
             
@@ -56,7 +83,11 @@ const BASE_FUNCTIONS: &str = r#"
 
         for (int i = 0; i < SET_CAPACITY; i++) {
             if ( array_set__SET_ID[i] == *k ) {
-                array_set__SET_ID[i] = -1;
+
+                if ( atomic_cmpxchg(&array_set__SET_ID[i], *k, -1) == *k ) {
+                    atomic_dec(&array_set_len__SET_ID);
+                }
+
                 return i;
             }
         }
@@ -79,6 +110,9 @@ const BASE_KERNELS: &str = r#"
 
         array_set__SET_ID[i] = -1;
 
+        if (i == 0) {
+            array_set_len__SET_ID = 0;
+        }
     }
     
     kernel void write_in_array_set__SET_ID(
@@ -99,6 +133,24 @@ const BASE_KERNELS: &str = r#"
         indices_output[i] = array_set_remove__SET_ID(&items_input[i]);
     }
 
+    kernel void contains_in_array_set__SET_ID(
+        global int* items_input,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        int k = items_input[i];
+
+        // -1 is the empty item
+        indices_output[i] = k == -1 ? -1 : array_set_get_index__SET_ID(&k);
+    }
+
+    kernel void get_array_set_len__SET_ID(
+        global int* len_output
+        ) {
+        len_output[0] = array_set_len__SET_ID;
+    }
+
     kernel void write_with_cmq_in_array_set__SET_ID(
         queue_t q0,
         global int* items_input,
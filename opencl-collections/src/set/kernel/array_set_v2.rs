@@ -4,6 +4,7 @@ use crate::set::kernel::common_replace;
 const GLOBALS: &str = r#"
     __global int array_set__SET_ID[SET_CAPACITY];
     __global int array_set_entries__SET_ID[SET_CAPACITY];
+    __global int array_set_len__SET_ID = 0;
     "#;
 
 const BASE_FUNCTIONS: &str = r#"    
@@ -55,6 +56,7 @@ const BASE_FUNCTIONS: &str = r#"
             if ( r == 0 ) {
 
                 array_set__SET_ID[i] = *k;
+                atomic_inc(&array_set_len__SET_ID);
 
                 return i;
 
@@ -68,9 +70,11 @@ const BASE_FUNCTIONS: &str = r#"
 
         for (int i = 0; i < SET_CAPACITY; i++) {
             if ( array_set__SET_ID[i] == *k ) {
-            
-                array_set__SET_ID[i] = -1;
-                array_set_entries__SET_ID[i] = 0;
+
+                if ( atomic_cmpxchg(&array_set__SET_ID[i], *k, -1) == *k ) {
+                    array_set_entries__SET_ID[i] = 0;
+                    atomic_dec(&array_set_len__SET_ID);
+                }
                 
                 return i;
             }
@@ -96,6 +100,10 @@ const BASE_KERNELS: &str = r#"
 
         array_set__SET_ID[i] = -1;
         array_set_entries__SET_ID[i] = 0;
+
+        if (i == 0) {
+            array_set_len__SET_ID = 0;
+        }
     }
     
     kernel void write_in_array_set__SET_ID(
@@ -115,6 +123,24 @@ const BASE_KERNELS: &str = r#"
 
         indices_output[i] = array_set_remove__SET_ID(&items_input[i]);
     }
+
+    kernel void contains_in_array_set__SET_ID(
+        global int* items_input,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        int k = items_input[i];
+
+        // -1 is the empty item
+        indices_output[i] = k == -1 ? -1 : array_set_get_index__SET_ID(&k);
+    }
+
+    kernel void get_array_set_len__SET_ID(
+        global int* len_output
+        ) {
+        len_output[0] = array_set_len__SET_ID;
+    }
     "#;
 
 impl SetSrc {
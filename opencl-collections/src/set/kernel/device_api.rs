@@ -0,0 +1,90 @@
+// device-callable functions, the kernels of the user can operate on the sets
+// (CollectionsSrc::enable_device_api or SetSrc::build_device_api)
+//
+// array set (v1 and v2) and hash set
+//   int set_insert__SET_ID(int k)          index of the key, -1 if the set is full
+//   int set_remove__SET_ID(int k)          index of the key, -1 if the key does not exist
+//   bool set_contains__SET_ID(int k)
+//   int set_len__SET_ID()
+
+use crate::set::config::{SetSrc, SetType};
+use crate::set::kernel::common_replace;
+
+const ARRAY_SET_API: &str = r#"
+    int set_insert__SET_ID(int k) {
+        return array_set_insert__SET_ID(&k);
+    }
+
+    int set_remove__SET_ID(int k) {
+        return array_set_remove__SET_ID(&k);
+    }
+
+    bool set_contains__SET_ID(int k) {
+        return k != -1 && array_set_get_index__SET_ID(&k) >= 0;
+    }
+
+    int set_len__SET_ID() {
+        return array_set_len__SET_ID;
+    }
+    "#;
+
+const HASH_SET_API: &str = r#"
+    int set_insert__SET_ID(int k) {
+        return hash_set_insert__SET_ID(k);
+    }
+
+    int set_remove__SET_ID(int k) {
+        int i = hash_set_search__SET_ID(k);
+        hash_set_remove__SET_ID(k, i);
+        return i;
+    }
+
+    bool set_contains__SET_ID(int k) {
+        return hash_set_search__SET_ID(k) >= 0;
+    }
+
+    int set_len__SET_ID() {
+        return hash_set_len__SET_ID;
+    }
+    "#;
+
+impl SetSrc {
+    /// the source must be added after the source of the sets (build)
+    pub fn build_device_api(&self) -> String {
+        let mut device_api = String::new();
+
+        for config in self.get_configs() {
+            let template = match config.set_type {
+                SetType::ArraySet => ARRAY_SET_API,
+                SetType::HashSet => HASH_SET_API,
+            };
+            device_api.push_str(&common_replace(template, config));
+        }
+
+        device_api
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+        set_src.add_hash_set(16);
+
+        let device_api = set_src.build_device_api();
+        println!("{device_api}");
+        assert!(device_api.contains("return array_set_insert__0(&k);"));
+        assert!(device_api.contains("return hash_set_insert__1(k);"));
+        assert!(device_api.contains("bool set_contains__1(int k) {"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let set_src = SetSrc::new();
+        assert!(set_src.build_device_api().is_empty());
+    }
+}
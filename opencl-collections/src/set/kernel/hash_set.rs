@@ -0,0 +1,349 @@
+use crate::set::config::{SetSrc, SetType};
+use crate::set::kernel::common_replace;
+
+const GLOBALS: &str = r#"
+    __global int hash_set__SET_ID[SET_CAPACITY];
+    __global int hash_set_len__SET_ID = 0;
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int hash_set_search__SET_ID(int k) {
+
+        if ( !hash_set_is_item(k) ) {
+            return -1;
+        }
+
+        uint start = hash_set_hash(k) % SET_CAPACITY;
+
+        for (int p = 0; p < SET_CAPACITY; p++) {
+            int i = (start + p) % SET_CAPACITY;
+            int v = hash_set__SET_ID[i];
+
+            if ( v == k ) {
+                return i;
+            }
+
+            if ( v == HASH_SET_EMPTY ) {
+                return -1;
+            }
+        }
+
+        return -1;
+    }
+
+    int hash_set_insert__SET_ID(int k) {
+
+        if ( !hash_set_is_item(k) ) {
+            return -1;
+        }
+
+        // the tombstones before the key must not be reused
+        int exist_index = hash_set_search__SET_ID(k);
+
+        if ( exist_index >= 0 ) {
+            return exist_index;
+        }
+
+        uint start = hash_set_hash(k) % SET_CAPACITY;
+
+        for (int p = 0; p < SET_CAPACITY; p++) {
+            int i = (start + p) % SET_CAPACITY;
+            int v = hash_set__SET_ID[i];
+
+            if ( v == k ) {
+                return i;
+            }
+
+            if ( v != HASH_SET_EMPTY && v != HASH_SET_TOMBSTONE ) {
+                continue;
+            }
+
+            int r = atomic_cmpxchg(&hash_set__SET_ID[i], v, k);
+
+            if ( r == v ) {
+                atomic_inc(&hash_set_len__SET_ID);
+                return i;
+            }
+
+            // the same key was inserted by another work item
+            if ( r == k ) {
+                return i;
+            }
+        }
+
+        return -1;
+    }
+
+    void hash_set_remove__SET_ID(int k, int i) {
+
+        if ( i < 0 ) {
+            return;
+        }
+
+        int r = atomic_cmpxchg(&hash_set__SET_ID[i], k, HASH_SET_TOMBSTONE);
+
+        if ( r == k ) {
+            atomic_dec(&hash_set_len__SET_ID);
+        }
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void hash_set_debug__SET_ID(
+        global int* items_output
+        ) {
+        int i = get_global_id(0);
+
+        items_output[i] = hash_set__SET_ID[i];
+
+        if (i == 0) {
+            items_output[SET_CAPACITY] = hash_set_len__SET_ID;
+        }
+    }
+
+    kernel void hash_set_reset__SET_ID() {
+        int i = get_global_id(0);
+
+        hash_set__SET_ID[i] = HASH_SET_EMPTY;
+
+        if (i == 0) {
+            hash_set_len__SET_ID = 0;
+        }
+    }
+
+    kernel void get_hash_set_len__SET_ID(
+        global int* len_output
+        ) {
+        len_output[0] = hash_set_len__SET_ID;
+    }
+
+    kernel void write_in_hash_set__SET_ID(
+        global int* items_input,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        indices_output[i] = hash_set_insert__SET_ID(items_input[i]);
+    }
+
+    kernel void contains_in_hash_set__SET_ID(
+        global int* items_input,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+
+        indices_output[i] = hash_set_search__SET_ID(items_input[i]);
+    }
+
+    // indices_input is the output of contains_in_hash_set
+    kernel void remove_in_hash_set__SET_ID(
+        global int* items_input,
+        global int* indices_input
+        ) {
+        int i = get_global_id(0);
+
+        hash_set_remove__SET_ID(items_input[i], indices_input[i]);
+    }
+    "#;
+
+const SET_ALGEBRA_KERNELS: &str = r#"
+    int hash_set_get_item(int set_id, int index) {
+        HASH_SET_GET_ITEM_CASES
+        return HASH_SET_EMPTY;
+    }
+
+    bool hash_set_contains(int set_id, int k) {
+        HASH_SET_CONTAINS_CASES
+        return false;
+    }
+
+    kernel void hash_set_union(
+        const int a_id,
+        const int a_capacity,
+        const int b_id,
+        global int* items_output
+        ) {
+        int i = get_global_id(0);
+
+        int k;
+
+        if (i < a_capacity) {
+            k = hash_set_get_item(a_id, i);
+        } else {
+            k = hash_set_get_item(b_id, i - a_capacity);
+
+            if ( hash_set_contains(a_id, k) ) {
+                k = HASH_SET_EMPTY;
+            }
+        }
+
+        items_output[i] = hash_set_is_item(k) ? k : HASH_SET_EMPTY;
+    }
+
+    kernel void hash_set_intersection(
+        const int a_id,
+        const int b_id,
+        global int* items_output
+        ) {
+        int i = get_global_id(0);
+
+        int k = hash_set_get_item(a_id, i);
+
+        if ( !hash_set_contains(b_id, k) ) {
+            k = HASH_SET_EMPTY;
+        }
+
+        items_output[i] = hash_set_is_item(k) ? k : HASH_SET_EMPTY;
+    }
+
+    kernel void hash_set_difference(
+        const int a_id,
+        const int b_id,
+        global int* items_output
+        ) {
+        int i = get_global_id(0);
+
+        int k = hash_set_get_item(a_id, i);
+
+        if ( hash_set_contains(b_id, k) ) {
+            k = HASH_SET_EMPTY;
+        }
+
+        items_output[i] = hash_set_is_item(k) ? k : HASH_SET_EMPTY;
+    }
+    "#;
+
+impl SetSrc {
+    fn generate_set_algebra_kernels(&self) -> String {
+        let mut get_item_cases = String::new();
+        let mut contains_cases = String::new();
+
+        for config in self.get_configs_by_type(SetType::HashSet) {
+            let id = config.id;
+
+            get_item_cases.push_str(&format!(
+                "
+        if (set_id == {id} && index < {}) {{
+            return hash_set__{id}[index];
+        }}",
+                config.capacity
+            ));
+
+            contains_cases.push_str(&format!(
+                "
+        if (set_id == {id}) {{
+            return hash_set_search__{id}(k) >= 0;
+        }}"
+            ));
+        }
+
+        SET_ALGEBRA_KERNELS
+            .replace("HASH_SET_GET_ITEM_CASES", &get_item_cases)
+            .replace("HASH_SET_CONTAINS_CASES", &contains_cases)
+    }
+
+    pub fn generate_hash_set_program_source(&self) -> String {
+        let blocks = self.get_configs_by_type(SetType::HashSet);
+
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+
+        let mut base_functions = String::new();
+        let mut base_kernels = String::new();
+
+        for config in blocks {
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config);
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config);
+            base_kernels.push_str(&template);
+        }
+
+        let set_algebra_kernels = self.generate_set_algebra_kernels();
+
+        format!(
+            "
+    /// *** HASH SET SRC *** ///
+
+    /// constants
+    const int HASH_SET_EMPTY = -1;
+    const int HASH_SET_TOMBSTONE = -2;
+
+    /// globals
+    {globals}
+
+    /// kernels
+    bool hash_set_is_item(int k) {{
+        return k != HASH_SET_EMPTY && k != HASH_SET_TOMBSTONE;
+    }}
+
+    // murmur3 finalizer
+    uint hash_set_hash(int k) {{
+        uint h = (uint) k;
+
+        h ^= h >> 16;
+        h *= 0x85ebca6b;
+        h ^= h >> 13;
+        h *= 0xc2b2ae35;
+        h ^= h >> 16;
+
+        return h;
+    }}
+
+    {base_functions}
+    {base_kernels}
+    {set_algebra_kernels}
+
+    /// *** HASH SET SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(8);
+
+        let program_source = set_src.generate_hash_set_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int hash_set__0[8];"));
+        assert!(program_source.contains("kernel void write_in_hash_set__0("));
+        assert!(program_source.contains("kernel void hash_set_union("));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut set_src = SetSrc::new();
+        set_src.add_hash_set(8);
+        set_src.add(32);
+        set_src.add_hash_set(16);
+
+        let program_source = set_src.generate_hash_set_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int hash_set__0[8];"));
+        assert!(program_source.contains("__global int hash_set__2[16];"));
+        assert!(!program_source.contains("hash_set__1"));
+
+        assert!(program_source.contains("return hash_set_search__2(k) >= 0;"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+
+        let program_source = set_src.generate_hash_set_program_source();
+        assert!(program_source.is_empty());
+    }
+}
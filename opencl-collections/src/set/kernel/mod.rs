@@ -2,6 +2,8 @@ use crate::set::config::{ArraySetVersion, SetConfig, SetSrc};
 
 pub mod array_set_v1;
 pub mod array_set_v2;
+pub mod device_api;
+pub mod hash_set;
 pub mod name;
 
 fn common_replace(src: &str, config: &SetConfig) -> String {
@@ -14,10 +16,14 @@ fn common_replace(src: &str, config: &SetConfig) -> String {
 
 impl SetSrc {
     pub fn build(&self, version: ArraySetVersion) -> String {
-        match version {
+        let array_set_src = match version {
             ArraySetVersion::V1 => self.generate_array_set_program_source_v1(),
             ArraySetVersion::V2 => self.generate_array_set_program_source_v2(),
-        }
+        };
+
+        let hash_set_src = self.generate_hash_set_program_source();
+
+        format!("{array_set_src}{hash_set_src}")
 
         // set_src
         // ...
@@ -63,6 +69,18 @@ mod tests {
         assert!(!program_source.is_empty());
     }
 
+    #[test]
+    fn test_hash_set() {
+        let mut set_src = SetSrc::new();
+        set_src.add(8);
+        set_src.add_hash_set(16);
+
+        let program_source = set_src.build(ArraySetVersion::V2);
+        println!("{program_source}");
+        assert!(program_source.contains("__global int array_set__0[8];"));
+        assert!(program_source.contains("__global int hash_set__1[16];"));
+    }
+
     #[test]
     fn test_is_empty() {
         let set_src = SetSrc::new();
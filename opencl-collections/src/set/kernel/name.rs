@@ -9,11 +9,27 @@ pub const ARRAY_SET_RESET: &str = "array_set_reset__SET_ID";
 
 pub const WRITE_IN_ARRAY_SET: &str = "write_in_array_set__SET_ID";
 pub const REMOVE_IN_ARRAY_SET: &str = "remove_in_array_set__SET_ID";
+pub const CONTAINS_IN_ARRAY_SET: &str = "contains_in_array_set__SET_ID";
+pub const GET_ARRAY_SET_LEN: &str = "get_array_set_len__SET_ID";
 
 // array set v1
 pub const WRITE_WITH_CMQ_IN_ARRAY_SET: &str = "write_with_cmq_in_array_set__SET_ID";
 pub const WRITE_WITH_SINGLE_THREAD_IN_ARRAY_SET: &str =
     "write_with_single_thread_in_array_set__SET_ID";
 
+// hash set
+
+pub const HASH_SET_DEBUG: &str = "hash_set_debug__SET_ID";
+pub const HASH_SET_RESET: &str = "hash_set_reset__SET_ID";
+pub const GET_HASH_SET_LEN: &str = "get_hash_set_len__SET_ID";
+
+pub const WRITE_IN_HASH_SET: &str = "write_in_hash_set__SET_ID";
+pub const CONTAINS_IN_HASH_SET: &str = "contains_in_hash_set__SET_ID";
+pub const REMOVE_IN_HASH_SET: &str = "remove_in_hash_set__SET_ID";
+
+pub const HASH_SET_UNION: &str = "hash_set_union";
+pub const HASH_SET_INTERSECTION: &str = "hash_set_intersection";
+pub const HASH_SET_DIFFERENCE: &str = "hash_set_difference";
+
 // set
 // ...
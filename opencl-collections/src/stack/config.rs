@@ -1,15 +1,42 @@
-use crate::error::{OpenClResult, OpenclError, CL_COLLECTION_INVALID_STACK_ID};
+use crate::error::{
+    OpenClResult, OpenclError, CL_COLLECTION_INVALID_STACK_ID, CL_COLLECTION_INVALID_VALUE_STACK_ID,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum StackType {
+    Int,
+    Value,
+}
 
 /// ...
 ///
 /// ```c
+/// // int
 /// __global int stack__STACK_ID[STACK_CAPACITY];
 /// __global int st_top__STACK_ID = -1;
+///
+/// // value (every slot holds STACK_VALUE_LEN items)
+/// __global int value_stack__STACK_ID[STACK_CAPACITY][STACK_VALUE_LEN];
+/// __global int value_stack_top__STACK_ID = -1;
 /// ```
 #[derive(Debug, Clone, PartialEq)]
 pub struct StackConfig {
     pub id: usize,
     pub capacity: usize,
+    /// 1 for the int stack
+    pub value_len: usize,
+    pub stack_type: StackType,
+}
+
+impl StackConfig {
+    pub fn new(id: usize, capacity: usize, value_len: usize, stack_type: StackType) -> Self {
+        Self {
+            id,
+            capacity,
+            value_len,
+            stack_type,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +53,17 @@ impl StackSrc {
         &self.blocks
     }
 
+    pub(crate) fn get_configs_mut(&mut self) -> &mut Vec<StackConfig> {
+        &mut self.blocks
+    }
+
+    pub fn get_configs_by_type(&self, stack_type: StackType) -> Vec<&StackConfig> {
+        self.blocks
+            .iter()
+            .filter(|&x| x.stack_type == stack_type)
+            .collect()
+    }
+
     pub fn len(&self) -> usize {
         self.blocks.len()
     }
@@ -37,7 +75,8 @@ impl StackSrc {
     pub fn add(&mut self, capacity: usize) {
         let id = self.blocks.len();
 
-        self.blocks.push(StackConfig { id, capacity });
+        self.blocks
+            .push(StackConfig::new(id, capacity, 1, StackType::Int));
     }
 
     pub fn add_many(&mut self, capacity: usize, quantity: usize) {
@@ -46,14 +85,50 @@ impl StackSrc {
         }
     }
 
+    pub fn add_value_stack(&mut self, value_len: usize, capacity: usize) {
+        let id = self.blocks.len();
+
+        self.blocks
+            .push(StackConfig::new(id, capacity, value_len, StackType::Value));
+    }
+
+    /// Add a copy of the config (of another src) with a new id, the new id is returned
+    pub(crate) fn add_config(&mut self, config: &StackConfig) -> usize {
+        let id = self.blocks.len();
+
+        self.blocks.push(StackConfig {
+            id,
+            ..config.clone()
+        });
+
+        id
+    }
+
     pub fn get_config_by_id(&self, id: usize) -> OpenClResult<&StackConfig> {
-        match self.blocks.iter().find(|x| x.id == id) {
+        match self
+            .blocks
+            .iter()
+            .find(|x| x.id == id && x.stack_type == StackType::Int)
+        {
             None => Err(OpenclError::OpenclCollection(
                 CL_COLLECTION_INVALID_STACK_ID,
             )),
             Some(c) => Ok(c),
         }
     }
+
+    pub fn get_value_stack_by_id(&self, id: usize) -> OpenClResult<&StackConfig> {
+        match self
+            .blocks
+            .iter()
+            .find(|x| x.id == id && x.stack_type == StackType::Value)
+        {
+            None => Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_VALUE_STACK_ID,
+            )),
+            Some(c) => Ok(c),
+        }
+    }
 }
 
 impl Default for StackSrc {
@@ -86,14 +161,11 @@ mod tests {
 
         assert_eq!(
             stack_src.get_config_by_id(0),
-            Ok(&StackConfig { id: 0, capacity: 8 })
+            Ok(&StackConfig::new(0, 8, 1, StackType::Int))
         );
         assert_eq!(
             stack_src.get_config_by_id(1),
-            Ok(&StackConfig {
-                id: 1,
-                capacity: 16,
-            })
+            Ok(&StackConfig::new(1, 16, 1, StackType::Int))
         );
 
         assert_eq!(
@@ -103,4 +175,30 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_stack_src_add_value_stack() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+        stack_src.add_value_stack(4, 16);
+
+        assert_eq!(stack_src.get_configs_by_type(StackType::Int).len(), 1);
+        assert_eq!(
+            stack_src.get_value_stack_by_id(1),
+            Ok(&StackConfig::new(1, 16, 4, StackType::Value))
+        );
+
+        assert_eq!(
+            stack_src.get_value_stack_by_id(0),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_VALUE_STACK_ID
+            ))
+        );
+        assert_eq!(
+            stack_src.get_config_by_id(1),
+            Err(OpenclError::OpenclCollection(
+                CL_COLLECTION_INVALID_STACK_ID
+            ))
+        );
+    }
 }
@@ -1 +1,2 @@
 pub mod stack_v1;
+pub mod value_stack;
@@ -2,7 +2,8 @@ use crate::config::DEBUG_MODE;
 use crate::error::OpenClResult;
 use crate::stack::config::StackConfig;
 use crate::stack::kernel::name::{
-    get_stack_kernel_name, READ_ON_STACK, STACK_DEBUG, STACK_RESET, WRITE_TO_STACK,
+    get_stack_kernel_name, GET_STACK_LEN, PEEK_ON_STACK, PUSH_AND_POP_ON_STACK, READ_ON_STACK,
+    STACK_DEBUG, STACK_RESET, WRITE_TO_STACK,
 };
 use opencl::opencl_sys::bindings::{cl_int, cl_uint};
 use opencl::wrapper::system::OpenclCommonOperation;
@@ -108,6 +109,78 @@ impl<T: OpenclCommonOperation> StackHandle<T> {
         Ok(())
     }
 
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_stack_kernel_name(GET_STACK_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// up to take values (the top first), the values are not removed from the stack
+    pub fn peek(&self, take: usize) -> OpenClResult<Vec<cl_int>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_stack_kernel_name(PEEK_ON_STACK, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let mut output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        output.truncate(meta_output[0] as usize);
+
+        Ok(output)
+    }
+
     pub fn push(&self, input: &[cl_int]) -> OpenClResult<Vec<cl_int>> {
         let global_work_size = input.len();
         let local_work_size = self
@@ -179,6 +252,59 @@ impl<T: OpenclCommonOperation> StackHandle<T> {
 
         Ok(output)
     }
+
+    /// the pushes and the pops run in the same kernel, returns the indices of the
+    /// pushes and the popped values (-1 if the stack was empty)
+    pub fn push_and_pop(
+        &self,
+        input: &[cl_int],
+        take: usize,
+    ) -> OpenClResult<(Vec<cl_int>, Vec<cl_int>)> {
+        let push_len = input.len();
+
+        let global_work_size = push_len + take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let input_buf = self.system.blocking_prepare_input_buffer(input)?;
+        let push_output_buf = self.system.create_output_buffer(push_len)?;
+        let pop_output_buf = self.system.create_output_buffer(take)?;
+
+        let kernel_name = get_stack_kernel_name(PUSH_AND_POP_ON_STACK, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        let push_len_arg = push_len as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&push_len_arg)?;
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&push_output_buf.get_cl_mem())?;
+            kernel.set_arg(&pop_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let push_output =
+            self.system
+                .blocking_enqueue_read_buffer(push_len, &push_output_buf, &[])?;
+
+        let pop_output = self
+            .system
+            .blocking_enqueue_read_buffer(take, &pop_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("push output {push_output:?}");
+            println!("pop output {pop_output:?}");
+        }
+
+        Ok((push_output, pop_output))
+    }
 }
 
 #[cfg(test)]
@@ -630,3 +756,101 @@ mod tests_stack_pop {
         st.print().unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests_stack_peek {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::stack::config::StackSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn stack_is_empty() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(32);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_config_by_id(0).unwrap();
+        let st = StackHandle::new(config, system);
+
+        assert_eq!(st.len().unwrap(), 0);
+        assert!(st.is_empty().unwrap());
+        assert_eq!(st.peek(4).unwrap(), Vec::<cl_int>::new());
+    }
+
+    #[test]
+    fn partially_full_stack() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(32);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_config_by_id(0).unwrap();
+        let st = StackHandle::new(config, system);
+
+        for value in 0..5 {
+            st.push(&[value]).unwrap();
+        }
+
+        assert_eq!(st.len().unwrap(), 5);
+        assert_eq!(st.peek(3).unwrap(), vec![4, 3, 2]);
+        assert_eq!(st.peek(8).unwrap(), vec![4, 3, 2, 1, 0]);
+
+        // the values are not removed
+        assert_eq!(st.len().unwrap(), 5);
+
+        let _ = st.pop(2).unwrap();
+        assert_eq!(st.peek(8).unwrap(), vec![2, 1, 0]);
+    }
+}
+
+#[cfg(test)]
+mod tests_stack_push_and_pop {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::stack::config::StackSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn concurrent_push_and_pop() {
+        let stack_capacity = 1024;
+        let rounds = 16;
+
+        let mut stack_src = StackSrc::new();
+        stack_src.add(stack_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_config_by_id(0).unwrap();
+        let st = StackHandle::new(config, system);
+        st.reset().unwrap();
+
+        for round in 0..rounds {
+            let base = (round * stack_capacity) as cl_int;
+
+            // the values are never 0 (a pop of an unwritten slot reads 0 after the reset)
+            let initial: Vec<cl_int> = (1..=256).map(|x| base + x).collect();
+            let input: Vec<cl_int> = (257..=512).map(|x| base + x).collect();
+
+            let indices = st.push(&initial).unwrap();
+            assert!(indices.iter().all(|&x| x >= 0));
+
+            let (indices, popped) = st.push_and_pop(&input, 384).unwrap();
+            assert!(indices.iter().all(|&x| x >= 0));
+
+            let mut popped: Vec<cl_int> = popped.into_iter().filter(|&x| x != -1).collect();
+            popped.append(&mut st.pop(stack_capacity).unwrap());
+            popped.retain(|&x| x != -1);
+
+            popped.sort();
+
+            // every item is popped once
+            let mut expected = initial;
+            expected.extend(input);
+            assert_eq!(popped, expected);
+
+            assert!(st.is_empty().unwrap());
+        }
+    }
+}
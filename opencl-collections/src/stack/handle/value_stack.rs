@@ -0,0 +1,497 @@
+use crate::config::DEBUG_MODE;
+use crate::error::OpenClResult;
+use crate::stack::config::StackConfig;
+use crate::stack::kernel::name::{
+    get_stack_kernel_name, GET_VALUE_STACK_LEN, PEEK_ON_VALUE_STACK, PUSH_AND_POP_ON_VALUE_STACK,
+    READ_ON_VALUE_STACK, VALUE_STACK_DEBUG, VALUE_STACK_RESET, WRITE_TO_VALUE_STACK,
+};
+use crate::utils::ensure_vec_size;
+use opencl::opencl_sys::bindings::{cl_int, cl_uint};
+use opencl::wrapper::system::OpenclCommonOperation;
+
+#[derive(Debug, PartialEq)]
+pub struct ValueStackSnapshot {
+    pub top: cl_int,
+    pub items: Vec<Vec<cl_int>>,
+}
+
+impl ValueStackSnapshot {
+    pub fn new(top: cl_int, items: Vec<Vec<cl_int>>) -> Self {
+        Self { top, items }
+    }
+
+    pub fn create_empty(value_len: usize, capacity: usize) -> Self {
+        Self::new(-1, vec![vec![0; value_len]; capacity])
+    }
+}
+
+#[derive(Debug)]
+pub struct ValueStackHandle<T: OpenclCommonOperation> {
+    config: StackConfig,
+    system: T,
+}
+
+impl<T: OpenclCommonOperation> ValueStackHandle<T> {
+    pub fn new(config: &StackConfig, system: T) -> Self {
+        Self {
+            config: config.clone(),
+            system,
+        }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.config.id
+    }
+
+    pub fn debug(&self) -> OpenClResult<ValueStackSnapshot> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let items_output_capacity = global_work_size * self.config.value_len;
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(items_output_capacity)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_stack_kernel_name(VALUE_STACK_DEBUG, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(items_output_capacity, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        let items = output
+            .chunks(self.config.value_len)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok(ValueStackSnapshot::new(meta_output[0], items))
+    }
+
+    pub fn print(&self) -> OpenClResult<ValueStackSnapshot> {
+        let sn = self.debug()?;
+        println!("{sn:?}");
+        Ok(sn)
+    }
+
+    pub fn reset(&self) -> OpenClResult<()> {
+        let global_work_size = self.config.capacity;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let kernel_name = get_stack_kernel_name(VALUE_STACK_RESET, self.get_id());
+        let kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self) -> OpenClResult<usize> {
+        let output_buf = self.system.create_output_buffer(1)?;
+
+        let kernel_name = get_stack_kernel_name(GET_VALUE_STACK_LEN, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                1,
+                1,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> = self
+            .system
+            .blocking_enqueue_read_buffer(1, &output_buf, &[])?;
+
+        Ok(output[0] as usize)
+    }
+
+    pub fn is_empty(&self) -> OpenClResult<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// up to take values (the top first), the values are not removed from the stack
+    pub fn peek(&self, take: usize) -> OpenClResult<Vec<Vec<cl_int>>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let items_output_capacity = global_work_size * self.config.value_len;
+        let meta_output_capacity = 1;
+
+        let output_buf = self.system.create_output_buffer(items_output_capacity)?;
+        let meta_buf = self.system.create_output_buffer(meta_output_capacity)?;
+
+        let kernel_name = get_stack_kernel_name(PEEK_ON_VALUE_STACK, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+            kernel.set_arg(&meta_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(items_output_capacity, &output_buf, &[])?;
+
+        let meta_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(meta_output_capacity, &meta_buf, &[])?;
+
+        let values = output
+            .chunks(self.config.value_len)
+            .take(meta_output[0] as usize)
+            .map(|x| x.to_vec())
+            .collect();
+
+        Ok(values)
+    }
+
+    /// the values are padded (or truncated) to value_len, the index of the slot
+    /// is returned or -1 if the stack is full
+    pub fn push(&self, values: &[Vec<cl_int>]) -> OpenClResult<Vec<cl_int>> {
+        let global_work_size = values.len();
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let mut input: Vec<cl_int> = Vec::with_capacity(global_work_size * self.config.value_len);
+
+        for value in values {
+            let mut v = ensure_vec_size(value, self.config.value_len);
+            input.append(&mut v);
+        }
+
+        let input_buf = self.system.blocking_prepare_input_buffer(&input)?;
+        let output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_stack_kernel_name(WRITE_TO_VALUE_STACK, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let output =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("push output {output:?}");
+        }
+
+        Ok(output)
+    }
+
+    /// up to take values, the top first
+    pub fn pop(&self, take: usize) -> OpenClResult<Vec<Vec<cl_int>>> {
+        if take == 0 {
+            return Ok(vec![]);
+        }
+
+        let global_work_size = take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let values_output_capacity = global_work_size * self.config.value_len;
+
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+        let indices_output_buf = self.system.create_output_buffer(global_work_size)?;
+
+        let kernel_name = get_stack_kernel_name(READ_ON_VALUE_STACK, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        unsafe {
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&indices_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let values_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let indices_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(global_work_size, &indices_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("pop indices output {indices_output:?}");
+        }
+
+        // the work items do not pop in order, the slot index gives the order
+        let mut popped: Vec<(cl_int, Vec<cl_int>)> = indices_output
+            .into_iter()
+            .zip(values_output.chunks(self.config.value_len))
+            .filter(|(index, _)| *index >= 0)
+            .map(|(index, value)| (index, value.to_vec()))
+            .collect();
+
+        popped.sort_by_key(|x| std::cmp::Reverse(x.0));
+
+        Ok(popped.into_iter().map(|(_, value)| value).collect())
+    }
+
+    /// the pushes and the pops run in the same kernel, returns the indices of the
+    /// pushes and up to take popped values (in no particular order)
+    pub fn push_and_pop(
+        &self,
+        values: &[Vec<cl_int>],
+        take: usize,
+    ) -> OpenClResult<(Vec<cl_int>, Vec<Vec<cl_int>>)> {
+        let push_len = values.len();
+
+        let global_work_size = push_len + take;
+        let local_work_size = self
+            .system
+            .first_device_check_local_work_size(global_work_size);
+
+        let mut input: Vec<cl_int> = Vec::with_capacity(push_len * self.config.value_len);
+
+        for value in values {
+            let mut v = ensure_vec_size(value, self.config.value_len);
+            input.append(&mut v);
+        }
+
+        let values_output_capacity = take * self.config.value_len;
+
+        let input_buf = self.system.blocking_prepare_input_buffer(&input)?;
+        let push_output_buf = self.system.create_output_buffer(push_len)?;
+        let values_output_buf = self.system.create_output_buffer(values_output_capacity)?;
+        let pop_output_buf = self.system.create_output_buffer(take)?;
+
+        let kernel_name = get_stack_kernel_name(PUSH_AND_POP_ON_VALUE_STACK, self.get_id());
+        let mut kernel = self.system.create_kernel(&kernel_name)?;
+
+        let push_len_arg = push_len as cl_uint;
+
+        unsafe {
+            kernel.set_arg(&push_len_arg)?;
+            kernel.set_arg(&input_buf.get_cl_mem())?;
+            kernel.set_arg(&push_output_buf.get_cl_mem())?;
+            kernel.set_arg(&values_output_buf.get_cl_mem())?;
+            kernel.set_arg(&pop_output_buf.get_cl_mem())?;
+
+            kernel.enqueue_nd_range_kernel_dim_1(
+                self.system.get_host_command_queue(),
+                global_work_size,
+                local_work_size,
+                &[],
+            )?;
+        }
+
+        let push_output =
+            self.system
+                .blocking_enqueue_read_buffer(push_len, &push_output_buf, &[])?;
+
+        let values_output: Vec<cl_int> = self.system.blocking_enqueue_read_buffer(
+            values_output_capacity,
+            &values_output_buf,
+            &[],
+        )?;
+
+        let pop_output: Vec<cl_int> =
+            self.system
+                .blocking_enqueue_read_buffer(take, &pop_output_buf, &[])?;
+
+        if DEBUG_MODE {
+            println!("push output {push_output:?}");
+            println!("pop indices output {pop_output:?}");
+        }
+
+        let popped = pop_output
+            .into_iter()
+            .zip(values_output.chunks(self.config.value_len))
+            .filter(|(index, _)| *index >= 0)
+            .map(|(_, value)| value.to_vec())
+            .collect();
+
+        Ok((push_output, popped))
+    }
+}
+
+#[cfg(test)]
+mod tests_value_stack {
+    use super::*;
+    use crate::config::DEFAULT_DEVICE_INDEX;
+    use crate::stack::config::StackSrc;
+    use opencl::wrapper::system::System;
+
+    #[test]
+    fn stack_is_empty() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(4, 32);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_value_stack_by_id(0).unwrap();
+        let st = ValueStackHandle::new(config, system);
+
+        let snapshot = st.print().unwrap();
+
+        assert_eq!(snapshot, ValueStackSnapshot::create_empty(4, 32));
+        assert!(st.is_empty().unwrap());
+        assert_eq!(st.pop(2).unwrap(), Vec::<Vec<cl_int>>::new());
+    }
+
+    #[test]
+    fn push_peek_and_pop() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(4, 32);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_value_stack_by_id(0).unwrap();
+        let st = ValueStackHandle::new(config, system);
+
+        // a single value per kernel, the order is known
+        st.push(&[vec![1, 2, 3, 4]]).unwrap();
+        st.push(&[vec![5, 6]]).unwrap();
+        st.push(&[vec![7, 8, 9, 10, 11]]).unwrap();
+
+        assert_eq!(st.len().unwrap(), 3);
+        assert_eq!(
+            st.peek(2).unwrap(),
+            vec![vec![7, 8, 9, 10], vec![5, 6, -1, -1]]
+        );
+
+        let snapshot = st.print().unwrap();
+        assert_eq!(snapshot.top, 2);
+        assert_eq!(snapshot.items[0], vec![1, 2, 3, 4]);
+
+        assert_eq!(
+            st.pop(5).unwrap(),
+            vec![vec![7, 8, 9, 10], vec![5, 6, -1, -1], vec![1, 2, 3, 4]]
+        );
+        assert!(st.is_empty().unwrap());
+    }
+
+    #[test]
+    fn stack_is_full() {
+        let stack_capacity = 64;
+
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+        stack_src.add_value_stack(2, stack_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_value_stack_by_id(1).unwrap();
+        let st = ValueStackHandle::new(config, system);
+
+        let input: Vec<Vec<cl_int>> = (0..(stack_capacity * 2) as cl_int)
+            .map(|x| vec![x, x * 10])
+            .collect();
+
+        let indices = st.push(&input).unwrap();
+        assert_eq!(indices.iter().filter(|x| **x == -1).count(), stack_capacity);
+
+        let mut output = st.pop(stack_capacity * 2).unwrap();
+        assert_eq!(output.len(), stack_capacity);
+
+        // every value is complete
+        assert!(output.iter().all(|x| x[1] == x[0] * 10));
+
+        output.sort();
+        output.dedup();
+        assert_eq!(output.len(), stack_capacity);
+
+        st.reset().unwrap();
+        assert_eq!(
+            st.debug().unwrap(),
+            ValueStackSnapshot::create_empty(2, stack_capacity)
+        );
+    }
+
+    #[test]
+    fn concurrent_push_and_pop() {
+        let stack_capacity = 512;
+
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(2, stack_capacity);
+
+        let system = System::new(DEFAULT_DEVICE_INDEX, &stack_src.build()).unwrap();
+
+        let config = stack_src.get_value_stack_by_id(0).unwrap();
+        let st = ValueStackHandle::new(config, system);
+
+        let initial: Vec<Vec<cl_int>> = (1..=128).map(|x| vec![x, x * 10]).collect();
+        let input: Vec<Vec<cl_int>> = (129..=256).map(|x| vec![x, x * 10]).collect();
+
+        st.push(&initial).unwrap();
+
+        let (indices, mut output) = st.push_and_pop(&input, 192).unwrap();
+        assert!(indices.iter().all(|&x| x >= 0));
+
+        output.append(&mut st.pop(stack_capacity).unwrap());
+
+        // every value is complete and popped once
+        assert!(output.iter().all(|x| x[1] == x[0] * 10));
+
+        output.sort();
+
+        let mut expected = initial;
+        expected.extend(input);
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,84 @@
+// device-callable functions, the kernels of the user can operate on the stacks
+// (CollectionsSrc::enable_device_api or StackSrc::build_device_api)
+//
+// int stack
+//   int stack_push__STACK_ID(int v)              index of the slot, -1 if the stack is full
+//   int stack_pop__STACK_ID(int* v)              index of the slot, -1 if the stack is empty
+//
+// value stack (STACK_VALUE_LEN items)
+//   int stack_push__STACK_ID(int* value)
+//   int stack_pop__STACK_ID(int* value_output)
+//
+//   int stack_len__STACK_ID()
+
+use crate::stack::config::{StackSrc, StackType};
+use crate::stack::kernel::common_replace;
+
+const INT_STACK_API: &str = r#"
+    int stack_push__STACK_ID(int v) {
+        return stack_try_push__STACK_ID(&v);
+    }
+
+    int stack_pop__STACK_ID(int* v) {
+        return stack_try_pop__STACK_ID(v);
+    }
+
+    int stack_len__STACK_ID() {
+        return stack_get_len__STACK_ID();
+    }
+    "#;
+
+const VALUE_STACK_API: &str = r#"
+    int stack_push__STACK_ID(int* value) {
+        return stack_try_push__STACK_ID(value);
+    }
+
+    int stack_pop__STACK_ID(int* value_output) {
+        return stack_try_pop__STACK_ID(value_output);
+    }
+
+    int stack_len__STACK_ID() {
+        return stack_get_len__STACK_ID();
+    }
+    "#;
+
+impl StackSrc {
+    /// the source must be added after the source of the stacks (build)
+    pub fn build_device_api(&self) -> String {
+        let mut device_api = String::new();
+
+        for config in self.get_configs() {
+            let template = match config.stack_type {
+                StackType::Int => INT_STACK_API,
+                StackType::Value => VALUE_STACK_API,
+            };
+            device_api.push_str(&common_replace(template, config));
+        }
+
+        device_api
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+        stack_src.add_value_stack(4, 16);
+
+        let device_api = stack_src.build_device_api();
+        println!("{device_api}");
+        assert!(device_api.contains("int stack_push__0(int v) {"));
+        assert!(device_api.contains("int stack_push__1(int* value) {"));
+        assert!(device_api.contains("int stack_len__1() {"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let stack_src = StackSrc::new();
+        assert!(stack_src.build_device_api().is_empty());
+    }
+}
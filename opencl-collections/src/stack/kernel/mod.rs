@@ -1,7 +1,9 @@
 use crate::stack::config::{StackConfig, StackSrc};
 
+pub mod device_api;
 pub mod name;
 pub mod stack_v1;
+pub mod value_stack;
 
 fn common_replace(src: &str, config: &StackConfig) -> String {
     let stack_max_capacity = (config.capacity - 1).to_string();
@@ -9,11 +11,16 @@ fn common_replace(src: &str, config: &StackConfig) -> String {
     src.replace("STACK_CAPACITY", &config.capacity.to_string())
         .replace("STACK_ID", &config.id.to_string())
         .replace("STACK_MAX_CAPACITY", &stack_max_capacity)
+        .replace("STACK_VALUE_LEN", &config.value_len.to_string())
 }
 
 impl StackSrc {
     pub fn build(&self) -> String {
-        self.generate_stack_program_source_v1()
+        format!(
+            "{}{}",
+            self.generate_stack_program_source_v1(),
+            self.generate_value_stack_program_source()
+        )
     }
 }
 
@@ -43,6 +50,17 @@ mod tests {
         assert!(!program_source.is_empty());
     }
 
+    #[test]
+    fn test_value_stack() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+        stack_src.add_value_stack(4, 16);
+
+        let program_source = stack_src.build();
+        assert!(program_source.contains("__global int stack__0[8];"));
+        assert!(program_source.contains("__global int value_stack__1[16][4];"));
+    }
+
     #[test]
     fn test_is_empty() {
         let stack_src = StackSrc::new();
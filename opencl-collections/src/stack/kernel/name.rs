@@ -5,6 +5,18 @@ pub fn get_stack_kernel_name(kernel_name: &str, id: usize) -> String {
 // stack v1
 pub const STACK_DEBUG: &str = "stack_debug__STACK_ID";
 pub const STACK_RESET: &str = "stack_reset__STACK_ID";
+pub const GET_STACK_LEN: &str = "get_stack_len__STACK_ID";
+pub const PEEK_ON_STACK: &str = "peek_on_stack__STACK_ID";
+pub const PUSH_AND_POP_ON_STACK: &str = "push_and_pop_on_stack__STACK_ID";
 
 pub const WRITE_TO_STACK: &str = "write_to_stack";
 pub const READ_ON_STACK: &str = "read_on_stack";
+
+// value stack
+pub const VALUE_STACK_DEBUG: &str = "value_stack_debug__STACK_ID";
+pub const VALUE_STACK_RESET: &str = "value_stack_reset__STACK_ID";
+pub const GET_VALUE_STACK_LEN: &str = "get_value_stack_len__STACK_ID";
+pub const PEEK_ON_VALUE_STACK: &str = "peek_on_value_stack__STACK_ID";
+pub const WRITE_TO_VALUE_STACK: &str = "write_to_value_stack__STACK_ID";
+pub const READ_ON_VALUE_STACK: &str = "read_on_value_stack__STACK_ID";
+pub const PUSH_AND_POP_ON_VALUE_STACK: &str = "push_and_pop_on_value_stack__STACK_ID";
@@ -1,9 +1,24 @@
-use crate::stack::config::StackSrc;
+// stack_try_push and stack_try_pop reserve the slot (stack_top) before the item is
+// written or read, every slot has a state to publish the item:
+//   0 empty      the slot is free for the push that reserved it
+//   1 writing    the push writes the item
+//   2 full       the item is visible for the pop that reserved the slot
+//   3 reading    the pop reads the item
+//
+// the pushes and the pops of a slot alternate (stack_top), but the push that reserved
+// the slot can find the pop before it still reading, or the pop can find the push that
+// is not written yet, they wait for the state of the slot
+//
+// the state is published with release and claimed with acquire before the slot is
+// accessed (the item is visible for the pop that claims the full slot)
+
+use crate::stack::config::{StackSrc, StackType};
 use crate::stack::kernel::common_replace;
 
 const GLOBALS: &str = r#"
     __global int stack__STACK_ID[STACK_CAPACITY];
     __global int stack_top__STACK_ID = -1;
+    __global uint stack_slot__STACK_ID[STACK_CAPACITY];
     "#;
 
 // To write and read from the stack, functions similar to opencl pipes are used.
@@ -36,7 +51,9 @@ const READ_PIPE_BODY_CASE: &str = r#"
                     front_i = atomic_fetch_sub(&stack_top__STACK_ID, 1);
 
                     if (front_i >= 0) {
+                        stack_claim_slot__STACK_ID(front_i, 2, 3);
                         *v = stack__STACK_ID[front_i];
+                        stack_release_slot__STACK_ID(front_i, 0);
                     }
                 }
 
@@ -54,7 +71,9 @@ const WRITE_PIPE_BODY_CASE: &str = r#"
 
                     if (STACK_MAX_CAPACITY >= rear_i) {
 
+                        stack_claim_slot__STACK_ID(rear_i, 0, 1);
                         stack__STACK_ID[rear_i] = *v;
+                        stack_release_slot__STACK_ID(rear_i, 2);
 
                     } else {
 
@@ -67,11 +86,82 @@ const WRITE_PIPE_BODY_CASE: &str = r#"
                 break;
     "#;
 
+// device-callable, other kernels can use the stack as a free list,
+// the index of the slot is returned or -1 if the stack is empty (pop) or full (push)
+const BASE_FUNCTIONS: &str = r#"
+    int stack_get_len__STACK_ID() {
+        return clamp(stack_top__STACK_ID + 1, 0, STACK_CAPACITY);
+    }
+
+    // waits until the previous push or pop of the slot is done
+    void stack_claim_slot__STACK_ID(int i, uint state, uint new_state) {
+        while (atomic_cmpxchg(&stack_slot__STACK_ID[i], state, new_state) != state) {}
+
+        atomic_work_item_fence(CLK_GLOBAL_MEM_FENCE, memory_order_acquire, memory_scope_device);
+    }
+
+    void stack_release_slot__STACK_ID(int i, uint state) {
+        atomic_store_explicit(
+            (volatile __global atomic_uint*) &stack_slot__STACK_ID[i],
+            state,
+            memory_order_release,
+            memory_scope_device
+        );
+    }
+
+    int stack_try_push__STACK_ID(int* value) {
+        int top = atomic_add(&stack_top__STACK_ID, 0);
+
+        for (;;) {
+            if (top >= STACK_MAX_CAPACITY) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&stack_top__STACK_ID, top, top + 1);
+
+            if (r == top) {
+                stack_claim_slot__STACK_ID(top + 1, 0, 1);
+                stack__STACK_ID[top + 1] = value[0];
+
+                // the item is visible for the pops
+                stack_release_slot__STACK_ID(top + 1, 2);
+                return top + 1;
+            }
+
+            top = r;
+        }
+    }
+
+    int stack_try_pop__STACK_ID(int* value_output) {
+        int top = atomic_add(&stack_top__STACK_ID, 0);
+
+        for (;;) {
+            if (top < 0) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&stack_top__STACK_ID, top, top - 1);
+
+            if (r == top) {
+                stack_claim_slot__STACK_ID(top, 2, 3);
+                value_output[0] = stack__STACK_ID[top];
+
+                // the slot is free for the next push
+                stack_release_slot__STACK_ID(top, 0);
+                return top;
+            }
+
+            top = r;
+        }
+    }
+    "#;
+
 const BASE_KERNELS: &str = r#"
     kernel void stack_reset__STACK_ID() {
         int i = get_global_id(0);
 
         stack__STACK_ID[i] = 0;
+        stack_slot__STACK_ID[i] = 0;
 
         if (i == 0) {
             stack_top__STACK_ID = -1;
@@ -90,6 +180,48 @@ const BASE_KERNELS: &str = r#"
             meta_output[0] = stack_top__STACK_ID;
         }
     }
+
+    // the first push_len work items push, the others pop (at the same time)
+    kernel void push_and_pop_on_stack__STACK_ID(
+        const uint push_len,
+        global int* input,
+        global int* push_output,
+        global int* pop_output
+        ) {
+        int i = get_global_id(0);
+
+        if (i < push_len) {
+            push_output[i] = stack_try_push__STACK_ID(&input[i]);
+            return;
+        }
+
+        int v = -1;
+        stack_try_pop__STACK_ID(&v);
+
+        pop_output[i - push_len] = v;
+    }
+
+    kernel void get_stack_len__STACK_ID(
+        global int* len_output
+        ) {
+        len_output[0] = stack_get_len__STACK_ID();
+    }
+
+    // the items are not removed, the top first
+    kernel void peek_on_stack__STACK_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        int top = stack_top__STACK_ID;
+
+        items_output[i] = (i <= top) ? stack__STACK_ID[top - i] : -1;
+
+        if (i == 0) {
+            meta_output[0] = stack_get_len__STACK_ID();
+        }
+    }
     "#;
 
 const BASIC_KERNELS: &str = r#"
@@ -117,21 +249,27 @@ const BASIC_KERNELS: &str = r#"
 
 impl StackSrc {
     pub(crate) fn generate_stack_program_source_v1(&self) -> String {
-        if self.is_empty() {
+        let blocks = self.get_configs_by_type(StackType::Int);
+
+        if blocks.is_empty() {
             return String::new();
         }
 
         let mut globals = String::new();
+        let mut base_functions = String::new();
 
         let mut fn_write_cases = String::new();
         let mut fn_read_cases = String::new();
 
         let mut base_kernels = String::new();
 
-        for st_config in self.get_configs() {
+        for st_config in blocks {
             let template = common_replace(GLOBALS, st_config);
             globals.push_str(&template);
 
+            let template = common_replace(BASE_FUNCTIONS, st_config);
+            base_functions.push_str(&template);
+
             let template = common_replace(WRITE_PIPE_BODY_CASE, st_config);
             fn_write_cases.push_str(&template);
 
@@ -159,6 +297,7 @@ impl StackSrc {
     {globals}
 
     /// kernels
+    {base_functions}
 
     {pipe_functions}
 
@@ -206,4 +345,19 @@ mod tests {
         println!("{program_source}");
         assert!(program_source.is_empty());
     }
+
+    #[test]
+    fn test_value_stack() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(4, 8);
+
+        let program_source = stack_src.generate_stack_program_source_v1();
+        assert!(program_source.is_empty());
+
+        stack_src.add(16);
+
+        let program_source = stack_src.generate_stack_program_source_v1();
+        assert!(program_source.contains("int stack_try_pop__1(int* value_output) {"));
+        assert!(!program_source.contains("stack__0"));
+    }
 }
@@ -0,0 +1,260 @@
+// every slot of the stack holds STACK_VALUE_LEN items (like the values of the lru cache)
+//
+// stack_try_push and stack_try_pop have the same signature as in the int stack,
+// the value is STACK_VALUE_LEN items
+//
+// the slots are published with a state as in the int stack (stack_v1.rs)
+
+use crate::stack::config::{StackSrc, StackType};
+use crate::stack::kernel::common_replace;
+
+const GLOBALS: &str = r#"
+    __global int value_stack__STACK_ID[STACK_CAPACITY][STACK_VALUE_LEN];
+    __global int value_stack_top__STACK_ID = -1;
+    __global uint value_stack_slot__STACK_ID[STACK_CAPACITY];
+    "#;
+
+const BASE_FUNCTIONS: &str = r#"
+    int stack_get_len__STACK_ID() {
+        return clamp(value_stack_top__STACK_ID + 1, 0, STACK_CAPACITY);
+    }
+
+    // 0 empty, 1 writing, 2 full, 3 reading
+    void stack_claim_slot__STACK_ID(int i, uint state, uint new_state) {
+        while (atomic_cmpxchg(&value_stack_slot__STACK_ID[i], state, new_state) != state) {}
+
+        atomic_work_item_fence(CLK_GLOBAL_MEM_FENCE, memory_order_acquire, memory_scope_device);
+    }
+
+    void stack_release_slot__STACK_ID(int i, uint state) {
+        atomic_store_explicit(
+            (volatile __global atomic_uint*) &value_stack_slot__STACK_ID[i],
+            state,
+            memory_order_release,
+            memory_scope_device
+        );
+    }
+
+    int stack_try_push__STACK_ID(int* value) {
+        int top = atomic_add(&value_stack_top__STACK_ID, 0);
+
+        for (;;) {
+            if (top >= STACK_MAX_CAPACITY) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&value_stack_top__STACK_ID, top, top + 1);
+
+            if (r == top) {
+                stack_claim_slot__STACK_ID(top + 1, 0, 1);
+
+                for (int j = 0; j < STACK_VALUE_LEN; j++) {
+                    value_stack__STACK_ID[top + 1][j] = value[j];
+                }
+
+                stack_release_slot__STACK_ID(top + 1, 2);
+                return top + 1;
+            }
+
+            top = r;
+        }
+    }
+
+    int stack_try_pop__STACK_ID(int* value_output) {
+        int top = atomic_add(&value_stack_top__STACK_ID, 0);
+
+        for (;;) {
+            if (top < 0) {
+                return -1;
+            }
+
+            int r = atomic_cmpxchg(&value_stack_top__STACK_ID, top, top - 1);
+
+            if (r == top) {
+                stack_claim_slot__STACK_ID(top, 2, 3);
+
+                for (int j = 0; j < STACK_VALUE_LEN; j++) {
+                    value_output[j] = value_stack__STACK_ID[top][j];
+                }
+
+                stack_release_slot__STACK_ID(top, 0);
+                return top;
+            }
+
+            top = r;
+        }
+    }
+    "#;
+
+const BASE_KERNELS: &str = r#"
+    kernel void value_stack_reset__STACK_ID() {
+        int i = get_global_id(0);
+
+        for (int j = 0; j < STACK_VALUE_LEN; j++) {
+            value_stack__STACK_ID[i][j] = 0;
+        }
+
+        value_stack_slot__STACK_ID[i] = 0;
+
+        if (i == 0) {
+            value_stack_top__STACK_ID = -1;
+        }
+    }
+
+    kernel void value_stack_debug__STACK_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        for (int j = 0; j < STACK_VALUE_LEN; j++) {
+            items_output[(i * STACK_VALUE_LEN) + j] = value_stack__STACK_ID[i][j];
+        }
+
+        if (i == 0) {
+            meta_output[0] = value_stack_top__STACK_ID;
+        }
+    }
+
+    kernel void get_value_stack_len__STACK_ID(
+        global int* len_output
+        ) {
+        len_output[0] = stack_get_len__STACK_ID();
+    }
+
+    // the items are not removed, the top first
+    kernel void peek_on_value_stack__STACK_ID(
+        global int* items_output,
+        global int* meta_output
+        ) {
+        int i = get_global_id(0);
+
+        int top = value_stack_top__STACK_ID;
+
+        for (int j = 0; j < STACK_VALUE_LEN; j++) {
+            items_output[(i * STACK_VALUE_LEN) + j] = (i <= top) ? value_stack__STACK_ID[top - i][j] : -1;
+        }
+
+        if (i == 0) {
+            meta_output[0] = stack_get_len__STACK_ID();
+        }
+    }
+
+    kernel void write_to_value_stack__STACK_ID(
+        global int* input,
+        global int* output
+        ) {
+        int i = get_global_id(0);
+        output[i] = stack_try_push__STACK_ID(&input[i * STACK_VALUE_LEN]);
+    }
+
+    kernel void read_on_value_stack__STACK_ID(
+        global int* values_output,
+        global int* indices_output
+        ) {
+        int i = get_global_id(0);
+        indices_output[i] = stack_try_pop__STACK_ID(&values_output[i * STACK_VALUE_LEN]);
+    }
+
+    // the first push_len work items push, the others pop (at the same time)
+    kernel void push_and_pop_on_value_stack__STACK_ID(
+        const uint push_len,
+        global int* input,
+        global int* push_output,
+        global int* values_output,
+        global int* pop_output
+        ) {
+        int i = get_global_id(0);
+
+        if (i < push_len) {
+            push_output[i] = stack_try_push__STACK_ID(&input[i * STACK_VALUE_LEN]);
+            return;
+        }
+
+        int k = i - push_len;
+        pop_output[k] = stack_try_pop__STACK_ID(&values_output[k * STACK_VALUE_LEN]);
+    }
+    "#;
+
+impl StackSrc {
+    pub(crate) fn generate_value_stack_program_source(&self) -> String {
+        let blocks = self.get_configs_by_type(StackType::Value);
+
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let mut globals = String::new();
+
+        let mut base_functions = String::new();
+        let mut base_kernels = String::new();
+
+        for config in blocks {
+            let template = common_replace(GLOBALS, config);
+            globals.push_str(&template);
+
+            let template = common_replace(BASE_FUNCTIONS, config);
+            base_functions.push_str(&template);
+
+            let template = common_replace(BASE_KERNELS, config);
+            base_kernels.push_str(&template);
+        }
+
+        format!(
+            "
+    /// *** VALUE STACK SRC *** ///
+
+    /// constants
+    // ...
+
+    /// globals
+    {globals}
+
+    /// kernels
+    {base_functions}
+
+    {base_kernels}
+
+    /// *** VALUE STACK SRC *** ///
+    "
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(4, 8);
+
+        let program_source = stack_src.generate_value_stack_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int value_stack__0[8][4];"));
+        assert!(program_source.contains("int stack_try_pop__0(int* value_output) {"));
+    }
+
+    #[test]
+    fn test_b() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add_value_stack(4, 8);
+        stack_src.add(32);
+        stack_src.add_value_stack(16, 64);
+
+        let program_source = stack_src.generate_value_stack_program_source();
+        println!("{program_source}");
+        assert!(program_source.contains("__global int value_stack__2[64][16];"));
+        assert!(!program_source.contains("stack__1"));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut stack_src = StackSrc::new();
+        stack_src.add(8);
+
+        let program_source = stack_src.generate_value_stack_program_source();
+        assert!(program_source.is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::config::ClTypeTrait;
 use num::{FromPrimitive, Zero};
+use opencl::opencl_sys::bindings::cl_int;
 use std::ops::{Add, Div};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -50,10 +51,28 @@ where
     }
 }
 
+/// deterministic keys (1..=distinct) skewed towards the lower keys, the hot keys
+/// are repeated more often than the cold ones
+pub fn skewed_keys(total: usize, distinct: usize, seed: u64) -> Vec<cl_int> {
+    let mut state = seed;
+
+    let mut next = || -> usize {
+        // lcg
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((state >> 33) as usize) % distinct
+    };
+
+    (0..total)
+        .map(|_| (next().min(next()) + 1) as cl_int)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use opencl::opencl_sys::bindings::{cl_int, cl_uchar};
+    use opencl::opencl_sys::bindings::cl_uchar;
 
     #[test]
     fn case_u8() {
@@ -110,4 +129,16 @@ mod tests {
         assert_eq!(test_matrix.values, values);
         assert_eq!(test_matrix.indices, indices);
     }
+
+    #[test]
+    fn skewed_keys_are_deterministic() {
+        let keys = skewed_keys(1000, 16, 7);
+
+        assert_eq!(keys, skewed_keys(1000, 16, 7));
+        assert!(keys.iter().all(|&x| (1..=16).contains(&x)));
+
+        let hot = keys.iter().filter(|&&x| x == 1).count();
+        let cold = keys.iter().filter(|&&x| x == 16).count();
+        assert!(hot > cold);
+    }
 }
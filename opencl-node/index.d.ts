@@ -15,12 +15,19 @@ export interface MiniLruCacheConfig {
   capacity: number
 }
 export interface LruCacheConfig {
+  keyLen?: number
   valueLen: number
   capacity: number
 }
+export interface TtlCacheConfig {
+  valueLen: number
+  capacity: number
+  defaultTtl: number
+}
 export interface CacheOptions {
   miniLru?: Array<MiniLruCacheConfig>
   lru?: Array<LruCacheConfig>
+  ttl?: Array<TtlCacheConfig>
 }
 export interface Collections {
   map?: MapOptions
@@ -46,6 +53,7 @@ export declare class Device {
   createMap(options: MapClOptions): MapCl
   createMiniLruCache(id: number): MiniLruCache
   createLruCache(id: number): LruCache
+  createTtlCache(id: number): TtlCache
 }
 export declare class LruCache {
   set(key: string, value: any): number
@@ -66,3 +74,11 @@ export declare class MiniLruCache {
   keys(): Array<number>
   debug(): void
 }
+export declare class TtlCache {
+  set(key: string, value: any, ttl?: number | undefined | null, now?: number | undefined | null): number
+  get(key: string, now?: number | undefined | null): any
+  has(key: string, now?: number | undefined | null): boolean
+  purge(now?: number | undefined | null): number
+  keys(now?: number | undefined | null): Array<string>
+  debug(): void
+}
@@ -310,10 +310,11 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { Device, LruCache, MapCl, MiniLruCache, sum } = nativeBinding
+const { Device, LruCache, MapCl, MiniLruCache, TtlCache, sum } = nativeBinding
 
 module.exports.Device = Device
 module.exports.LruCache = LruCache
 module.exports.MapCl = MapCl
 module.exports.MiniLruCache = MiniLruCache
+module.exports.TtlCache = TtlCache
 module.exports.sum = sum
@@ -3,6 +3,7 @@ pub mod ocl;
 pub mod lru_cache;
 pub mod map;
 pub mod mini_lru_cache;
+pub mod ttl_cache;
 
 pub mod serialize;
 
@@ -2,7 +2,7 @@ use crate::serialize::{from_buf_i32_to_vec_u8, serialize_map_entry_i32, MapEntry
 use bincode::config;
 use opencl_collections::cache::handle::lru::LRUCacheHandle;
 use opencl_collections::opencl::wrapper::system::System;
-use opencl_collections::utils::{from_buf_u8_to_vec_i32, remove_padding_cl_default};
+use opencl_collections::utils::from_buf_u8_to_vec_i32;
 use serde_json::Value;
 use std::io;
 
@@ -85,13 +85,11 @@ impl LruCache {
     let keys: Vec<_> = keys_raw
       .into_iter()
       .filter_map(|x| -> Option<String> {
-        let v = remove_padding_cl_default(&x.key);
-
-        if v.is_empty() {
+        if x.key.is_empty() {
           return None;
         }
 
-        let vec_u8 = from_buf_i32_to_vec_u8(&v);
+        let vec_u8 = from_buf_i32_to_vec_u8(&x.key);
         Some(String::from_utf8(vec_u8).unwrap())
       })
       .collect();
@@ -1,11 +1,14 @@
 use crate::lru_cache::LruCache;
 use crate::map::MapCl;
 use crate::mini_lru_cache::MiniLruCache;
+use crate::ttl_cache::TtlCache;
 
 use io::Error as IoError;
 use opencl_collections::cache::config::CacheSrc;
 use opencl_collections::cache::handle::lru::LRUCacheHandle;
 use opencl_collections::cache::handle::mini_lru::MiniLRUCacheHandle;
+use opencl_collections::cache::handle::ttl::TTLCacheHandle;
+use opencl_collections::collections::config::CollectionsSrc;
 use opencl_collections::config::DEFAULT_DEVICE_INDEX;
 use opencl_collections::map::config::{MapSrc, MAX_FIND_WORK_SIZE};
 use opencl_collections::map::handle::{Handle, MapHandle};
@@ -37,15 +40,25 @@ pub struct MiniLRUCacheConfig {
 #[derive(Debug)]
 #[napi(object)]
 pub struct LRUCacheConfig {
+  pub key_len: Option<i32>,
   pub value_len: i32,
   pub capacity: i32,
 }
 
+#[derive(Debug)]
+#[napi(object)]
+pub struct TTLCacheConfig {
+  pub value_len: i32,
+  pub capacity: i32,
+  pub default_ttl: i64,
+}
+
 #[derive(Debug)]
 #[napi(object)]
 pub struct CacheOptions {
   pub mini_lru: Option<Vec<MiniLRUCacheConfig>>,
   pub lru: Option<Vec<LRUCacheConfig>>,
+  pub ttl: Option<Vec<TTLCacheConfig>>,
 }
 
 #[derive(Debug)]
@@ -61,17 +74,19 @@ pub struct MapClOptions {
   pub map_id: i32,
 }
 
+const MAP_NAMESPACE: &str = "map";
+const CACHE_NAMESPACE: &str = "cache";
+const CACHE_DEFAULT_KEY_LEN: usize = 256;
+
 #[napi]
 pub struct Device {
-  cache_src: CacheSrc,
-  cache_ocl_block: Option<Arc<System>>,
-  map_src: MapSrc<i32>,
-  map_ocl_block: Option<Arc<System>>,
+  collections_src: CollectionsSrc<i32>,
+  ocl_block: Option<Arc<System>>,
 }
 
 impl Device {
   pub fn create(collections: Collections) -> io::Result<Self> {
-    let mut map_src = MapSrc::new(1);
+    let mut map_src = MapSrc::new(1)?;
 
     let mut cache_src = CacheSrc::new();
 
@@ -79,7 +94,7 @@ impl Device {
 
     if let Some(map_options) = collections.map {
       if let Some(n) = map_options.total_maps {
-        map_src.set_total_maps(n as usize);
+        map_src.set_total_maps(n as usize)?;
       }
 
       for c in map_options.blocks {
@@ -96,64 +111,78 @@ impl Device {
 
       if let Some(configs) = cache_options.lru {
         for c in configs {
-          cache_src.add_lru(256, c.value_len as usize, c.capacity as usize)
+          let key_len = c.key_len.map_or(CACHE_DEFAULT_KEY_LEN, |x| x as usize);
+          cache_src.add_lru(key_len, c.value_len as usize, c.capacity as usize)
+        }
+      }
+
+      if let Some(configs) = cache_options.ttl {
+        for c in configs {
+          cache_src.add_ttl(
+            CACHE_DEFAULT_KEY_LEN,
+            c.value_len as usize,
+            c.capacity as usize,
+            c.default_ttl,
+          )
         }
       }
     }
 
-    // initialize
-    let mut map_ocl_block = None;
+    // a single program (and a single context) for all collections
+    let mut collections_src = CollectionsSrc::new();
 
     if !map_src.get_configs().is_empty() {
-      map_src.add_map_insert_program_src(MAX_FIND_WORK_SIZE);
-      map_src.add_map_get_program_src(MAX_FIND_WORK_SIZE);
+      collections_src.add_map(MAP_NAMESPACE, &map_src)?;
 
-      let ocl_block = System::new(DEFAULT_DEVICE_INDEX, &map_src.build())?;
-      let arc_ocl_block = Arc::new(ocl_block);
-
-      let map_handle = Handle::new(&map_src, arc_ocl_block.clone());
-      map_handle.initialize_all_maps()?;
+      if let Some(map_src) = collections_src.get_map_src_mut() {
+        map_src.add_map_insert_program_src(MAX_FIND_WORK_SIZE);
+        map_src.add_map_get_program_src(MAX_FIND_WORK_SIZE);
+      }
+    }
 
-      map_ocl_block = Some(arc_ocl_block);
+    if !cache_src.is_empty() {
+      collections_src.add_cache(CACHE_NAMESPACE, &cache_src)?;
     }
 
-    let mut cache_ocl_block = None;
+    // initialize
+    let mut ocl_block = None;
 
-    if !cache_src.is_empty() {
-      let ocl_block = System::new(DEFAULT_DEVICE_INDEX, &cache_src.build())?;
-      let arc_ocl_block = Arc::new(ocl_block);
+    if !collections_src.is_empty() {
+      let system = System::new(DEFAULT_DEVICE_INDEX, &collections_src.build()?)?;
+      let arc_ocl_block = Arc::new(system);
+
+      if let Some(map_src) = collections_src.get_map_src() {
+        let map_handle = Handle::new(map_src, arc_ocl_block.clone());
+        map_handle.initialize_all_maps()?;
+      }
 
-      cache_ocl_block = Some(arc_ocl_block);
+      ocl_block = Some(arc_ocl_block);
     }
 
     Ok(Self {
-      cache_src,
-      cache_ocl_block,
-      map_src,
-      map_ocl_block,
+      collections_src,
+      ocl_block,
     })
   }
 
   pub fn _create_map(&mut self, options: MapClOptions) -> io::Result<MapCl> {
-    match &self.map_ocl_block {
-      None => Err(IoError::new(ErrorKind::Other, "map src empty")),
-      Some(map_ocl_block) => {
-        let map_base = MapHandle::new(
-          options.map_id as usize,
-          &self.map_src,
-          map_ocl_block.clone(),
-        );
+    match (&self.ocl_block, self.collections_src.get_map_src()) {
+      (Some(ocl_block), Some(map_src)) => {
+        let map_base = MapHandle::new(options.map_id as usize, map_src, ocl_block.clone());
         Ok(MapCl::new(map_base))
       }
+      _ => Err(IoError::new(ErrorKind::Other, "map src empty")),
     }
   }
 
   pub fn _create_mini_lru_cache(&self, id: i32) -> io::Result<MiniLruCache> {
-    match &self.cache_ocl_block {
+    match &self.ocl_block {
       None => Err(IoError::new(ErrorKind::Other, "cache src empty")),
-      Some(cache_ocl_block) => {
-        let config = self.cache_src.get_mini_lru_by_id(id as usize)?;
-        let cache_base = MiniLRUCacheHandle::new(config, cache_ocl_block.clone());
+      Some(ocl_block) => {
+        let config = self
+          .collections_src
+          .get_mini_lru_config(CACHE_NAMESPACE, id as usize)?;
+        let cache_base = MiniLRUCacheHandle::new(config, ocl_block.clone());
         cache_base.initialize()?;
 
         Ok(MiniLruCache::new(cache_base))
@@ -162,17 +191,34 @@ impl Device {
   }
 
   pub fn _create_lru_cache(&self, id: i32) -> io::Result<LruCache> {
-    match &self.cache_ocl_block {
+    match &self.ocl_block {
       None => Err(IoError::new(ErrorKind::Other, "cache src empty")),
-      Some(cache_ocl_block) => {
-        let config = self.cache_src.get_lru_by_id(id as usize)?;
-        let cache_base = LRUCacheHandle::new(config, cache_ocl_block.clone());
+      Some(ocl_block) => {
+        let config = self
+          .collections_src
+          .get_lru_config(CACHE_NAMESPACE, id as usize)?;
+        let cache_base = LRUCacheHandle::new(config, ocl_block.clone());
         cache_base.initialize()?;
 
         Ok(LruCache::new(cache_base))
       }
     }
   }
+
+  pub fn _create_ttl_cache(&self, id: i32) -> io::Result<TtlCache> {
+    match &self.ocl_block {
+      None => Err(IoError::new(ErrorKind::Other, "cache src empty")),
+      Some(ocl_block) => {
+        let config = self
+          .collections_src
+          .get_ttl_cache_config(CACHE_NAMESPACE, id as usize)?;
+        let cache_base = TTLCacheHandle::new(config, ocl_block.clone());
+        cache_base.initialize()?;
+
+        Ok(TtlCache::new(cache_base))
+      }
+    }
+  }
 }
 
 #[napi]
@@ -185,13 +231,13 @@ impl Device {
 
   #[napi]
   pub fn print_map_src(&self) -> napi::Result<()> {
-    println!("{:#?}", self.map_src);
+    println!("{:#?}", self.collections_src.get_map_src());
     Ok(())
   }
 
   #[napi]
   pub fn print_cache_src(&self) -> napi::Result<()> {
-    println!("{:#?}", self.cache_src);
+    println!("{:#?}", self.collections_src.get_cache_src());
     Ok(())
   }
 
@@ -212,4 +258,10 @@ impl Device {
     let c = self._create_lru_cache(id)?;
     Ok(c)
   }
+
+  #[napi]
+  pub fn create_ttl_cache(&self, id: i32) -> napi::Result<TtlCache> {
+    let c = self._create_ttl_cache(id)?;
+    Ok(c)
+  }
 }
@@ -0,0 +1,159 @@
+use crate::serialize::{from_buf_i32_to_vec_u8, serialize_map_entry_i32, MapEntry};
+use bincode::config;
+use opencl_collections::cache::handle::ttl::TTLCacheHandle;
+use opencl_collections::opencl::wrapper::system::System;
+use opencl_collections::utils::{from_buf_u8_to_vec_i32, remove_padding_cl_default};
+use serde_json::Value;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// milliseconds since UNIX_EPOCH, used when the timestamp is not supplied
+fn get_now(now: Option<i64>) -> i64 {
+  match now {
+    Some(n) => n,
+    None => SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|x| x.as_millis() as i64)
+      .unwrap_or(0),
+  }
+}
+
+#[napi]
+pub struct TtlCache {
+  inner: TTLCacheHandle<System>,
+}
+
+impl TtlCache {
+  pub fn new(inner: TTLCacheHandle<System>) -> Self {
+    Self { inner }
+  }
+
+  pub fn _set(
+    &self,
+    key: String,
+    value: Value,
+    ttl: Option<i64>,
+    now: Option<i64>,
+  ) -> io::Result<i32> {
+    let key_buf = from_buf_u8_to_vec_i32(key.as_bytes());
+
+    let map_entry: MapEntry = value.into();
+    let value_buf = serialize_map_entry_i32(map_entry);
+
+    let ttl = ttl.map(|x| vec![x]);
+
+    let mut indices = self.inner.put(
+      &vec![key_buf],
+      &vec![value_buf],
+      ttl.as_deref(),
+      get_now(now),
+    )?;
+
+    Ok(indices.pop().unwrap())
+  }
+
+  pub fn _get(&self, key: String, now: Option<i64>) -> io::Result<Value> {
+    let key_buf = from_buf_u8_to_vec_i32(key.as_bytes());
+    let conf = config::standard();
+
+    let (mut values, mut indices) = self.inner.get(&vec![key_buf], get_now(now))?;
+    let entry_index = indices.pop().unwrap();
+
+    if entry_index < 0 {
+      return Ok(Value::Null);
+    }
+
+    let value_buf = values.pop().unwrap();
+
+    let value_u8 = from_buf_i32_to_vec_u8(&value_buf);
+
+    let (map_entry, _): (MapEntry, usize) = bincode::decode_from_slice(&value_u8, conf).unwrap();
+
+    let value: Value = map_entry.into();
+    Ok(value)
+  }
+
+  pub fn _has(&self, key: String, now: Option<i64>) -> io::Result<bool> {
+    let key_buf = from_buf_u8_to_vec_i32(key.as_bytes());
+
+    let (_, mut indices) = self.inner.get(&vec![key_buf], get_now(now))?;
+
+    Ok(indices.pop().unwrap() >= 0)
+  }
+
+  pub fn _purge(&self, now: Option<i64>) -> io::Result<u32> {
+    let indices = self.inner.purge(get_now(now))?;
+    Ok(indices.len() as u32)
+  }
+
+  pub fn _debug(&self) -> io::Result<()> {
+    let _cs = self.inner.print()?;
+    Ok(())
+  }
+
+  pub fn _keys(&self, now: Option<i64>) -> io::Result<Vec<String>> {
+    let mut keys_raw = self.inner.keys(get_now(now))?;
+    keys_raw.sort_by_key(|x| x.expiry);
+
+    let keys: Vec<_> = keys_raw
+      .into_iter()
+      .filter_map(|x| -> Option<String> {
+        let v = remove_padding_cl_default(&x.key);
+
+        if v.is_empty() {
+          return None;
+        }
+
+        let vec_u8 = from_buf_i32_to_vec_u8(&v);
+        Some(String::from_utf8(vec_u8).unwrap())
+      })
+      .collect();
+
+    Ok(keys)
+  }
+}
+
+#[napi]
+impl TtlCache {
+  #[napi]
+  pub fn set(
+    &self,
+    key: String,
+    value: Value,
+    ttl: Option<i64>,
+    now: Option<i64>,
+  ) -> napi::Result<i32> {
+    let r = self._set(key, value, ttl, now)?;
+    Ok(r)
+  }
+
+  #[napi]
+  pub fn get(&self, key: String, now: Option<i64>) -> napi::Result<Value> {
+    let r = self._get(key, now)?;
+    Ok(r)
+  }
+
+  #[napi]
+  pub fn has(&self, key: String, now: Option<i64>) -> napi::Result<bool> {
+    let r = self._has(key, now)?;
+    Ok(r)
+  }
+
+  #[napi]
+  pub fn purge(&self, now: Option<i64>) -> napi::Result<u32> {
+    let r = self._purge(now)?;
+    Ok(r)
+  }
+
+  #[napi]
+  pub fn keys(&self, now: Option<i64>) -> napi::Result<Vec<String>> {
+    let r = self._keys(now)?;
+    Ok(r)
+  }
+
+  #[napi]
+  pub fn debug(&self) -> napi::Result<()> {
+    self._debug()?;
+    Ok(())
+  }
+}
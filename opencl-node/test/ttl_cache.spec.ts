@@ -1,5 +1,6 @@
-import {sum} from "../index";
+import {Device} from "../index";
 import TTLCache from "@isaacs/ttlcache";
+import {unreachable} from "./helpers";
 
 function sleep(ms: number) {
     return new Promise(resolve => setTimeout(resolve, ms));
@@ -34,4 +35,94 @@ describe("ttl_cache", () => {
             expect(v2).toEqual(undefined);
         });
     })
+
+    describe("ttl_cache cl", function () {
+        let device: Device;
+
+        beforeAll(function () {
+            device = new Device({
+                cache: {
+                    ttl: [
+                        // 256 byte
+                        { capacity: 16, valueLen: 256, defaultTtl: 1000 },
+                        { capacity: 4, valueLen: 256, defaultTtl: 1000 },
+                    ]
+                }
+            });
+        })
+
+        it("example 1", () => {
+            const cache = device.createTtlCache(0);
+
+            // fake clock (ms)
+            let now = 0;
+
+            cache.set("1", 2, null, now)
+
+            // 999 ms later
+            now = 999;
+            expect(cache.has("1", now)).toEqual(true);
+            expect(cache.get("1", now)).toEqual(2);
+
+            // 1000 ms later
+            now = 1000;
+            expect(cache.has("1", now)).toEqual(false);
+            expect(cache.get("1", now)).toEqual(null);
+        });
+
+        it("example 1 system clock", async () => {
+            const cache = device.createTtlCache(0);
+
+            cache.set("key_1", "value", 100)
+
+            expect(cache.has("key_1")).toEqual(true);
+            expect(cache.get("key_1")).toEqual("value");
+
+            // clearly past the ttl (100 ms)
+            await sleep(250);
+
+            expect(cache.has("key_1")).toEqual(false);
+        });
+
+        it("purge", () => {
+            const cache = device.createTtlCache(1);
+
+            cache.set("key_1", 1, 10, 0)
+            cache.set("key_2", 2, 50, 0)
+            cache.set("key_3", 3, 10, 0)
+
+            expect(cache.keys(20)).toEqual(["key_2"]);
+            expect(cache.purge(20)).toEqual(2);
+            expect(cache.purge(20)).toEqual(0);
+
+            expect(cache.get("key_2", 20)).toEqual(2);
+        });
+
+        it("cache is full", () => {
+            const cache = device.createTtlCache(1);
+            cache.purge(Number.MAX_SAFE_INTEGER);
+
+            for (let i = 1; i <= 4; i++) {
+                cache.set(`key_${i}`, i, i * 10, 0)
+            }
+
+            // replaces the entry closest to expire
+            cache.set("key_5", 5, 100, 0)
+
+            expect(cache.has("key_1", 0)).toEqual(false);
+            expect(cache.get("key_5", 0)).toEqual(5);
+        });
+
+        it("cache config empty", () => {
+            const invalid_device = new Device({})
+
+            try {
+                invalid_device.createTtlCache(0)
+                unreachable()
+            } catch (e) {
+                let error = e as Error;
+                expect(error.message).toEqual("cache src empty")
+            }
+        });
+    })
 });
\ No newline at end of file
@@ -13,6 +13,19 @@ pub const CL_WRAPPER_FIRST_PLATFORM_NOT_FOUND: cl_int = -100;
 
 pub const CL_WRAPPER_EMPTY_PROGRAM_SOURCE: cl_int = -101;
 
+pub const CL_WRAPPER_DEVICE_ENQUEUE_KERNEL_FAILED: cl_int = -102;
+
+pub fn get_wrapper_error_message(code: cl_int) -> &'static str {
+    match code {
+        CL_WRAPPER_FIRST_PLATFORM_NOT_FOUND => "first platform not found",
+        CL_WRAPPER_EMPTY_PROGRAM_SOURCE => "empty program source",
+        CL_WRAPPER_DEVICE_ENQUEUE_KERNEL_FAILED => {
+            "enqueue_kernel (device side) returned a value other than CL_SUCCESS"
+        }
+        _ => "unknown wrapper error",
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum OclError {
     // opencl error code
@@ -38,9 +51,13 @@ impl From<OclError> for IoError {
 
         match e {
             OclError::Code(code) => Self::new(error_kind, format!("opencl error code: {code}")),
-            OclError::Wrapper(code) => {
-                Self::new(error_kind, format!("opencl wrapper error code: {code}"))
-            }
+            OclError::Wrapper(code) => Self::new(
+                error_kind,
+                format!(
+                    "opencl wrapper error code: {code} ({})",
+                    get_wrapper_error_message(code)
+                ),
+            ),
         }
     }
 }
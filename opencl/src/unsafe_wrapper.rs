@@ -72,14 +72,14 @@ use opencl_sys::bindings::{
     clReleaseProgram, clSetKernelArg, cl_bool, cl_char, cl_command_queue, cl_context, cl_device_id,
     cl_device_type, cl_event, cl_kernel, cl_mem, cl_mem_flags, cl_mem_object_type, cl_platform_id,
     cl_program, cl_queue_properties, cl_uint, cl_ulong, CL_BUILD_PROGRAM_FAILURE,
-    CL_CONTEXT_NUM_DEVICES, CL_CONTEXT_REFERENCE_COUNT, CL_DEVICE_MAX_WORK_GROUP_SIZE,
-    CL_DEVICE_NAME, CL_DEVICE_TYPE_GPU, CL_DEVICE_VENDOR, CL_INVALID_VALUE,
-    CL_KERNEL_LOCAL_MEM_SIZE, CL_KERNEL_NUM_ARGS, CL_KERNEL_REFERENCE_COUNT,
-    CL_KERNEL_WORK_GROUP_SIZE, CL_MEM_REFERENCE_COUNT, CL_MEM_SIZE, CL_MEM_TYPE, CL_PLATFORM_NAME,
-    CL_PLATFORM_PROFILE, CL_PLATFORM_VERSION, CL_PROGRAM_BINARY_SIZES,
-    CL_PROGRAM_BUILD_GLOBAL_VARIABLE_TOTAL_SIZE, CL_PROGRAM_BUILD_LOG, CL_PROGRAM_KERNEL_NAMES,
-    CL_PROGRAM_NUM_DEVICES, CL_PROGRAM_REFERENCE_COUNT, CL_QUEUE_PROPERTIES,
-    CL_QUEUE_REFERENCE_COUNT,
+    CL_CONTEXT_NUM_DEVICES, CL_CONTEXT_REFERENCE_COUNT, CL_DEVICE_GLOBAL_MEM_SIZE,
+    CL_DEVICE_MAX_MEM_ALLOC_SIZE, CL_DEVICE_MAX_WORK_GROUP_SIZE, CL_DEVICE_NAME,
+    CL_DEVICE_TYPE_GPU, CL_DEVICE_VENDOR, CL_INVALID_VALUE, CL_KERNEL_LOCAL_MEM_SIZE,
+    CL_KERNEL_NUM_ARGS, CL_KERNEL_REFERENCE_COUNT, CL_KERNEL_WORK_GROUP_SIZE,
+    CL_MEM_REFERENCE_COUNT, CL_MEM_SIZE, CL_MEM_TYPE, CL_PLATFORM_NAME, CL_PLATFORM_PROFILE,
+    CL_PLATFORM_VERSION, CL_PROGRAM_BINARY_SIZES, CL_PROGRAM_BUILD_GLOBAL_VARIABLE_TOTAL_SIZE,
+    CL_PROGRAM_BUILD_LOG, CL_PROGRAM_KERNEL_NAMES, CL_PROGRAM_NUM_DEVICES,
+    CL_PROGRAM_REFERENCE_COUNT, CL_QUEUE_PROPERTIES, CL_QUEUE_REFERENCE_COUNT,
 };
 use std::ffi::{c_void, CString};
 use std::{mem, ptr};
@@ -235,6 +235,10 @@ pub struct DeviceInfo {
     pub name: String,
     pub vendor: String,
     pub max_work_group_size: usize,
+    /// bytes
+    pub global_mem_size: cl_ulong,
+    /// bytes
+    pub max_mem_alloc_size: cl_ulong,
 }
 
 /// ...
@@ -283,6 +287,30 @@ pub unsafe fn cl_get_device_info(device: cl_device_id) -> OclResult<DeviceInfo>
     );
     cl_check(ret)?;
 
+    let mut global_mem_size: cl_ulong = 0;
+    let mut global_mem_size_param_value_size = mem::size_of::<cl_ulong>();
+
+    let ret = clGetDeviceInfo(
+        device,
+        CL_DEVICE_GLOBAL_MEM_SIZE,
+        global_mem_size_param_value_size,
+        &mut global_mem_size as *mut _ as *mut c_void,
+        &mut global_mem_size_param_value_size,
+    );
+    cl_check(ret)?;
+
+    let mut max_mem_alloc_size: cl_ulong = 0;
+    let mut max_mem_alloc_size_param_value_size = mem::size_of::<cl_ulong>();
+
+    let ret = clGetDeviceInfo(
+        device,
+        CL_DEVICE_MAX_MEM_ALLOC_SIZE,
+        max_mem_alloc_size_param_value_size,
+        &mut max_mem_alloc_size as *mut _ as *mut c_void,
+        &mut max_mem_alloc_size_param_value_size,
+    );
+    cl_check(ret)?;
+
     let name = buf_i8_to_string(&device_name).unwrap();
 
     Ok(DeviceInfo {
@@ -290,6 +318,8 @@ pub unsafe fn cl_get_device_info(device: cl_device_id) -> OclResult<DeviceInfo>
         name,
         vendor: buf_i8_to_string(&vendor_name).unwrap(),
         max_work_group_size,
+        global_mem_size,
+        max_mem_alloc_size,
     })
 }
 
@@ -5,7 +5,7 @@
 //! ...
 //!
 
-use crate::error::OclResult;
+use crate::error::{OclError, OclResult, CL_WRAPPER_DEVICE_ENQUEUE_KERNEL_FAILED};
 use crate::wrapper::context::{CommandQueue, Context, DeviceCommandQueue};
 use crate::wrapper::memory::Buffer;
 use crate::wrapper::platform::{Device, Platform};
@@ -16,6 +16,17 @@ use opencl_sys::bindings::{
 };
 use utilities::uuid::Uuid;
 
+/// Verify that each result of an enqueue_kernel call (device side) is equal to CL_SUCCESS
+pub fn check_device_enqueue_kernel_output(enqueue_kernel_output: &[cl_int]) -> OclResult<()> {
+    if enqueue_kernel_output
+        .iter()
+        .any(|&x| x != CL_SUCCESS as cl_int)
+    {
+        return Err(OclError::Wrapper(CL_WRAPPER_DEVICE_ENQUEUE_KERNEL_FAILED));
+    }
+    Ok(())
+}
+
 ///
 /// Grouping common operations when using the opencl wrapper
 ///
@@ -98,8 +109,8 @@ pub trait OpenclCommonOperation {
     ///
     /// 2 - Read (blocking_read) the contents of an enqueue_kernel_output_buf and store the result in a vector (rust) (clEnqueueReadBuffer)
     ///
-    /// 3 - Verify that each result is equal to CL_SUCCESS. In case of error it will call panic! macro,
-    /// otherwise it will return the vector with the results.
+    /// 3 - Verify that each result is equal to CL_SUCCESS. In case of error it will return
+    /// CL_WRAPPER_DEVICE_ENQUEUE_KERNEL_FAILED, otherwise it will return the vector with the results.
     ///
     fn assert_device_enqueue_kernel(
         &self,
@@ -118,12 +129,7 @@ pub trait OpenclCommonOperation {
             )?
         };
 
-        if enqueue_kernel_output
-            .iter()
-            .any(|&x| x != CL_SUCCESS as i32)
-        {
-            panic!("error device enqueue kernel: {enqueue_kernel_output:?}");
-        }
+        check_device_enqueue_kernel_output(&enqueue_kernel_output)?;
 
         Ok(enqueue_kernel_output)
     }
@@ -365,6 +371,29 @@ impl OpenclCommonOperation for System {
     }
 }
 
+/// shared system, several handles on the same program
+impl<S: OpenclCommonOperation + ?Sized> OpenclCommonOperation for &S {
+    fn get_context(&self) -> &Context {
+        (**self).get_context()
+    }
+
+    fn get_host_command_queue(&self) -> &CommandQueue {
+        (**self).get_host_command_queue()
+    }
+
+    fn get_device_command_queue_0(&self) -> &DeviceCommandQueue {
+        (**self).get_device_command_queue_0()
+    }
+
+    fn get_devices(&self) -> &Vec<Device> {
+        (**self).get_devices()
+    }
+
+    fn get_program(&self) -> &Program {
+        (**self).get_program()
+    }
+}
+
 #[cfg(test)]
 mod tests_system {
     use super::*;
@@ -470,3 +499,25 @@ mod tests_system {
         assert!(output_c.iter().all(|&x| x == list_size as i32));
     }
 }
+
+#[cfg(test)]
+mod tests_check_device_enqueue_kernel_output {
+    use super::*;
+
+    #[test]
+    fn all_success() {
+        let output = vec![CL_SUCCESS as cl_int; 8];
+        assert_eq!(check_device_enqueue_kernel_output(&output), Ok(()));
+    }
+
+    #[test]
+    fn one_failure() {
+        let mut output = vec![CL_SUCCESS as cl_int; 8];
+        output[3] = -1;
+
+        assert_eq!(
+            check_device_enqueue_kernel_output(&output),
+            Err(OclError::Wrapper(CL_WRAPPER_DEVICE_ENQUEUE_KERNEL_FAILED))
+        );
+    }
+}